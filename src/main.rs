@@ -6,11 +6,12 @@ use std::sync::{Arc, Mutex, RwLock, TryLockError};
 
 use lazy_static::lazy_static;
 use object::{init_stack, Class, ContextData, Method};
+use object::symbol::Symbol;
 use vm::bytecode::{Literal, SpecialInstruction};
 use object::ObjectBox;
 
 use crate::vm::bytecode::ByteCode;
-use crate::vm::interpreter::Interpreter;
+use crate::vm::interpreter::{self, Cores, Interpreter};
 use clap::Parser;
 
 lazy_static! {
@@ -26,6 +27,8 @@ struct Args {
     server_mode: bool,
     #[clap(short, long)]
     object_files: Vec<String>,
+    #[clap(short, long)]
+    image: Option<String>,
     args: Vec<String>
 }
 
@@ -38,8 +41,22 @@ impl Args {
 fn load_object_file(file: &str) -> Result<(), Box<dyn std::error::Error>> {
     let data = std::fs::read(file)?;
     let binary = vm::create_binary(data.as_slice()).map_err(|e| format!("Error loading object file: {:?}", e))?;
-    for (name, class) in binary.into_iter() {
-        object::add_class(&name, class)
+    let traits: Vec<_> = binary.traits().map(|(name, trait_)| (name.clone(), trait_.clone())).collect();
+    let interfaces: Vec<_> = binary.interfaces().map(|(name, interface)| (name.clone(), interface.clone())).collect();
+    let classes: Vec<(String, Class)> = binary.into_iter().collect();
+    // Nothing is added until the whole file checks out so a bad file can't leave half its classes loaded
+    if let Err(faults) = object::check_classes(&classes, &interfaces) {
+        let errors: Vec<String> = faults.iter().map(|fault| fault.to_string()).collect();
+        return Err(format!("Error loading object file {}: {}", file, errors.join("; ")).into());
+    }
+    for (name, trait_) in traits {
+        object::add_trait(&name, trait_)
+    }
+    for (name, interface) in interfaces {
+        object::add_interface(&name, interface)
+    }
+    for (name, class) in classes {
+        object::add_class(&name, class);
     }
     Ok(())
 }
@@ -50,8 +67,9 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     
     let args = Args::parse();
     for file in &args.object_files {
-        load_object_file(&file)?;
+        load_object_file(file)?;
     }
+    let image = args.image.clone();
     let mut context = ContextData::new(init_stack());
 
     //let x = create_i64(8);
@@ -60,21 +78,21 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     /*let instructions = vec![
         ByteCode::PushLiteral(x),
         ByteCode::PushLiteral(y),
-        ByteCode::SendMsg(1,String::from("add")),
+        ByteCode::SendMsg(1,Symbol::from("add")),
         ByteCode::Halt
     ];*/
     
     let bytecode = vec![
         //ByteCode::AccessTemp(3),
         ByteCode::PushLiteral(Literal::String(String::from("Logger"))),
-        ByteCode::SendMsg(1,String::from("new")),
-        ByteCode::SendMsg(0,String::from("init")),
+        ByteCode::SendMsg(1,Symbol::from("new")),
+        ByteCode::SendMsg(0,Symbol::from("init")),
         ByteCode::PushLiteral(Literal::String(String::from("Hello World 1"))),
-        ByteCode::SendMsg(1,String::from("println")),
+        ByteCode::SendMsg(1,Symbol::from("println")),
         ByteCode::SpecialInstruction(SpecialInstruction::BackSkip(2)),
     ];
     let mut methods = HashMap::new();
-    methods.insert("main".to_string(), Arc::new(Method::BytecodeMethod{ block: object::create_block(bytecode) }));
+    methods.insert(Symbol::from("main"), Arc::new(Method::BytecodeMethod{ block: object::create_block(bytecode) }));
     let vtable = object::VTable::new(methods);
     let overrides = vec![];
     let class = Class::new(Some("Object"), vtable, overrides);
@@ -90,8 +108,8 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     if args.server_mode {
         unimplemented!()
 
-    } else {
-        let arguments: Vec<ObjectBox> = args.into_iter().map(|x| object::create_string(x)).collect();
+    } else if image.is_none() {
+        let arguments: Vec<ObjectBox> = args.into_iter().map(object::create_string).collect();
         for (i, arg) in arguments.iter().enumerate() {
             context.set_argument(i, arg.clone());
         }
@@ -111,7 +129,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         }
     }
     let mut tasks: VecDeque<Interpreter> = VecDeque::new();
-    let current_tasks: Arc<RwLock<Vec<Arc<Mutex<Option<Interpreter>>>>>> = Arc::new(RwLock::new(Vec::with_capacity(core_count)));
+    let current_tasks: Cores = Arc::new(RwLock::new(Vec::with_capacity(core_count)));
     let mut next_task = 0;
 
     let mut handles = Vec::new();
@@ -132,125 +150,129 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     }
 
     let (sender, receiver) = std::sync::mpsc::channel();
-    sender.send(context).unwrap();
+    if let Some(image) = &image {
+        tasks.extend(vm::image::load_image(image)?);
+    } else {
+        sender.send(context).unwrap();
 
     
-    let bytecode = vec![
-        //ByteCode::AccessTemp(3),
-        ByteCode::PushLiteral(Literal::String(String::from("Logger"))),
-        ByteCode::SendMsg(1,String::from("new")),
-        ByteCode::SendMsg(0,String::from("init")),
-        ByteCode::PushLiteral(Literal::String(String::from("Hello World 2"))),
-        ByteCode::SendMsg(1,String::from("println")),
-        ByteCode::SpecialInstruction(SpecialInstruction::BackSkip(2)),
-    ];
-    let mut context = ContextData::new(init_stack());
-    context.attach_code(bytecode.into());
-    sender.send(context).unwrap();
+        let bytecode = vec![
+            //ByteCode::AccessTemp(3),
+            ByteCode::PushLiteral(Literal::String(String::from("Logger"))),
+            ByteCode::SendMsg(1,Symbol::from("new")),
+            ByteCode::SendMsg(0,Symbol::from("init")),
+            ByteCode::PushLiteral(Literal::String(String::from("Hello World 2"))),
+            ByteCode::SendMsg(1,Symbol::from("println")),
+            ByteCode::SpecialInstruction(SpecialInstruction::BackSkip(2)),
+        ];
+        let mut context = ContextData::new(init_stack());
+        context.attach_code(bytecode.into());
+        sender.send(context).unwrap();
     
-    let bytecode = vec![
-        //ByteCode::AccessTemp(3),
-        ByteCode::PushLiteral(Literal::String(String::from("Logger"))),
-        ByteCode::SendMsg(1,String::from("new")),
-        ByteCode::SendMsg(0,String::from("init")),
-        ByteCode::PushLiteral(Literal::String(String::from("Hello World 3"))),
-        ByteCode::SendMsg(1,String::from("println")),
-        ByteCode::SpecialInstruction(SpecialInstruction::BackSkip(2)),
-    ];
-    let mut context = ContextData::new(init_stack());
-    context.attach_code(bytecode.into());
-    sender.send(context).unwrap();
+        let bytecode = vec![
+            //ByteCode::AccessTemp(3),
+            ByteCode::PushLiteral(Literal::String(String::from("Logger"))),
+            ByteCode::SendMsg(1,Symbol::from("new")),
+            ByteCode::SendMsg(0,Symbol::from("init")),
+            ByteCode::PushLiteral(Literal::String(String::from("Hello World 3"))),
+            ByteCode::SendMsg(1,Symbol::from("println")),
+            ByteCode::SpecialInstruction(SpecialInstruction::BackSkip(2)),
+        ];
+        let mut context = ContextData::new(init_stack());
+        context.attach_code(bytecode.into());
+        sender.send(context).unwrap();
     
-    let bytecode = vec![
-        //ByteCode::AccessTemp(3),
-        ByteCode::PushLiteral(Literal::String(String::from("Logger"))),
-        ByteCode::SendMsg(1,String::from("new")),
-        ByteCode::SendMsg(0,String::from("init")),
-        ByteCode::PushLiteral(Literal::String(String::from("Hello World 4"))),
-        ByteCode::SendMsg(1,String::from("println")),
-        ByteCode::SpecialInstruction(SpecialInstruction::BackSkip(2)),
-    ];
-    let mut context = ContextData::new(init_stack());
-    context.attach_code(bytecode.into());
-    sender.send(context).unwrap();
+        let bytecode = vec![
+            //ByteCode::AccessTemp(3),
+            ByteCode::PushLiteral(Literal::String(String::from("Logger"))),
+            ByteCode::SendMsg(1,Symbol::from("new")),
+            ByteCode::SendMsg(0,Symbol::from("init")),
+            ByteCode::PushLiteral(Literal::String(String::from("Hello World 4"))),
+            ByteCode::SendMsg(1,Symbol::from("println")),
+            ByteCode::SpecialInstruction(SpecialInstruction::BackSkip(2)),
+        ];
+        let mut context = ContextData::new(init_stack());
+        context.attach_code(bytecode.into());
+        sender.send(context).unwrap();
     
-    let bytecode = vec![
-        //ByteCode::AccessTemp(3),
-        ByteCode::PushLiteral(Literal::String(String::from("Logger"))),
-        ByteCode::SendMsg(1,String::from("new")),
-        ByteCode::SendMsg(0,String::from("init")),
-        ByteCode::PushLiteral(Literal::String(String::from("Hello World 5"))),
-        ByteCode::SendMsg(1,String::from("println")),
-        ByteCode::SpecialInstruction(SpecialInstruction::BackSkip(2)),
-    ];
-    let mut context = ContextData::new(init_stack());
-    context.attach_code(bytecode.into());
-    sender.send(context).unwrap();
+        let bytecode = vec![
+            //ByteCode::AccessTemp(3),
+            ByteCode::PushLiteral(Literal::String(String::from("Logger"))),
+            ByteCode::SendMsg(1,Symbol::from("new")),
+            ByteCode::SendMsg(0,Symbol::from("init")),
+            ByteCode::PushLiteral(Literal::String(String::from("Hello World 5"))),
+            ByteCode::SendMsg(1,Symbol::from("println")),
+            ByteCode::SpecialInstruction(SpecialInstruction::BackSkip(2)),
+        ];
+        let mut context = ContextData::new(init_stack());
+        context.attach_code(bytecode.into());
+        sender.send(context).unwrap();
     
-    let bytecode = vec![
-        //ByteCode::AccessTemp(3),
-        ByteCode::PushLiteral(Literal::String(String::from("Logger"))),
-        ByteCode::SendMsg(1,String::from("new")),
-        ByteCode::SendMsg(0,String::from("init")),
-        ByteCode::PushLiteral(Literal::String(String::from("Hello World 6"))),
-        ByteCode::SendMsg(1,String::from("println")),
-        ByteCode::SpecialInstruction(SpecialInstruction::BackSkip(2)),
-    ];
-    let mut context = ContextData::new(init_stack());
-    context.attach_code(bytecode.into());
-    sender.send(context).unwrap();
+        let bytecode = vec![
+            //ByteCode::AccessTemp(3),
+            ByteCode::PushLiteral(Literal::String(String::from("Logger"))),
+            ByteCode::SendMsg(1,Symbol::from("new")),
+            ByteCode::SendMsg(0,Symbol::from("init")),
+            ByteCode::PushLiteral(Literal::String(String::from("Hello World 6"))),
+            ByteCode::SendMsg(1,Symbol::from("println")),
+            ByteCode::SpecialInstruction(SpecialInstruction::BackSkip(2)),
+        ];
+        let mut context = ContextData::new(init_stack());
+        context.attach_code(bytecode.into());
+        sender.send(context).unwrap();
     
-    let bytecode = vec![
-        //ByteCode::AccessTemp(3),
-        ByteCode::PushLiteral(Literal::String(String::from("Logger"))),
-        ByteCode::SendMsg(1,String::from("new")),
-        ByteCode::SendMsg(0,String::from("init")),
-        ByteCode::PushLiteral(Literal::String(String::from("Hello World 7"))),
-        ByteCode::SendMsg(1,String::from("println")),
-        ByteCode::SpecialInstruction(SpecialInstruction::BackSkip(2)),
-    ];
-    let mut context = ContextData::new(init_stack());
-    context.attach_code(bytecode.into());
-    sender.send(context).unwrap();
+        let bytecode = vec![
+            //ByteCode::AccessTemp(3),
+            ByteCode::PushLiteral(Literal::String(String::from("Logger"))),
+            ByteCode::SendMsg(1,Symbol::from("new")),
+            ByteCode::SendMsg(0,Symbol::from("init")),
+            ByteCode::PushLiteral(Literal::String(String::from("Hello World 7"))),
+            ByteCode::SendMsg(1,Symbol::from("println")),
+            ByteCode::SpecialInstruction(SpecialInstruction::BackSkip(2)),
+        ];
+        let mut context = ContextData::new(init_stack());
+        context.attach_code(bytecode.into());
+        sender.send(context).unwrap();
     
-    let bytecode = vec![
-        //ByteCode::AccessTemp(3),
-        ByteCode::PushLiteral(Literal::String(String::from("Logger"))),
-        ByteCode::SendMsg(1,String::from("new")),
-        ByteCode::SendMsg(0,String::from("init")),
-        ByteCode::PushLiteral(Literal::String(String::from("Hello World 8"))),
-        ByteCode::SendMsg(1,String::from("println")),
-        ByteCode::SpecialInstruction(SpecialInstruction::BackSkip(2)),
-    ];
-    let mut context = ContextData::new(init_stack());
-    context.attach_code(bytecode.into());
-    sender.send(context).unwrap();
+        let bytecode = vec![
+            //ByteCode::AccessTemp(3),
+            ByteCode::PushLiteral(Literal::String(String::from("Logger"))),
+            ByteCode::SendMsg(1,Symbol::from("new")),
+            ByteCode::SendMsg(0,Symbol::from("init")),
+            ByteCode::PushLiteral(Literal::String(String::from("Hello World 8"))),
+            ByteCode::SendMsg(1,Symbol::from("println")),
+            ByteCode::SpecialInstruction(SpecialInstruction::BackSkip(2)),
+        ];
+        let mut context = ContextData::new(init_stack());
+        context.attach_code(bytecode.into());
+        sender.send(context).unwrap();
     
-    let bytecode = vec![
-        //ByteCode::AccessTemp(3),
-        ByteCode::PushLiteral(Literal::String(String::from("Logger"))),
-        ByteCode::SendMsg(1,String::from("new")),
-        ByteCode::SendMsg(0,String::from("init")),
-        ByteCode::PushLiteral(Literal::String(String::from("Hello World 9"))),
-        ByteCode::SendMsg(1,String::from("println")),
-        ByteCode::SpecialInstruction(SpecialInstruction::BackSkip(2)),
-    ];
-    let mut context = ContextData::new(init_stack());
-    context.attach_code(bytecode.into());
-    sender.send(context).unwrap();
+        let bytecode = vec![
+            //ByteCode::AccessTemp(3),
+            ByteCode::PushLiteral(Literal::String(String::from("Logger"))),
+            ByteCode::SendMsg(1,Symbol::from("new")),
+            ByteCode::SendMsg(0,Symbol::from("init")),
+            ByteCode::PushLiteral(Literal::String(String::from("Hello World 9"))),
+            ByteCode::SendMsg(1,Symbol::from("println")),
+            ByteCode::SpecialInstruction(SpecialInstruction::BackSkip(2)),
+        ];
+        let mut context = ContextData::new(init_stack());
+        context.attach_code(bytecode.into());
+        sender.send(context).unwrap();
     
-    let bytecode = vec![
-        //ByteCode::AccessTemp(3),
-        ByteCode::PushLiteral(Literal::String(String::from("Logger"))),
-        ByteCode::SendMsg(1,String::from("new")),
-        ByteCode::SendMsg(0,String::from("init")),
-        ByteCode::PushLiteral(Literal::String(String::from("Hello World 10"))),
-        ByteCode::SendMsg(1,String::from("println")),
-        ByteCode::SpecialInstruction(SpecialInstruction::BackSkip(2)),
-    ];
-    let mut context = ContextData::new(init_stack());
-    context.attach_code(bytecode.into());
-    sender.send(context).unwrap();
+        let bytecode = vec![
+            //ByteCode::AccessTemp(3),
+            ByteCode::PushLiteral(Literal::String(String::from("Logger"))),
+            ByteCode::SendMsg(1,Symbol::from("new")),
+            ByteCode::SendMsg(0,Symbol::from("init")),
+            ByteCode::PushLiteral(Literal::String(String::from("Hello World 10"))),
+            ByteCode::SendMsg(1,Symbol::from("println")),
+            ByteCode::SpecialInstruction(SpecialInstruction::BackSkip(2)),
+        ];
+        let mut context = ContextData::new(init_stack());
+        context.attach_code(bytecode.into());
+        sender.send(context).unwrap();
+    }
     **SEND_CHANNEL.lock().as_mut().unwrap() = Some(sender);
     let mut start_time = std::time::Instant::now();
 
@@ -330,7 +352,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                     return Ok(())
                 }
             };
-            let Some(current_task) = current_tasks.get(i).clone() else {
+            let Some(current_task) = current_tasks.get(i) else {
                 continue;
             };
             // Here false is back and true is front
@@ -384,7 +406,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         let mut indices = Vec::new();
         for (i, task) in current_tasks.iter().enumerate() {
             if let Ok(task) = task.try_lock() {
-                if let None = task.as_ref() {
+                if task.as_ref().is_none() {
                     indices.push(i);
                 }
             }
@@ -392,9 +414,32 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         for i in indices.into_iter().rev() {
             current_tasks.remove(i);
         }
-        if current_tasks.len() == 0 && tasks.len() == 0 {
+        for object in object::gc::take_finalizable() {
+            let mut context = ContextData::new(init_stack());
+            context.push(object);
+            context.attach_code(vec![ByteCode::SendMsg(0, Symbol::from("finalize")), ByteCode::Halt].into());
+            tasks.push_back(Interpreter::new(context));
+        }
+        if current_tasks.is_empty() && tasks.is_empty() {
             break;
         }
+        // Once every core is stopped it is safe to look at the whole heap, otherwise it waits for the next switch
+        let stopped = (object::gc::collection_requested() || vm::image::save_requested())
+            && interpreter::wait_for_cores(std::time::Duration::from_millis(100));
+        if stopped && object::gc::collection_requested() {
+            object::gc::collect_cycles();
+        }
+        if stopped && vm::image::save_requested() {
+            // A core that stopped at a safepoint is in the middle of a Rust method, which can't be
+            // saved, so its task stays locked and the image waits for the next switch
+            let running = current_tasks.iter().map(|task| task.try_lock().ok()).collect::<Option<Vec<_>>>();
+            if let Some((running, path)) = running.zip(vm::image::take_save_request()) {
+                let interpreters = tasks.iter().chain(running.iter().filter_map(|task| task.as_ref())).collect::<Vec<_>>();
+                if let Err(fault) = vm::image::save_image(&path, &interpreters) {
+                    eprintln!("Could not save image to {}: {:?}", path, fault);
+                }
+            }
+        }
         drop(locked_locks);
 
         start_time = std::time::Instant::now();
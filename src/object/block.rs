@@ -5,7 +5,9 @@ use crate::object::Method;
 use std::sync::Arc;
 
 use crate::vm::bytecode::ByteCode;
+use crate::vm::interpreter::Interpreter;
 use super::{ContextData, VTable};
+use crate::object::symbol::Symbol;
 
 
 
@@ -28,13 +30,37 @@ impl Block {
     }
     pub fn make_vtable() -> VTable {
         let mut methods = HashMap::new();
-        methods.insert(String::from("value"), Arc::new(Method::RustMethod { fun: Box::new(value) }));
+        methods.insert(Symbol::from("value"), Arc::new(Method::RustMethod { fun: Box::new(value) }));
         VTable::new(methods)
     }
     pub fn call(&self, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
         context.attach_code(self.bytecode.clone());
         Ok(None)
     }
+    /// Run a block to the end and get the value that it returns
+    /// Rust methods that need the result of a block before they can go on use this instead of
+    /// call. The block gets its own interpreter with the arguments followed by its captures, and
+    /// the receiver at the bottom of its frame when it is the block of a method.
+    pub fn evaluate(block: &ObjectBox, receiver: Option<ObjectBox>, arguments: Vec<ObjectBox>) -> Result<Option<ObjectBox>, Fault> {
+        let (bytecode, captures) = {
+            let block = block.borrow();
+            let block = block.downcast_ref::<Block>().ok_or(Fault::InvalidType("Block evaluate: Expected Block".to_string()))?;
+            (block.bytecode.clone(), block.captures.clone())
+        };
+        let mut context = ContextData::new(super::init_stack());
+        let start_index = arguments.len();
+        context.set_arguments(arguments);
+        for (i, capture) in captures.into_iter().enumerate() {
+            context.set_argument(i + start_index, capture);
+        }
+        context.push_frame(None);
+        if let Some(receiver) = receiver {
+            context.push(receiver);
+        }
+        let mut interpreter = Interpreter::resume(vec![(0, bytecode)], None);
+        interpreter.run_nested(&mut context)?;
+        Ok(context.pop())
+    }
 }
 
 impl Object for Block {
@@ -54,7 +80,7 @@ impl Object for Block {
         None
     }
     fn duplicate(&self) -> ObjectBox {
-        let block = Block::make_object(self.super_object.borrow().duplicate(), self.bytecode.clone().to_vec());
+        let block = Block::make_object(self.super_object.clone(), self.bytecode.clone().to_vec());
         let mut blk = block.borrow_mut();
         blk.initialize(Vec::new(), self.vtable.clone());
         drop(blk);
@@ -62,9 +88,15 @@ impl Object for Block {
     }
     fn initialize(&mut self, args: Vec<ObjectBox>, vtable: VTable) {
         self.captures = args;
-        self.vtable.extend(Block::make_vtable());
         self.vtable.extend(vtable);
-        self.super_object.borrow_mut().initialize(vec![], VTable::new_empty());
+    }
+    fn children(&self) -> Vec<ObjectBox> {
+        let mut children = self.captures.clone();
+        children.push(self.super_object.clone());
+        children
+    }
+    fn clear_children(&mut self) {
+        self.captures.clear();
     }
 }
 
@@ -0,0 +1,183 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use super::primitive::PrimitiveObject;
+use super::string::StringObject;
+use super::symbol::Symbol;
+use super::{ContextData, Fault, Method, Object, ObjectBox, VTable};
+
+
+/// StringBuilder
+/// A string that grows in place. Appending doesn't make a new object so building a long string
+/// in a loop only copies it when it outgrows its buffer, and build makes the String at the end.
+pub struct StringBuilder {
+    super_object: Option<ObjectBox>,
+    vtable: VTable,
+    pub value: String,
+}
+
+impl StringBuilder {
+    pub fn make_object(parent: ObjectBox, value: String) -> ObjectBox {
+        ObjectBox::new(StringBuilder { super_object: Some(parent), vtable: VTable::new_empty(), value })
+    }
+    pub fn make_vtable() -> VTable {
+        let mut methods = HashMap::new();
+        methods.insert(Symbol::from("append"), Arc::new(Method::RustMethod { fun: Box::new(builder_append) }));
+        methods.insert(Symbol::from("append_line"), Arc::new(Method::RustMethod { fun: Box::new(builder_append_line) }));
+        methods.insert(Symbol::from("length"), Arc::new(Method::RustMethod { fun: Box::new(builder_length) }));
+        methods.insert(Symbol::from("clear"), Arc::new(Method::RustMethod { fun: Box::new(builder_clear) }));
+        methods.insert(Symbol::from("build"), Arc::new(Method::RustMethod { fun: Box::new(builder_build) }));
+        methods.insert(Symbol::from("to_string"), Arc::new(Method::RustMethod { fun: Box::new(builder_build) }));
+        VTable::new(methods)
+    }
+}
+
+impl Object for StringBuilder {
+    fn get_vtable(&self) -> &VTable {
+        &self.vtable
+    }
+    fn get_super_object(&self) -> Option<ObjectBox> {
+        self.super_object.clone()
+    }
+    fn get_field(&self, _index: usize) -> Option<ObjectBox> {
+        panic!("StringBuilder does not have fields");
+    }
+    fn set_field(&mut self, _index: usize, _value: ObjectBox) {
+        panic!("StringBuilder does not have fields");
+    }
+    fn size(&self) -> Option<usize> {
+        None
+    }
+    fn duplicate(&self) -> ObjectBox {
+        let builder = StringBuilder::make_object(self.super_object.clone().unwrap(), self.value.clone());
+        let mut builder_mut = builder.borrow_mut();
+        builder_mut.initialize(Vec::new(), self.vtable.clone());
+        drop(builder_mut);
+        builder
+    }
+    fn initialize(&mut self, _: Vec<ObjectBox>, vtable: VTable) {
+        self.vtable.extend(vtable);
+    }
+}
+
+/// Make a StringBuilder that starts with the text of its argument if it has one
+pub fn from_arguments(arguments: &[ObjectBox]) -> Result<String, Fault> {
+    match arguments {
+        [] => Ok(String::new()),
+        [value] => text(value),
+        _ => Err(Fault::InvalidType(format!("expected at most 1 argument, got {}", arguments.len()))),
+    }
+}
+
+/// Get the text to append for an object, anything that isn't a String or a Char is sent to_string
+fn text(object: &ObjectBox) -> Result<String, Fault> {
+    let borrowed = object.borrow();
+    if let Some(string) = borrowed.downcast_ref::<StringObject>() {
+        return Ok(string.value.clone());
+    }
+    if let Some(c) = borrowed.downcast_ref::<PrimitiveObject<char>>() {
+        return Ok(c.data.to_string());
+    }
+    drop(borrowed);
+    super::format::to_text(object)
+}
+
+fn with_builder<T>(object: &ObjectBox, name: &str, fun: impl FnOnce(&mut String) -> T) -> Result<T, Fault> {
+    let mut object = object.borrow_mut();
+    let object = object.downcast_mut::<StringBuilder>().ok_or(Fault::InvalidType(format!("StringBuilder {}: Expected StringBuilder", name)))?;
+    Ok(fun(&mut object.value))
+}
+
+fn builder_append(object: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    // The argument can be the builder itself so it is turned into text before the builder is borrowed
+    let text = text(&context.arguments[0])?;
+    with_builder(&object, "append", |value| value.push_str(&text))?;
+    Ok(None)
+}
+
+fn builder_append_line(object: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let text = text(&context.arguments[0])?;
+    with_builder(&object, "append_line", |value| {
+        value.push_str(&text);
+        value.push('\n');
+    })?;
+    Ok(None)
+}
+
+/// The number of chars like the length of a String
+fn builder_length(object: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let length = with_builder(&object, "length", |value| value.chars().count())?;
+    Ok(Some(super::create_u64(length as u64)))
+}
+
+fn builder_clear(object: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    with_builder(&object, "clear", String::clear)?;
+    Ok(None)
+}
+
+fn builder_build(object: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let value = with_builder(&object, "build", |value| value.clone())?;
+    Ok(Some(super::create_string(value)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::value::Immediate;
+    use super::super::{create_character, create_i64, create_object, create_string, Nil};
+
+    fn builder(arguments: &[ObjectBox]) -> ObjectBox {
+        create_object("StringBuilder", arguments).unwrap().unwrap()
+    }
+
+    fn send(receiver: &ObjectBox, selector: &str, arguments: Vec<ObjectBox>) -> Result<Option<ObjectBox>, Fault> {
+        let method = receiver.borrow().lookup_method(Symbol::from(selector)).unwrap();
+        method.evaluate(receiver.clone(), arguments)
+    }
+
+    fn built(builder: &ObjectBox) -> String {
+        let string = send(builder, "build", Vec::new()).unwrap().unwrap();
+        let value = string.borrow().downcast_ref::<StringObject>().unwrap().value.clone();
+        value
+    }
+
+    #[test]
+    fn appending_grows_the_builder_in_place() {
+        let builder = builder(&[create_string("a".to_string())]);
+        assert!(send(&builder, "append", vec![create_string("bc".to_string())]).unwrap().is_none());
+        send(&builder, "append", vec![create_character('é')]).unwrap();
+        send(&builder, "append_line", vec![create_i64(-4)]).unwrap();
+        send(&builder, "append", vec![Nil::new()]).unwrap();
+        assert_eq!(built(&builder), "abcé-4\nnil");
+        let length = send(&builder, "length", Vec::new()).unwrap().unwrap();
+        assert_eq!(Immediate::from_object(&*length.borrow()), Some(Immediate::U64(10)));
+    }
+
+    #[test]
+    fn a_builder_can_append_itself() {
+        let builder = builder(&[create_string("ab".to_string())]);
+        send(&builder, "append", vec![builder.clone()]).unwrap();
+        assert_eq!(built(&builder), "abab");
+    }
+
+    #[test]
+    fn built_strings_dont_change_with_the_builder() {
+        let builder = builder(&[]);
+        send(&builder, "append", vec![create_string("first".to_string())]).unwrap();
+        let first = send(&builder, "build", Vec::new()).unwrap().unwrap();
+        let copy = builder.borrow().duplicate();
+        send(&builder, "clear", Vec::new()).unwrap();
+        send(&builder, "append", vec![create_string("second".to_string())]).unwrap();
+        assert_eq!(first.borrow().downcast_ref::<StringObject>().unwrap().value, "first");
+        assert_eq!(built(&copy), "first");
+        assert_eq!(built(&builder), "second");
+        let string = send(&builder, "to_string", Vec::new()).unwrap().unwrap();
+        assert_eq!(string.borrow().downcast_ref::<StringObject>().unwrap().value, "second");
+    }
+
+    #[test]
+    fn builders_take_at_most_one_argument() {
+        assert!(create_object("StringBuilder", &[create_i64(1), create_i64(2)]).is_err());
+        assert_eq!(built(&builder(&[create_i64(12)])), "12");
+    }
+}
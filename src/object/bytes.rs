@@ -0,0 +1,407 @@
+//! ByteArray
+//! Binary data kept as one buffer of bytes instead of a Vector of U8 objects.
+//! A String is encoded to a ByteArray as UTF-8 and a ByteArray decodes back to a String either
+//! strictly or replacing invalid sequences. Bytes are also written and read as hex and base64.
+//! Every integer type can be read from and written to an offset with read_<type> and
+//! write_<type>, and the types wider than a byte take a _le or _be suffix for their byte order.
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+
+use super::primitive::exact::integer_argument;
+use super::symbol::Symbol;
+use super::vector::VectorObject;
+use super::{ContextData, Fault, Method, Object, ObjectBox, VTable};
+
+
+pub struct ByteArray {
+    super_object: Option<ObjectBox>,
+    vtable: VTable,
+    pub value: Vec<u8>,
+}
+
+impl ByteArray {
+    pub fn make_object(parent: ObjectBox, value: Vec<u8>) -> ObjectBox {
+        ObjectBox::new(ByteArray { super_object: Some(parent), vtable: VTable::new_empty(), value })
+    }
+    pub fn make_vtable() -> VTable {
+        let mut methods = HashMap::new();
+        methods.insert(Symbol::from("length"), Arc::new(Method::RustMethod { fun: Box::new(bytes_length) }));
+        methods.insert(Symbol::from("get"), Arc::new(Method::RustMethod { fun: Box::new(bytes_get) }));
+        methods.insert(Symbol::from("set"), Arc::new(Method::RustMethod { fun: Box::new(bytes_set) }));
+        methods.insert(Symbol::from("slice"), Arc::new(Method::RustMethod { fun: Box::new(bytes_slice) }));
+        methods.insert(Symbol::from("concat"), Arc::new(Method::RustMethod { fun: Box::new(bytes_concat) }));
+        methods.insert(Symbol::from("append"), Arc::new(Method::RustMethod { fun: Box::new(bytes_append) }));
+        methods.insert(Symbol::from("equals"), Arc::new(Method::RustMethod { fun: Box::new(bytes_equals) }));
+        methods.insert(Symbol::from("hash"), Arc::new(Method::RustMethod { fun: Box::new(bytes_hash) }));
+        methods.insert(Symbol::from("decode_utf8"), Arc::new(Method::RustMethod { fun: Box::new(bytes_decode_utf8) }));
+        methods.insert(Symbol::from("decode_utf8_lossy"), Arc::new(Method::RustMethod { fun: Box::new(bytes_decode_utf8_lossy) }));
+        methods.insert(Symbol::from("encode_hex"), Arc::new(Method::RustMethod { fun: Box::new(bytes_encode_hex) }));
+        methods.insert(Symbol::from("encode_base64"), Arc::new(Method::RustMethod { fun: Box::new(bytes_encode_base64) }));
+        add_integer_methods::<u8>(&mut methods);
+        add_integer_methods::<i8>(&mut methods);
+        add_integer_methods::<u16>(&mut methods);
+        add_integer_methods::<i16>(&mut methods);
+        add_integer_methods::<u32>(&mut methods);
+        add_integer_methods::<i32>(&mut methods);
+        add_integer_methods::<u64>(&mut methods);
+        add_integer_methods::<i64>(&mut methods);
+        VTable::new(methods)
+    }
+}
+
+impl Object for ByteArray {
+    fn get_vtable(&self) -> &VTable {
+        &self.vtable
+    }
+    fn get_super_object(&self) -> Option<ObjectBox> {
+        self.super_object.clone()
+    }
+    fn get_field(&self, _index: usize) -> Option<ObjectBox> {
+        panic!("ByteArray does not have fields");
+    }
+    fn set_field(&mut self, _index: usize, _value: ObjectBox) {
+        panic!("ByteArray does not have fields");
+    }
+    fn size(&self) -> Option<usize> {
+        Some(self.value.len())
+    }
+    fn duplicate(&self) -> ObjectBox {
+        let bytes = ByteArray::make_object(self.super_object.clone().unwrap(), self.value.clone());
+        let mut bytes_mut = bytes.borrow_mut();
+        bytes_mut.initialize(Vec::new(), self.vtable.clone());
+        drop(bytes_mut);
+        bytes
+    }
+    fn initialize(&mut self, _: Vec<ObjectBox>, vtable: VTable) {
+        self.vtable.extend(vtable);
+    }
+}
+
+/// Make the bytes of a new ByteArray
+/// With no argument it is empty, an integer gives that many zeros and a Vector gives its integers.
+pub fn from_arguments(arguments: &[ObjectBox]) -> Result<Vec<u8>, Fault> {
+    match arguments {
+        [] => Ok(Vec::new()),
+        [argument] => {
+            let elements = argument.borrow().downcast_ref::<VectorObject>().map(|vector| vector.value.to_vec());
+            match elements {
+                Some(elements) => elements.iter().map(|element| byte_argument(element, "ByteArray")).collect(),
+                None => {
+                    let size = integer_argument(argument, "ByteArray")?;
+                    let size = usize::try_from(size).map_err(|_| Fault::InvalidOperation(format!("ByteArray: size {} is negative", size)))?;
+                    Ok(vec![0; size])
+                }
+            }
+        }
+        _ => Err(Fault::InvalidType(format!("expected at most 1 argument, got {}", arguments.len()))),
+    }
+}
+
+/// Decode hex digits, two for each byte
+pub fn from_hex(text: &str, name: &str) -> Result<Vec<u8>, Fault> {
+    if !text.len().is_multiple_of(2) {
+        return Err(Fault::InvalidOperation(format!("{}: hex has an odd number of digits", name)));
+    }
+    text.as_bytes().chunks(2)
+        .map(|pair| {
+            // from_str_radix would take a sign as well as digits
+            std::str::from_utf8(pair).ok().filter(|pair| pair.bytes().all(|digit| digit.is_ascii_hexdigit()))
+                .and_then(|pair| u8::from_str_radix(pair, 16).ok())
+                .ok_or_else(|| Fault::InvalidOperation(format!("{}: {:?} is not hex", name, String::from_utf8_lossy(pair))))
+        })
+        .collect()
+}
+
+/// Decode standard base64 with padding
+pub fn from_base64(text: &str, name: &str) -> Result<Vec<u8>, Fault> {
+    BASE64.decode(text).map_err(|error| Fault::InvalidOperation(format!("{}: {}", name, error)))
+}
+
+fn byte_argument(object: &ObjectBox, name: &str) -> Result<u8, Fault> {
+    let value = integer_argument(object, name)?;
+    u8::try_from(value).map_err(|_| Fault::Overflow(format!("{}: {} doesn't fit in a byte", name, value)))
+}
+
+fn index_argument(context: &ContextData, position: usize, name: &str) -> Result<usize, Fault> {
+    let index = integer_argument(&context.arguments[position], &format!("ByteArray {}", name))?;
+    usize::try_from(index).map_err(|_| Fault::InvalidOperation(format!("ByteArray {}: index {} is negative", name, index)))
+}
+
+fn out_of_range(name: &str, index: usize, length: usize) -> Fault {
+    Fault::InvalidOperation(format!("ByteArray {}: index {} is out of range for length {}", name, index, length))
+}
+
+fn span_out_of_range(name: &str, offset: usize, size: usize, length: usize) -> Fault {
+    Fault::InvalidOperation(format!("ByteArray {}: {} bytes at {} are out of range for length {}", name, size, offset, length))
+}
+
+fn with_bytes<T>(object: &ObjectBox, name: &str, fun: impl FnOnce(&mut Vec<u8>) -> Result<T, Fault>) -> Result<T, Fault> {
+    let mut object = object.borrow_mut();
+    let object = object.downcast_mut::<ByteArray>().ok_or(Fault::InvalidType(format!("ByteArray {}: Expected ByteArray", name)))?;
+    fun(&mut object.value)
+}
+
+/// Copy the bytes of an argument that has to be a ByteArray
+fn bytes_argument(context: &ContextData, position: usize, name: &str) -> Result<Vec<u8>, Fault> {
+    context.arguments[position].borrow().downcast_ref::<ByteArray>().map(|bytes| bytes.value.clone())
+        .ok_or(Fault::InvalidType(format!("ByteArray {}: Expected ByteArray", name)))
+}
+
+fn bytes_length(object: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let length = with_bytes(&object, "length", |value| Ok(value.len()))?;
+    Ok(Some(super::create_u64(length as u64)))
+}
+
+fn bytes_get(object: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let index = index_argument(context, 0, "get")?;
+    let byte = with_bytes(&object, "get", |value| value.get(index).copied().ok_or_else(|| out_of_range("get", index, value.len())))?;
+    Ok(Some(super::create_u8(byte)))
+}
+
+fn bytes_set(object: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let index = index_argument(context, 0, "set")?;
+    let byte = byte_argument(&context.arguments[1], "ByteArray set")?;
+    with_bytes(&object, "set", |value| {
+        let length = value.len();
+        *value.get_mut(index).ok_or_else(|| out_of_range("set", index, length))? = byte;
+        Ok(())
+    })?;
+    Ok(None)
+}
+
+/// The bytes from the first index up to but not including the second
+fn bytes_slice(object: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let start = index_argument(context, 0, "slice")?;
+    let end = index_argument(context, 1, "slice")?;
+    if start > end {
+        return Err(Fault::InvalidOperation(format!("ByteArray slice: start {} is after end {}", start, end)));
+    }
+    let slice = with_bytes(&object, "slice", |value| {
+        value.get(start..end).map(<[u8]>::to_vec).ok_or_else(|| out_of_range("slice", end, value.len()))
+    })?;
+    Ok(Some(super::create_byte_array(slice)))
+}
+
+fn bytes_concat(object: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let other = bytes_argument(context, 0, "concat")?;
+    let joined = with_bytes(&object, "concat", |value| Ok([value.as_slice(), &other].concat()))?;
+    Ok(Some(super::create_byte_array(joined)))
+}
+
+/// Add a byte or the bytes of a ByteArray to the end in place
+fn bytes_append(object: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    // The argument can be the receiver itself so its bytes are copied before the receiver is borrowed
+    let is_bytes = context.arguments[0].borrow().is::<ByteArray>();
+    let other = if is_bytes {
+        bytes_argument(context, 0, "append")?
+    } else {
+        vec![byte_argument(&context.arguments[0], "ByteArray append")?]
+    };
+    with_bytes(&object, "append", |value| {
+        value.extend(other);
+        Ok(())
+    })?;
+    Ok(None)
+}
+
+fn bytes_equals(object: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let other = bytes_argument(context, 0, "equals")?;
+    let equal = with_bytes(&object, "equals", |value| Ok(*value == other))?;
+    Ok(Some(super::create_boolean(equal)))
+}
+
+fn bytes_hash(object: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let hash = with_bytes(&object, "hash", |value| Ok(super::set::hash_of(value)))?;
+    Ok(Some(super::create_u64(hash)))
+}
+
+/// Decode the bytes as UTF-8, invalid UTF-8 is a fault
+fn bytes_decode_utf8(object: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let value = with_bytes(&object, "decode_utf8", |value| Ok(value.clone()))?;
+    let text = String::from_utf8(value).map_err(|error| {
+        Fault::InvalidOperation(format!("ByteArray decode_utf8: invalid UTF-8 at byte {}", error.utf8_error().valid_up_to()))
+    })?;
+    Ok(Some(super::create_string(text)))
+}
+
+/// Decode the bytes as UTF-8 replacing each invalid sequence with U+FFFD
+fn bytes_decode_utf8_lossy(object: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let text = with_bytes(&object, "decode_utf8_lossy", |value| Ok(String::from_utf8_lossy(value).into_owned()))?;
+    Ok(Some(super::create_string(text)))
+}
+
+/// Lowercase hex with two digits for each byte
+fn bytes_encode_hex(object: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let text = with_bytes(&object, "encode_hex", |value| Ok(value.iter().map(|byte| format!("{:02x}", byte)).collect()))?;
+    Ok(Some(super::create_string(text)))
+}
+
+fn bytes_encode_base64(object: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let text = with_bytes(&object, "encode_base64", |value| Ok(BASE64.encode(value)))?;
+    Ok(Some(super::create_string(text)))
+}
+
+
+/// An integer type that can be read from and written to bytes
+trait Integer: Copy + TryFrom<i128> + 'static {
+    const NAME: &'static str;
+    const SIZE: usize;
+    fn read(bytes: &[u8], little: bool) -> Self;
+    fn write(self, little: bool) -> Vec<u8>;
+    fn create(self) -> ObjectBox;
+}
+
+macro_rules! integer {
+    ($($t:ty => $create:ident),*) => {
+        $(impl Integer for $t {
+            const NAME: &'static str = stringify!($t);
+            const SIZE: usize = std::mem::size_of::<$t>();
+            fn read(bytes: &[u8], little: bool) -> Self {
+                let bytes = bytes.try_into().unwrap();
+                if little { <$t>::from_le_bytes(bytes) } else { <$t>::from_be_bytes(bytes) }
+            }
+            fn write(self, little: bool) -> Vec<u8> {
+                if little { self.to_le_bytes().to_vec() } else { self.to_be_bytes().to_vec() }
+            }
+            fn create(self) -> ObjectBox {
+                super::$create(self)
+            }
+        })*
+    };
+}
+
+integer!(u8 => create_u8, i8 => create_i8, u16 => create_u16, i16 => create_i16,
+    u32 => create_u32, i32 => create_i32, u64 => create_u64, i64 => create_i64);
+
+/// Add read_<type> and write_<type> for one integer type
+/// Both take the offset of the first byte, write takes the integer as well and faults if it doesn't fit.
+fn add_integer_methods<T: Integer>(methods: &mut HashMap<Symbol, Arc<Method>>) {
+    let orders: &[(&str, bool)] = if T::SIZE == 1 { &[("", true)] } else { &[("_le", true), ("_be", false)] };
+    for &(suffix, little) in orders {
+        let read = format!("read_{}{}", T::NAME, suffix);
+        let name = read.clone();
+        let fun = move |object: ObjectBox, context: &mut ContextData| -> Result<Option<ObjectBox>, Fault> {
+            let offset = index_argument(context, 0, &name)?;
+            let value = with_bytes(&object, &name, |value| {
+                let bytes = value.get(offset..offset.saturating_add(T::SIZE)).ok_or_else(|| span_out_of_range(&name, offset, T::SIZE, value.len()))?;
+                Ok(T::read(bytes, little))
+            })?;
+            Ok(Some(value.create()))
+        };
+        methods.insert(Symbol::intern(&read), Arc::new(Method::RustMethod { fun: Box::new(fun) }));
+
+        let write = format!("write_{}{}", T::NAME, suffix);
+        let name = write.clone();
+        let fun = move |object: ObjectBox, context: &mut ContextData| -> Result<Option<ObjectBox>, Fault> {
+            let offset = index_argument(context, 0, &name)?;
+            let value = integer_argument(&context.arguments[1], &format!("ByteArray {}", name))?;
+            let value = T::try_from(value).map_err(|_| Fault::Overflow(format!("ByteArray {}: {} doesn't fit in a {}", name, value, T::NAME)))?;
+            with_bytes(&object, &name, |bytes| {
+                let length = bytes.len();
+                let target = bytes.get_mut(offset..offset.saturating_add(T::SIZE)).ok_or_else(|| span_out_of_range(&name, offset, T::SIZE, length))?;
+                target.copy_from_slice(&value.write(little));
+                Ok(())
+            })?;
+            Ok(None)
+        };
+        methods.insert(Symbol::intern(&write), Arc::new(Method::RustMethod { fun: Box::new(fun) }));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::string::StringObject;
+    use super::super::value::Immediate;
+    use super::super::{create_byte_array, create_i64, create_string};
+
+    fn send(receiver: &ObjectBox, selector: &str, arguments: Vec<ObjectBox>) -> Result<Option<ObjectBox>, Fault> {
+        let method = receiver.borrow().lookup_method(Symbol::from(selector)).unwrap();
+        method.evaluate(receiver.clone(), arguments)
+    }
+
+    fn bytes(object: &ObjectBox) -> Vec<u8> {
+        object.borrow().downcast_ref::<ByteArray>().unwrap().value.clone()
+    }
+
+    fn text(object: &ObjectBox) -> String {
+        object.borrow().downcast_ref::<StringObject>().unwrap().value.clone()
+    }
+
+    fn read(array: &ObjectBox, selector: &str, offset: i64) -> Result<Option<Immediate>, Fault> {
+        let value = send(array, selector, vec![create_i64(offset)])?.unwrap();
+        let value = Immediate::from_object(&*value.borrow());
+        Ok(value)
+    }
+
+    #[test]
+    fn hex_round_trips() {
+        let array = create_byte_array(vec![0x00, 0x7f, 0xab, 0xff]);
+        let hex = send(&array, "encode_hex", Vec::new()).unwrap().unwrap();
+        assert_eq!(text(&hex), "007fabff");
+        let decoded = send(&hex, "decode_hex", Vec::new()).unwrap().unwrap();
+        assert_eq!(bytes(&decoded), [0x00, 0x7f, 0xab, 0xff]);
+        assert_eq!(from_hex("ABcd", "test").unwrap(), [0xab, 0xcd]);
+        assert_eq!(from_hex("", "test").unwrap(), []);
+    }
+
+    #[test]
+    fn bad_hex_faults() {
+        assert!(matches!(from_hex("abc", "test"), Err(Fault::InvalidOperation(_))));
+        assert!(matches!(from_hex("zz", "test"), Err(Fault::InvalidOperation(_))));
+        assert!(matches!(from_hex("+1", "test"), Err(Fault::InvalidOperation(_))));
+        // Two bytes of one char are not two hex digits
+        assert!(matches!(from_hex("é0", "test"), Err(Fault::InvalidOperation(_))));
+        assert!(send(&create_string("0g".to_string()), "decode_hex", Vec::new()).is_err());
+    }
+
+    #[test]
+    fn base64_round_trips() {
+        for value in [vec![], vec![0xff], vec![0xfb, 0xff], b"hello".to_vec()] {
+            let array = create_byte_array(value.clone());
+            let encoded = send(&array, "encode_base64", Vec::new()).unwrap().unwrap();
+            let decoded = send(&encoded, "decode_base64", Vec::new()).unwrap().unwrap();
+            assert_eq!(bytes(&decoded), value);
+        }
+        let encoded = send(&create_byte_array(vec![0xfb, 0xff]), "encode_base64", Vec::new()).unwrap().unwrap();
+        assert_eq!(text(&encoded), "+/8=");
+    }
+
+    #[test]
+    fn bad_base64_faults() {
+        assert!(matches!(from_base64("aGk", "test"), Err(Fault::InvalidOperation(_))));
+        assert!(matches!(from_base64("a$==", "test"), Err(Fault::InvalidOperation(_))));
+        assert!(matches!(from_base64("-_8=", "test"), Err(Fault::InvalidOperation(_))));
+        assert!(send(&create_string("aGk=!".to_string()), "decode_base64", Vec::new()).is_err());
+    }
+
+    #[test]
+    fn integers_are_read_in_either_byte_order() {
+        let array = create_byte_array(vec![0x01, 0x02, 0x03, 0x04, 0xff]);
+        assert_eq!(read(&array, "read_u16_le", 0).unwrap(), Some(Immediate::U16(0x0201)));
+        assert_eq!(read(&array, "read_u16_be", 0).unwrap(), Some(Immediate::U16(0x0102)));
+        assert_eq!(read(&array, "read_u32_be", 1).unwrap(), Some(Immediate::U32(0x020304ff)));
+        assert_eq!(read(&array, "read_i8", 4).unwrap(), Some(Immediate::I8(-1)));
+        assert_eq!(read(&array, "read_u8", 4).unwrap(), Some(Immediate::U8(0xff)));
+        assert!(matches!(read(&array, "read_u32_le", 2), Err(Fault::InvalidOperation(_))));
+        assert!(matches!(read(&array, "read_u8", 5), Err(Fault::InvalidOperation(_))));
+        assert!(matches!(read(&array, "read_u64_le", i64::MAX), Err(Fault::InvalidOperation(_))));
+    }
+
+    #[test]
+    fn integers_are_written_in_either_byte_order() {
+        let array = create_byte_array(vec![0; 6]);
+        send(&array, "write_u32_le", vec![create_i64(1), create_i64(0x0a0b0c0d)]).unwrap();
+        assert_eq!(bytes(&array), [0, 0x0d, 0x0c, 0x0b, 0x0a, 0]);
+        send(&array, "write_i16_be", vec![create_i64(4), create_i64(-2)]).unwrap();
+        assert_eq!(bytes(&array), [0, 0x0d, 0x0c, 0x0b, 0xff, 0xfe]);
+        assert_eq!(read(&array, "read_i16_be", 4).unwrap(), Some(Immediate::I16(-2)));
+        assert!(matches!(send(&array, "write_u8", vec![create_i64(0), create_i64(256)]), Err(Fault::Overflow(_))));
+        assert!(matches!(send(&array, "write_u16_le", vec![create_i64(0), create_i64(-1)]), Err(Fault::Overflow(_))));
+        assert!(matches!(send(&array, "write_u16_le", vec![create_i64(5), create_i64(1)]), Err(Fault::InvalidOperation(_))));
+        assert_eq!(bytes(&array), [0, 0x0d, 0x0c, 0x0b, 0xff, 0xfe]);
+    }
+}
@@ -0,0 +1,91 @@
+use std::sync::Arc;
+use std::collections::HashMap;
+use crate::object::ContextData;
+use crate::object::Method;
+
+use super::{Fault, Object, ObjectBox};
+use crate::object::VTable;
+use crate::object::symbol::Symbol;
+
+
+/// ClassObject
+/// The object that stands for a class at runtime. There is only one per class and it holds the
+/// class variables and the class-side methods.
+/// Its super object is the class object of the parent class so class-side methods are inherited.
+/// Class variables aren't, every class gets its own.
+pub struct ClassObject {
+    super_object: Option<ObjectBox>,
+    vtable: VTable,
+    pub name: String,
+    fields: Box<[ObjectBox]>,
+}
+
+impl ClassObject {
+    pub fn make_object(parent: ObjectBox, name: String, field_count: usize) -> ObjectBox {
+        let class = ClassObject {
+            super_object: Some(parent),
+            vtable: VTable::new_empty(),
+            name,
+            fields: vec![super::Nil::new(); field_count].into_boxed_slice(),
+        };
+        ObjectBox::new(class)
+    }
+    /// Point the class at the class object of its parent after the parent class was replaced
+    pub fn set_parent(&mut self, parent: ObjectBox) {
+        self.super_object = Some(parent);
+    }
+    pub fn make_vtable() -> VTable {
+        let mut methods = HashMap::new();
+        methods.insert(Symbol::from("new"), Arc::new(Method::RustMethod { fun: Box::new(class_new)}));
+        methods.insert(Symbol::from("name"), Arc::new(Method::RustMethod { fun: Box::new(class_name)}));
+        VTable::new(methods)
+    }
+}
+
+impl Object for ClassObject {
+    fn get_vtable(&self) -> &VTable {
+        &self.vtable
+    }
+    fn get_super_object(&self) -> Option<ObjectBox> {
+        self.super_object.clone()
+    }
+    fn get_field(&self, index: usize) -> Option<ObjectBox> {
+        self.fields.get(index).cloned()
+    }
+    fn set_field(&mut self, index: usize, value: ObjectBox) {
+        self.fields[index] = value;
+    }
+    fn size(&self) -> Option<usize> {
+        Some(self.fields.len())
+    }
+    /// There is only one object per class so duplicating it gives back the same class
+    fn duplicate(&self) -> ObjectBox {
+        super::get_class_object(&self.name).expect("ClassObject: class was removed")
+    }
+    fn initialize(&mut self, _args: Vec<ObjectBox>, vtable: VTable) {
+        self.vtable.extend(vtable);
+    }
+    fn children(&self) -> Vec<ObjectBox> {
+        self.fields.iter().cloned().chain(self.super_object.clone()).collect()
+    }
+    fn clear_children(&mut self) {
+        self.fields = Box::new([]);
+    }
+}
+
+
+fn class_new(object: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let name = {
+        let object = object.borrow();
+        let object = object.downcast_ref::<ClassObject>().ok_or(Fault::InvalidType("Class new: Expected Class".to_string()))?;
+        object.name.clone()
+    };
+    context.flush_arguments();
+    super::create_object(&name, &context.arguments[..context.arg_count])
+}
+
+fn class_name(object: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let object = object.borrow();
+    let object = object.downcast_ref::<ClassObject>().ok_or(Fault::InvalidType("Class name: Expected Class".to_string()))?;
+    Ok(Some(super::create_string(object.name.clone())))
+}
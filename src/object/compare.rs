@@ -0,0 +1,171 @@
+//! Comparisons built on order and equals
+//! Every ordered type understands less_than, less_equal, greater_than, greater_equal, not_equals,
+//! min and max. They send order or equals to the receiver so numbers of different types compare
+//! the same way they do there, and a type that overrides order gets comparisons that agree with it.
+//! min and max give a copy of the receiver when both sides are equal.
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use super::symbol::predefined::{EQUALS, GREATER_EQUAL, GREATER_THAN, LESS_EQUAL, LESS_THAN, MAX, MIN, NOT_EQUALS, ORDER};
+use super::symbol::Symbol;
+use super::value::Immediate;
+use super::{ContextData, Fault, Method, ObjectBox};
+
+/// A comparison message and the orderings it is true for
+type Comparison = (Symbol, fn(Ordering) -> bool);
+
+/// Add the comparison methods to a vtable
+pub fn add_methods(methods: &mut HashMap<Symbol, Arc<Method>>) {
+    let comparisons: [Comparison; 4] = [
+        (LESS_THAN, Ordering::is_lt),
+        (LESS_EQUAL, Ordering::is_le),
+        (GREATER_THAN, Ordering::is_gt),
+        (GREATER_EQUAL, Ordering::is_ge),
+    ];
+    for (selector, test) in comparisons {
+        let fun = move |object: ObjectBox, context: &mut ContextData| -> Result<Option<ObjectBox>, Fault> {
+            let ordering = ordering(&object, context, selector.as_str())?;
+            Ok(Some(super::create_boolean(test(ordering))))
+        };
+        methods.insert(selector, Arc::new(Method::RustMethod { fun: Box::new(fun) }));
+    }
+    methods.insert(NOT_EQUALS, Arc::new(Method::RustMethod { fun: Box::new(not_equals) }));
+    methods.insert(MIN, Arc::new(Method::RustMethod { fun: Box::new(min) }));
+    methods.insert(MAX, Arc::new(Method::RustMethod { fun: Box::new(max) }));
+}
+
+/// Send a message to the receiver with the argument of the message being handled
+/// The method is evaluated to the end so an order or equals written in bytecode gives its result here.
+fn send(object: &ObjectBox, context: &mut ContextData, selector: Symbol, name: &str) -> Result<ObjectBox, Fault> {
+    let method = object.borrow().lookup_method(selector)
+        .ok_or(Fault::MethodNotFound(format!("{} needs {}", name, selector.as_str())))?;
+    method.evaluate(object.clone(), vec![context.arguments[0].clone()])?
+        .ok_or(Fault::InvalidOperation(format!("{}: {} didn't give a result", name, selector.as_str())))
+}
+
+fn ordering(object: &ObjectBox, context: &mut ContextData, name: &str) -> Result<Ordering, Fault> {
+    let order = send(object, context, ORDER, name)?;
+    to_ordering(&order, name)
+}
+
+/// Compare two objects by sending order to the first
+pub fn order(object: &ObjectBox, other: &ObjectBox, name: &str) -> Result<Ordering, Fault> {
+    let method = object.borrow().lookup_method(ORDER)
+        .ok_or(Fault::MethodNotFound(format!("{} needs order", name)))?;
+    let order = method.evaluate(object.clone(), vec![other.clone()])?
+        .ok_or(Fault::InvalidOperation(format!("{}: order didn't give a result", name)))?;
+    to_ordering(&order, name)
+}
+
+/// Read the result of order, any integer whose sign is the ordering
+pub fn to_ordering(order: &ObjectBox, name: &str) -> Result<Ordering, Fault> {
+    let order = Immediate::from_object(&*order.borrow());
+    let sign = match order {
+        Some(Immediate::I8(value)) => value.signum() as i64,
+        Some(Immediate::I16(value)) => value.signum() as i64,
+        Some(Immediate::I32(value)) => value.signum() as i64,
+        Some(Immediate::I64(value)) => value.signum(),
+        Some(Immediate::U8(value)) => value.min(1) as i64,
+        Some(Immediate::U16(value)) => value.min(1) as i64,
+        Some(Immediate::U32(value)) => value.min(1) as i64,
+        Some(Immediate::U64(value)) => value.min(1) as i64,
+        _ => return Err(Fault::InvalidType(format!("{}: order didn't give an integer", name))),
+    };
+    Ok(sign.cmp(&0))
+}
+
+fn not_equals(object: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let equal = send(&object, context, EQUALS, "not_equals")?;
+    let equal = match Immediate::from_object(&*equal.borrow()) {
+        Some(Immediate::Boolean(equal)) => equal,
+        _ => return Err(Fault::InvalidType("not_equals: equals didn't give a Boolean".to_string())),
+    };
+    Ok(Some(super::create_boolean(!equal)))
+}
+
+fn min(object: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let chosen = match ordering(&object, context, "min")? {
+        Ordering::Greater => context.arguments[0].clone(),
+        _ => object,
+    };
+    // Numbers change in place so the result can't be the same object as either side
+    let copy = chosen.borrow().duplicate();
+    Ok(Some(copy))
+}
+
+fn max(object: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let chosen = match ordering(&object, context, "max")? {
+        Ordering::Less => context.arguments[0].clone(),
+        _ => object,
+    };
+    let copy = chosen.borrow().duplicate();
+    Ok(Some(copy))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::object::primitive::rational::Rational;
+    use crate::object::{create_f64, create_i64, create_i8, create_rational, create_string};
+
+    fn send_message(receiver: &ObjectBox, selector: &str, argument: ObjectBox) -> Result<Option<ObjectBox>, Fault> {
+        let method = receiver.borrow().lookup_method(Symbol::from(selector)).unwrap();
+        method.evaluate(receiver.clone(), vec![argument])
+    }
+
+    fn test(receiver: &ObjectBox, selector: &str, argument: ObjectBox) -> bool {
+        let result = send_message(receiver, selector, argument).unwrap().unwrap();
+        let result = Immediate::from_object(&*result.borrow());
+        match result {
+            Some(Immediate::Boolean(value)) => value,
+            other => panic!("{} gave {:?}", selector, other),
+        }
+    }
+
+    #[test]
+    fn comparisons_agree_with_order() {
+        let two = create_i64(2);
+        assert!(test(&two, "less_than", create_i64(3)));
+        assert!(!test(&two, "less_than", create_i64(2)));
+        assert!(test(&two, "less_equal", create_i64(2)));
+        assert!(!test(&two, "greater_than", create_i64(2)));
+        assert!(test(&two, "greater_equal", create_i64(1)));
+        assert!(test(&two, "not_equals", create_i64(3)));
+        assert!(!test(&two, "not_equals", create_i64(2)));
+    }
+
+    #[test]
+    fn different_types_compare_by_value() {
+        assert!(test(&create_i8(1), "less_than", create_i64(300)));
+        assert!(test(&create_f64(0.4), "less_than", create_rational(Rational::new(1, 2).unwrap())));
+        assert!(test(&create_rational(Rational::new(3, 2).unwrap()), "greater_than", create_i64(1)));
+    }
+
+    #[test]
+    fn strings_are_ordered() {
+        let apple = create_string("apple".to_string());
+        assert!(test(&apple, "less_than", create_string("banana".to_string())));
+        assert!(test(&apple, "greater_than", create_string("Apple".to_string())));
+        assert!(test(&apple, "not_equals", create_string("apples".to_string())));
+        let max = send_message(&apple, "max", create_string("pear".to_string())).unwrap().unwrap();
+        assert_eq!(max.borrow().downcast_ref::<crate::object::string::StringObject>().unwrap().value, "pear");
+    }
+
+    #[test]
+    fn min_and_max_give_copies() {
+        let one = create_i64(1);
+        let other = create_i64(1);
+        let min = send_message(&one, "min", other.clone()).unwrap().unwrap();
+        assert_ne!(min.as_ptr(), one.as_ptr());
+        assert_ne!(min.as_ptr(), other.as_ptr());
+        assert_eq!(Immediate::from_object(&*min.borrow()), Some(Immediate::I64(1)));
+        let max = send_message(&one, "max", create_i64(5)).unwrap().unwrap();
+        assert_eq!(Immediate::from_object(&*max.borrow()), Some(Immediate::I64(5)));
+    }
+
+    #[test]
+    fn comparing_unrelated_types_faults() {
+        assert!(send_message(&create_i64(1), "less_than", create_string("1".to_string())).is_err());
+    }
+}
@@ -0,0 +1,272 @@
+//! String formatting
+//! A template has placeholders in braces. {} takes the next argument and {2} takes the argument at
+//! that index. After a colon a placeholder can give [[fill]align][+][#][0][width][.precision][radix]
+//! where align is one of < ^ >, + shows the sign of positive numbers, # puts 0x, 0o or 0b before a
+//! radix, 0 pads a number with zeros after its sign, and radix is one of x X o b.
+//! Precision is the number of decimals of a number or the most chars of anything else.
+//! Each argument is turned into text by sending it to_string. {{ and }} are literal braces.
+use super::primitive::bigint::BigIntObject;
+use super::primitive::exact::Exact;
+use super::string::StringObject;
+use super::symbol::Symbol;
+use super::{Fault, Nil, ObjectBox};
+
+#[derive(Clone, Copy)]
+enum Align {
+    Left,
+    Center,
+    Right,
+}
+
+struct Spec {
+    fill: char,
+    align: Option<Align>,
+    sign: bool,
+    prefix: bool,
+    zero: bool,
+    width: usize,
+    precision: Option<usize>,
+    radix: Option<char>,
+}
+
+fn bad_template(reason: String) -> Fault {
+    Fault::InvalidOperation(format!("format: {}", reason))
+}
+
+impl Spec {
+    fn parse(text: &str) -> Result<Spec, Fault> {
+        let mut spec = Spec { fill: ' ', align: None, sign: false, prefix: false, zero: false, width: 0, precision: None, radix: None };
+        let chars: Vec<char> = text.chars().collect();
+        let align = |c: char| match c {
+            '<' => Some(Align::Left),
+            '^' => Some(Align::Center),
+            '>' => Some(Align::Right),
+            _ => None,
+        };
+        let mut index = 0;
+        if let Some(found) = chars.get(1).copied().and_then(align) {
+            spec.fill = chars[0];
+            spec.align = Some(found);
+            index = 2;
+        } else if let Some(found) = chars.first().copied().and_then(align) {
+            spec.align = Some(found);
+            index = 1;
+        }
+        let mut flag = |c: char| {
+            let found = chars.get(index) == Some(&c);
+            if found {
+                index += 1;
+            }
+            found
+        };
+        spec.sign = flag('+');
+        spec.prefix = flag('#');
+        spec.zero = flag('0');
+        let digits = |index: &mut usize| {
+            let start = *index;
+            while chars.get(*index).is_some_and(char::is_ascii_digit) {
+                *index += 1;
+            }
+            let digits: String = chars[start..*index].iter().collect();
+            (!digits.is_empty()).then(|| digits.parse::<usize>())
+        };
+        if let Some(width) = digits(&mut index) {
+            spec.width = width.map_err(|_| bad_template(format!("width in {:?} is too big", text)))?;
+        }
+        if chars.get(index) == Some(&'.') {
+            index += 1;
+            let precision = digits(&mut index).ok_or_else(|| bad_template(format!("missing precision in {:?}", text)))?;
+            spec.precision = Some(precision.map_err(|_| bad_template(format!("precision in {:?} is too big", text)))?);
+        }
+        if let Some(radix) = chars.get(index).filter(|c| matches!(c, 'x' | 'X' | 'o' | 'b')) {
+            spec.radix = Some(*radix);
+            index += 1;
+        }
+        if index < chars.len() {
+            return Err(bad_template(format!("unknown specifier {:?}", text)));
+        }
+        Ok(spec)
+    }
+}
+
+/// Send to_string to an object and get the text
+pub fn to_text(object: &ObjectBox) -> Result<String, Fault> {
+    if object.borrow().is::<Nil>() {
+        return Ok(String::from("nil"));
+    }
+    let method = object.borrow().lookup_method(Symbol::from("to_string"))
+        .ok_or(Fault::MethodNotFound(String::from("to_string")))?;
+    let text = method.evaluate(object.clone(), Vec::new())?
+        .ok_or(Fault::InvalidOperation("format: to_string didn't give a String".to_string()))?;
+    let text = text.borrow();
+    let text = text.downcast_ref::<StringObject>().ok_or(Fault::InvalidType("format: to_string didn't give a String".to_string()))?;
+    Ok(text.value.clone())
+}
+
+/// Format one argument as the spec says
+fn format_argument(argument: &ObjectBox, spec: &Spec) -> Result<String, Fault> {
+    // A BigInt that doesn't fit in an i128 is still a number but it can only be written out whole
+    let big = argument.borrow().downcast_ref::<BigIntObject>().map(|big| big.value.clone());
+    let number = match &big {
+        Some(value) => value.to_i128().map(Exact::Integer),
+        None => Exact::from_object(&*argument.borrow(), "format").ok(),
+    };
+    let numeric = number.is_some() || big.is_some();
+    let text = match (spec.radix, spec.precision, number) {
+        (Some(radix), _, Some(Exact::Integer(value))) => {
+            let magnitude = value.unsigned_abs();
+            let (digits, prefix) = match radix {
+                'x' => (format!("{:x}", magnitude), "0x"),
+                'X' => (format!("{:X}", magnitude), "0x"),
+                'o' => (format!("{:o}", magnitude), "0o"),
+                _ => (format!("{:b}", magnitude), "0b"),
+            };
+            let sign = if value < 0 { "-" } else { "" };
+            format!("{}{}{}", sign, if spec.prefix { prefix } else { "" }, digits)
+        }
+        (Some(_), _, _) => return Err(Fault::InvalidType("format: a radix needs an integer that fits in an i128".to_string())),
+        (None, Some(precision), Some(Exact::Float(value))) => format!("{:.*}", precision, value),
+        (None, Some(precision), Some(exact)) => exact.to_decimal(precision as u32)?.to_string(),
+        (None, Some(0), None) if numeric => to_text(argument)?,
+        (None, Some(precision), None) if numeric => format!("{}.{}", to_text(argument)?, "0".repeat(precision)),
+        (None, Some(precision), None) => to_text(argument)?.chars().take(precision).collect(),
+        (None, None, _) => to_text(argument)?,
+    };
+    let (sign, digits) = match text.strip_prefix('-') {
+        Some(digits) if numeric => ("-", digits.to_string()),
+        _ if numeric && spec.sign => ("+", text),
+        _ => ("", text),
+    };
+    let length = sign.chars().count() + digits.chars().count();
+    let padding = spec.width.saturating_sub(length);
+    if spec.zero && numeric {
+        // Zeros go after the sign and the radix prefix
+        let split = if spec.prefix && spec.radix.is_some() { 2 } else { 0 };
+        return Ok(format!("{}{}{}{}", sign, &digits[..split], "0".repeat(padding), &digits[split..]));
+    }
+    let default = if numeric { Align::Right } else { Align::Left };
+    let (before, after) = match spec.align.unwrap_or(default) {
+        Align::Left => (0, padding),
+        Align::Center => (padding / 2, padding - padding / 2),
+        Align::Right => (padding, 0),
+    };
+    let fill = |count: usize| spec.fill.to_string().repeat(count);
+    Ok(format!("{}{}{}{}", fill(before), sign, digits, fill(after)))
+}
+
+/// Put the arguments into a template
+pub fn format(template: &str, arguments: &[ObjectBox]) -> Result<String, Fault> {
+    let mut output = String::with_capacity(template.len());
+    let mut next = 0;
+    let mut chars = template.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                output.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                output.push('}');
+            }
+            '}' => return Err(bad_template(format!("unmatched }} in {:?}", template))),
+            '{' => {
+                let mut placeholder = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => placeholder.push(c),
+                        None => return Err(bad_template(format!("unclosed {{ in {:?}", template))),
+                    }
+                }
+                let (index, spec) = placeholder.split_once(':').unwrap_or((&placeholder, ""));
+                let index = if index.is_empty() {
+                    next += 1;
+                    next - 1
+                } else {
+                    index.trim().parse().map_err(|_| bad_template(format!("{:?} isn't an argument index", index)))?
+                };
+                let argument = arguments.get(index)
+                    .ok_or_else(|| bad_template(format!("there is no argument {}, there are {}", index, arguments.len())))?;
+                output.push_str(&format_argument(argument, &Spec::parse(spec)?)?);
+            }
+            c => output.push(c),
+        }
+    }
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::primitive::bigint::BigInt;
+    use super::super::primitive::rational::Rational;
+    use super::super::{create_bigint, create_character, create_f64, create_i64, create_rational, create_string, create_u8};
+
+    fn format_with(template: &str, arguments: Vec<ObjectBox>) -> Result<String, Fault> {
+        format(template, &arguments)
+    }
+
+    #[test]
+    fn placeholders_take_arguments_in_order_or_by_index() {
+        let arguments = || vec![create_string("a".to_string()), create_i64(2), create_character('c')];
+        assert_eq!(format_with("{} {} {}", arguments()).unwrap(), "a 2 c");
+        assert_eq!(format_with("{2}{0}{2}", arguments()).unwrap(), "cac");
+        assert_eq!(format_with("{{{}}}", arguments()).unwrap(), "{a}");
+        assert_eq!(format_with("{}", vec![Nil::new()]).unwrap(), "nil");
+    }
+
+    #[test]
+    fn width_fill_and_alignment() {
+        assert_eq!(format_with("[{:5}]", vec![create_string("ab".to_string())]).unwrap(), "[ab   ]");
+        assert_eq!(format_with("[{:5}]", vec![create_i64(42)]).unwrap(), "[   42]");
+        assert_eq!(format_with("[{:*^6}]", vec![create_string("ab".to_string())]).unwrap(), "[**ab**]");
+        assert_eq!(format_with("[{:<4}]", vec![create_i64(7)]).unwrap(), "[7   ]");
+        assert_eq!(format_with("[{:3}]", vec![create_string("ü".to_string())]).unwrap(), "[ü  ]");
+    }
+
+    #[test]
+    fn numbers_take_signs_zeros_and_radixes() {
+        assert_eq!(format_with("{:+}", vec![create_i64(5)]).unwrap(), "+5");
+        assert_eq!(format_with("{:05}", vec![create_i64(-42)]).unwrap(), "-0042");
+        assert_eq!(format_with("{:x} {:X} {:o} {:b}", vec![create_i64(255), create_i64(255), create_u8(8), create_i64(5)]).unwrap(), "ff FF 10 101");
+        assert_eq!(format_with("{:#06x}", vec![create_i64(255)]).unwrap(), "0x00ff");
+        assert_eq!(format_with("{:#b}", vec![create_i64(-2)]).unwrap(), "-0b10");
+        assert!(format_with("{:x}", vec![create_f64(1.5)]).is_err());
+    }
+
+    #[test]
+    fn precision_is_decimals_or_chars() {
+        assert_eq!(format_with("{:.2}", vec![create_f64(1.23456)]).unwrap(), "1.23");
+        assert_eq!(format_with("{:.3}", vec![create_rational(Rational::new(1, 3).unwrap())]).unwrap(), "0.333");
+        assert_eq!(format_with("{:8.1}", vec![create_i64(-3)]).unwrap(), "    -3.0");
+        assert_eq!(format_with("{:.3}", vec![create_string("abcdef".to_string())]).unwrap(), "abc");
+        let big = BigInt::from(10).pow(40);
+        assert_eq!(format_with("{:.2}", vec![create_bigint(big.clone())]).unwrap(), format!("{}.00", big));
+    }
+
+    #[test]
+    fn bad_templates_fault() {
+        assert!(matches!(format_with("{", Vec::new()), Err(Fault::InvalidOperation(_))));
+        assert!(matches!(format_with("}", Vec::new()), Err(Fault::InvalidOperation(_))));
+        assert!(matches!(format_with("{}", Vec::new()), Err(Fault::InvalidOperation(_))));
+        assert!(matches!(format_with("{x}", vec![create_i64(1)]), Err(Fault::InvalidOperation(_))));
+        assert!(matches!(format_with("{:.}", vec![create_i64(1)]), Err(Fault::InvalidOperation(_))));
+        assert!(matches!(format_with("{:q}", vec![create_i64(1)]), Err(Fault::InvalidOperation(_))));
+    }
+
+    #[test]
+    fn strings_format_and_loggers_printf() {
+        let template = create_string("{}-{}".to_string());
+        let method = template.borrow().lookup_method(Symbol::from("format")).unwrap();
+        let arguments = super::super::create_vector(vec![create_i64(1), create_i64(2)]);
+        let text = method.evaluate(template.clone(), vec![arguments]).unwrap().unwrap();
+        assert_eq!(text.borrow().downcast_ref::<StringObject>().unwrap().value, "1-2");
+
+        let logger = super::super::create_logger();
+        let method = logger.borrow().lookup_method(Symbol::from("printf")).unwrap();
+        let bad = create_string("{".to_string());
+        assert!(method.evaluate(logger.clone(), vec![bad, super::super::create_vector(Vec::new())]).is_err());
+        assert!(method.evaluate(logger.clone(), vec![template, create_i64(1)]).is_err());
+    }
+}
@@ -0,0 +1,487 @@
+//! Cycle collector for ObjectBox graphs.
+//!
+//! Objects are reference counted so anything that isn't part of a cycle is freed as soon as its
+//! last reference goes away. This module finds the cycles that reference counting misses by
+//! trial deletion: every reference held from inside the heap is subtracted from an object's
+//! strong count and whatever is left must come from outside of it (a ContextData stack, its
+//! arguments, the class registry or a Rust local). Those objects and everything reachable from
+//! them are live and the rest is garbage.
+//!
+//! Every thread collects the objects it creates in a buffer of its own so that allocating doesn't
+//! contend on a global lock. The collector moves the buffers into the heap registry when it runs.
+//!
+//! Objects can also ask to be finalized. The registry of those objects holds a reference to each
+//! of them so that the object is still around when its last other reference goes away. At that
+//! point it is handed to the scheduler which sends it the finalize message in a new task.
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+
+use lazy_static::lazy_static;
+use log::info;
+
+use super::{Object, ObjectBox};
+
+/// The smallest number of tracked objects before we bother pruning or collecting.
+const MIN_THRESHOLD: usize = 1024;
+
+lazy_static! {
+    static ref HEAP: Mutex<Heap> = Mutex::new(Heap::new());
+    /// The registration buffer of every thread that has created an object
+    static ref BUFFERS: Mutex<Vec<Arc<Mutex<Buffer>>>> = Mutex::new(Vec::new());
+}
+
+thread_local! {
+    static BUFFER: Arc<Mutex<Buffer>> = {
+        let buffer = Arc::new(Mutex::new(Buffer::new()));
+        BUFFERS.lock().expect("gc::BUFFER: lock poisoned").push(buffer.clone());
+        buffer
+    };
+}
+
+static COLLECTION_REQUESTED: AtomicBool = AtomicBool::new(false);
+/// The amount of objects created since the last collection
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+/// When ALLOCATED gets this big we ask for a collection
+static COLLECT_THRESHOLD: AtomicUsize = AtomicUsize::new(MIN_THRESHOLD * 64);
+
+/// Heap
+/// Every object created through ObjectBox::new is tracked here so that the collector can find
+/// objects that are only reachable from each other.
+struct Heap {
+    objects: Vec<Weak<Mutex<dyn Object>>>,
+    /// The total amount of objects freed by the collector
+    freed: usize,
+    /// The objects that get sent the finalize message when nothing else refers to them, by address
+    finalizable: HashMap<usize, ObjectBox>,
+    /// The objects that the collector found to be garbage that are waiting to be finalized
+    ready: Vec<ObjectBox>,
+}
+
+impl Heap {
+    fn new() -> Heap {
+        Heap {
+            objects: Vec::new(),
+            freed: 0,
+            finalizable: HashMap::new(),
+            ready: Vec::new(),
+        }
+    }
+
+    fn prune(&mut self) {
+        self.objects.retain(|object| object.strong_count() > 0);
+    }
+
+    fn register_finalizer(&mut self, object: &ObjectBox) {
+        self.finalizable.entry(object.as_ptr() as usize).or_insert_with(|| object.clone());
+    }
+
+    fn take_finalizable(&mut self) -> Vec<ObjectBox> {
+        let mut ready = std::mem::take(&mut self.ready);
+        let (unreachable, reachable): (HashMap<_, _>, HashMap<_, _>) = self.finalizable.drain()
+            .partition(|(_, object)| Arc::strong_count(&object.data) == 1);
+        self.finalizable = reachable;
+        ready.extend(unreachable.into_values());
+        ready
+    }
+
+    /// Free every object in the registry that is only reachable through a cycle
+    fn collect(&mut self) -> CollectionStats {
+        self.prune();
+        let objects: Vec<ObjectBox> = self.objects.iter()
+            .filter_map(|object| object.upgrade())
+            .map(|data| ObjectBox { data })
+            .collect();
+        let finalizable: Vec<usize> = self.finalizable.keys().copied().collect();
+        let indices: HashMap<usize, usize> = objects.iter()
+            .enumerate()
+            .map(|(i, object)| (object.as_ptr() as usize, i))
+            .collect();
+
+        // Every count is one higher than it should be because of the upgrade above
+        let mut external: Vec<usize> = objects.iter()
+            .map(|object| Arc::strong_count(&object.data) - 1)
+            .collect();
+
+        // An object that is locked can't be inspected so we leave its children's counts alone.
+        // That keeps them alive since the reference will look like it comes from outside the heap.
+        let mut edges: Vec<Option<Vec<usize>>> = Vec::with_capacity(objects.len());
+        for object in objects.iter() {
+            match object.data.try_lock() {
+                Ok(object) => {
+                    let children = object.children().iter()
+                        .filter_map(|child| indices.get(&(child.as_ptr() as usize)).copied())
+                        .collect();
+                    edges.push(Some(children));
+                }
+                Err(_) => edges.push(None),
+            }
+        }
+        for children in edges.iter().flatten() {
+            for &child in children {
+                external[child] = external[child].saturating_sub(1);
+            }
+        }
+        // The reference from the registry doesn't count either or these would never be garbage
+        let finalizable: Vec<usize> = finalizable.iter()
+            .filter_map(|object| indices.get(object).copied())
+            .collect();
+        for &i in finalizable.iter() {
+            external[i] = external[i].saturating_sub(1);
+        }
+
+        let mut live = vec![false; objects.len()];
+        let worklist: Vec<usize> = (0..objects.len())
+            .filter(|&i| external[i] > 0 || edges[i].is_none())
+            .collect();
+        for &i in worklist.iter() {
+            live[i] = true;
+        }
+        mark(&mut live, &edges, worklist);
+
+        // Garbage that is waiting to be finalized survives until its finalizer has run and so does
+        // everything that it refers to
+        let resurrected: Vec<usize> = finalizable.into_iter()
+            .filter(|&i| !live[i])
+            .collect();
+        for &i in resurrected.iter() {
+            live[i] = true;
+        }
+        mark(&mut live, &edges, resurrected.clone());
+
+        let garbage: Vec<ObjectBox> = objects.iter()
+            .zip(live.iter())
+            .filter(|(_, live)| !**live)
+            .map(|(object, _)| object.clone())
+            .collect();
+        for object in garbage.iter() {
+            if let Ok(mut object) = object.data.try_lock() {
+                object.clear_children();
+            }
+        }
+
+        let resurrected: Vec<usize> = resurrected.iter().map(|&i| objects[i].as_ptr() as usize).collect();
+        let stats = CollectionStats {
+            scanned: objects.len(),
+            live: objects.len() - garbage.len(),
+            freed: garbage.len(),
+        };
+        drop(garbage);
+        drop(objects);
+
+        for object in resurrected {
+            if let Some(object) = self.finalizable.remove(&object) {
+                self.ready.push(object);
+            }
+        }
+        self.freed += stats.freed;
+        self.prune();
+        stats
+    }
+
+    /// Move the objects that the threads of these buffers have registered since the last drain
+    /// into the registry
+    fn drain(&mut self, buffers: &mut Vec<Arc<Mutex<Buffer>>>) {
+        for buffer in buffers.iter() {
+            let mut buffer = buffer.lock().expect("gc::drain: lock poisoned");
+            self.objects.append(&mut buffer.objects);
+            buffer.prune_threshold = MIN_THRESHOLD;
+        }
+        // Only the list refers to the buffers of threads that have exited and those are empty now
+        buffers.retain(|buffer| Arc::strong_count(buffer) > 1);
+    }
+}
+
+/// Buffer
+/// The objects that one thread has created since the collector last looked.
+struct Buffer {
+    objects: Vec<Weak<Mutex<dyn Object>>>,
+    /// When the buffer grows past this we drop the entries of dead objects
+    prune_threshold: usize,
+}
+
+impl Buffer {
+    fn new() -> Buffer {
+        Buffer {
+            objects: Vec::new(),
+            prune_threshold: MIN_THRESHOLD,
+        }
+    }
+
+    fn push(&mut self, object: Weak<Mutex<dyn Object>>) {
+        self.objects.push(object);
+        if self.objects.len() >= self.prune_threshold {
+            self.objects.retain(|object| object.strong_count() > 0);
+            self.prune_threshold = (self.objects.len() * 2).max(MIN_THRESHOLD);
+        }
+    }
+}
+
+/// CollectionStats
+/// The statistics of a single run of the cycle collector.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CollectionStats {
+    /// The amount of objects that were alive when the collection started
+    pub scanned: usize,
+    /// The amount of objects that survived
+    pub live: usize,
+    /// The amount of objects that were only reachable through cycles
+    pub freed: usize,
+}
+
+impl std::fmt::Display for CollectionStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "scanned {} objects, {} live, {} freed", self.scanned, self.live, self.freed)
+    }
+}
+
+/// Start tracking an object
+/// This gets called by ObjectBox::new so there should be no need to call it anywhere else.
+pub(crate) fn register(object: &ObjectBox) {
+    let mut weak = Some(Arc::downgrade(&object.data));
+    let buffered = BUFFER.try_with(|buffer| {
+        buffer.lock().expect("gc::register: lock poisoned").push(weak.take().unwrap());
+    });
+    if buffered.is_err() {
+        // The thread is exiting and its buffer is already gone
+        HEAP.lock().expect("gc::register: lock poisoned").objects.extend(weak);
+    }
+    if ALLOCATED.fetch_add(1, Ordering::Relaxed) + 1 >= COLLECT_THRESHOLD.load(Ordering::Relaxed) {
+        COLLECTION_REQUESTED.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Ask for a collection to be run the next time the scheduler stops the world.
+pub fn request_collection() {
+    COLLECTION_REQUESTED.store(true, Ordering::Relaxed);
+}
+
+/// Check if a collection has been requested either explicitly or because the heap grew.
+pub fn collection_requested() -> bool {
+    COLLECTION_REQUESTED.load(Ordering::Relaxed)
+}
+
+/// Send the finalize message to an object once nothing else refers to it.
+/// The message is only sent once, an object has to register again if it wants to be finalized
+/// again.
+pub fn register_finalizer(object: &ObjectBox) {
+    HEAP.lock().expect("gc::register_finalizer: lock poisoned").register_finalizer(object);
+}
+
+/// Take the objects that need to be finalized
+/// These are the objects that only the registry refers to and the ones that the collector found in
+/// garbage cycles. Like collect_cycles this must only be called while no interpreter is running.
+pub fn take_finalizable() -> Vec<ObjectBox> {
+    HEAP.lock().expect("gc::take_finalizable: lock poisoned").take_finalizable()
+}
+
+/// The objects that are registered to be finalized including the ones waiting for their finalizer
+pub fn finalizable_objects() -> Vec<ObjectBox> {
+    let heap = HEAP.lock().expect("gc::finalizable_objects: lock poisoned");
+    heap.finalizable.values().chain(heap.ready.iter()).cloned().collect()
+}
+
+/// The amount of objects that are currently alive
+pub fn heap_size() -> usize {
+    let mut heap = HEAP.lock().expect("gc::heap_size: lock poisoned");
+    heap.drain(&mut BUFFERS.lock().expect("gc::heap_size: lock poisoned"));
+    heap.prune();
+    heap.objects.len()
+}
+
+/// The total amount of objects that the collector has freed so far
+pub fn total_freed() -> usize {
+    HEAP.lock().expect("gc::total_freed: lock poisoned").freed
+}
+
+/// Free every object that is only reachable through a cycle.
+/// This must only be called while no interpreter is running since the object graph must not
+/// change while it is being scanned. The scheduler does this while it holds every core's lock.
+pub fn collect_cycles() -> CollectionStats {
+    COLLECTION_REQUESTED.store(false, Ordering::Relaxed);
+    let mut heap = HEAP.lock().expect("gc::collect_cycles: lock poisoned");
+    heap.drain(&mut BUFFERS.lock().expect("gc::collect_cycles: lock poisoned"));
+    let stats = heap.collect();
+    ALLOCATED.store(0, Ordering::Relaxed);
+    COLLECT_THRESHOLD.store(heap.objects.len().max(MIN_THRESHOLD * 64), Ordering::Relaxed);
+    drop(heap);
+
+    info!("cycle collection: {}", stats);
+    stats
+}
+
+/// Mark everything reachable from the worklist as live
+fn mark(live: &mut [bool], edges: &[Option<Vec<usize>>], mut worklist: Vec<usize>) {
+    while let Some(i) = worklist.pop() {
+        if let Some(children) = &edges[i] {
+            for &child in children {
+                if !live[child] {
+                    live[child] = true;
+                    worklist.push(child);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::object::vector::VectorObject;
+    use crate::object::create_base_object;
+
+    // Every test collects a heap of its own so that it never scans the objects of the tests
+    // that run next to it
+
+    /// An empty vector, create_vector would want its size as an argument
+    fn create_vector(elements: Vec<ObjectBox>) -> ObjectBox {
+        VectorObject::make_object(create_base_object(), elements.into_boxed_slice())
+    }
+
+    fn track(heap: &mut Heap, objects: &[&ObjectBox]) {
+        heap.objects.extend(objects.iter().map(|object| Arc::downgrade(&object.data)));
+    }
+
+    fn link(from: &ObjectBox, to: &ObjectBox) {
+        let mut from = from.borrow_mut();
+        let vector = from.downcast_mut::<VectorObject>().unwrap();
+        let mut elements = std::mem::take(&mut vector.value).into_vec();
+        elements.push(to.clone());
+        vector.value = elements.into_boxed_slice();
+    }
+
+    fn cycle(heap: &mut Heap) -> (ObjectBox, ObjectBox) {
+        let first = create_vector(Vec::new());
+        let second = create_vector(Vec::new());
+        link(&first, &second);
+        link(&second, &first);
+        track(heap, &[&first, &second]);
+        (first, second)
+    }
+
+    #[test]
+    fn unreachable_cycle_is_freed() {
+        let mut heap = Heap::new();
+        let (first, second) = cycle(&mut heap);
+        let (first_weak, second_weak) = (Arc::downgrade(&first.data), Arc::downgrade(&second.data));
+        drop((first, second));
+        assert!(first_weak.upgrade().is_some());
+        let stats = heap.collect();
+        assert_eq!(stats.freed, 2);
+        assert_eq!(heap.freed, 2);
+        assert!(first_weak.upgrade().is_none());
+        assert!(second_weak.upgrade().is_none());
+        assert!(heap.objects.is_empty());
+    }
+
+    #[test]
+    fn rooted_cycle_is_kept() {
+        let mut heap = Heap::new();
+        let (first, second) = cycle(&mut heap);
+        let second_weak = Arc::downgrade(&second.data);
+        drop(second);
+        let stats = heap.collect();
+        assert_eq!((stats.scanned, stats.live, stats.freed), (2, 2, 0));
+        let second = second_weak.upgrade().map(|data| ObjectBox { data }).expect("cycle rooted by a local was freed");
+        assert_eq!(first.borrow().downcast_ref::<VectorObject>().unwrap().value.len(), 1);
+        assert_eq!(second.borrow().downcast_ref::<VectorObject>().unwrap().value.len(), 1);
+    }
+
+    #[test]
+    fn cycle_reachable_from_a_root_is_kept() {
+        let mut heap = Heap::new();
+        let root = create_vector(Vec::new());
+        track(&mut heap, &[&root]);
+        let (first, second) = cycle(&mut heap);
+        link(&root, &first);
+        let first_weak = Arc::downgrade(&first.data);
+        drop((first, second));
+        heap.collect();
+        assert!(first_weak.upgrade().is_some());
+        drop(root);
+        assert_eq!(heap.collect().freed, 2);
+        assert!(first_weak.upgrade().is_none());
+    }
+
+    #[test]
+    fn weak_refs_dont_keep_a_cycle_alive() {
+        let mut heap = Heap::new();
+        let (first, second) = cycle(&mut heap);
+        let weak = crate::object::create_weak_ref(&first);
+        link(&second, &weak);
+        track(&mut heap, &[&weak]);
+        let first_weak = first.downgrade();
+        drop((first, second, weak));
+        assert_eq!(heap.collect().freed, 3);
+        assert!(first_weak.upgrade().is_none());
+    }
+
+    #[test]
+    fn locked_objects_are_kept() {
+        let mut heap = Heap::new();
+        let (first, second) = cycle(&mut heap);
+        let (first_weak, second_weak) = (Arc::downgrade(&first.data), Arc::downgrade(&second.data));
+        // SAFETY: second keeps first alive until the cycle is collected, which can only happen
+        // after the lock below is released
+        let mutex = unsafe { &*Arc::as_ptr(&first.data) };
+        drop((first, second));
+        let lock = mutex.lock().unwrap();
+        heap.collect();
+        drop(lock);
+        assert!(first_weak.upgrade().is_some());
+        assert!(second_weak.upgrade().is_some());
+        heap.collect();
+        assert!(first_weak.upgrade().is_none());
+        assert!(second_weak.upgrade().is_none());
+    }
+
+    #[test]
+    fn objects_from_other_threads_are_drained() {
+        let mut heap = Heap::new();
+        let buffer = std::thread::spawn(|| {
+            cycle(&mut Heap::new());
+            BUFFER.with(|buffer| buffer.clone())
+        }).join().unwrap();
+        heap.drain(&mut vec![buffer]);
+        let stats = heap.collect();
+        assert!(stats.freed >= 2);
+        assert_eq!(stats.live, 0);
+        assert!(heap.objects.is_empty());
+    }
+
+    #[test]
+    fn garbage_is_finalized_once() {
+        let mut heap = Heap::new();
+        let (first, second) = cycle(&mut heap);
+        let (first_weak, second_weak) = (first.downgrade(), second.downgrade());
+        heap.register_finalizer(&first);
+        heap.register_finalizer(&first);
+        drop((first, second));
+        // The cycle is kept alive until its finalizer has run
+        assert_eq!(heap.collect().freed, 0);
+        let ready = heap.take_finalizable();
+        assert_eq!(ready.len(), 1);
+        assert_eq!(ready[0].as_ptr(), first_weak.upgrade().unwrap().as_ptr());
+        assert!(heap.finalizable.is_empty());
+        drop(ready);
+        assert_eq!(heap.collect().freed, 2);
+        assert!(second_weak.upgrade().is_none());
+        assert!(heap.take_finalizable().is_empty());
+    }
+
+    #[test]
+    fn objects_only_the_finalizer_table_refers_to_are_finalized() {
+        let mut heap = Heap::new();
+        let object = create_vector(Vec::new());
+        let kept = create_vector(Vec::new());
+        heap.register_finalizer(&object);
+        heap.register_finalizer(&kept);
+        let weak = object.downgrade();
+        drop(object);
+        let ready = heap.take_finalizable();
+        assert_eq!(ready.len(), 1);
+        assert_eq!(ready[0].as_ptr(), weak.upgrade().unwrap().as_ptr());
+        assert_eq!(heap.finalizable.len(), 1);
+        assert!(heap.finalizable.contains_key(&(kept.as_ptr() as usize)));
+    }
+}
@@ -0,0 +1,460 @@
+//! Iterators
+//! A collection gives an iterator when it is sent iterator. An iterator is any object that
+//! understands has_next and next, so an object of a class can be one too, and it gives itself when
+//! it is sent iterator.
+//! The builtin Iterator adds lazy combinators that wrap it in another Iterator and only pull an
+//! element when one is asked for, and terminal operations that run the pipeline to the end.
+//! Blocks given to the combinators get the element as their argument.
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use super::block::Block;
+use super::primitive::exact::integer_argument;
+use super::range::{Kind, Range};
+use super::string::StringObject;
+use super::symbol::Symbol;
+use super::value::Immediate;
+use super::{ContextData, Fault, Method, Nil, Object, ObjectBox, VTable};
+
+
+/// Source
+/// Where an Iterator gets its elements from
+#[derive(Clone)]
+pub enum Source {
+    /// The fields of a Vector or a List, read when they are reached
+    Fields { collection: ObjectBox, index: usize },
+    /// The chars of a String from a byte offset
+    Chars { string: ObjectBox, offset: usize },
+    /// A copy of the elements of a collection that can't be read in place
+    Elements { elements: Vec<ObjectBox>, index: usize },
+    /// The elements of a Range, made when they are reached
+    Range { range: Range, index: u128 },
+    Map { inner: ObjectBox, block: ObjectBox },
+    Filter { inner: ObjectBox, block: ObjectBox },
+    Take { inner: ObjectBox, remaining: usize },
+    Skip { inner: ObjectBox, remaining: usize },
+    Zip { first: ObjectBox, second: ObjectBox },
+    Enumerate { inner: ObjectBox, index: u64 },
+    Chain { first: ObjectBox, second: ObjectBox },
+    FlatMap { inner: ObjectBox, block: ObjectBox, current: Option<ObjectBox> },
+}
+
+pub struct IteratorObject {
+    super_object: Option<ObjectBox>,
+    vtable: VTable,
+    pub source: Source,
+    /// The element that has_next pulled ahead, None when it hasn't looked
+    pub peeked: Option<Option<ObjectBox>>,
+}
+
+impl IteratorObject {
+    pub fn make_object(parent: ObjectBox, source: Source) -> ObjectBox {
+        ObjectBox::new(IteratorObject { super_object: Some(parent), vtable: VTable::new_empty(), source, peeked: None })
+    }
+    pub fn make_vtable() -> VTable {
+        let mut methods = HashMap::new();
+        methods.insert(Symbol::from("has_next"), Arc::new(Method::RustMethod { fun: Box::new(iterator_has_next) }));
+        methods.insert(Symbol::from("next"), Arc::new(Method::RustMethod { fun: Box::new(iterator_next) }));
+        methods.insert(Symbol::from("iterator"), Arc::new(Method::RustMethod { fun: Box::new(iterator_iterator) }));
+        methods.insert(Symbol::from("map"), Arc::new(Method::RustMethod { fun: Box::new(iterator_map) }));
+        methods.insert(Symbol::from("filter"), Arc::new(Method::RustMethod { fun: Box::new(iterator_filter) }));
+        methods.insert(Symbol::from("take"), Arc::new(Method::RustMethod { fun: Box::new(iterator_take) }));
+        methods.insert(Symbol::from("skip"), Arc::new(Method::RustMethod { fun: Box::new(iterator_skip) }));
+        methods.insert(Symbol::from("zip"), Arc::new(Method::RustMethod { fun: Box::new(iterator_zip) }));
+        methods.insert(Symbol::from("enumerate"), Arc::new(Method::RustMethod { fun: Box::new(iterator_enumerate) }));
+        methods.insert(Symbol::from("chain"), Arc::new(Method::RustMethod { fun: Box::new(iterator_chain) }));
+        methods.insert(Symbol::from("flat_map"), Arc::new(Method::RustMethod { fun: Box::new(iterator_flat_map) }));
+        methods.insert(Symbol::from("collect"), Arc::new(Method::RustMethod { fun: Box::new(iterator_collect) }));
+        methods.insert(Symbol::from("fold"), Arc::new(Method::RustMethod { fun: Box::new(iterator_fold) }));
+        methods.insert(Symbol::from("any"), Arc::new(Method::RustMethod { fun: Box::new(iterator_any) }));
+        methods.insert(Symbol::from("all"), Arc::new(Method::RustMethod { fun: Box::new(iterator_all) }));
+        methods.insert(Symbol::from("find"), Arc::new(Method::RustMethod { fun: Box::new(iterator_find) }));
+        methods.insert(Symbol::from("count"), Arc::new(Method::RustMethod { fun: Box::new(iterator_count) }));
+        methods.insert(Symbol::from("sum"), Arc::new(Method::RustMethod { fun: Box::new(iterator_sum) }));
+        VTable::new(methods)
+    }
+}
+
+impl Object for IteratorObject {
+    fn get_vtable(&self) -> &VTable {
+        &self.vtable
+    }
+    fn get_super_object(&self) -> Option<ObjectBox> {
+        self.super_object.clone()
+    }
+    fn get_field(&self, _index: usize) -> Option<ObjectBox> {
+        panic!("Iterator does not have fields");
+    }
+    fn set_field(&mut self, _index: usize, _value: ObjectBox) {
+        panic!("Iterator does not have fields");
+    }
+    fn size(&self) -> Option<usize> {
+        None
+    }
+    fn duplicate(&self) -> ObjectBox {
+        // The copy starts at the same position but shares the iterators it wraps, so stepping
+        // one of two copies of a combinator also moves the other
+        let iterator = ObjectBox::new(IteratorObject {
+            super_object: self.super_object.clone(),
+            vtable: VTable::new_empty(),
+            source: self.source.clone(),
+            peeked: self.peeked.clone(),
+        });
+        let mut iterator_mut = iterator.borrow_mut();
+        iterator_mut.initialize(Vec::new(), self.vtable.clone());
+        drop(iterator_mut);
+        iterator
+    }
+    fn initialize(&mut self, _: Vec<ObjectBox>, vtable: VTable) {
+        self.vtable.extend(vtable);
+    }
+    fn children(&self) -> Vec<ObjectBox> {
+        let mut children = match &self.source {
+            Source::Fields { collection, .. } => vec![collection.clone()],
+            Source::Chars { string, .. } => vec![string.clone()],
+            Source::Elements { elements, .. } => elements.clone(),
+            Source::Range { .. } => Vec::new(),
+            Source::Map { inner, block } | Source::Filter { inner, block } => vec![inner.clone(), block.clone()],
+            Source::Take { inner, .. } | Source::Skip { inner, .. } | Source::Enumerate { inner, .. } => vec![inner.clone()],
+            Source::Zip { first, second } | Source::Chain { first, second } => vec![first.clone(), second.clone()],
+            Source::FlatMap { inner, block, current } => vec![inner.clone(), block.clone()].into_iter().chain(current.clone()).collect(),
+        };
+        children.extend(self.peeked.clone().flatten());
+        children.extend(self.super_object.clone());
+        children
+    }
+    fn clear_children(&mut self) {
+        self.source = Source::Elements { elements: Vec::new(), index: 0 };
+        self.peeked = None;
+    }
+}
+
+/// Make an iterator over the elements of a Vector, a List or a String that reads them in place
+pub fn over_fields(collection: ObjectBox) -> ObjectBox {
+    super::create_iterator(Source::Fields { collection, index: 0 })
+}
+
+pub fn over_chars(string: ObjectBox) -> ObjectBox {
+    super::create_iterator(Source::Chars { string, offset: 0 })
+}
+
+pub fn over_elements(elements: Vec<ObjectBox>) -> ObjectBox {
+    super::create_iterator(Source::Elements { elements, index: 0 })
+}
+
+/// Get an iterator for an object by sending it iterator
+pub fn iterator_of(object: &ObjectBox, name: &str) -> Result<ObjectBox, Fault> {
+    if object.borrow().is::<IteratorObject>() {
+        return Ok(object.clone());
+    }
+    let method = object.borrow().lookup_method(Symbol::from("iterator"))
+        .ok_or(Fault::MethodNotFound(format!("Iterator {}: the argument has no iterator", name)))?;
+    method.evaluate(object.clone(), Vec::new())?
+        .ok_or(Fault::InvalidOperation(format!("Iterator {}: iterator didn't give a result", name)))
+}
+
+fn send(object: &ObjectBox, selector: &str, arguments: Vec<ObjectBox>) -> Result<ObjectBox, Fault> {
+    let method = object.borrow().lookup_method(Symbol::from(selector))
+        .ok_or(Fault::MethodNotFound(format!("Iterator: {}", selector)))?;
+    method.evaluate(object.clone(), arguments)?
+        .ok_or(Fault::InvalidOperation(format!("Iterator: {} didn't give a result", selector)))
+}
+
+fn boolean(object: &ObjectBox, name: &str) -> Result<bool, Fault> {
+    match Immediate::from_object(&*object.borrow()) {
+        Some(Immediate::Boolean(value)) => Ok(value),
+        _ => Err(Fault::InvalidType(format!("Iterator {}: expected a Boolean", name))),
+    }
+}
+
+/// Evaluate a block with an element and get what it gives
+fn apply(block: &ObjectBox, arguments: Vec<ObjectBox>, name: &str) -> Result<ObjectBox, Fault> {
+    Block::evaluate(block, None, arguments)?
+        .ok_or(Fault::InvalidOperation(format!("Iterator {}: the block didn't give a result", name)))
+}
+
+/// Get the next element of any iterator, None when it is done
+/// A builtin Iterator is stepped directly, anything else is sent has_next and next.
+pub fn step(iterator: &ObjectBox) -> Result<Option<ObjectBox>, Fault> {
+    let peeked = {
+        let mut object = iterator.borrow_mut();
+        match object.downcast_mut::<IteratorObject>() {
+            Some(object) => Some(object.peeked.take()),
+            None => None,
+        }
+    };
+    match peeked {
+        Some(Some(element)) => Ok(element),
+        Some(None) => pull(iterator),
+        None => {
+            if !boolean(&send(iterator, "has_next", Vec::new())?, "has_next")? {
+                return Ok(None);
+            }
+            send(iterator, "next", Vec::new()).map(Some)
+        }
+    }
+}
+
+fn with_source<T>(object: &ObjectBox, fun: impl FnOnce(&mut Source) -> T) -> T {
+    let mut object = object.borrow_mut();
+    fun(&mut object.downcast_mut::<IteratorObject>().expect("pull needs an Iterator").source)
+}
+
+/// Work out the next element of a builtin Iterator
+/// The iterator is only borrowed between steps because the blocks and the inner iterators can run
+/// any code.
+fn pull(iterator_object: &ObjectBox) -> Result<Option<ObjectBox>, Fault> {
+    enum Step {
+        Done(Option<ObjectBox>),
+        Make(Kind, i128),
+        Map(ObjectBox, ObjectBox),
+        Filter(ObjectBox, ObjectBox),
+        Inner(ObjectBox),
+        Skip(ObjectBox, usize),
+        Zip(ObjectBox, ObjectBox),
+        Enumerate(ObjectBox, u64),
+        Chain(ObjectBox, ObjectBox),
+        FlatMap(ObjectBox, ObjectBox, Option<ObjectBox>),
+    }
+    let next = with_source(iterator_object, |source| match source {
+        Source::Fields { collection, index } => {
+            let element = collection.borrow().get_field(*index);
+            *index += element.is_some() as usize;
+            Step::Done(element)
+        }
+        Source::Chars { string, offset } => {
+            let c = string.borrow().downcast_ref::<StringObject>().and_then(|string| string.value.get(*offset..)?.chars().next());
+            *offset += c.map_or(0, char::len_utf8);
+            Step::Done(c.map(super::create_character))
+        }
+        Source::Elements { elements, index } => {
+            let element = elements.get(*index).cloned();
+            *index += element.is_some() as usize;
+            Step::Done(element)
+        }
+        Source::Range { range, index } => match range.get(*index) {
+            Some(value) => {
+                *index += 1;
+                Step::Make(range.kind, value)
+            }
+            None => Step::Done(None),
+        },
+        Source::Map { inner, block } => Step::Map(inner.clone(), block.clone()),
+        Source::Filter { inner, block } => Step::Filter(inner.clone(), block.clone()),
+        Source::Take { remaining: 0, .. } => Step::Done(None),
+        Source::Take { inner, remaining } => {
+            *remaining -= 1;
+            Step::Inner(inner.clone())
+        }
+        Source::Skip { inner, remaining } => Step::Skip(inner.clone(), std::mem::take(remaining)),
+        Source::Zip { first, second } => Step::Zip(first.clone(), second.clone()),
+        Source::Enumerate { inner, index } => Step::Enumerate(inner.clone(), *index),
+        Source::Chain { first, second } => Step::Chain(first.clone(), second.clone()),
+        Source::FlatMap { inner, block, current } => Step::FlatMap(inner.clone(), block.clone(), current.take()),
+    });
+    match next {
+        Step::Done(element) => Ok(element),
+        Step::Make(kind, value) => kind.create(value).map(Some),
+        Step::Map(inner, block) => match step(&inner)? {
+            Some(element) => apply(&block, vec![element], "map").map(Some),
+            None => Ok(None),
+        },
+        Step::Filter(inner, block) => {
+            while let Some(element) = step(&inner)? {
+                if boolean(&apply(&block, vec![element.clone()], "filter")?, "filter")? {
+                    return Ok(Some(element));
+                }
+            }
+            Ok(None)
+        }
+        Step::Inner(inner) => step(&inner),
+        Step::Skip(inner, remaining) => {
+            for _ in 0..remaining {
+                if step(&inner)?.is_none() {
+                    return Ok(None);
+                }
+            }
+            step(&inner)
+        }
+        Step::Zip(first, second) => match (step(&first)?, step(&second)?) {
+            (Some(a), Some(b)) => Ok(Some(super::create_vector(vec![a, b]))),
+            _ => Ok(None),
+        },
+        Step::Enumerate(inner, index) => match step(&inner)? {
+            Some(element) => {
+                with_source(iterator_object, |source| {
+                    if let Source::Enumerate { index, .. } = source {
+                        *index += 1;
+                    }
+                });
+                Ok(Some(super::create_vector(vec![super::create_u64(index), element])))
+            }
+            None => Ok(None),
+        },
+        Step::Chain(first, second) => match step(&first)? {
+            Some(element) => Ok(Some(element)),
+            None => step(&second),
+        },
+        Step::FlatMap(inner, block, mut current) => loop {
+            if let Some(iterator) = &current {
+                if let Some(element) = step(iterator)? {
+                    // The iterator the block gave is kept until it runs out
+                    with_source(iterator_object, |source| {
+                        if let Source::FlatMap { current: stored, .. } = source {
+                            *stored = current.clone();
+                        }
+                    });
+                    return Ok(Some(element));
+                }
+            }
+            let Some(element) = step(&inner)? else { return Ok(None) };
+            current = Some(iterator_of(&apply(&block, vec![element], "flat_map")?, "flat_map")?);
+        },
+    }
+}
+
+fn block_argument(context: &ContextData, position: usize, name: &str) -> Result<ObjectBox, Fault> {
+    let block = context.arguments[position].clone();
+    if !block.borrow().is::<Block>() {
+        return Err(Fault::InvalidType(format!("Iterator {}: Expected Block", name)));
+    }
+    Ok(block)
+}
+
+fn count_argument(context: &ContextData, name: &str) -> Result<usize, Fault> {
+    let count = integer_argument(&context.arguments[0], &format!("Iterator {}", name))?;
+    usize::try_from(count).map_err(|_| Fault::InvalidOperation(format!("Iterator {}: count {} is negative", name, count)))
+}
+
+/// Look ahead so has_next can answer without losing the element
+fn iterator_has_next(object: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let element = step(&object)?;
+    let has_next = element.is_some();
+    let mut iterator = object.borrow_mut();
+    let iterator = iterator.downcast_mut::<IteratorObject>().ok_or(Fault::InvalidType("Iterator has_next: Expected Iterator".to_string()))?;
+    iterator.peeked = Some(element);
+    Ok(Some(super::create_boolean(has_next)))
+}
+
+fn iterator_next(object: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let element = step(&object)?.ok_or(Fault::InvalidOperation("Iterator next: there are no more elements".to_string()))?;
+    Ok(Some(element))
+}
+
+fn iterator_iterator(object: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    Ok(Some(object))
+}
+
+fn iterator_map(object: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let block = block_argument(context, 0, "map")?;
+    Ok(Some(super::create_iterator(Source::Map { inner: object, block })))
+}
+
+fn iterator_filter(object: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let block = block_argument(context, 0, "filter")?;
+    Ok(Some(super::create_iterator(Source::Filter { inner: object, block })))
+}
+
+fn iterator_take(object: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let remaining = count_argument(context, "take")?;
+    Ok(Some(super::create_iterator(Source::Take { inner: object, remaining })))
+}
+
+fn iterator_skip(object: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let remaining = count_argument(context, "skip")?;
+    Ok(Some(super::create_iterator(Source::Skip { inner: object, remaining })))
+}
+
+/// Pair the elements with those of the argument in 2 element Vectors until either runs out
+fn iterator_zip(object: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let second = iterator_of(&context.arguments[0], "zip")?;
+    Ok(Some(super::create_iterator(Source::Zip { first: object, second })))
+}
+
+/// Give each element in a Vector after its index
+fn iterator_enumerate(object: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    Ok(Some(super::create_iterator(Source::Enumerate { inner: object, index: 0 })))
+}
+
+fn iterator_chain(object: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let second = iterator_of(&context.arguments[0], "chain")?;
+    Ok(Some(super::create_iterator(Source::Chain { first: object, second })))
+}
+
+/// Give the elements of whatever the block gives for each element
+fn iterator_flat_map(object: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let block = block_argument(context, 0, "flat_map")?;
+    Ok(Some(super::create_iterator(Source::FlatMap { inner: object, block, current: None })))
+}
+
+fn iterator_collect(object: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let mut elements = Vec::new();
+    while let Some(element) = step(&object)? {
+        elements.push(element);
+    }
+    Ok(Some(super::create_vector(elements)))
+}
+
+/// Like the fold of a Vector the block gets the result so far and the element
+fn iterator_fold(object: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let block = block_argument(context, 0, "fold")?;
+    let mut result = context.arguments[1].clone();
+    while let Some(element) = step(&object)? {
+        result = apply(&block, vec![result, element], "fold")?;
+    }
+    Ok(Some(result))
+}
+
+/// Find the first element the block is true for, stopping there
+fn first_match(object: &ObjectBox, block: &ObjectBox, expected: bool, name: &str) -> Result<Option<ObjectBox>, Fault> {
+    while let Some(element) = step(object)? {
+        if boolean(&apply(block, vec![element.clone()], name)?, name)? == expected {
+            return Ok(Some(element));
+        }
+    }
+    Ok(None)
+}
+
+fn iterator_any(object: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let block = block_argument(context, 0, "any")?;
+    let found = first_match(&object, &block, true, "any")?;
+    Ok(Some(super::create_boolean(found.is_some())))
+}
+
+fn iterator_all(object: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let block = block_argument(context, 0, "all")?;
+    let found = first_match(&object, &block, false, "all")?;
+    Ok(Some(super::create_boolean(found.is_none())))
+}
+
+/// Give the first element the block is true for or Nil
+fn iterator_find(object: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let block = block_argument(context, 0, "find")?;
+    let found = first_match(&object, &block, true, "find")?;
+    Ok(Some(found.unwrap_or_else(Nil::new)))
+}
+
+fn iterator_count(object: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let mut count = 0u64;
+    while step(&object)?.is_some() {
+        count += 1;
+    }
+    Ok(Some(super::create_u64(count)))
+}
+
+/// Add up the elements by sending add, nothing sums to 0
+/// The total and each element added to it are copies because add can change its receiver in place,
+/// and an integer added to a float changes the float and gives it back.
+fn iterator_sum(object: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let Some(first) = step(&object)? else { return Ok(Some(super::create_i64(0))) };
+    let mut total = first.borrow().duplicate();
+    while let Some(element) = step(&object)? {
+        let element = element.borrow().duplicate();
+        let add = total.borrow().lookup_method(Symbol::from("add"))
+            .ok_or(Fault::MethodNotFound("Iterator sum: add".to_string()))?;
+        if let Some(result) = add.evaluate(total.clone(), vec![element])? {
+            total = result;
+        }
+    }
+    Ok(Some(total))
+}
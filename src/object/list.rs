@@ -0,0 +1,291 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use super::primitive::exact::integer_argument;
+use super::symbol::Symbol;
+use super::vector::VectorObject;
+use super::{ContextData, Fault, Method, Object, ObjectBox, VTable};
+
+
+/// List
+/// A Vector that grows. push, insert and remove change the list in place, and capacity and reserve
+/// expose the room it has before it has to grow again.
+pub struct ListObject {
+    super_object: Option<ObjectBox>,
+    vtable: VTable,
+    pub value: Vec<ObjectBox>,
+}
+
+impl ListObject {
+    pub fn make_object(parent: ObjectBox, value: Vec<ObjectBox>) -> ObjectBox {
+        ObjectBox::new(ListObject { super_object: Some(parent), vtable: VTable::new_empty(), value })
+    }
+    pub fn make_vtable() -> VTable {
+        let mut methods = HashMap::new();
+        methods.insert(Symbol::from("length"), Arc::new(Method::RustMethod { fun: Box::new(list_length) }));
+        methods.insert(Symbol::from("get"), Arc::new(Method::RustMethod { fun: Box::new(list_get) }));
+        methods.insert(Symbol::from("set"), Arc::new(Method::RustMethod { fun: Box::new(list_set) }));
+        methods.insert(Symbol::from("push"), Arc::new(Method::RustMethod { fun: Box::new(list_push) }));
+        methods.insert(Symbol::from("pop"), Arc::new(Method::RustMethod { fun: Box::new(list_pop) }));
+        methods.insert(Symbol::from("insert"), Arc::new(Method::RustMethod { fun: Box::new(list_insert) }));
+        methods.insert(Symbol::from("remove_at"), Arc::new(Method::RustMethod { fun: Box::new(list_remove_at) }));
+        methods.insert(Symbol::from("clear"), Arc::new(Method::RustMethod { fun: Box::new(list_clear) }));
+        methods.insert(Symbol::from("capacity"), Arc::new(Method::RustMethod { fun: Box::new(list_capacity) }));
+        methods.insert(Symbol::from("reserve"), Arc::new(Method::RustMethod { fun: Box::new(list_reserve) }));
+        methods.insert(Symbol::from("to_vector"), Arc::new(Method::RustMethod { fun: Box::new(list_to_vector) }));
+        methods.insert(Symbol::from("iterator"), Arc::new(Method::RustMethod { fun: Box::new(list_iterator) }));
+        VTable::new(methods)
+    }
+}
+
+impl Object for ListObject {
+    fn get_vtable(&self) -> &VTable {
+        &self.vtable
+    }
+    fn get_super_object(&self) -> Option<ObjectBox> {
+        self.super_object.clone()
+    }
+    fn get_field(&self, index: usize) -> Option<ObjectBox> {
+        self.value.get(index).cloned()
+    }
+    fn set_field(&mut self, index: usize, value: ObjectBox) {
+        self.value[index] = value;
+    }
+    fn size(&self) -> Option<usize> {
+        Some(self.value.len())
+    }
+    fn duplicate(&self) -> ObjectBox {
+        let list = ListObject::make_object(self.super_object.clone().unwrap(), self.value.clone());
+        let mut list_mut = list.borrow_mut();
+        list_mut.initialize(Vec::new(), self.vtable.clone());
+        drop(list_mut);
+        list
+    }
+    fn initialize(&mut self, _: Vec<ObjectBox>, vtable: VTable) {
+        self.vtable.extend(vtable);
+    }
+    fn children(&self) -> Vec<ObjectBox> {
+        self.value.iter().cloned().chain(self.super_object.clone()).collect()
+    }
+    fn clear_children(&mut self) {
+        self.value = Vec::new();
+    }
+}
+
+/// Make the elements of a new List
+/// With no argument it is empty, a Vector gives its elements and an integer is the capacity to start with.
+pub fn from_arguments(arguments: &[ObjectBox]) -> Result<Vec<ObjectBox>, Fault> {
+    match arguments {
+        [] => Ok(Vec::new()),
+        [argument] => {
+            let elements = argument.borrow().downcast_ref::<VectorObject>().map(|vector| vector.value.to_vec());
+            match elements {
+                Some(elements) => Ok(elements),
+                None => {
+                    let capacity = integer_argument(argument, "List")?;
+                    let capacity = usize::try_from(capacity).map_err(|_| Fault::InvalidOperation(format!("List: capacity {} is negative", capacity)))?;
+                    Ok(Vec::with_capacity(capacity))
+                }
+            }
+        }
+        _ => Err(Fault::InvalidType(format!("expected at most 1 argument, got {}", arguments.len()))),
+    }
+}
+
+fn index_argument(context: &ContextData, position: usize, name: &str) -> Result<usize, Fault> {
+    let index = integer_argument(&context.arguments[position], &format!("List {}", name))?;
+    usize::try_from(index).map_err(|_| Fault::InvalidOperation(format!("List {}: index {} is negative", name, index)))
+}
+
+fn out_of_range(name: &str, index: usize, length: usize) -> Fault {
+    Fault::InvalidOperation(format!("List {}: index {} is out of range for length {}", name, index, length))
+}
+
+fn with_list<T>(object: &ObjectBox, name: &str, fun: impl FnOnce(&mut Vec<ObjectBox>) -> Result<T, Fault>) -> Result<T, Fault> {
+    let mut object = object.borrow_mut();
+    let object = object.downcast_mut::<ListObject>().ok_or(Fault::InvalidType(format!("List {}: Expected List", name)))?;
+    fun(&mut object.value)
+}
+
+fn list_length(object: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let length = with_list(&object, "length", |value| Ok(value.len()))?;
+    Ok(Some(super::create_u64(length as u64)))
+}
+
+fn list_get(object: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let index = index_argument(context, 0, "get")?;
+    let element = with_list(&object, "get", |value| value.get(index).cloned().ok_or_else(|| out_of_range("get", index, value.len())))?;
+    Ok(Some(element))
+}
+
+fn list_set(object: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let index = index_argument(context, 0, "set")?;
+    let element = context.arguments[1].clone();
+    with_list(&object, "set", |value| {
+        let length = value.len();
+        *value.get_mut(index).ok_or_else(|| out_of_range("set", index, length))? = element;
+        Ok(())
+    })?;
+    Ok(None)
+}
+
+fn list_push(object: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let element = context.arguments[0].clone();
+    with_list(&object, "push", |value| {
+        value.push(element);
+        Ok(())
+    })?;
+    Ok(None)
+}
+
+/// Remove the last element and give it, an empty list is a fault
+fn list_pop(object: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let element = with_list(&object, "pop", |value| value.pop().ok_or(Fault::InvalidOperation("List pop: the list is empty".to_string())))?;
+    Ok(Some(element))
+}
+
+/// Insert the first argument before the index in the second, the length is the end of the list
+fn list_insert(object: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let element = context.arguments[0].clone();
+    let index = index_argument(context, 1, "insert")?;
+    with_list(&object, "insert", |value| {
+        if index > value.len() {
+            return Err(out_of_range("insert", index, value.len()));
+        }
+        value.insert(index, element);
+        Ok(())
+    })?;
+    Ok(None)
+}
+
+/// Remove the element at an index and give it
+fn list_remove_at(object: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let index = index_argument(context, 0, "remove_at")?;
+    let element = with_list(&object, "remove_at", |value| {
+        if index >= value.len() {
+            return Err(out_of_range("remove_at", index, value.len()));
+        }
+        Ok(value.remove(index))
+    })?;
+    Ok(Some(element))
+}
+
+fn list_clear(object: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    with_list(&object, "clear", |value| {
+        value.clear();
+        Ok(())
+    })?;
+    Ok(None)
+}
+
+/// The number of elements the list can hold before it grows
+fn list_capacity(object: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let capacity = with_list(&object, "capacity", |value| Ok(value.capacity()))?;
+    Ok(Some(super::create_u64(capacity as u64)))
+}
+
+/// Make room for at least this many more elements
+fn list_reserve(object: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let additional = index_argument(context, 0, "reserve")?;
+    with_list(&object, "reserve", |value| {
+        value.try_reserve(additional).map_err(|_| Fault::InvalidOperation(format!("List reserve: can't make room for {} more elements", additional)))
+    })?;
+    Ok(None)
+}
+
+fn list_to_vector(object: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let elements = with_list(&object, "to_vector", |value| Ok(value.clone()))?;
+    Ok(Some(super::create_vector(elements)))
+}
+
+/// Iterate over the list as it is when each element is reached, so elements pushed on the way are seen
+fn list_iterator(object: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    with_list(&object, "iterator", |_| Ok(()))?;
+    Ok(Some(super::iterator::over_fields(object)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::value::Immediate;
+    use super::super::{create_i64, create_object, create_vector};
+
+    fn list(arguments: &[ObjectBox]) -> ObjectBox {
+        create_object("List", arguments).unwrap().unwrap()
+    }
+
+    fn send(receiver: &ObjectBox, selector: &str, arguments: Vec<ObjectBox>) -> Result<Option<ObjectBox>, Fault> {
+        let method = receiver.borrow().lookup_method(Symbol::from(selector)).unwrap();
+        method.evaluate(receiver.clone(), arguments)
+    }
+
+    fn number(object: Option<ObjectBox>) -> Option<Immediate> {
+        Immediate::from_object(&*object.unwrap().borrow())
+    }
+
+    fn elements(list: &ObjectBox) -> Vec<Option<Immediate>> {
+        list.borrow().downcast_ref::<ListObject>().unwrap().value.iter().map(|element| Immediate::from_object(&*element.borrow())).collect()
+    }
+
+    fn numbers(values: &[i64]) -> Vec<Option<Immediate>> {
+        values.iter().map(|value| Some(Immediate::I64(*value))).collect()
+    }
+
+    #[test]
+    fn lists_grow_and_shrink_in_place() {
+        let list = list(&[]);
+        for value in [1, 2, 3] {
+            assert!(send(&list, "push", vec![create_i64(value)]).unwrap().is_none());
+        }
+        send(&list, "insert", vec![create_i64(0), create_i64(0)]).unwrap();
+        send(&list, "insert", vec![create_i64(4), create_i64(4)]).unwrap();
+        assert_eq!(elements(&list), numbers(&[0, 1, 2, 3, 4]));
+        assert_eq!(number(send(&list, "remove_at", vec![create_i64(1)]).unwrap()), Some(Immediate::I64(1)));
+        assert_eq!(number(send(&list, "pop", Vec::new()).unwrap()), Some(Immediate::I64(4)));
+        send(&list, "set", vec![create_i64(0), create_i64(9)]).unwrap();
+        assert_eq!(number(send(&list, "get", vec![create_i64(0)]).unwrap()), Some(Immediate::I64(9)));
+        assert_eq!(number(send(&list, "length", Vec::new()).unwrap()), Some(Immediate::U64(3)));
+        send(&list, "clear", Vec::new()).unwrap();
+        assert_eq!(elements(&list), []);
+    }
+
+    #[test]
+    fn indexes_out_of_range_fault() {
+        let list = list(&[create_vector(vec![create_i64(1)])]);
+        assert!(matches!(send(&list, "get", vec![create_i64(1)]), Err(Fault::InvalidOperation(_))));
+        assert!(matches!(send(&list, "get", vec![create_i64(-1)]), Err(Fault::InvalidOperation(_))));
+        assert!(matches!(send(&list, "set", vec![create_i64(1), create_i64(0)]), Err(Fault::InvalidOperation(_))));
+        assert!(matches!(send(&list, "insert", vec![create_i64(0), create_i64(2)]), Err(Fault::InvalidOperation(_))));
+        assert!(matches!(send(&list, "remove_at", vec![create_i64(1)]), Err(Fault::InvalidOperation(_))));
+        send(&list, "pop", Vec::new()).unwrap();
+        assert!(matches!(send(&list, "pop", Vec::new()), Err(Fault::InvalidOperation(_))));
+    }
+
+    #[test]
+    fn capacity_is_room_before_growing() {
+        let list = list(&[create_i64(8)]);
+        assert_eq!(elements(&list), []);
+        let capacity = |list: &ObjectBox| match number(send(list, "capacity", Vec::new()).unwrap()) {
+            Some(Immediate::U64(capacity)) => capacity,
+            other => panic!("capacity gave {:?}", other),
+        };
+        assert!(capacity(&list) >= 8);
+        send(&list, "push", vec![create_i64(1)]).unwrap();
+        send(&list, "reserve", vec![create_i64(20)]).unwrap();
+        assert!(capacity(&list) >= 21);
+        assert!(send(&list, "reserve", vec![create_i64(-1)]).is_err());
+        assert!(matches!(create_object("List", &[create_i64(-1)]), Err(Fault::InvalidOperation(_))));
+    }
+
+    #[test]
+    fn vectors_are_copies_of_the_list() {
+        let list = list(&[create_vector(vec![create_i64(1), create_i64(2)])]);
+        let vector = send(&list, "to_vector", Vec::new()).unwrap().unwrap();
+        send(&list, "push", vec![create_i64(3)]).unwrap();
+        assert_eq!(vector.borrow().downcast_ref::<VectorObject>().unwrap().value.len(), 2);
+        let copy = list.borrow().duplicate();
+        send(&list, "pop", Vec::new()).unwrap();
+        assert_eq!(elements(&copy), numbers(&[1, 2, 3]));
+        assert_eq!(elements(&list), numbers(&[1, 2]));
+    }
+}
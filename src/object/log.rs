@@ -2,12 +2,14 @@ use std::sync::Arc;
 use std::collections::HashMap;
 use crate::object::ContextData;
 use crate::object::string::StringObject;
+use crate::object::vector::VectorObject;
 use crate::object::Method;
 use std::io::Write;
 use log::{info, warn, error, debug, trace};
 
 use super::{Fault, Object, ObjectBox};
 use crate::object::VTable;
+use crate::object::symbol::Symbol;
 
 
 
@@ -38,15 +40,16 @@ impl Logger {
     }
     pub fn make_vtable() -> VTable {
         let mut methods = HashMap::new();
-        methods.insert("println".to_string(), Arc::new(Method::RustMethod { fun: Box::new(log_println)}));
-        methods.insert("print".to_string(), Arc::new(Method::RustMethod { fun: Box::new(log_print)}));
-        methods.insert("eprintln".to_string(), Arc::new(Method::RustMethod { fun: Box::new(log_eprintln)}));
-        methods.insert("eprint".to_string(), Arc::new(Method::RustMethod { fun: Box::new(log_eprint)}));
-        methods.insert("info".to_string(), Arc::new(Method::RustMethod { fun: Box::new(log_info)}));
-        methods.insert("trace".to_string(), Arc::new(Method::RustMethod { fun: Box::new(log_trace)}));
-        methods.insert("warn".to_string(), Arc::new(Method::RustMethod { fun: Box::new(log_warn)}));
-        methods.insert("error".to_string(), Arc::new(Method::RustMethod { fun: Box::new(log_error)}));
-        methods.insert("debug".to_string(), Arc::new(Method::RustMethod { fun: Box::new(log_debug)}));
+        methods.insert(Symbol::from("println"), Arc::new(Method::RustMethod { fun: Box::new(log_println)}));
+        methods.insert(Symbol::from("print"), Arc::new(Method::RustMethod { fun: Box::new(log_print)}));
+        methods.insert(Symbol::from("eprintln"), Arc::new(Method::RustMethod { fun: Box::new(log_eprintln)}));
+        methods.insert(Symbol::from("eprint"), Arc::new(Method::RustMethod { fun: Box::new(log_eprint)}));
+        methods.insert(Symbol::from("printf"), Arc::new(Method::RustMethod { fun: Box::new(log_printf)}));
+        methods.insert(Symbol::from("info"), Arc::new(Method::RustMethod { fun: Box::new(log_info)}));
+        methods.insert(Symbol::from("trace"), Arc::new(Method::RustMethod { fun: Box::new(log_trace)}));
+        methods.insert(Symbol::from("warn"), Arc::new(Method::RustMethod { fun: Box::new(log_warn)}));
+        methods.insert(Symbol::from("error"), Arc::new(Method::RustMethod { fun: Box::new(log_error)}));
+        methods.insert(Symbol::from("debug"), Arc::new(Method::RustMethod { fun: Box::new(log_debug)}));
 
         VTable::new(methods)
     }
@@ -70,22 +73,14 @@ impl Object for Logger {
         None
     }
     fn duplicate(&self) -> ObjectBox {
-        let logger = Logger::make_object(self.super_object.clone().unwrap().borrow().duplicate());
+        let logger = Logger::make_object(self.super_object.clone().unwrap());
         let mut log = logger.borrow_mut();
         log.initialize(vec![], self.vtable.clone());
         drop(log);
         logger
     }
     fn initialize(&mut self, _: Vec<ObjectBox>, vtable: VTable) {
-        self.vtable.extend(Logger::make_vtable());
         self.vtable.extend(vtable);
-        match &mut self.super_object {
-            Some(super_object) => {
-                let mut super_object = super_object.borrow_mut();
-                super_object.initialize(vec![], VTable::new_empty());
-            }
-            None => {}
-        }
     }
 }
 
@@ -94,7 +89,7 @@ impl Object for Logger {
 fn log_println(_: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
     let message = context.arguments[0].clone();
     let message = message.borrow();
-    let message = message.downcast_ref::<StringObject>().ok_or(Fault::InvalidType(format!("Logger println: argument was not a string")))?;
+    let message = message.downcast_ref::<StringObject>().ok_or(Fault::InvalidType("Logger println: argument was not a string".to_string()))?;
     println!("{}", message.value);
     Ok(None)
 }
@@ -102,16 +97,27 @@ fn log_println(_: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectB
 fn log_print(_: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
     let message = context.arguments[0].clone();
     let message = message.borrow();
-    let message = message.downcast_ref::<StringObject>().ok_or(Fault::InvalidType(format!("Logger print: argument was not a string")))?;
+    let message = message.downcast_ref::<StringObject>().ok_or(Fault::InvalidType("Logger print: argument was not a string".to_string()))?;
     print!("{}", message.value);
-    std::io::stdout().flush().map_err(|x| Fault::IO(x))?;
+    std::io::stdout().flush().map_err(Fault::IO)?;
+    Ok(None)
+}
+
+/// Print a template with the elements of a Vector in its placeholders, without a newline like print
+fn log_printf(_: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let template = context.arguments[0].borrow().downcast_ref::<StringObject>().map(|template| template.value.clone())
+        .ok_or(Fault::InvalidType("Logger printf: first argument was not a string".to_string()))?;
+    let arguments = context.arguments[1].borrow().downcast_ref::<VectorObject>().map(|vector| vector.value.to_vec())
+        .ok_or(Fault::InvalidType("Logger printf: second argument was not a vector".to_string()))?;
+    print!("{}", super::format::format(&template, &arguments)?);
+    std::io::stdout().flush().map_err(Fault::IO)?;
     Ok(None)
 }
 
 fn log_eprintln(_: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
     let message = context.arguments[0].clone();
     let message = message.borrow();
-    let message = message.downcast_ref::<StringObject>().ok_or(Fault::InvalidType(format!("Logger eprintln: argument was not a string")))?;
+    let message = message.downcast_ref::<StringObject>().ok_or(Fault::InvalidType("Logger eprintln: argument was not a string".to_string()))?;
     println!("{}", message.value);
     Ok(None)
 }
@@ -119,16 +125,16 @@ fn log_eprintln(_: ObjectBox, context: &mut ContextData) -> Result<Option<Object
 fn log_eprint(_: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
     let message = context.arguments[0].clone();
     let message = message.borrow();
-    let message = message.downcast_ref::<StringObject>().ok_or(Fault::InvalidType(format!("Logger eprint: argument was not a string")))?;
+    let message = message.downcast_ref::<StringObject>().ok_or(Fault::InvalidType("Logger eprint: argument was not a string".to_string()))?;
     print!("{}", message.value);
-    std::io::stdout().flush().map_err(|x| Fault::IO(x))?;
+    std::io::stdout().flush().map_err(Fault::IO)?;
     Ok(None)
 }
 
 fn log_info(_: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
     let message = context.arguments[0].clone();
     let message = message.borrow();
-    let message = message.downcast_ref::<StringObject>().ok_or(Fault::InvalidType(format!("Logger info: argument was not a string")))?;
+    let message = message.downcast_ref::<StringObject>().ok_or(Fault::InvalidType("Logger info: argument was not a string".to_string()))?;
     info!("{}", message.value);
     Ok(None)
 }
@@ -136,7 +142,7 @@ fn log_info(_: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>
 fn log_trace(_: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
     let message = context.arguments[0].clone();
     let message = message.borrow();
-    let message = message.downcast_ref::<StringObject>().ok_or(Fault::InvalidType(format!("Logger trace: argument was not a string")))?;
+    let message = message.downcast_ref::<StringObject>().ok_or(Fault::InvalidType("Logger trace: argument was not a string".to_string()))?;
     trace!("{}", message.value);
     Ok(None)
 }
@@ -144,7 +150,7 @@ fn log_trace(_: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox
 fn log_warn(_: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
     let message = context.arguments[0].clone();
     let message = message.borrow();
-    let message = message.downcast_ref::<StringObject>().ok_or(Fault::InvalidType(format!("Logger warn: argument was not a string")))?;
+    let message = message.downcast_ref::<StringObject>().ok_or(Fault::InvalidType("Logger warn: argument was not a string".to_string()))?;
     warn!("{}", message.value);
     Ok(None)
 }
@@ -152,7 +158,7 @@ fn log_warn(_: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>
 fn log_error(_: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
     let message = context.arguments[0].clone();
     let message = message.borrow();
-    let message = message.downcast_ref::<StringObject>().ok_or(Fault::InvalidType(format!("Logger error: argument was not a string")))?;
+    let message = message.downcast_ref::<StringObject>().ok_or(Fault::InvalidType("Logger error: argument was not a string".to_string()))?;
     error!("{}", message.value);
     Ok(None)
 }
@@ -160,7 +166,7 @@ fn log_error(_: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox
 fn log_debug(_: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
     let message = context.arguments[0].clone();
     let message = message.borrow();
-    let message = message.downcast_ref::<StringObject>().ok_or(Fault::InvalidType(format!("Logger debug: argument was not a string")))?;
+    let message = message.downcast_ref::<StringObject>().ok_or(Fault::InvalidType("Logger debug: argument was not a string".to_string()))?;
     debug!("{}", message.value);
     Ok(None)
 }
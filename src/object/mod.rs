@@ -5,11 +5,26 @@ pub mod string;
 pub mod log;
 pub mod vector;
 pub mod system;
+pub mod gc;
+pub mod value;
+pub mod symbol;
+pub mod weak;
+pub mod class;
+pub mod compare;
+pub mod format;
+pub mod regex;
+pub mod builder;
+pub mod bytes;
+pub mod list;
+pub mod set;
+pub mod iterator;
+pub mod range;
 
 use lazy_static::lazy_static;
-use std::sync::{Arc, Mutex, MutexGuard};
-use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard, Weak};
+use std::collections::{HashMap, HashSet};
 use std::sync::RwLock;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 use crate::vm::bytecode::ByteCode;
 
@@ -17,10 +32,16 @@ use self::log::Logger;
 use self::stack::Stack;
 use self::primitive::boolean::BooleanObject;
 use self::primitive::character::CharacterObject;
+use self::primitive::symbol::SymbolObject;
 use self::primitive::float::{F32Object, F64Object, FloatObject};
 use self::primitive::integer::{I16Object, I32Object, I64Object, I8Object, IntegerObject, U16Object, U32Object, U64Object, U8Object};
+use self::primitive::bigint::{BigInt, BigIntObject};
+use self::primitive::decimal::{Decimal, DecimalObject};
+use self::primitive::rational::{Rational, RationalObject};
 use self::primitive::{NumberObject, PrimitiveObject};
 use self::string::StringObject;
+use self::symbol::Symbol;
+use self::value::{Immediate, Value};
 
 #[derive(Debug)]
 pub enum Fault {
@@ -30,6 +51,7 @@ pub enum Fault {
     DivideByZero,
     IO(std::io::Error),
     MethodNotFound(String),
+    Overflow(String),
 }
 
 impl std::fmt::Display for Fault {
@@ -41,6 +63,7 @@ impl std::fmt::Display for Fault {
             Fault::DivideByZero => write!(f, "Divide by zero"),
             Fault::IO(e) => write!(f, "IO error: {}", e),
             Fault::MethodNotFound(name) => write!(f, "Method not found: {}", name),
+            Fault::Overflow(string) => write!(f, "Overflow: {}", string),
         }
     }
 }
@@ -49,8 +72,6 @@ impl std::error::Error for Fault {}
 
 //pub type ObjectBox = Rc<RefCell<dyn Object>>;
 
-unsafe impl Send for ObjectBox {}
-
 #[derive(Clone)]
 pub struct ObjectBox {
     pub data: Arc<Mutex<dyn Object>>,
@@ -58,28 +79,60 @@ pub struct ObjectBox {
 
 impl ObjectBox {
     pub fn new<O: Object>(data: O) -> ObjectBox {
-        ObjectBox {
+        let object = ObjectBox {
             data: Arc::new(Mutex::new(data))
-        }
+        };
+        gc::register(&object);
+        object
     }
 
-    pub fn borrow(&self) -> MutexGuard<dyn Object> {
+    pub fn borrow(&self) -> MutexGuard<'_, dyn Object> {
         self.data.lock().expect("ObjectBox::borrow: lock poisoned")
     }
 
-    pub fn borrow_mut(&self) -> MutexGuard<dyn Object> {
+    pub fn borrow_mut(&self) -> MutexGuard<'_, dyn Object> {
         self.data.lock().expect("ObjectBox::borrow_mut: lock poisoned")
     }
 
     pub fn as_ptr(&self) -> *const () {
         Arc::as_ptr(&self.data) as *const ()
     }
+
+    /// Make a reference to this object that doesn't keep it alive
+    pub fn downgrade(&self) -> WeakObjectBox {
+        WeakObjectBox {
+            data: Arc::downgrade(&self.data)
+        }
+    }
+}
+
+/// WeakObjectBox
+/// A reference to an object that doesn't keep it alive.
+#[derive(Clone)]
+pub struct WeakObjectBox {
+    pub data: Weak<Mutex<dyn Object>>,
+}
+
+impl WeakObjectBox {
+    /// Get the object if it is still alive
+    pub fn upgrade(&self) -> Option<ObjectBox> {
+        self.data.upgrade().map(|data| ObjectBox { data })
+    }
+
+    pub fn strong_count(&self) -> usize {
+        self.data.strong_count()
+    }
+
+    /// The address of the object, which can't be reused while this reference exists
+    pub fn as_ptr(&self) -> *const () {
+        self.data.as_ptr() as *const ()
+    }
 }
 
 
 /// This object defines the interface for all objects in the system.
 /// This is so that all objects are trait objects.
-pub trait Object: downcast_rs::Downcast {
+pub trait Object: downcast_rs::Downcast + Send {
     /// Get the vtable for the object
     fn get_vtable(&self) -> &VTable;
     /// Get the super object
@@ -93,12 +146,17 @@ pub trait Object: downcast_rs::Downcast {
     /// Handle a message
     /// This method gets a method from a vtable and if it doesn't find it, it looks in the super object.
     fn handle_message(&self, message: &Message) -> Option<Arc<Method>> {
-        let mut method = self.get_vtable().get_method(&message.index);
+        self.lookup_method(message.index)
+    }
+    /// Look up a method by its name
+    /// This does the same as handle_message but doesn't need a Message object.
+    fn lookup_method(&self, index: Symbol) -> Option<Arc<Method>> {
+        let mut method = self.get_vtable().get_method(index);
         let mut object = self.get_super_object();
         while method.is_none() {
             if let Some(obj) = object {
                 let obj = obj.borrow();
-                method = obj.get_vtable().get_method(&message.index);
+                method = obj.get_vtable().get_method(index);
                 object = obj.get_super_object();
             } else {
                 break;
@@ -112,7 +170,7 @@ pub trait Object: downcast_rs::Downcast {
     /// The message is an object so it can be anything but we can assume it's a message.
     fn process_message(&self, message: ObjectBox) -> Option<Arc<Method>> {
         let message = message.borrow();
-        if let Some(message) = (&*message).downcast_ref::<Message>() {
+        if let Some(message) = (*message).downcast_ref::<Message>() {
             self.handle_message(message)
         } else {
             panic!("Object::process_message: message is not a Message")
@@ -124,8 +182,18 @@ pub trait Object: downcast_rs::Downcast {
     /// Initialize the object
     /// This method initializes the object. This should get called when the init message is passed
     /// into the object.
+    /// The super object is shared with every other instance of the type so this must only change
+    /// the object itself.
     fn initialize(&mut self, arguments: Vec<ObjectBox>, vtable: VTable);
-    
+    /// Get the objects that this object holds references to
+    /// This is used by the cycle collector to find the references that live inside the heap.
+    /// Objects that store other objects must override this.
+    fn children(&self) -> Vec<ObjectBox> {
+        self.get_super_object().into_iter().collect()
+    }
+    /// Drop the references that this object holds to other objects
+    /// This is used by the cycle collector to break up cycles that are garbage.
+    fn clear_children(&mut self) {}
 }
 downcast_rs::impl_downcast!(Object);
 
@@ -135,6 +203,8 @@ downcast_rs::impl_downcast!(Object);
 pub struct Nil;
 
 impl Nil {
+    // Objects only ever live behind an ObjectBox so that is what new gives back
+    #[allow(clippy::new_ret_no_self)]
     pub fn new() -> ObjectBox {
         ObjectBox::new(Nil)
     }
@@ -162,6 +232,9 @@ impl Object for Nil {
     fn handle_message(&self, _message: &Message) -> Option<Arc<Method>> {
         None
     }
+    fn lookup_method(&self, _index: Symbol) -> Option<Arc<Method>> {
+        None
+    }
     fn process_message(&self, _message: ObjectBox) -> Option<Arc<Method>> {
         None
     }
@@ -169,7 +242,6 @@ impl Object for Nil {
         Nil::new()
     }
     fn initialize(&mut self, _arguments: Vec<ObjectBox>, _: VTable) {
-        ()
     }
 }
 
@@ -184,21 +256,21 @@ pub struct BaseObject {
 impl BaseObject {
     /*pub fn make_class() -> Class {
         let mut methods = HashMap::new();
-        methods.insert("clone".to_string(), Arc::new(Method::RustMethod { fun: Box::new(obj_clone) }));
-        methods.insert("equals".to_string(), Arc::new(Method::RustMethod { fun: Box::new(obj_equals) }));
-        methods.insert("hash".to_string(), Arc::new(Method::RustMethod { fun: Box::new(obj_hash) }));
-        methods.insert("to_string".to_string(), Arc::new(Method::RustMethod { fun: Box::new(obj_to_string) }));
-        methods.insert("order".to_string(), Arc::new(Method::RustMethod { fun: Box::new(obj_order) }));
-        methods.insert("initalize".to_string(), Arc::new(Method::RustMethod { fun: Box::new(obj_initalize) }));
+        methods.insert(Symbol::from("clone"), Arc::new(Method::RustMethod { fun: Box::new(obj_clone) }));
+        methods.insert(Symbol::from("equals"), Arc::new(Method::RustMethod { fun: Box::new(obj_equals) }));
+        methods.insert(Symbol::from("hash"), Arc::new(Method::RustMethod { fun: Box::new(obj_hash) }));
+        methods.insert(Symbol::from("to_string"), Arc::new(Method::RustMethod { fun: Box::new(obj_to_string) }));
+        methods.insert(Symbol::from("order"), Arc::new(Method::RustMethod { fun: Box::new(obj_order) }));
+        methods.insert(Symbol::from("initalize"), Arc::new(Method::RustMethod { fun: Box::new(obj_initalize) }));
         Class::new(None, methods)
     }*/
     pub fn make_vtable() -> VTable {
         let mut methods = HashMap::new();
-        methods.insert("clone".to_string(), Arc::new(Method::RustMethod { fun: Box::new(obj_clone) }));
-        methods.insert("equals".to_string(), Arc::new(Method::RustMethod { fun: Box::new(obj_equals) }));
-        methods.insert("to_string".to_string(), Arc::new(Method::RustMethod { fun: Box::new(obj_to_string) }));
-        methods.insert("order".to_string(), Arc::new(Method::RustMethod { fun: Box::new(obj_order) }));
-        methods.insert("init".to_string(), Arc::new(Method::RustMethod { fun: Box::new(obj_initalize) }));
+        methods.insert(Symbol::from("clone"), Arc::new(Method::RustMethod { fun: Box::new(obj_clone) }));
+        methods.insert(Symbol::from("equals"), IDENTITY_EQUALS.clone());
+        methods.insert(Symbol::from("to_string"), Arc::new(Method::RustMethod { fun: Box::new(obj_to_string) }));
+        methods.insert(Symbol::from("order"), Arc::new(Method::RustMethod { fun: Box::new(obj_order) }));
+        methods.insert(Symbol::from("init"), Arc::new(Method::RustMethod { fun: Box::new(obj_initalize) }));
         VTable::new(methods)
     }
 
@@ -226,26 +298,20 @@ impl Object for BaseObject {
     fn handle_message(&self, _message: &Message) -> Option<Arc<Method>> {
         None
     }
+    fn lookup_method(&self, _index: Symbol) -> Option<Arc<Method>> {
+        None
+    }
     fn process_message(&self, _message: ObjectBox) -> Option<Arc<Method>> {
         None
     }
     fn duplicate(&self) -> ObjectBox {
-        let obje = BaseObject::make_object(self.super_object.as_ref().unwrap().borrow().duplicate());
-        let mut obj = obje.borrow_mut();
-        obj.initialize(vec![], self.vtable.clone());
-        drop(obj);
-        obje
+        ObjectBox::new(BaseObject {vtable: self.vtable.clone(), super_object: self.super_object.clone()})
     }
     fn initialize(&mut self, _arguments: Vec<ObjectBox>, vtable: VTable) {
-        self.vtable.extend(BaseObject::make_vtable());
         self.vtable.extend(vtable);
-        match &mut self.super_object {
-            Some(super_object) => {
-                let mut super_object = super_object.borrow_mut();
-                super_object.initialize(vec![], VTable::new_empty());
-            }
-            None => {}
-        }
+    }
+    fn children(&self) -> Vec<ObjectBox> {
+        self.super_object.iter().cloned().collect()
     }
 }
 
@@ -255,6 +321,17 @@ fn obj_clone(object: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>
     Result::Ok(Some(new_object))
 }
 
+lazy_static! {
+    /// The equals of Object, which compares identity
+    /// Every object shares this one method so that it can tell if an object overrides it.
+    static ref IDENTITY_EQUALS: Arc<Method> = Arc::new(Method::RustMethod { fun: Box::new(obj_equals) });
+}
+
+/// Check if a method is the equals of Object, which only finds an object equal to itself
+pub fn is_identity_equals(method: &Arc<Method>) -> bool {
+    Arc::ptr_eq(method, &IDENTITY_EQUALS)
+}
+
 fn obj_equals(object: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
     let object_ptr = object.as_ptr();
     let other_ptr = context.arguments[0].as_ptr();
@@ -283,9 +360,9 @@ fn obj_to_string(object: ObjectBox, _: &mut ContextData) -> Result<Option<Object
 fn obj_order(object: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
     let object_ptr = object.as_ptr();
     let other_ptr = context.arguments[0].as_ptr();
-    if object_ptr as *const () < other_ptr as *const () {
+    if object_ptr < other_ptr {
         Ok(Some(create_i8(-1)))
-    } else if object_ptr as *const () > other_ptr as *const () {
+    } else if object_ptr > other_ptr {
         Ok(Some(create_i8(1)))
     } else {
         Ok(Some(create_i8(0)))
@@ -300,6 +377,10 @@ fn obj_initalize(object: ObjectBox, context: &mut ContextData) -> Result<Option<
     }
     match context.vtable.take() {
         Some(vtable) => {
+            // The object may be the parent of others whose cached lookups go through its vtable
+            if !vtable.empty() {
+                CLASS_EPOCH.fetch_add(1, Ordering::Release);
+            }
             object.initialize(arguments, vtable);
         },
         None => {
@@ -316,19 +397,37 @@ fn obj_initalize(object: ObjectBox, context: &mut ContextData) -> Result<Option<
 pub struct ObjectStruct {
     class: Option<Arc<Class>>,
     super_object: Option<ObjectBox>,
+    /// Whether the super object is a copy that belongs to this object alone
+    owns_super_object: bool,
     fields: Box<[ObjectBox]>,
     vtable: VTable,
 }
 
 impl ObjectStruct {
+    #[allow(clippy::new_ret_no_self)]
     pub fn new(class: Option<Arc<Class>>, super_object: Option<ObjectBox>) -> ObjectBox {
         ObjectBox::new(ObjectStruct {
             class,
             super_object,
+            owns_super_object: false,
             fields: Box::new([]),
             vtable: VTable::new_empty(),
         })
     }
+    pub fn get_class(&self) -> Option<Arc<Class>> {
+        self.class.clone()
+    }
+    /// Get the super object that a super send runs its method on
+    /// The instances of a class share the parent chain that the factory built for it. The parent's
+    /// methods can store things in it, so the first super send gives the object a copy of its
+    /// parent to keep and the chain is never changed through an instance.
+    pub fn own_super_object(&mut self) -> Option<ObjectBox> {
+        if !self.owns_super_object {
+            self.super_object = self.super_object.as_ref().map(|parent| parent.borrow().duplicate());
+            self.owns_super_object = true;
+        }
+        self.super_object.clone()
+    }
 }
 
 
@@ -340,7 +439,7 @@ impl Object for ObjectStruct {
         self.super_object.clone()
     }
     fn get_field(&self, index: usize) -> Option<ObjectBox> {
-        self.fields.get(index).map(|field| field.clone())
+        self.fields.get(index).cloned()
     }
     fn set_field(&mut self, index: usize, value: ObjectBox) {
         self.fields[index] = value;
@@ -356,6 +455,7 @@ impl Object for ObjectStruct {
         let object = ObjectStruct {
             class: self.class.clone(),
             super_object: self.super_object.clone(),
+            owns_super_object: self.owns_super_object,
             fields: fields.into_boxed_slice(),
             vtable: self.vtable.clone(),
         };
@@ -365,67 +465,128 @@ impl Object for ObjectStruct {
         self.fields = arguments.into_boxed_slice();
         self.vtable.extend(self.class.as_ref().unwrap().get_vtable());
         self.vtable.extend(vtable);
-        let mut super_object = self.super_object.clone();
-        let mut overrides = self.class.as_ref().unwrap().get_overrides();
-        while super_object.is_some() {
-            match super_object {
-                Some(super_object_ref) => {
-                    let mut super_object_ref2 = super_object_ref.borrow_mut();
-                    let vtable = match overrides.pop() {
-                        Some(vtable) => vtable,
-                        None => VTable::new_empty(),
-                    };
-                    super_object_ref2.initialize(vec![], vtable);
-                    drop(super_object_ref2);
-                    super_object = super_object_ref.borrow().get_super_object();
-                }
-                None => {}
-            }
-        }
+    }
+    fn children(&self) -> Vec<ObjectBox> {
+        self.fields.iter().cloned().chain(self.super_object.clone()).collect()
+    }
+    fn clear_children(&mut self) {
+        self.fields = Box::new([]);
     }
 }
 
 /// VTable
 /// This is the vtable for an object. It contains a hashmap of methods.
 /// This is so that we can call methods on an object.
+/// The table is shared between every object that uses it and only gets copied when one of them
+/// changes it. This means that cloning a vtable is cheap.
 #[derive(Clone, Debug)]
 pub struct VTable {
-    table: HashMap<String, Arc<Method>>,
+    table: Option<Arc<HashMap<Symbol, Arc<Method>>>>,
 }
 
 impl VTable {
-    pub fn new(table: HashMap<String, Arc<Method>>) -> VTable {
+    pub fn new(table: HashMap<Symbol, Arc<Method>>) -> VTable {
         VTable {
-            table,
+            table: Some(Arc::new(table)),
         }
     }
     pub fn new_empty() -> VTable {
         VTable {
-            table: HashMap::new(),
+            table: None,
         }
     }
+    /// Add the methods of another vtable, replacing the ones with the same name
+    /// This is how an object's own methods override the ones it was given. Methods that come from
+    /// places that shouldn't override each other are added with extend_disjoint instead.
     pub fn extend(&mut self, vtable: VTable) {
-        self.table.extend(vtable.table);
+        match (&mut self.table, vtable.table) {
+            (_, None) => {}
+            // Extending with nothing mustn't copy a table that is shared
+            (_, Some(other)) if other.is_empty() => {}
+            (None, table) => self.table = table,
+            (Some(table), Some(other)) => {
+                let table = Arc::make_mut(table);
+                table.extend(other.iter().map(|(name, method)| (*name, method.clone())));
+            }
+        }
     }
-    pub fn get_method(&self, index: &str) -> Option<Arc<Method>> {
-        self.table.get(index).cloned()
+    /// Add the methods of another vtable unless some of them are already in this one
+    /// Unlike extend this leaves the vtable as it was and gives back the names that are in both
+    /// when there is a conflict.
+    pub fn extend_disjoint(&mut self, vtable: VTable) -> Result<(), Vec<Symbol>> {
+        let conflicts: Vec<Symbol> = vtable.iter()
+            .filter(|(name, _)| self.get_method(**name).is_some())
+            .map(|(name, _)| *name)
+            .collect();
+        if !conflicts.is_empty() {
+            return Err(conflicts);
+        }
+        self.extend(vtable);
+        Ok(())
+    }
+    pub fn get_method(&self, index: Symbol) -> Option<Arc<Method>> {
+        self.table.as_ref().and_then(|table| table.get(&index).cloned())
     }
-    pub fn insert(&mut self, index: String, method: Arc<Method>) {
-        self.table.insert(index, method);
+    pub fn insert(&mut self, index: Symbol, method: Arc<Method>) {
+        let table = self.table.get_or_insert_with(|| Arc::new(HashMap::new()));
+        Arc::make_mut(table).insert(index, method);
+    }
+    pub fn remove(&mut self, index: Symbol) {
+        if let Some(table) = &mut self.table {
+            if table.contains_key(&index) {
+                Arc::make_mut(table).remove(&index);
+            }
+        }
     }
     pub fn empty(&self) -> bool {
-        self.table.is_empty()
+        self.table.as_ref().is_none_or(|table| table.is_empty())
+    }
+    /// Check if two vtables share the same table
+    pub fn same_table(&self, other: &VTable) -> bool {
+        match (&self.table, &other.table) {
+            (None, None) => true,
+            (Some(table), Some(other)) => Arc::ptr_eq(table, other),
+            _ => false,
+        }
+    }
+    /// Make a reference to the table that doesn't keep it alive
+    pub fn downgrade(&self) -> WeakVTable {
+        WeakVTable {
+            table: self.table.as_ref().map(Arc::downgrade),
+        }
+    }
+    /// Check if this vtable still shares the table that a weak reference was made from
+    pub fn is(&self, other: &WeakVTable) -> bool {
+        match (&self.table, &other.table) {
+            (None, None) => true,
+            (Some(table), Some(other)) => std::ptr::eq(Arc::as_ptr(table), other.as_ptr()),
+            _ => false,
+        }
+    }
+    /// Iterate over the methods in the vtable
+    pub fn iter(&self) -> impl Iterator<Item = (&Symbol, &Arc<Method>)> {
+        self.table.iter().flat_map(|table| table.iter())
     }
 }
 
+/// WeakVTable
+/// A reference to the table of a vtable that doesn't keep it alive.
+/// While it exists the table's address can't be reused, and changing a vtable whose table has no
+/// other strong references still copies it.
+#[derive(Clone, Debug)]
+pub struct WeakVTable {
+    table: Option<Weak<HashMap<Symbol, Arc<Method>>>>,
+}
+
 impl crate::vm::binary::ToBinary for VTable {
     fn to_binary(&self, string_table: Option<&mut crate::vm::binary::StringTable>) -> Vec<u8> {
         let mut output = Vec::new(); 
         let string_table = string_table.expect("VTable::to_binary called without a StringTable");
-        for (name, method) in self.table.iter() {
-            let idx = string_table.add_string(name.clone());
+        output.extend_from_slice(self.iter().count().to_binary(None).as_slice());
+        for (name, method) in self.iter() {
+            let idx = string_table.add_string(name.as_str().to_string());
             output.extend_from_slice(idx.to_binary(None).as_slice());
-            method.to_binary(None);
+            output.extend_from_slice(method.to_binary(Some(string_table)).as_slice());
         }
         output
     }
@@ -463,6 +624,15 @@ pub struct Class {
     /// The vtables of the parent classes
     /// This is sorted by depth with the deepest at the start and the shallowest at the end.
     overrides: Vec<VTable>,
+    /// The methods of the class object
+    class_methods: VTable,
+    /// The number of class variables
+    class_field_count: usize,
+    /// The selectors that the class leaves to its subclasses
+    /// A class that declares any can't be instantiated.
+    abstract_methods: Vec<Symbol>,
+    /// The names of the interfaces that the class implements
+    interfaces: Vec<String>,
 }
 
 impl Class {
@@ -471,9 +641,25 @@ impl Class {
             parent: parent.map(|x| x.to_string()),
             methods,
             overrides,
+            class_methods: VTable::new_empty(),
+            class_field_count: 0,
+            abstract_methods: Vec::new(),
+            interfaces: Vec::new(),
         }
     }
-    pub fn get_method(&self, index: &str) -> Option<Arc<Method>> {
+    /// Give the class class-side methods and class variables
+    pub fn with_class_side(mut self, class_methods: VTable, class_field_count: usize) -> Class {
+        self.class_methods = class_methods;
+        self.class_field_count = class_field_count;
+        self
+    }
+    /// Give the class the selectors that it requires from its subclasses and the interfaces that it implements
+    pub fn with_requirements(mut self, abstract_methods: Vec<Symbol>, interfaces: Vec<String>) -> Class {
+        self.abstract_methods = abstract_methods;
+        self.interfaces = interfaces;
+        self
+    }
+    pub fn get_method(&self, index: Symbol) -> Option<Arc<Method>> {
         self.methods.get_method(index)
     }
     pub fn get_vtable(&self) -> VTable {
@@ -482,23 +668,164 @@ impl Class {
     pub fn get_overrides(&self) -> Vec<VTable> {
         self.overrides.clone()
     }
+    pub fn get_parent(&self) -> Option<&str> {
+        self.parent.as_deref()
+    }
+    pub fn get_class_vtable(&self) -> VTable {
+        self.class_methods.clone()
+    }
+    pub fn get_class_field_count(&self) -> usize {
+        self.class_field_count
+    }
+    pub fn get_abstract_methods(&self) -> &[Symbol] {
+        &self.abstract_methods
+    }
+    pub fn get_interfaces(&self) -> &[String] {
+        &self.interfaces
+    }
+    pub fn is_abstract(&self) -> bool {
+        !self.abstract_methods.is_empty()
+    }
+    /// Compose traits into the class
+    /// The class's own methods take precedence over the ones from its traits and the ones from its
+    /// traits take precedence over inherited ones. A method that more than one trait gives the
+    /// class is a conflict unless the class defines it itself or all but one of the uses exclude
+    /// or alias it away.
+    pub fn compose(mut self, name: &str, uses: &[(TraitUse, Arc<Trait>)]) -> Result<Class, Fault> {
+        let mut composed = VTable::new_empty();
+        for (trait_use, trait_) in uses {
+            let mut methods = trait_use.methods(trait_)?;
+            for (method, _) in self.methods.iter() {
+                methods.remove(*method);
+            }
+            if let Err(conflicts) = composed.extend_disjoint(methods) {
+                let conflicts: Vec<&str> = conflicts.iter().map(|name| name.as_str()).collect();
+                return Err(Fault::InvalidOperation(format!("class {} gets {} from more than one trait, trait {} must exclude or alias them",
+                    name, conflicts.join(", "), trait_use.name)));
+            }
+        }
+        // The class's own methods were taken out of the trait methods above so a clash here is a bug
+        composed.extend_disjoint(self.methods).map_err(|conflicts| {
+            let conflicts: Vec<&str> = conflicts.iter().map(|name| name.as_str()).collect();
+            Fault::InvalidOperation(format!("class {} has {} from a trait and from itself", name, conflicts.join(", ")))
+        })?;
+        self.methods = composed;
+        Ok(self)
+    }
+}
+
+/// Trait
+/// A Trait is a bundle of methods that gets composed into classes when they are loaded.
+pub struct Trait {
+    methods: VTable,
+}
+
+impl Trait {
+    pub fn new(methods: VTable) -> Trait {
+        Trait {
+            methods,
+        }
+    }
+    pub fn get_vtable(&self) -> VTable {
+        self.methods.clone()
+    }
+}
+
+/// Interface
+/// An Interface is a named list of selectors that a class promises to understand.
+pub struct Interface {
+    selectors: Vec<Symbol>,
+}
+
+impl Interface {
+    pub fn new(selectors: Vec<Symbol>) -> Interface {
+        Interface {
+            selectors,
+        }
+    }
+    pub fn get_selectors(&self) -> &[Symbol] {
+        &self.selectors
+    }
+}
+
+/// Classes and interfaces of a binary that is being checked before it is added
+struct Pending<'a> {
+    classes: HashMap<&'a str, &'a Class>,
+    interfaces: HashMap<&'a str, &'a Interface>,
+}
+
+/// TraitUse
+/// How a class uses a trait. Excluded methods are left out and each alias adds one of the trait's
+/// methods under another name. These are how conflicts between traits get resolved.
+#[derive(Clone, Debug)]
+pub struct TraitUse {
+    /// The name of the trait
+    pub name: String,
+    pub excluded: Vec<Symbol>,
+    /// The new name of a method and the name it has in the trait
+    pub aliases: Vec<(Symbol, Symbol)>,
+}
+
+impl TraitUse {
+    /// Get the methods that the trait gives to a class
+    fn methods(&self, trait_: &Trait) -> Result<VTable, Fault> {
+        let mut methods = trait_.get_vtable();
+        for (alias, method) in self.aliases.iter() {
+            let method = trait_.methods.get_method(*method)
+                .ok_or(Fault::MethodNotFound(format!("{} in trait {}", method, self.name)))?;
+            if methods.get_method(*alias).is_some() {
+                return Err(Fault::InvalidOperation(format!("alias {} is already a method of trait {}", alias, self.name)));
+            }
+            methods.insert(*alias, method);
+        }
+        for method in self.excluded.iter() {
+            methods.remove(*method);
+        }
+        Ok(methods)
+    }
 }
 
+/// Writes a class table entry without the name of the class, which the class doesn't know
 impl crate::vm::binary::ToBinary for Class {
     fn to_binary(&self, string_table: Option<&mut crate::vm::binary::StringTable>) -> Vec<u8> {
         let mut output = Vec::new(); 
         let string_table = string_table.expect("Class::to_binary called without a StringTable");
         if let Some(parent) = &self.parent {
             output.extend_from_slice(&[0x01]);
-            output.extend_from_slice(parent.as_bytes());
+            let idx = string_table.add_string(parent.clone());
+            output.extend_from_slice(idx.to_binary(None).as_slice());
         } else {
             output.extend_from_slice(&[0x00]);
         }
-        self.methods.to_binary(Some(string_table));
+        output.extend_from_slice(self.methods.to_binary(Some(string_table)).as_slice());
 
+        // The loader keeps the deepest override first, so they are written from the shallowest on
         output.extend_from_slice(self.overrides.len().to_binary(None).as_slice());
-        for override_ in self.overrides.iter().rev() {
-            override_.to_binary(Some(string_table));
+        for (depth, override_) in self.overrides.iter().rev().enumerate() {
+            output.extend_from_slice(override_.iter().count().to_binary(None).as_slice());
+            output.extend_from_slice(depth.to_binary(None).as_slice());
+            for (name, method) in override_.iter() {
+                let idx = string_table.add_string(name.as_str().to_string());
+                output.extend_from_slice(idx.to_binary(None).as_slice());
+                output.extend_from_slice(method.to_binary(Some(string_table)).as_slice());
+            }
+        }
+
+        output.extend_from_slice(self.class_field_count.to_binary(None).as_slice());
+        output.extend_from_slice(self.class_methods.to_binary(Some(string_table)).as_slice());
+
+        // Traits were composed into the methods when the class was loaded, so it uses none
+        output.extend_from_slice(0usize.to_binary(None).as_slice());
+
+        output.extend_from_slice(self.abstract_methods.len().to_binary(None).as_slice());
+        for selector in self.abstract_methods.iter() {
+            let idx = string_table.add_string(selector.as_str().to_string());
+            output.extend_from_slice(idx.to_binary(None).as_slice());
+        }
+        output.extend_from_slice(self.interfaces.len().to_binary(None).as_slice());
+        for interface in self.interfaces.iter() {
+            let idx = string_table.add_string(interface.clone());
+            output.extend_from_slice(idx.to_binary(None).as_slice());
         }
 
         output
@@ -506,14 +833,15 @@ impl crate::vm::binary::ToBinary for Class {
 }
 
 
-unsafe impl Send for Method {}
-unsafe impl Sync for Method {}
 /// Method
+/// The signature of a method written in Rust
+pub type RustFunction = dyn Fn(ObjectBox, &mut ContextData) -> Result<Option<ObjectBox>, Fault> + Send + Sync;
+
 /// A method is a function that an object can respond to. It can be a Rust function or a Block
 /// object.
 pub enum Method {
     RustMethod {
-        fun: Box<dyn Fn(ObjectBox, &mut ContextData) -> Result<Option<ObjectBox>, Fault>>,
+        fun: Box<RustFunction>,
     },
     BytecodeMethod {
         block: ObjectBox,
@@ -533,11 +861,26 @@ impl Method {
             }
         }
     }
+    /// Run the method to the end and get what it returns
+    /// Unlike call this doesn't leave bytecode for the interpreter to run later, so Rust methods
+    /// can use the result of sending a message right away.
+    pub fn evaluate(&self, object: ObjectBox, arguments: Vec<ObjectBox>) -> Result<Option<ObjectBox>, Fault> {
+        match self {
+            Method::RustMethod { fun } => {
+                let mut context = ContextData::new(init_stack());
+                context.set_arguments(arguments);
+                context.push(object.clone());
+                fun(object, &mut context)
+            },
+            Method::BytecodeMethod { block } => block::Block::evaluate(block, Some(object), arguments),
+        }
+    }
 }
 
 impl crate::vm::binary::ToBinary for Method {
-    fn to_binary(&self, _: Option<&mut crate::vm::binary::StringTable>) -> Vec<u8> {
+    fn to_binary(&self, string_table: Option<&mut crate::vm::binary::StringTable>) -> Vec<u8> {
         let mut output = Vec::new(); 
+        let string_table = string_table.expect("Method::to_binary called without a StringTable");
         match self {
             Method::RustMethod { fun: _ } => {
                 panic!("Cannot convert a RustMethod to binary");
@@ -547,7 +890,7 @@ impl crate::vm::binary::ToBinary for Method {
                 let bytecode = block.downcast_ref::<block::Block>().unwrap().bytecode.clone();
                 output.extend_from_slice(bytecode.len().to_binary(None).as_slice());
                 for byte in bytecode.iter() {
-                    output.extend_from_slice(byte.to_binary(None).as_slice());
+                    output.extend_from_slice(byte.to_binary(Some(string_table)).as_slice());
                 }
             }
         }
@@ -569,7 +912,7 @@ impl std::fmt::Debug for Method {
 pub struct Message {
     super_object: ObjectBox,
     vtable: VTable,
-    index: String,
+    pub index: Symbol,
 }
 
 
@@ -579,7 +922,7 @@ impl Message {
         Class::new(Some(parent), methods)
     }*/
     pub fn make_object(parent: ObjectBox, 
-                       index: String) -> ObjectBox {
+                       index: Symbol) -> ObjectBox {
         let message = Message {
             super_object: parent,
             index,
@@ -609,7 +952,7 @@ impl Object for Message {
     fn duplicate(&self) -> ObjectBox {
         let message = Message {
             super_object: self.super_object.clone(),
-            index: self.index.clone(),
+            index: self.index,
             vtable: self.vtable.clone(),
         };
         ObjectBox::new(message)
@@ -627,17 +970,161 @@ lazy_static! {
 }
 
 
+/// Prototype
+/// The part of an object that every instance of a type shares.
+/// Instances only hold a reference to the parent and a copy of the vtable which shares its table
+/// so creating an object only allocates the object itself.
+#[derive(Clone)]
+struct Prototype {
+    /// The super object of every instance
+    parent: ObjectBox,
+    /// The vtable every instance starts out with
+    vtable: VTable,
+}
+
+impl Prototype {
+    fn new(parent: ObjectBox, vtable: VTable) -> Prototype {
+        Prototype {
+            parent,
+            vtable,
+        }
+    }
+
+    /// Create an instance with make and give it the shared vtable
+    fn instantiate(&self, make: impl FnOnce(ObjectBox) -> ObjectBox) -> ObjectBox {
+        let object = make(self.parent.clone());
+        if !self.vtable.empty() {
+            object.borrow_mut().initialize(vec![], self.vtable.clone());
+        }
+        object
+    }
+
+    /// Build the prototype of a builtin type
+    /// Every object in the parent chain is new so this should only get called once per type
+    /// unless a class needs to change the chain with its overrides.
+    fn build(name: &str, nil: &ObjectBox) -> Option<Prototype> {
+        let object = |vtable: VTable| {
+            let object = BaseObject::make_object(nil.clone());
+            let mut object_mut = object.borrow_mut();
+            object_mut.initialize(vec![], BaseObject::make_vtable());
+            object_mut.initialize(vec![], vtable);
+            drop(object_mut);
+            object
+        };
+        let number = |parent: ObjectBox, vtable: VTable| {
+            let number = NumberObject::make_object(parent);
+            let mut number_mut = number.borrow_mut();
+            number_mut.initialize(vec![], NumberObject::make_vtable());
+            number_mut.initialize(vec![], vtable);
+            drop(number_mut);
+            number
+        };
+        let integer = |object_vtable: VTable, number_vtable: VTable, integer_vtable: VTable| {
+            let integer = IntegerObject::make_object(number(object(object_vtable), number_vtable));
+            let mut integer_mut = integer.borrow_mut();
+            integer_mut.initialize(vec![], IntegerObject::make_vtable());
+            integer_mut.initialize(vec![], integer_vtable);
+            drop(integer_mut);
+            Prototype::new(integer, VTable::new_empty())
+        };
+        let float = |object_vtable: VTable, number_vtable: VTable, float_vtable: VTable| {
+            let float = FloatObject::make_object(number(object(object_vtable), number_vtable));
+            let mut float_mut = float.borrow_mut();
+            float_mut.initialize(vec![], FloatObject::make_vtable());
+            float_mut.initialize(vec![], float_vtable);
+            drop(float_mut);
+            Prototype::new(float, VTable::new_empty())
+        };
+        let plain = |vtable: VTable| Prototype::new(object(VTable::new_empty()), vtable);
+
+        let prototype = match name {
+            "Object" => Prototype::new(nil.clone(), BaseObject::make_vtable()),
+            "Number" => Prototype::new(object(VTable::new_empty()), NumberObject::make_vtable()),
+            "Integer" => Prototype::new(number(object(VTable::new_empty()), VTable::new_empty()), IntegerObject::make_vtable()),
+            "Float" => Prototype::new(number(object(VTable::new_empty()), VTable::new_empty()), FloatObject::make_vtable()),
+            "I64" => integer(I64Object::make_object_vtable(), I64Object::make_number_vtable(), I64Object::make_integer_vtable()),
+            "U64" => integer(U64Object::make_object_vtable(), U64Object::make_number_vtable(), U64Object::make_integer_vtable()),
+            "I32" => integer(I32Object::make_object_vtable(), I32Object::make_number_vtable(), I32Object::make_integer_vtable()),
+            "U32" => integer(U32Object::make_object_vtable(), U32Object::make_number_vtable(), U32Object::make_integer_vtable()),
+            "I16" => integer(I16Object::make_object_vtable(), I16Object::make_number_vtable(), I16Object::make_integer_vtable()),
+            "U16" => integer(U16Object::make_object_vtable(), U16Object::make_number_vtable(), U16Object::make_integer_vtable()),
+            "I8" => integer(I8Object::make_object_vtable(), I8Object::make_number_vtable(), I8Object::make_integer_vtable()),
+            "U8" => integer(U8Object::make_object_vtable(), U8Object::make_number_vtable(), U8Object::make_integer_vtable()),
+            "F64" => float(F64Object::make_object_vtable(), F64Object::make_number_vtable(), F64Object::make_float_vtable()),
+            "BigInt" => integer(BigIntObject::make_object_vtable(), BigIntObject::make_number_vtable(), BigIntObject::make_integer_vtable()),
+            "F32" => float(F32Object::make_object_vtable(), F32Object::make_number_vtable(), F32Object::make_float_vtable()),
+            "Rational" => Prototype::new(number(object(RationalObject::make_object_vtable()), RationalObject::make_number_vtable()), VTable::new_empty()),
+            "Decimal" => Prototype::new(number(object(DecimalObject::make_object_vtable()), DecimalObject::make_number_vtable()), VTable::new_empty()),
+            "String" => Prototype::new(object(StringObject::make_object_vtable()), StringObject::make_vtable()),
+            "Char" => Prototype::new(object(CharacterObject::make_object_vtable()), CharacterObject::make_vtable()),
+            "Symbol" => Prototype::new(object(SymbolObject::make_object_vtable()), SymbolObject::make_vtable()),
+            "Boolean" => Prototype::new(object(BooleanObject::make_object_vtable()), BooleanObject::make_vtable()),
+            "Message" => plain(VTable::new_empty()),
+            "Logger" => plain(Logger::make_vtable()),
+            "Stack" => plain(stack::Stack::make_vtable()),
+            "Block" => plain(block::Block::make_vtable()),
+            "Vector" => plain(vector::VectorObject::make_vtable()),
+            "Regex" => plain(self::regex::RegexObject::make_vtable()),
+            "Match" => plain(self::regex::MatchObject::make_vtable()),
+            "StringBuilder" => plain(builder::StringBuilder::make_vtable()),
+            "ByteArray" => plain(bytes::ByteArray::make_vtable()),
+            "List" => plain(list::ListObject::make_vtable()),
+            "Set" => plain(set::SetObject::make_vtable()),
+            "Iterator" => plain(iterator::IteratorObject::make_vtable()),
+            "Range" => plain(range::RangeObject::make_vtable()),
+            "WeakRef" => plain(weak::WeakRef::make_vtable()),
+            "Class" => plain(class::ClassObject::make_vtable()),
+            "System" => plain(system::System::make_vtable()),
+            "Context" => plain(Context::make_vtable()),
+            _ => return None,
+        };
+        Some(prototype)
+    }
+}
+
+/// The builtin types that get a shared prototype
+const BUILTIN_TYPES: &[&str] = &[
+    "Object", "Number", "Integer", "Float", "I64", "U64", "I32", "U32", "I16", "U16", "I8", "U8",
+    "BigInt", "F64", "F32", "Rational", "Decimal", "String", "Char", "Symbol", "Boolean", "Message", "Logger", "Stack", "Block", "Vector",
+    "Regex", "Match", "StringBuilder", "ByteArray", "List", "Set", "Iterator", "Range", "System", "Context", "WeakRef", "Class",
+];
 
 pub struct ObjectFactory {
     classes: HashMap<String, Arc<Class>>,
     parents: HashMap<String, String>,
+    /// The shared prototypes of the builtin types
+    prototypes: HashMap<String, Prototype>,
+    /// The shared super objects of the instances of each class
+    /// These get built the first time a class is instantiated.
+    class_parents: Mutex<HashMap<String, ObjectBox>>,
+    /// The class object of each class
+    /// These get built the first time they are asked for.
+    class_objects: Mutex<HashMap<String, ObjectBox>>,
+    /// The class objects whose parent class was replaced before the new parent could be built
+    unbound_class_objects: Mutex<HashSet<String>>,
+    /// The traits that have been loaded so that classes in later binaries can use them
+    traits: HashMap<String, Arc<Trait>>,
+    /// The interfaces that have been loaded
+    interfaces: HashMap<String, Arc<Interface>>,
+    nil: ObjectBox,
 }
 
 impl ObjectFactory {
     fn new() -> ObjectFactory {
+        let nil = Nil::new();
+        let prototypes = BUILTIN_TYPES.iter()
+            .map(|name| (name.to_string(), Prototype::build(name, &nil).unwrap()))
+            .collect();
         let mut context = ObjectFactory {
             classes: HashMap::new(),
             parents: HashMap::new(),
+            prototypes,
+            class_parents: Mutex::new(HashMap::new()),
+            class_objects: Mutex::new(HashMap::new()),
+            unbound_class_objects: Mutex::new(HashSet::new()),
+            traits: HashMap::new(),
+            interfaces: HashMap::new(),
+            nil,
         };
         
         context.parents.insert(String::from("Message"), String::from("Object"));
@@ -646,6 +1133,7 @@ impl ObjectFactory {
         context.parents.insert(String::from("Float"), String::from("Number"));
         context.parents.insert(String::from("String"), String::from("Object"));
         context.parents.insert(String::from("Char"), String::from("Object"));
+        context.parents.insert(String::from("Symbol"), String::from("Object"));
         context.parents.insert(String::from("Stack"), String::from("Object"));
         context.parents.insert(String::from("Block"), String::from("Object"));
         context.parents.insert(String::from("Logger"), String::from("Object"));
@@ -657,166 +1145,495 @@ impl ObjectFactory {
         context.parents.insert(String::from("U16"), String::from("Integer"));
         context.parents.insert(String::from("I8"), String::from("Integer"));
         context.parents.insert(String::from("U8"), String::from("Integer"));
+        context.parents.insert(String::from("BigInt"), String::from("Integer"));
         context.parents.insert(String::from("F64"), String::from("Float"));
         context.parents.insert(String::from("F32"), String::from("Float"));
+        context.parents.insert(String::from("Rational"), String::from("Number"));
+        context.parents.insert(String::from("Decimal"), String::from("Number"));
         context.parents.insert(String::from("Boolean"), String::from("Object"));
         context.parents.insert(String::from("Vector"), String::from("Object"));
+        context.parents.insert(String::from("Regex"), String::from("Object"));
+        context.parents.insert(String::from("Match"), String::from("Object"));
+        context.parents.insert(String::from("StringBuilder"), String::from("Object"));
+        context.parents.insert(String::from("ByteArray"), String::from("Object"));
+        context.parents.insert(String::from("List"), String::from("Object"));
+        context.parents.insert(String::from("Set"), String::from("Object"));
+        context.parents.insert(String::from("Iterator"), String::from("Object"));
+        context.parents.insert(String::from("Range"), String::from("Object"));
         context.parents.insert(String::from("System"), String::from("Object"));
+        context.parents.insert(String::from("WeakRef"), String::from("Object"));
+        context.parents.insert(String::from("Class"), String::from("Object"));
 
 
         context
     }
     fn add_class(&mut self, name: &str, class: Class) {
         match &class.parent {
-            Some(parent) => {
-                self.parents.insert(name.to_string(), parent.to_string());
+            Some(parent) => self.parents.insert(name.to_string(), parent.to_string()),
+            None => self.parents.remove(name),
+        };
+        let class = Arc::new(class);
+        self.classes.insert(name.to_string(), class);
+        // A class may be replaced so any parent built from the old one is stale
+        self.class_parents.lock().unwrap().clear();
+        // The class object of the class itself is rebuilt from the new definition. Its subclasses
+        // keep theirs so that their class variables survive and only get pointed at the new one.
+        let mut class_objects = self.class_objects.lock().unwrap();
+        class_objects.remove(name);
+        let mut unbound = self.unbound_class_objects.lock().unwrap();
+        unbound.extend(class_objects.keys().filter(|class| self.parents.get(*class).is_some_and(|parent| parent == name)).cloned());
+        let unbound_classes: Vec<String> = unbound.drain().collect();
+        drop(unbound);
+        drop(class_objects);
+        for class in unbound_classes {
+            self.rebind_class_object(&class);
+        }
+    }
+    /// Point a class object at the current class object of its parent
+    /// When the parent can't be built yet, because one of its ancestors hasn't been loaded, this
+    /// is tried again every time a class is added.
+    fn rebind_class_object(&self, name: &str) {
+        let Some(class_object) = self.class_objects.lock().unwrap().get(name).cloned() else {
+            return;
+        };
+        let parent = self.parents.get(name).map(|parent| self.get_class_object(parent));
+        match parent {
+            Some(Ok(parent)) => {
+                if let Some(class_object) = class_object.borrow_mut().downcast_mut::<class::ClassObject>() {
+                    class_object.set_parent(parent);
+                }
+            }
+            Some(Err(_)) => {
+                self.unbound_class_objects.lock().unwrap().insert(name.to_string());
             }
             None => {}
         }
-        let class = Arc::new(class);
-        self.classes.insert(name.to_string(), class);
+    }
+    /// Get the object that stands for a class or a builtin type
+    fn get_class_object(&self, name: &str) -> Result<ObjectBox, Fault> {
+        if let Some(class_object) = self.class_objects.lock().unwrap().get(name) {
+            return Ok(class_object.clone());
+        }
+        let (vtable, field_count) = match self.get_class(name) {
+            Some(class) => (class.get_class_vtable(), class.get_class_field_count()),
+            None if self.prototypes.contains_key(name) => (VTable::new_empty(), 0),
+            None => return Err(Fault::InvalidType(format!("object not found: {}", name))),
+        };
+        let class_object = match self.parents.get(name) {
+            Some(parent) => class::ClassObject::make_object(self.get_class_object(parent)?, name.to_string(), field_count),
+            None => self.prototype("Class").instantiate(|parent| class::ClassObject::make_object(parent, name.to_string(), field_count)),
+        };
+        if !vtable.empty() {
+            class_object.borrow_mut().initialize(vec![], vtable);
+        }
+        // Another core may have built it first and there must only be one
+        let class_object = self.class_objects.lock().unwrap()
+            .entry(name.to_string())
+            .or_insert(class_object)
+            .clone();
+        Ok(class_object)
     }
     fn get_class(&self, name: &str) -> Option<Arc<Class>> {
         self.classes.get(name).cloned()
     } 
+    fn add_trait(&mut self, name: &str, trait_: Arc<Trait>) {
+        self.traits.insert(name.to_string(), trait_);
+    }
+    fn get_trait(&self, name: &str) -> Option<Arc<Trait>> {
+        self.traits.get(name).cloned()
+    }
+    fn add_interface(&mut self, name: &str, interface: Arc<Interface>) {
+        self.interfaces.insert(name.to_string(), interface);
+    }
+
+    /// Find the selectors that a class requires but doesn't provide
+    /// The requirements are the abstract selectors and the interfaces of the class and its
+    /// ancestors. A selector is provided when the class or one of its ancestors has a method for
+    /// it. Abstract classes leave this to their subclasses so they never miss anything.
+    /// Classes and interfaces in pending are used before the ones that have been added so that a
+    /// binary can be checked before anything in it is added.
+    fn missing_methods(&self, name: &str, pending: &Pending) -> Result<Vec<Symbol>, Fault> {
+        let get_class = |name: &str| pending.classes.get(name).copied()
+            .or_else(|| self.classes.get(name).map(|class| &**class));
+        let class = get_class(name).ok_or(Fault::InvalidType(format!("object not found: {}", name)))?;
+        if class.is_abstract() {
+            return Ok(Vec::new());
+        }
+        let mut required: Vec<Symbol> = Vec::new();
+        let mut provided: Vec<VTable> = Vec::new();
+        let mut current = Some(name);
+        while let Some(name) = current {
+            match get_class(name) {
+                Some(ancestor) => {
+                    required.extend(ancestor.get_abstract_methods());
+                    for interface in ancestor.get_interfaces() {
+                        let interface = pending.interfaces.get(interface.as_str()).copied()
+                            .or_else(|| self.interfaces.get(interface).map(|interface| &**interface))
+                            .ok_or(Fault::InvalidType(format!("interface not found: {}", interface)))?;
+                        required.extend(interface.get_selectors());
+                    }
+                    provided.push(ancestor.get_vtable());
+                    provided.extend(ancestor.get_overrides());
+                    current = ancestor.get_parent();
+                }
+                None => {
+                    if let Some(prototype) = self.prototypes.get(name) {
+                        provided.push(prototype.vtable.clone());
+                    }
+                    current = self.parents.get(name).map(|parent| parent.as_str());
+                }
+            }
+        }
+        let mut missing: Vec<Symbol> = Vec::new();
+        for selector in required {
+            if missing.contains(&selector) {
+                continue;
+            }
+            if !provided.iter().any(|vtable| vtable.get_method(selector).is_some()) {
+                missing.push(selector);
+            }
+        }
+        Ok(missing)
+    }
+
+    fn prototype(&self, name: &str) -> &Prototype {
+        self.prototypes.get(name).expect("ObjectFactory: missing builtin prototype")
+    }
 
     fn create_base_object(&self) -> ObjectBox {
-        let object = BaseObject::make_object(Nil::new());
-        let mut object_mut = object.borrow_mut();
-        object_mut.initialize(vec![], VTable::new_empty());
-        drop(object_mut);
-        object
+        self.prototype("Object").instantiate(BaseObject::make_object)
     }
     fn create_boolean(&self, value: bool) -> ObjectBox {
-        BooleanObject::make_object(self.create_base_object(), value)
-    }
-    fn create_number(&self) -> ObjectBox {
-        let number = NumberObject::make_object(self.create_base_object());
-        let mut object = number.borrow_mut();
-        object.initialize(vec![], VTable::new_empty());
-        drop(object);
-        number
-    }
-    fn create_integer(&self) -> ObjectBox {
-        let number = IntegerObject::make_object(self.create_number());
-        let mut object = number.borrow_mut();
-        object.initialize(vec![], VTable::new_empty());
-        drop(object);
-        number
+        self.prototype("Boolean").instantiate(|parent| BooleanObject::make_object(parent, value))
     }
     fn create_i64(&self, value: i64) -> ObjectBox {
-        I64Object::make_object(self.create_integer(), value) 
+        self.prototype("I64").instantiate(|parent| I64Object::make_object(parent, value))
     }
     fn create_u64(&self, value: u64) -> ObjectBox {
-        U64Object::make_object(self.create_integer(), value)
+        self.prototype("U64").instantiate(|parent| U64Object::make_object(parent, value))
     }
     fn create_i32(&self, value: i32) -> ObjectBox {
-        I32Object::make_object(self.create_integer(), value)
+        self.prototype("I32").instantiate(|parent| I32Object::make_object(parent, value))
     }
     fn create_u32(&self, value: u32) -> ObjectBox {
-        U32Object::make_object(self.create_integer(), value)
+        self.prototype("U32").instantiate(|parent| U32Object::make_object(parent, value))
     }
     fn create_i16(&self, value: i16) -> ObjectBox {
-        I16Object::make_object(self.create_integer(), value) 
+        self.prototype("I16").instantiate(|parent| I16Object::make_object(parent, value))
     }
     fn create_u16(&self, value: u16) -> ObjectBox {
-        U16Object::make_object(self.create_integer(), value)
+        self.prototype("U16").instantiate(|parent| U16Object::make_object(parent, value))
     }
     fn create_i8(&self, value: i8) -> ObjectBox {
-        I8Object::make_object(self.create_integer(), value)
+        self.prototype("I8").instantiate(|parent| I8Object::make_object(parent, value))
     }
     fn create_u8(&self, value: u8) -> ObjectBox {
-        U8Object::make_object(self.create_integer(), value)
+        self.prototype("U8").instantiate(|parent| U8Object::make_object(parent, value))
     }
-    fn create_float(&self) -> ObjectBox {
-        FloatObject::make_object(self.create_number())
+    fn create_bigint(&self, value: BigInt) -> ObjectBox {
+        self.prototype("BigInt").instantiate(|parent| BigIntObject::make_object(parent, value))
     }
     fn create_f64(&self, value: f64) -> ObjectBox {
-        F64Object::make_object(self.create_float(), value) 
+        self.prototype("F64").instantiate(|parent| F64Object::make_object(parent, value))
     }
     fn create_f32(&self, value: f32) -> ObjectBox {
-        F32Object::make_object(self.create_float(), value)
+        self.prototype("F32").instantiate(|parent| F32Object::make_object(parent, value))
+    }
+    fn create_rational(&self, value: Rational) -> ObjectBox {
+        self.prototype("Rational").instantiate(|parent| RationalObject::make_object(parent, value))
+    }
+    fn create_decimal(&self, value: Decimal) -> ObjectBox {
+        self.prototype("Decimal").instantiate(|parent| DecimalObject::make_object(parent, value))
     }
     fn create_string(&self, value: String) -> ObjectBox {
-        StringObject::make_object(self.create_base_object(), value)    
+        self.prototype("String").instantiate(|parent| StringObject::make_object(parent, value))
     }
     fn create_character(&self, value: char) -> ObjectBox {
-        CharacterObject::make_object(self.create_base_object(), value)    
+        self.prototype("Char").instantiate(|parent| CharacterObject::make_object(parent, value))
+    }
+    fn create_symbol(&self, value: Symbol) -> ObjectBox {
+        self.prototype("Symbol").instantiate(|parent| SymbolObject::make_object(parent, value))
     }
     fn create_message(&self, index: &str) -> ObjectBox {
-        Message::make_object(self.create_base_object(), index.to_string()) 
+        self.prototype("Message").instantiate(|parent| Message::make_object(parent, Symbol::intern(index)))
     }
     fn create_logger(&self) -> ObjectBox {
-        Logger::make_object(self.create_base_object())
+        self.prototype("Logger").instantiate(Logger::make_object)
     }
     fn init_stack(&self) -> ObjectBox {
-        let context = Context::make_object();
-        let mut context_mut = context.borrow_mut();
-        context_mut.initialize(vec![], VTable::new_empty());
-        drop(context_mut);
+        let context = self.prototype("Context").instantiate(Context::make_object);
         let framedata = vec![context];
-        let frame = vec![stack::Stack::make_object_with_stack(self.create_base_object(),framedata)];
-        stack::Stack::make_object_with_stack(self.create_base_object(), frame) 
+        let frame = vec![self.prototype("Stack").instantiate(|parent| stack::Stack::make_object_with_stack(parent, framedata))];
+        self.prototype("Stack").instantiate(|parent| stack::Stack::make_object_with_stack(parent, frame))
     }
     fn create_stack(&self) -> ObjectBox {
-        stack::Stack::make_object(self.create_base_object())
+        self.prototype("Stack").instantiate(stack::Stack::make_object)
     }
     fn create_block(&self, bytecode: Vec<ByteCode>) -> ObjectBox {
-        block::Block::make_object(self.create_base_object(), bytecode)
+        self.prototype("Block").instantiate(|parent| block::Block::make_object(parent, bytecode))
     }
     fn create_vector(&self, vector: Vec<ObjectBox>) -> ObjectBox {
-        vector::VectorObject::make_object(self.create_base_object(), vector.into())
+        self.prototype("Vector").instantiate(|parent| vector::VectorObject::make_object(parent, vector.into()))
+    }
+    fn create_match(&self, value: self::regex::Match) -> ObjectBox {
+        self.prototype("Match").instantiate(|parent| self::regex::MatchObject::make_object(parent, value))
+    }
+    fn create_list(&self, value: Vec<ObjectBox>) -> ObjectBox {
+        self.prototype("List").instantiate(|parent| list::ListObject::make_object(parent, value))
+    }
+    fn create_set(&self, value: set::ObjectSet) -> ObjectBox {
+        self.prototype("Set").instantiate(|parent| set::SetObject::make_object(parent, value))
+    }
+    fn create_iterator(&self, source: iterator::Source) -> ObjectBox {
+        self.prototype("Iterator").instantiate(|parent| iterator::IteratorObject::make_object(parent, source))
+    }
+    fn create_range(&self, value: range::Range) -> ObjectBox {
+        self.prototype("Range").instantiate(|parent| range::RangeObject::make_object(parent, value))
+    }
+    fn create_byte_array(&self, value: Vec<u8>) -> ObjectBox {
+        self.prototype("ByteArray").instantiate(|parent| bytes::ByteArray::make_object(parent, value))
     }
     fn create_system(&self) -> ObjectBox {
-        system::System::make_object(self.create_base_object())
+        self.prototype("System").instantiate(system::System::make_object)
+    }
+    fn create_weak_ref(&self, target: &ObjectBox) -> ObjectBox {
+        self.prototype("WeakRef").instantiate(|parent| weak::WeakRef::make_object(parent, Some(target.downgrade())))
     }
 
+    /// Get the shared super object for instances of a class
+    /// Every instance of the class refers to the same parent chain. A super send gives an instance
+    /// its own copy of the parent first, see ObjectStruct::own_super_object.
     fn make_parent(&self, name: &str) -> Result<ObjectBox, Fault> {
-        self.create_object(self.parents.get(name).ok_or(Fault::InvalidType(format!("object not found: {}", name)))?, &[])
+        if let Some(parent) = self.class_parents.lock().unwrap().get(name) {
+            return Ok(parent.clone());
+        }
+        let parent = self.build_parent(name, false)?;
+        // Another core may have built it first and the instances should all share one
+        let parent = self.class_parents.lock().unwrap()
+            .entry(name.to_string())
+            .or_insert(parent)
+            .clone();
+        Ok(parent)
+    }
+
+    /// Build the super object for instances of a class and apply the class's overrides to it.
+    /// The overrides change the objects in the parent chain so when there are any the chain
+    /// gets built from scratch instead of reusing the shared objects.
+    fn build_parent(&self, name: &str, detached: bool) -> Result<ObjectBox, Fault> {
+        let parent_name = self.parents.get(name).ok_or(Fault::InvalidType(format!("object not found: {}", name)))?;
+        let mut overrides = self.get_class(name).map(|class| class.get_overrides()).unwrap_or_default();
+        let detached = detached || !overrides.is_empty();
+        let parent = self.create_parent_instance(parent_name, detached)?;
+        let mut object = Some(parent.clone());
+        while let Some(current) = object {
+            let vtable = match overrides.pop() {
+                Some(vtable) => vtable,
+                None => break,
+            };
+            current.borrow_mut().initialize(vec![], vtable);
+            object = current.borrow().get_super_object();
+        }
+        Ok(parent)
     }
-    
-    
-    fn create_object(&self, name: &str, arguments: &[ObjectBox]) -> Result<ObjectBox, Fault> {
-        match name {
-            "Object" => Ok(self.create_base_object()),
-            "Number" => Ok(self.create_number()),
-            "Integer" => Ok(self.create_integer()),
-            "Float" => Ok(self.create_float()),
-            "I64" => Ok(self.create_i64(0)),
-            "U64" => Ok(self.create_u64(0)),
-            "I32" => Ok(self.create_i32(0)),
-            "U32" => Ok(self.create_u32(0)),
-            "I16" => Ok(self.create_i16(0)),
-            "U16" => Ok(self.create_u16(0)),
-            "I8" => Ok(self.create_i8(0)),
-            "U8" => Ok(self.create_u8(0)),
-            "F64" => Ok(self.create_f64(0.0)),
-            "F32" => Ok(self.create_f32(0.0)),
-            "String" => Ok(self.create_string("".to_string())),//TODO: add way to create it from vector
-            "Char" => Ok(self.create_character(' ')),
+
+    /// Create an initialized object that is used as the super object of a class's instances
+    fn create_parent_instance(&self, name: &str, detached: bool) -> Result<ObjectBox, Fault> {
+        if self.prototypes.contains_key(name) {
+            if detached {
+                let prototype = Prototype::build(name, &self.nil).unwrap();
+                return self.create_builtin(name, &prototype, &[]);
+            }
+            return self.create_builtin(name, self.prototype(name), &[]);
+        }
+        let parent = if detached {
+            self.build_parent(name, true)?
+        } else {
+            self.make_parent(name)?
+        };
+        let object = ObjectStruct::new(self.get_class(name), Some(parent));
+        object.borrow_mut().initialize(vec![], VTable::new_empty());
+        Ok(object)
+    }
+
+    fn create_builtin(&self, name: &str, prototype: &Prototype, arguments: &[ObjectBox]) -> Result<ObjectBox, Fault> {
+        let object = match name {
+            "Object" => prototype.instantiate(BaseObject::make_object),
+            "Number" => prototype.instantiate(NumberObject::make_object),
+            "Integer" => prototype.instantiate(IntegerObject::make_object),
+            "Float" => prototype.instantiate(FloatObject::make_object),
+            "I64" => prototype.instantiate(|parent| I64Object::make_object(parent, 0)),
+            "U64" => prototype.instantiate(|parent| U64Object::make_object(parent, 0)),
+            "I32" => prototype.instantiate(|parent| I32Object::make_object(parent, 0)),
+            "U32" => prototype.instantiate(|parent| U32Object::make_object(parent, 0)),
+            "I16" => prototype.instantiate(|parent| I16Object::make_object(parent, 0)),
+            "U16" => prototype.instantiate(|parent| U16Object::make_object(parent, 0)),
+            "I8" => prototype.instantiate(|parent| I8Object::make_object(parent, 0)),
+            "U8" => prototype.instantiate(|parent| U8Object::make_object(parent, 0)),
+            "BigInt" => {
+                let value = match arguments {
+                    [] => BigInt::zero(),
+                    [argument] => primitive::bigint::from_object(argument)?,
+                    _ => return Err(Fault::InvalidType(format!("expected at most 1 argument, got {}", arguments.len()))),
+                };
+                prototype.instantiate(|parent| BigIntObject::make_object(parent, value))
+            },
+            "F64" => prototype.instantiate(|parent| F64Object::make_object(parent, 0.0)),
+            "F32" => prototype.instantiate(|parent| F32Object::make_object(parent, 0.0)),
+            "Rational" => {
+                let value = primitive::rational::from_arguments(arguments)?;
+                prototype.instantiate(|parent| RationalObject::make_object(parent, value))
+            },
+            "Decimal" => {
+                let value = primitive::decimal::from_arguments(arguments)?;
+                prototype.instantiate(|parent| DecimalObject::make_object(parent, value))
+            },
+            "String" => prototype.instantiate(|parent| StringObject::make_object(parent, "".to_string())),//TODO: add way to create it from vector
+            "Char" => prototype.instantiate(|parent| CharacterObject::make_object(parent, ' ')),
+            "Symbol" => {
+                if arguments.len() == 1 {
+                    let name = arguments[0].borrow();
+                    let name = name.downcast_ref::<StringObject>().ok_or(Fault::InvalidType("argument wasn't a string".to_string()))?;
+                    prototype.instantiate(|parent| SymbolObject::make_object(parent, Symbol::intern(&name.value)))
+                } else {
+                    return Err(Fault::InvalidType(format!("expected 1 argument, got {}", arguments.len())));
+                }
+            },
+            "Boolean" => prototype.instantiate(|parent| BooleanObject::make_object(parent, false)),
             "Message" => {
                 if arguments.len() == 1 {
                     let message = arguments[0].borrow();
-                    let message = message.downcast_ref::<StringObject>().ok_or(Fault::InvalidType(format!("argument wasn't a string")))?;
-                    Ok(self.create_message(&message.value))
+                    let index = if let Some(symbol) = message.downcast_ref::<PrimitiveObject<Symbol>>() {
+                        symbol.data
+                    } else {
+                        let message = message.downcast_ref::<StringObject>().ok_or(Fault::InvalidType("argument wasn't a string or symbol".to_string()))?;
+                        Symbol::intern(&message.value)
+                    };
+                    prototype.instantiate(|parent| Message::make_object(parent, index))
                 } else {
-                    Err(Fault::InvalidType(format!("expected 1 argument, got {}", arguments.len())))
+                    return Err(Fault::InvalidType(format!("expected 1 argument, got {}", arguments.len())));
                 }
             },
-            "Logger" => Ok(self.create_logger()),
-            "Stack" => Ok(self.create_stack()),
-            "Block" => Ok(self.create_block(vec![])),
-            "Vector" => {
-                let vector = Vec::new();
-                Ok(self.create_vector(vector))
+            "Logger" => prototype.instantiate(Logger::make_object),
+            "Stack" => prototype.instantiate(stack::Stack::make_object),
+            "Block" => prototype.instantiate(|parent| block::Block::make_object(parent, vec![])),
+            "Vector" => prototype.instantiate(|parent| vector::VectorObject::make_object(parent, Vec::new().into())),
+            "Regex" => {
+                let value = self::regex::from_arguments(arguments)?;
+                prototype.instantiate(|parent| self::regex::RegexObject::make_object(parent, value))
+            },
+            "Match" => return Err(Fault::InvalidOperation("Match objects are made by a Regex".to_string())),
+            "StringBuilder" => {
+                let value = builder::from_arguments(arguments)?;
+                prototype.instantiate(|parent| builder::StringBuilder::make_object(parent, value))
+            },
+            "ByteArray" => {
+                let value = bytes::from_arguments(arguments)?;
+                prototype.instantiate(|parent| bytes::ByteArray::make_object(parent, value))
+            },
+            "List" => {
+                let value = list::from_arguments(arguments)?;
+                prototype.instantiate(|parent| list::ListObject::make_object(parent, value))
             },
-            "System" => Ok(self.create_system()),
-            x => {
-                let object = ObjectStruct::new(self.get_class(x), Some(self.make_parent(x)?));
+            "Set" => {
+                let value = set::from_arguments(arguments)?;
+                prototype.instantiate(|parent| set::SetObject::make_object(parent, value))
+            },
+            "Iterator" => return Err(Fault::InvalidOperation("Iterator objects are made by sending iterator to a collection".to_string())),
+            "Range" => {
+                let value = range::from_arguments(arguments)?;
+                prototype.instantiate(|parent| range::RangeObject::make_object(parent, value))
+            },
+            "System" => prototype.instantiate(system::System::make_object),
+            "Context" => prototype.instantiate(Context::make_object),
+            "WeakRef" => {
+                if arguments.len() <= 1 {
+                    let target = arguments.first().map(ObjectBox::downgrade);
+                    prototype.instantiate(|parent| weak::WeakRef::make_object(parent, target))
+                } else {
+                    return Err(Fault::InvalidType(format!("expected at most 1 argument, got {}", arguments.len())));
+                }
+            },
+            x => return Err(Fault::InvalidType(format!("object not found: {}", x))),
+        };
+        Ok(object)
+    }
+    
+    fn create_object(&self, name: &str, arguments: &[ObjectBox]) -> Result<ObjectBox, Fault> {
+        match self.prototypes.get(name) {
+            Some(prototype) => self.create_builtin(name, prototype, arguments),
+            None => {
+                let class = self.get_class(name);
+                if class.as_ref().is_some_and(|class| class.is_abstract()) {
+                    return Err(Fault::InvalidOperation(format!("can't instantiate abstract class {}", name)));
+                }
+                let object = ObjectStruct::new(class, Some(self.make_parent(name)?));
                 Ok(object)
             }
         }
     }
+
+    /// Create an object like create_object but with the given super object instead of the shared one
+    fn create_object_with_parent(&self, name: &str, parent: Option<ObjectBox>, arguments: &[ObjectBox]) -> Result<ObjectBox, Fault> {
+        let parent = parent.unwrap_or_else(|| self.nil.clone());
+        match self.prototypes.get(name) {
+            Some(prototype) => {
+                let prototype = Prototype::new(parent, prototype.vtable.clone());
+                self.create_builtin(name, &prototype, arguments)
+            }
+            None => {
+                let class = self.get_class(name).ok_or(Fault::InvalidType(format!("object not found: {}", name)))?;
+                if class.is_abstract() {
+                    return Err(Fault::InvalidOperation(format!("can't instantiate abstract class {}", name)));
+                }
+                Ok(ObjectStruct::new(Some(class), Some(parent)))
+            }
+        }
+    }
+
+    /// Find every object that the factory shares between instances
+    fn shared_objects(&self) -> Vec<(SharedObject, ObjectBox)> {
+        let mut shared = Vec::new();
+        let mut walk = |make: &dyn Fn(usize) -> SharedObject, parent: &ObjectBox| {
+            let mut object = Some(parent.clone());
+            let mut depth = 0;
+            while let Some(current) = object {
+                shared.push((make(depth), current.clone()));
+                object = current.borrow().get_super_object();
+                depth += 1;
+            }
+        };
+        for (name, prototype) in self.prototypes.iter() {
+            walk(&|depth| SharedObject::Builtin(name.clone(), depth), &prototype.parent);
+        }
+        shared
+    }
+
+    fn get_shared_object(&self, shared: &SharedObject) -> Result<ObjectBox, Fault> {
+        let (mut object, depth) = match shared {
+            SharedObject::Builtin(name, depth) => {
+                let prototype = self.prototypes.get(name).ok_or(Fault::InvalidType(format!("object not found: {}", name)))?;
+                (prototype.parent.clone(), *depth)
+            }
+        };
+        for _ in 0..depth {
+            let parent = object.borrow().get_super_object();
+            object = parent.ok_or(Fault::InvalidOperation("shared object is deeper than its parent chain".to_string()))?;
+        }
+        Ok(object)
+    }
+
+    fn class_name(&self, class: &Arc<Class>) -> Option<String> {
+        self.classes.iter()
+            .find(|(_, other)| Arc::ptr_eq(class, other))
+            .map(|(name, _)| name.clone())
+    }
+}
+
+/// SharedObject
+/// An object in the parent chain that the factory shares between every instance of a builtin type.
+/// The depth is how far down the chain from the shared super object it is.
+pub enum SharedObject {
+    Builtin(String, usize),
 }
 
 fn get_factory<'a>() -> std::sync::RwLockReadGuard<'a, ObjectFactory> {
@@ -845,9 +1662,60 @@ fn get_factory_mut<'a>() -> std::sync::RwLockWriteGuard<'a, ObjectFactory> {
     }
 }
 
+/// Bumped every time a class is added or replaced, or an object is given methods of its own, so
+/// that cached method lookups can tell that they might be stale.
+static CLASS_EPOCH: AtomicUsize = AtomicUsize::new(0);
+
 pub fn add_class(name: &str, class: Class) {
     let mut factory = get_factory_mut();
     factory.add_class(name, class);
+    CLASS_EPOCH.fetch_add(1, Ordering::Release);
+}
+
+/// Add a trait so that classes that get loaded later can use it
+pub fn add_trait(name: &str, trait_: Arc<Trait>) {
+    get_factory_mut().add_trait(name, trait_);
+}
+
+pub fn get_trait(name: &str) -> Option<Arc<Trait>> {
+    get_factory().get_trait(name)
+}
+
+pub fn add_interface(name: &str, interface: Arc<Interface>) {
+    get_factory_mut().add_interface(name, interface);
+}
+
+/// Check that every concrete class of a binary provides the selectors that it and its ancestors require
+/// The classes and interfaces aren't added, so a binary that fails the check leaves the loaded
+/// classes as they were. Ancestors are looked up in the binary first since they may come later
+/// in its class table.
+pub fn check_classes(classes: &[(String, Class)], interfaces: &[(String, Arc<Interface>)]) -> Result<(), Vec<Fault>> {
+    let pending = Pending {
+        classes: classes.iter().map(|(name, class)| (name.as_str(), class)).collect(),
+        interfaces: interfaces.iter().map(|(name, interface)| (name.as_str(), &**interface)).collect(),
+    };
+    let factory = get_factory();
+    let faults: Vec<Fault> = classes.iter()
+        .filter_map(|(name, _)| match factory.missing_methods(name, &pending) {
+            Ok(missing) if missing.is_empty() => None,
+            Ok(missing) => {
+                let missing: Vec<&str> = missing.iter().map(|selector| selector.as_str()).collect();
+                Some(Fault::MethodNotFound(format!("class {} doesn't implement {}", name, missing.join(", "))))
+            }
+            Err(fault) => Some(fault),
+        })
+        .collect();
+    if faults.is_empty() {
+        Ok(())
+    } else {
+        Err(faults)
+    }
+}
+
+/// The current class epoch
+/// Anything that caches methods must drop its cache when this changes.
+pub fn class_epoch() -> usize {
+    CLASS_EPOCH.load(Ordering::Acquire)
 }
 
 pub fn create_base_object() -> ObjectBox {
@@ -855,115 +1723,75 @@ pub fn create_base_object() -> ObjectBox {
 }
 
 pub fn create_boolean(value: bool) -> ObjectBox {
-    let boolean = get_factory().create_boolean(value);
-    let mut object = boolean.borrow_mut();
-    object.initialize(vec![], VTable::new_empty());
-    drop(object);
-    boolean
+    get_factory().create_boolean(value)
 }
 
 pub fn create_i64(value: i64) -> ObjectBox {
-    let integer = get_factory().create_i64(value);
-    let mut object = integer.borrow_mut();
-    object.initialize(vec![], VTable::new_empty());
-    drop(object);
-    integer
+    get_factory().create_i64(value)
 }
 
 pub fn create_u64(value: u64) -> ObjectBox {
-    let integer = get_factory().create_u64(value);
-    let mut object = integer.borrow_mut();
-    object.initialize(vec![], VTable::new_empty());
-    drop(object);
-    integer
+    get_factory().create_u64(value)
 }
 
 pub fn create_i32(value: i32) -> ObjectBox {
-    let integer = get_factory().create_i32(value);
-    let mut object = integer.borrow_mut();
-    object.initialize(vec![], VTable::new_empty());
-    drop(object);
-    integer
+    get_factory().create_i32(value)
 }
 
 pub fn create_u32(value: u32) -> ObjectBox {
-    let integer = get_factory().create_u32(value);
-    let mut object = integer.borrow_mut();
-    object.initialize(vec![], VTable::new_empty());
-    drop(object);
-    integer
+    get_factory().create_u32(value)
 }
 
 pub fn create_i16(value: i16) -> ObjectBox {
-    let integer = get_factory().create_i16(value);
-    let mut object = integer.borrow_mut();
-    object.initialize(vec![], VTable::new_empty());
-    drop(object);
-    integer
+    get_factory().create_i16(value)
 }
 
 pub fn create_u16(value: u16) -> ObjectBox {
-    let integer = get_factory().create_u16(value);
-    let mut object = integer.borrow_mut();
-    object.initialize(vec![], VTable::new_empty());
-    drop(object);
-    integer
+    get_factory().create_u16(value)
 }
 
 pub fn create_i8(value: i8) -> ObjectBox {
-    let integer = get_factory().create_i8(value);
-    let mut object = integer.borrow_mut();
-    object.initialize(vec![], VTable::new_empty());
-    drop(object);
-    integer
+    get_factory().create_i8(value)
 }
 
 pub fn create_u8(value: u8) -> ObjectBox {
-    let integer = get_factory().create_u8(value);
-    let mut object = integer.borrow_mut();
-    object.initialize(vec![], VTable::new_empty());
-    drop(object);
-    integer
+    get_factory().create_u8(value)
+}
+
+pub fn create_bigint(value: BigInt) -> ObjectBox {
+    get_factory().create_bigint(value)
 }
 
 pub fn create_f64(value: f64) -> ObjectBox {
-    let float = get_factory().create_f64(value);
-    let mut object = float.borrow_mut();
-    object.initialize(vec![], VTable::new_empty());
-    drop(object);
-    float
+    get_factory().create_f64(value)
 }
 
 pub fn create_f32(value: f32) -> ObjectBox {
-    let float = get_factory().create_f32(value);
-    let mut object = float.borrow_mut();
-    object.initialize(vec![], VTable::new_empty());
-    drop(object);
-    float
+    get_factory().create_f32(value)
+}
+
+pub fn create_rational(value: Rational) -> ObjectBox {
+    get_factory().create_rational(value)
+}
+
+pub fn create_decimal(value: Decimal) -> ObjectBox {
+    get_factory().create_decimal(value)
 }
 
 pub fn create_string(value: String) -> ObjectBox {
-    let string = get_factory().create_string(value);
-    let mut object = string.borrow_mut();
-    object.initialize(vec![], VTable::new_empty());
-    drop(object);
-    string
+    get_factory().create_string(value)
 }
 
 pub fn create_character(value: char) -> ObjectBox {
-    let character = get_factory().create_character(value);
-    let mut object = character.borrow_mut();
-    object.initialize(vec![], VTable::new_empty());
-    drop(object);
-    character
+    get_factory().create_character(value)
+}
+
+pub fn create_symbol(value: Symbol) -> ObjectBox {
+    get_factory().create_symbol(value)
 }
 
 pub fn create_message(index: &str) -> ObjectBox {
-    let msg = get_factory().create_message(index);
-    let mut object = msg.borrow_mut();
-    object.initialize(vec![], VTable::new_empty());
-    drop(object);
-    msg
+    get_factory().create_message(index)
 }
 
 pub fn create_logger() -> ObjectBox {
@@ -971,49 +1799,97 @@ pub fn create_logger() -> ObjectBox {
 }
 
 pub fn init_stack() -> ObjectBox {
-    let stack = get_factory().init_stack();
-    let mut object = stack.borrow_mut();
-    object.initialize(vec![], VTable::new_empty());
-    drop(object);
-    stack
+    get_factory().init_stack()
 }
 
 pub fn create_stack() -> ObjectBox {
-    let stack = get_factory().create_stack();
-    let mut object = stack.borrow_mut();
-    object.initialize(vec![], VTable::new_empty());
-    drop(object);
-    stack
+    get_factory().create_stack()
 }
 
 pub fn create_block(bytecode: Vec<ByteCode>) -> ObjectBox {
-    let block = get_factory().create_block(bytecode);
-    let mut object = block.borrow_mut();
-    object.initialize(vec![], VTable::new_empty());
-    drop(object);
-    block
+    get_factory().create_block(bytecode)
 }
 
 pub fn create_vector(vector: Vec<ObjectBox>) -> ObjectBox {
-    let vector = get_factory().create_vector(vector);
-    let mut object = vector.borrow_mut();
-    object.initialize(vec![], VTable::new_empty());
-    drop(object);
-    vector
+    get_factory().create_vector(vector)
+}
+
+pub fn create_match(value: self::regex::Match) -> ObjectBox {
+    get_factory().create_match(value)
+}
+
+pub fn create_list(value: Vec<ObjectBox>) -> ObjectBox {
+    get_factory().create_list(value)
+}
+
+pub fn create_set(value: set::ObjectSet) -> ObjectBox {
+    get_factory().create_set(value)
+}
+
+pub fn create_iterator(source: iterator::Source) -> ObjectBox {
+    get_factory().create_iterator(source)
+}
+
+pub fn create_range(value: range::Range) -> ObjectBox {
+    get_factory().create_range(value)
+}
+
+pub fn create_byte_array(value: Vec<u8>) -> ObjectBox {
+    get_factory().create_byte_array(value)
 }
 
 pub fn create_system() -> ObjectBox {
-    let system = get_factory().create_system();
-    let mut object = system.borrow_mut();
-    object.initialize(vec![], VTable::new_empty());
-    drop(object);
-    system
+    get_factory().create_system()
+}
+
+/// Get the object that stands for a class
+/// Class-side methods are sent to this object and it holds the class variables.
+pub fn get_class_object(name: &str) -> Result<ObjectBox, Fault> {
+    get_factory().get_class_object(name)
+}
+
+pub fn create_weak_ref(target: &ObjectBox) -> ObjectBox {
+    get_factory().create_weak_ref(target)
 }
 
 
 pub fn create_object(name: &str, arguments: &[ObjectBox]) -> Result<Option<ObjectBox>, Fault> {
     let factory = get_factory();
-    factory.create_object(name, arguments).map(|object| Some(object))
+    factory.create_object(name, arguments).map(Some)
+}
+
+pub fn create_object_with_parent(name: &str, parent: Option<ObjectBox>, arguments: &[ObjectBox]) -> Result<ObjectBox, Fault> {
+    get_factory().create_object_with_parent(name, parent, arguments)
+}
+
+/// Get the vtable that new instances of a builtin type start out with
+pub fn prototype_vtable(name: &str) -> Option<VTable> {
+    get_factory().prototypes.get(name).map(|prototype| prototype.vtable.clone())
+}
+
+pub fn shared_objects() -> Vec<(SharedObject, ObjectBox)> {
+    get_factory().shared_objects()
+}
+
+pub fn get_shared_object(shared: &SharedObject) -> Result<ObjectBox, Fault> {
+    get_factory().get_shared_object(shared)
+}
+
+pub fn get_class(name: &str) -> Option<Arc<Class>> {
+    get_factory().get_class(name)
+}
+
+pub fn get_classes() -> Vec<(String, Arc<Class>)> {
+    get_factory().classes.iter().map(|(name, class)| (name.clone(), class.clone())).collect()
+}
+
+pub fn get_interfaces() -> Vec<(String, Arc<Interface>)> {
+    get_factory().interfaces.iter().map(|(name, interface)| (name.clone(), interface.clone())).collect()
+}
+
+/// Find the name that a class was added under
+pub fn class_name(class: &Arc<Class>) -> Option<String> {
+    get_factory().class_name(class)
 }
 
 
@@ -1023,7 +1899,10 @@ pub struct ContextData {
     pub receiver: Option<ObjectBox>,
     pub arg_count: usize,
     pub vtable: Option<VTable>,
-    pub code: Option<Arc<Vec<ByteCode>>>
+    pub code: Option<Arc<Vec<ByteCode>>>,
+    /// The first argument of the last send that was handled inline.
+    /// It only gets boxed into arguments when something actually looks at it.
+    deferred_argument: Option<Immediate>,
 }
 
 impl ContextData {
@@ -1035,6 +1914,7 @@ impl ContextData {
             arg_count: 0,
             vtable: None,
             code: None,
+            deferred_argument: None,
         }
     }
 
@@ -1065,36 +1945,64 @@ impl ContextData {
         let stack = stack.downcast_mut::<stack::Stack>().unwrap();
         stack.pop()
     }
-    pub fn push(&mut self, value: ObjectBox) {
+    fn with_frame<R>(&self, f: impl FnOnce(&mut stack::Stack) -> R) -> R {
         let stack = self.stack.borrow();
         let stack = stack.downcast_ref::<stack::Stack>().unwrap();
-        let mut stack = stack.data.last().unwrap().borrow_mut();
-        let stack = stack.downcast_mut::<stack::Stack>().unwrap();
-        stack.push(value);
+        let frame = match stack.data.last().unwrap() {
+            Value::Object(frame) => frame,
+            Value::Immediate(_) => panic!("ContextData: stack frame was not a Stack"),
+        };
+        let mut frame = frame.borrow_mut();
+        f(frame.downcast_mut::<stack::Stack>().unwrap())
+    }
+    pub fn push(&mut self, value: ObjectBox) {
+        self.with_frame(|frame| frame.push(value))
+    }
+    pub fn push_value(&mut self, value: Value) {
+        self.with_frame(|frame| frame.push_value(value))
     }
     pub fn pop(&mut self) -> Option<ObjectBox> {
-        let stack = self.stack.borrow();
-        let stack = stack.downcast_ref::<stack::Stack>().unwrap();
-        let mut stack = stack.data.last().unwrap().borrow_mut();
-        let stack = stack.downcast_mut::<stack::Stack>().unwrap();
-        stack.pop()
+        self.with_frame(|frame| frame.pop())
+    }
+    pub fn pop_value(&mut self) -> Option<Value> {
+        self.with_frame(|frame| frame.pop_value())
     }
+    /// Get the top of the stack, boxing it in place if it is an immediate
     pub fn top(&self) -> Option<ObjectBox> {
-        let stack = self.stack.borrow();
-        let stack = stack.downcast_ref::<stack::Stack>().unwrap();
-        let stack = stack.data.last().unwrap().borrow();
-        let stack = stack.downcast_ref::<stack::Stack>().unwrap();
-        stack.data.last().map(|x| x.clone())
+        self.with_frame(|frame| frame.top())
+    }
+    pub fn peek_immediate(&self, index: usize) -> Option<Immediate> {
+        self.with_frame(|frame| frame.peek_immediate(index))
+    }
+    pub fn replace_top(&mut self, value: Value) {
+        self.with_frame(|frame| frame.replace_top(value))
     }
     pub fn get_argument(&self, index: usize) -> Option<ObjectBox> {
-        self.arguments.get(index).map(|x| x.clone())
+        self.arguments.get(index).cloned()
     }
     pub fn set_argument(&mut self, index: usize, value: ObjectBox) {
         if index >= self.arguments.len() {
             self.arguments.resize(index + 1, Nil::new());
         }
+        if index == 0 {
+            self.deferred_argument = None;
+        }
         self.arguments[index] = value;
     }
+    /// Remember the argument of a send that was handled inline without boxing it.
+    pub fn defer_argument(&mut self, value: Immediate) {
+        self.deferred_argument = Some(value);
+    }
+    pub fn deferred_argument(&self) -> Option<Immediate> {
+        self.deferred_argument
+    }
+    /// Box the argument of the last inline send so that arguments is up to date.
+    /// This must be called before anything reads arguments directly.
+    pub fn flush_arguments(&mut self) {
+        if let Some(value) = self.deferred_argument.take() {
+            self.set_argument(0, value.into_object());
+        }
+    }
     pub fn set_arguments(&mut self, arguments: Vec<ObjectBox>) {
         for (index, argument) in arguments.iter().enumerate() {
             self.set_argument(index, argument.clone());
@@ -1118,28 +2026,26 @@ pub struct Context {
 impl Context {
     /*fn make_class() -> Class {
         let mut methods = HashMap::new();
-        methods.insert("new".to_string(), Arc::new(Method::RustMethod { fun: Box::new(context_new) }));
+        methods.insert(Symbol::from("new"), Arc::new(Method::RustMethod { fun: Box::new(context_new) }));
         Class::new(Some(parent), methods)
     }*/
     pub fn make_vtable() -> VTable {
         let mut methods = HashMap::new();
-        methods.insert("new".to_string(), Arc::new(Method::RustMethod { fun: Box::new(context_new) }));
-        methods.insert("stack".to_string(), Arc::new(Method::RustMethod { fun: Box::new(context_get_stack) }));
-        methods.insert("current_frame".to_string(), Arc::new(Method::RustMethod { fun: Box::new(context_get_current_frame) }));
+        methods.insert(Symbol::from("new"), Arc::new(Method::RustMethod { fun: Box::new(context_new) }));
+        methods.insert(Symbol::from("stack"), Arc::new(Method::RustMethod { fun: Box::new(context_get_stack) }));
+        methods.insert(Symbol::from("current_frame"), Arc::new(Method::RustMethod { fun: Box::new(context_get_current_frame) }));
+        methods.insert(Symbol::from("class"), Arc::new(Method::RustMethod { fun: Box::new(context_get_class) }));
         VTable::new(methods)
     }
 
-    pub fn new() -> Context {
-        let context = Context {
-            super_object: Some(create_base_object()),
+    pub fn new(parent: ObjectBox) -> Context {
+        Context {
+            super_object: Some(parent),
             vtable: VTable::new_empty()
-        };
-        context
+        }
     }
-    pub fn make_object() -> ObjectBox {
-        let mut context = Context::new();
-        context.vtable.extend(Context::make_vtable());
-        ObjectBox::new(context)
+    pub fn make_object(parent: ObjectBox) -> ObjectBox {
+        ObjectBox::new(Context::new(parent))
     }
 
 }
@@ -1161,10 +2067,9 @@ impl Object for Context {
         panic!("Context does not have a size");
     }
     fn duplicate(&self) -> ObjectBox {
-        Context::make_object()
+        ObjectBox::new(Context {super_object: self.super_object.clone(), vtable: self.vtable.clone()})
     }
     fn initialize(&mut self, _arguments: Vec<ObjectBox>, vtable: VTable) {
-        self.vtable.extend(Context::make_vtable());
         self.vtable.extend(vtable);
     }
 }
@@ -1173,8 +2078,15 @@ impl Object for Context {
 fn context_new(_: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
     let string = context.arguments[0].clone();
     let string = string.borrow();
-    let string = string.downcast_ref::<StringObject>().ok_or(Fault::InvalidType(format!("argument was not a string")))?;
-    return create_object(&string.value, &context.arguments[1..]);
+    let string = string.downcast_ref::<StringObject>().ok_or(Fault::InvalidType("argument was not a string".to_string()))?;
+    create_object(&string.value, &context.arguments[1..])
+}
+
+fn context_get_class(_: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let string = context.arguments[0].clone();
+    let string = string.borrow();
+    let string = string.downcast_ref::<StringObject>().ok_or(Fault::InvalidType("argument was not a string".to_string()))?;
+    get_class_object(&string.value).map(Some)
 }
 
 fn context_get_stack(_: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
@@ -1217,3 +2129,201 @@ fn context_get_current_frame(_: ObjectBox, context: &mut ContextData) -> Result<
         return object.clone()
     }
 }*/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parent_of(object: &ObjectBox) -> *const () {
+        object.borrow().get_super_object().unwrap().as_ptr()
+    }
+
+    fn class(parent: Option<&str>) -> Class {
+        Class::new(parent, VTable::new_empty(), Vec::new()).with_class_side(VTable::new_empty(), 1)
+    }
+
+    fn own_parent(object: &ObjectBox) -> ObjectBox {
+        object.borrow_mut().downcast_mut::<ObjectStruct>().unwrap().own_super_object().unwrap()
+    }
+
+    #[test]
+    fn instances_of_a_class_share_their_parents() {
+        let mut factory = ObjectFactory::new();
+        factory.add_class("Parent", class(Some("Object")));
+        factory.add_class("Child", class(Some("Parent")));
+        let first = factory.create_object("Child", &[]).unwrap();
+        let second = factory.create_object("Child", &[]).unwrap();
+        assert_eq!(parent_of(&first), parent_of(&second));
+        // The chain is shared all the way down to the builtin prototype
+        let parent = first.borrow().get_super_object().unwrap();
+        let grandparent = factory.create_object("Parent", &[]).unwrap();
+        assert_eq!(parent_of(&parent), parent_of(&grandparent));
+
+        factory.add_class("Parent", class(Some("Object")));
+        let third = factory.create_object("Child", &[]).unwrap();
+        assert_ne!(parent_of(&first), parent_of(&third));
+    }
+
+    #[test]
+    fn a_super_send_gets_a_parent_of_its_own() {
+        let mut factory = ObjectFactory::new();
+        factory.add_class("Parent", class(Some("Object")));
+        factory.add_class("Child", class(Some("Parent")));
+        let first = factory.create_object("Child", &[]).unwrap();
+        let second = factory.create_object("Child", &[]).unwrap();
+
+        // What the parent's initialize stores belongs to that instance alone
+        let parent = own_parent(&first);
+        assert_ne!(parent.as_ptr(), parent_of(&second));
+        parent.borrow_mut().initialize(vec![factory.create_i64(1)], VTable::new_empty());
+        assert!(second.borrow().get_super_object().unwrap().borrow().get_field(0).is_none());
+        assert!(factory.create_object("Child", &[]).unwrap().borrow().get_super_object().unwrap().borrow().get_field(0).is_none());
+        assert_eq!(own_parent(&first).as_ptr(), parent.as_ptr());
+    }
+
+    fn method() -> Arc<Method> {
+        Arc::new(Method::RustMethod { fun: Box::new(|_, _| Ok(None)) })
+    }
+
+    #[test]
+    fn initializing_an_instance_keeps_the_class_vtable_shared() {
+        let mut factory = ObjectFactory::new();
+        let methods = HashMap::from([(Symbol::from("name"), method())]);
+        factory.add_class("Shape", Class::new(Some("Object"), VTable::new(methods), Vec::new()));
+        let first = factory.create_object("Shape", &[]).unwrap();
+        let second = factory.create_object("Shape", &[]).unwrap();
+        // This is what sending initialize does
+        first.borrow_mut().initialize(vec![], VTable::new(HashMap::new()));
+        second.borrow_mut().initialize(vec![], VTable::new(HashMap::new()));
+        assert!(first.borrow().get_vtable().same_table(second.borrow().get_vtable()));
+    }
+
+    #[test]
+    fn builtin_instances_share_their_prototype() {
+        let factory = ObjectFactory::new();
+        let first = factory.create_i64(1);
+        let second = factory.create_i64(2);
+        assert_eq!(parent_of(&first), parent_of(&second));
+    }
+
+    #[test]
+    fn objects_can_be_shared_between_threads() {
+        fn shareable<T: Send + Sync>() {}
+        shareable::<ObjectBox>();
+        shareable::<WeakObjectBox>();
+        shareable::<ObjectFactory>();
+    }
+
+    #[test]
+    fn replacing_a_class_keeps_the_class_variables_of_its_subclasses() {
+        let mut factory = ObjectFactory::new();
+        factory.add_class("Parent", class(None));
+        factory.add_class("Child", class(Some("Parent")));
+        let child = factory.get_class_object("Child").unwrap();
+        child.borrow_mut().set_field(0, create_i64(5));
+        let old_parent = factory.get_class_object("Parent").unwrap();
+
+        factory.add_class("Parent", class(None));
+        let parent = factory.get_class_object("Parent").unwrap();
+        assert_ne!(parent.as_ptr(), old_parent.as_ptr());
+        let child_again = factory.get_class_object("Child").unwrap();
+        assert_eq!(child_again.as_ptr(), child.as_ptr());
+        assert_eq!(parent_of(&child), parent.as_ptr());
+        let field = child.borrow().get_field(0).unwrap();
+        assert_eq!(field.borrow().downcast_ref::<PrimitiveObject<i64>>().unwrap().data, 5);
+    }
+
+    #[test]
+    fn subclasses_are_rebound_once_a_missing_ancestor_is_added() {
+        let mut factory = ObjectFactory::new();
+        factory.add_class("Parent", class(None));
+        factory.add_class("Child", class(Some("Parent")));
+        let child = factory.get_class_object("Child").unwrap();
+
+        factory.add_class("Parent", class(Some("Grandparent")));
+        assert!(factory.get_class_object("Parent").is_err());
+        factory.add_class("Grandparent", class(None));
+        let parent = factory.get_class_object("Parent").unwrap();
+        assert_eq!(parent_of(&child), parent.as_ptr());
+        assert_eq!(factory.get_class_object("Child").unwrap().as_ptr(), child.as_ptr());
+    }
+
+    fn uses(traits: &[(&str, &[&str])]) -> Vec<(TraitUse, Arc<Trait>)> {
+        traits.iter()
+            .map(|(name, selectors)| {
+                let methods = selectors.iter().map(|selector| (Symbol::from(*selector), method())).collect();
+                let trait_use = TraitUse { name: name.to_string(), excluded: Vec::new(), aliases: Vec::new() };
+                (trait_use, Arc::new(Trait::new(VTable::new(methods))))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn traits_that_give_the_same_method_conflict() {
+        let class = Class::new(Some("Object"), VTable::new_empty(), Vec::new());
+        let uses = uses(&[("Walk", &["move", "walk"]), ("Swim", &["move", "swim"])]);
+        let fault = class.compose("Duck", &uses).err().unwrap();
+        assert!(fault.to_string().contains("move"));
+    }
+
+    #[test]
+    fn a_conflict_can_be_excluded_or_defined_by_the_class() {
+        let mut uses = uses(&[("Walk", &["move", "walk"]), ("Swim", &["move", "swim"])]);
+        let mut methods = HashMap::new();
+        let own = method();
+        methods.insert(Symbol::from("move"), own.clone());
+        let class = Class::new(Some("Object"), VTable::new(methods), Vec::new()).compose("Duck", &uses).unwrap();
+        assert!(Arc::ptr_eq(&class.get_method(Symbol::from("move")).unwrap(), &own));
+        assert!(class.get_method(Symbol::from("swim")).is_some());
+
+        uses[1].0.excluded.push(Symbol::from("move"));
+        uses[1].0.aliases.push((Symbol::from("paddle"), Symbol::from("move")));
+        let class = Class::new(Some("Object"), VTable::new_empty(), Vec::new()).compose("Duck", &uses).unwrap();
+        let walk = uses[0].1.get_vtable().get_method(Symbol::from("move")).unwrap();
+        assert!(Arc::ptr_eq(&class.get_method(Symbol::from("move")).unwrap(), &walk));
+        assert!(class.get_method(Symbol::from("paddle")).is_some());
+    }
+
+    #[test]
+    fn extend_disjoint_leaves_the_vtable_alone_on_a_conflict() {
+        let mut methods = HashMap::new();
+        methods.insert(Symbol::from("size"), method());
+        let mut vtable = VTable::new(methods.clone());
+        methods.insert(Symbol::from("length"), method());
+        let conflicts = vtable.extend_disjoint(VTable::new(methods)).unwrap_err();
+        assert_eq!(conflicts, vec![Symbol::from("size")]);
+        assert!(vtable.get_method(Symbol::from("length")).is_none());
+    }
+
+    #[test]
+    fn classes_are_checked_against_the_classes_and_interfaces_of_their_binary() {
+        let factory = ObjectFactory::new();
+        let shape = Interface::new(vec![Symbol::from("area"), Symbol::from("name")]);
+        let mut methods = HashMap::new();
+        methods.insert(Symbol::from("name"), method());
+        let base = Class::new(Some("Object"), VTable::new(methods), Vec::new())
+            .with_requirements(Vec::new(), vec!["Shape".to_string()]);
+        let square = Class::new(Some("Base"), VTable::new_empty(), Vec::new());
+        // The subclass comes first like it can in a class table
+        let pending = Pending {
+            classes: HashMap::from([("Square", &square), ("Base", &base)]),
+            interfaces: HashMap::from([("Shape", &shape)]),
+        };
+        assert_eq!(factory.missing_methods("Square", &pending).unwrap(), vec![Symbol::from("area")]);
+        assert!(factory.get_class("Square").is_none());
+
+        let unknown = Class::new(Some("Object"), VTable::new_empty(), Vec::new())
+            .with_requirements(Vec::new(), vec!["Unknown".to_string()]);
+        let pending = Pending { classes: HashMap::from([("Broken", &unknown)]), interfaces: HashMap::new() };
+        assert!(factory.missing_methods("Broken", &pending).is_err());
+    }
+
+    #[test]
+    fn abstract_classes_are_not_missing_anything() {
+        let factory = ObjectFactory::new();
+        let shape = Class::new(Some("Object"), VTable::new_empty(), Vec::new())
+            .with_requirements(vec![Symbol::from("area")], Vec::new());
+        let pending = Pending { classes: HashMap::from([("Shape", &shape)]), interfaces: HashMap::new() };
+        assert!(factory.missing_methods("Shape", &pending).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,727 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::object::symbol::Symbol;
+use crate::object::string::StringObject;
+use crate::object::value::Immediate;
+use crate::object::primitive::exact::Exact;
+use crate::object::primitive::{NumberOperation, NUMBER_OPERATIONS};
+use crate::object::{ContextData, Fault, Method, Object, ObjectBox, VTable};
+
+/// The largest power of ten that fits in a limb, used to convert to and from decimal
+const DECIMAL_BASE: u32 = 1_000_000_000;
+const DECIMAL_DIGITS: usize = 9;
+
+/// BigInt
+/// An integer of any size. It is stored as a sign and a magnitude of 32 bit limbs with the least
+/// significant limb first. The magnitude never ends in a zero limb and zero is never negative so
+/// every value has exactly one representation.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct BigInt {
+    negative: bool,
+    magnitude: Vec<u32>,
+}
+
+impl BigInt {
+    pub fn zero() -> BigInt {
+        BigInt::default()
+    }
+
+    /// Build a BigInt from its sign and limbs, least significant first
+    pub fn from_parts(negative: bool, magnitude: Vec<u32>) -> BigInt {
+        let mut value = BigInt { negative, magnitude };
+        value.normalize();
+        value
+    }
+
+    /// The sign and the limbs of the value, least significant first
+    pub fn parts(&self) -> (bool, &[u32]) {
+        (self.negative, &self.magnitude)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.magnitude.is_empty()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    fn normalize(&mut self) {
+        while self.magnitude.last() == Some(&0) {
+            self.magnitude.pop();
+        }
+        if self.magnitude.is_empty() {
+            self.negative = false;
+        }
+    }
+
+    /// Parse a decimal number with an optional sign
+    pub fn parse(text: &str) -> Option<BigInt> {
+        let (negative, digits) = match text.strip_prefix('-') {
+            Some(digits) => (true, digits),
+            None => (false, text.strip_prefix('+').unwrap_or(text)),
+        };
+        if digits.is_empty() || !digits.bytes().all(|digit| digit.is_ascii_digit()) {
+            return None;
+        }
+        let mut magnitude = Vec::new();
+        let first = digits.len() % DECIMAL_DIGITS;
+        let chunks = (first > 0).then(|| &digits[..first]).into_iter()
+            .chain(digits.as_bytes()[first..].chunks(DECIMAL_DIGITS).map(|chunk| std::str::from_utf8(chunk).unwrap()));
+        for chunk in chunks {
+            let scale = 10u32.pow(chunk.len() as u32);
+            mul_small_add(&mut magnitude, scale, chunk.parse().unwrap());
+        }
+        Some(BigInt::from_parts(negative, magnitude))
+    }
+
+    pub fn abs(&self) -> BigInt {
+        BigInt { negative: false, magnitude: self.magnitude.clone() }
+    }
+
+    pub fn neg(&self) -> BigInt {
+        BigInt::from_parts(!self.negative, self.magnitude.clone())
+    }
+
+    pub fn add(&self, other: &BigInt) -> BigInt {
+        if self.negative == other.negative {
+            return BigInt::from_parts(self.negative, add_magnitude(&self.magnitude, &other.magnitude));
+        }
+        match compare_magnitude(&self.magnitude, &other.magnitude) {
+            Ordering::Less => BigInt::from_parts(other.negative, sub_magnitude(&other.magnitude, &self.magnitude)),
+            _ => BigInt::from_parts(self.negative, sub_magnitude(&self.magnitude, &other.magnitude)),
+        }
+    }
+
+    pub fn sub(&self, other: &BigInt) -> BigInt {
+        self.add(&other.neg())
+    }
+
+    pub fn mul(&self, other: &BigInt) -> BigInt {
+        BigInt::from_parts(self.negative != other.negative, mul_magnitude(&self.magnitude, &other.magnitude))
+    }
+
+    /// Divide and get the remainder, the quotient is truncated toward zero like Rust's integers
+    pub fn div_rem(&self, other: &BigInt) -> Result<(BigInt, BigInt), Fault> {
+        if other.is_zero() {
+            return Err(Fault::DivideByZero);
+        }
+        let (quotient, remainder) = div_rem_magnitude(&self.magnitude, &other.magnitude);
+        Ok((BigInt::from_parts(self.negative != other.negative, quotient), BigInt::from_parts(self.negative, remainder)))
+    }
+
+    pub fn pow(&self, mut exponent: u32) -> BigInt {
+        let mut result = BigInt::from(1);
+        let mut base = self.clone();
+        while exponent > 0 {
+            if exponent & 1 == 1 {
+                result = result.mul(&base);
+            }
+            exponent >>= 1;
+            if exponent > 0 {
+                base = base.mul(&base);
+            }
+        }
+        result
+    }
+
+    pub fn shift_left(&self, bits: usize) -> BigInt {
+        BigInt::from_parts(self.negative, shift_left_magnitude(&self.magnitude, bits))
+    }
+
+    /// Shift right rounding toward negative infinity like an arithmetic shift does
+    pub fn shift_right(&self, bits: usize) -> BigInt {
+        if !self.negative {
+            return BigInt::from_parts(false, shift_right_magnitude(&self.magnitude, bits));
+        }
+        let one = BigInt::from(1);
+        let shifted = shift_right_magnitude(&self.abs().sub(&one).magnitude, bits);
+        BigInt::from_parts(false, shifted).add(&one).neg()
+    }
+
+    /// Combine two values bit by bit as if they were in two's complement
+    pub fn bitwise(&self, other: &BigInt, operation: impl Fn(u32, u32) -> u32) -> BigInt {
+        let length = self.magnitude.len().max(other.magnitude.len()) + 1;
+        let (a, b) = (self.twos_complement(length), other.twos_complement(length));
+        let limbs = a.iter().zip(b.iter()).map(|(a, b)| operation(*a, *b)).collect();
+        BigInt::from_twos_complement(limbs)
+    }
+
+    fn twos_complement(&self, length: usize) -> Vec<u32> {
+        let mut limbs = self.magnitude.clone();
+        limbs.resize(length, 0);
+        if self.negative {
+            let mut carry = true;
+            for limb in limbs.iter_mut() {
+                let (value, overflowed) = (!*limb).overflowing_add(carry as u32);
+                *limb = value;
+                carry = overflowed;
+            }
+        }
+        limbs
+    }
+
+    fn from_twos_complement(limbs: Vec<u32>) -> BigInt {
+        let negative = limbs.last().is_some_and(|limb| limb >> 31 == 1);
+        if !negative {
+            return BigInt::from_parts(false, limbs);
+        }
+        let value = BigInt::from_parts(false, limbs.into_iter().map(|limb| !limb).collect());
+        value.add(&BigInt::from(1)).neg()
+    }
+
+    /// Get the value if it fits in an i128
+    pub fn to_i128(&self) -> Option<i128> {
+        if self.magnitude.len() > 4 {
+            return None;
+        }
+        let magnitude = self.magnitude.iter().rev().fold(0u128, |value, limb| value << 32 | *limb as u128);
+        if self.negative {
+            0i128.checked_sub_unsigned(magnitude)
+        } else {
+            i128::try_from(magnitude).ok()
+        }
+    }
+
+    /// Get the low 128 bits of the value in two's complement
+    pub fn to_i128_wrapping(&self) -> i128 {
+        let limbs = self.twos_complement(self.magnitude.len().max(4) + 1);
+        limbs[..4].iter().rev().fold(0u128, |value, limb| value << 32 | *limb as u128) as i128
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        let magnitude = self.magnitude.iter().rev().fold(0.0, |value, limb| value * 4294967296.0 + *limb as f64);
+        if self.negative { -magnitude } else { magnitude }
+    }
+
+    /// Get the integer part of a float, None if it isn't finite
+    pub fn from_f64(value: f64) -> Option<BigInt> {
+        if !value.is_finite() {
+            return None;
+        }
+        let negative = value < 0.0;
+        let mut value = value.abs().trunc();
+        let mut magnitude = Vec::new();
+        while value >= 1.0 {
+            magnitude.push((value % 4294967296.0) as u32);
+            value = (value / 4294967296.0).trunc();
+        }
+        Some(BigInt::from_parts(negative, magnitude))
+    }
+}
+
+impl From<i128> for BigInt {
+    fn from(value: i128) -> BigInt {
+        let mut magnitude = Vec::new();
+        let mut rest = value.unsigned_abs();
+        while rest > 0 {
+            magnitude.push(rest as u32);
+            rest >>= 32;
+        }
+        BigInt::from_parts(value < 0, magnitude)
+    }
+}
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &BigInt) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (false, false) => compare_magnitude(&self.magnitude, &other.magnitude),
+            (true, true) => compare_magnitude(&other.magnitude, &self.magnitude),
+        }
+    }
+}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &BigInt) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl std::fmt::Display for BigInt {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        if self.is_zero() {
+            return write!(f, "0");
+        }
+        let mut chunks = Vec::new();
+        let mut rest = self.magnitude.clone();
+        while !rest.is_empty() {
+            chunks.push(div_small(&mut rest, DECIMAL_BASE));
+        }
+        if self.negative {
+            write!(f, "-")?;
+        }
+        let mut chunks = chunks.iter().rev();
+        write!(f, "{}", chunks.next().unwrap())?;
+        for chunk in chunks {
+            write!(f, "{:0width$}", chunk, width = DECIMAL_DIGITS)?;
+        }
+        Ok(())
+    }
+}
+
+fn compare_magnitude(a: &[u32], b: &[u32]) -> Ordering {
+    a.len().cmp(&b.len()).then_with(|| a.iter().rev().cmp(b.iter().rev()))
+}
+
+fn add_magnitude(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let (long, short) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+    let mut result = Vec::with_capacity(long.len() + 1);
+    let mut carry = 0u64;
+    for (i, limb) in long.iter().enumerate() {
+        let sum = *limb as u64 + *short.get(i).unwrap_or(&0) as u64 + carry;
+        result.push(sum as u32);
+        carry = sum >> 32;
+    }
+    if carry > 0 {
+        result.push(carry as u32);
+    }
+    result
+}
+
+/// Subtract b from a, a must not be smaller than b
+fn sub_magnitude(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut result = Vec::with_capacity(a.len());
+    let mut borrow = false;
+    for (i, limb) in a.iter().enumerate() {
+        let (value, first) = limb.overflowing_sub(*b.get(i).unwrap_or(&0));
+        let (value, second) = value.overflowing_sub(borrow as u32);
+        result.push(value);
+        borrow = first || second;
+    }
+    result
+}
+
+fn mul_magnitude(a: &[u32], b: &[u32]) -> Vec<u32> {
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+    let mut result = vec![0u32; a.len() + b.len()];
+    for (i, x) in a.iter().enumerate() {
+        let mut carry = 0u64;
+        for (j, y) in b.iter().enumerate() {
+            let product = *x as u64 * *y as u64 + result[i + j] as u64 + carry;
+            result[i + j] = product as u32;
+            carry = product >> 32;
+        }
+        result[i + b.len()] = carry as u32;
+    }
+    result
+}
+
+/// Multiply a magnitude by a small number and add another to it in place
+fn mul_small_add(magnitude: &mut Vec<u32>, factor: u32, addend: u32) {
+    let mut carry = addend as u64;
+    for limb in magnitude.iter_mut() {
+        let value = *limb as u64 * factor as u64 + carry;
+        *limb = value as u32;
+        carry = value >> 32;
+    }
+    if carry > 0 {
+        magnitude.push(carry as u32);
+    }
+}
+
+/// Divide a magnitude by a small number in place and return the remainder
+fn div_small(magnitude: &mut Vec<u32>, divisor: u32) -> u32 {
+    let mut remainder = 0u64;
+    for limb in magnitude.iter_mut().rev() {
+        let value = remainder << 32 | *limb as u64;
+        *limb = (value / divisor as u64) as u32;
+        remainder = value % divisor as u64;
+    }
+    while magnitude.last() == Some(&0) {
+        magnitude.pop();
+    }
+    remainder as u32
+}
+
+fn shift_left_magnitude(magnitude: &[u32], bits: usize) -> Vec<u32> {
+    if magnitude.is_empty() {
+        return Vec::new();
+    }
+    let (limbs, bits) = (bits / 32, bits % 32);
+    let mut result = vec![0u32; limbs];
+    if bits == 0 {
+        result.extend_from_slice(magnitude);
+        return result;
+    }
+    let mut carry = 0u32;
+    for limb in magnitude {
+        result.push(limb << bits | carry);
+        carry = limb >> (32 - bits);
+    }
+    result.push(carry);
+    result
+}
+
+fn shift_right_magnitude(magnitude: &[u32], bits: usize) -> Vec<u32> {
+    let (limbs, bits) = (bits / 32, bits % 32);
+    if limbs >= magnitude.len() {
+        return Vec::new();
+    }
+    let magnitude = &magnitude[limbs..];
+    if bits == 0 {
+        return magnitude.to_vec();
+    }
+    (0..magnitude.len())
+        .map(|i| magnitude[i] >> bits | magnitude.get(i + 1).map_or(0, |next| next << (32 - bits)))
+        .collect()
+}
+
+/// Long division of magnitudes (Knuth's algorithm D)
+fn div_rem_magnitude(dividend: &[u32], divisor: &[u32]) -> (Vec<u32>, Vec<u32>) {
+    if compare_magnitude(dividend, divisor) == Ordering::Less {
+        return (Vec::new(), dividend.to_vec());
+    }
+    if divisor.len() == 1 {
+        let mut quotient = dividend.to_vec();
+        let remainder = div_small(&mut quotient, divisor[0]);
+        return (quotient, vec![remainder]);
+    }
+    // Normalize so that the top bit of the divisor is set which keeps the estimates close
+    let shift = divisor.last().unwrap().leading_zeros() as usize;
+    let v = shift_left_magnitude(divisor, shift);
+    let v = &v[..divisor.len()];
+    let mut u = shift_left_magnitude(dividend, shift);
+    u.resize(dividend.len() + 1, 0);
+    let n = v.len();
+    let m = u.len() - n;
+    let mut quotient = vec![0u32; m];
+    let base = 1u64 << 32;
+    for j in (0..m).rev() {
+        let numerator = (u[j + n] as u64) << 32 | u[j + n - 1] as u64;
+        let mut estimate = numerator / v[n - 1] as u64;
+        let mut remainder = numerator % v[n - 1] as u64;
+        while estimate >= base || estimate * v[n - 2] as u64 > (remainder << 32 | u[j + n - 2] as u64) {
+            estimate -= 1;
+            remainder += v[n - 1] as u64;
+            if remainder >= base {
+                break;
+            }
+        }
+        let mut borrow = 0i64;
+        let mut carry = 0u64;
+        for i in 0..n {
+            let product = estimate * v[i] as u64 + carry;
+            carry = product >> 32;
+            let difference = u[i + j] as i64 - borrow - (product & 0xffff_ffff) as i64;
+            u[i + j] = difference as u32;
+            borrow = (difference < 0) as i64;
+        }
+        let difference = u[j + n] as i64 - borrow - carry as i64;
+        u[j + n] = difference as u32;
+        // The estimate was one too large so add the divisor back
+        if difference < 0 {
+            estimate -= 1;
+            let mut carry = 0u64;
+            for i in 0..n {
+                let sum = u[i + j] as u64 + v[i] as u64 + carry;
+                u[i + j] = sum as u32;
+                carry = sum >> 32;
+            }
+            u[j + n] = u[j + n].wrapping_add(carry as u32);
+        }
+        quotient[j] = estimate as u32;
+    }
+    let remainder = shift_right_magnitude(&u[..n], shift);
+    (quotient, remainder)
+}
+
+/// Apply an arithmetic operation to two BigInts
+pub fn apply(operation: NumberOperation, a: &BigInt, b: &BigInt) -> Result<BigInt, Fault> {
+    match operation {
+        NumberOperation::Add => Ok(a.add(b)),
+        NumberOperation::Sub => Ok(a.sub(b)),
+        NumberOperation::Mul => Ok(a.mul(b)),
+        NumberOperation::Div => Ok(a.div_rem(b)?.0),
+        NumberOperation::Mod => Ok(a.div_rem(b)?.1),
+        NumberOperation::Pow => Ok(a.pow(exponent(b)?)),
+    }
+}
+
+fn exponent(value: &BigInt) -> Result<u32, Fault> {
+    if value.is_negative() {
+        return Err(Fault::InvalidOperation(format!("BigInt pow: negative exponent {}", value)));
+    }
+    value.to_i128().and_then(|value| u32::try_from(value).ok())
+        .ok_or(Fault::Overflow(format!("BigInt pow: exponent {} is too large", value)))
+}
+
+/// Promote
+/// Lets the fixed width numbers do arithmetic with a BigInt. Integers are promoted to a BigInt
+/// and floats stay floats.
+pub trait Promote: Copy {
+    fn promote(self, operation: NumberOperation, other: &BigInt) -> Result<ObjectBox, Fault>;
+    fn compare(self, other: &BigInt) -> Option<Ordering>;
+}
+
+macro_rules! promote_integer {
+    ($($type:ty),*) => {
+        $(impl Promote for $type {
+            fn promote(self, operation: NumberOperation, other: &BigInt) -> Result<ObjectBox, Fault> {
+                Ok(crate::object::create_bigint(apply(operation, &BigInt::from(self as i128), other)?))
+            }
+            fn compare(self, other: &BigInt) -> Option<Ordering> {
+                Some(BigInt::from(self as i128).cmp(other))
+            }
+        })*
+    };
+}
+
+promote_integer!(i8, i16, i32, i64, u8, u16, u32, u64);
+
+impl Promote for f64 {
+    fn promote(self, operation: NumberOperation, other: &BigInt) -> Result<ObjectBox, Fault> {
+        Ok(crate::object::create_f64(operation.apply_float(self, other.to_f64())?))
+    }
+    fn compare(self, other: &BigInt) -> Option<Ordering> {
+        self.partial_cmp(&other.to_f64())
+    }
+}
+
+impl Promote for f32 {
+    fn promote(self, operation: NumberOperation, other: &BigInt) -> Result<ObjectBox, Fault> {
+        Ok(crate::object::create_f32(operation.apply_float(self as f64, other.to_f64())? as f32))
+    }
+    fn compare(self, other: &BigInt) -> Option<Ordering> {
+        (self as f64).partial_cmp(&other.to_f64())
+    }
+}
+
+/// The other side of a message to a BigInt
+enum Operand {
+    Integer(BigInt),
+    Float(f64),
+}
+
+fn operand(object: &ObjectBox, name: &str) -> Result<Operand, Fault> {
+    let object = object.borrow();
+    if let Some(big) = object.downcast_ref::<BigIntObject>() {
+        return Ok(Operand::Integer(big.value.clone()));
+    }
+    match Immediate::from_object(&*object) {
+        Some(Immediate::F32(value)) => Ok(Operand::Float(value as f64)),
+        Some(Immediate::F64(value)) => Ok(Operand::Float(value)),
+        Some(value) => integer(value).map(|value| Operand::Integer(BigInt::from(value)))
+            .ok_or(Fault::InvalidType(format!("BigInt {}: Not a number", name))),
+        None => Err(Fault::InvalidType(format!("BigInt {}: Not a number", name))),
+    }
+}
+
+fn integer(value: Immediate) -> Option<i128> {
+    match value {
+        Immediate::I8(value) => Some(value as i128),
+        Immediate::I16(value) => Some(value as i128),
+        Immediate::I32(value) => Some(value as i128),
+        Immediate::I64(value) => Some(value as i128),
+        Immediate::U8(value) => Some(value as i128),
+        Immediate::U16(value) => Some(value as i128),
+        Immediate::U32(value) => Some(value as i128),
+        Immediate::U64(value) => Some(value as i128),
+        _ => None,
+    }
+}
+
+/// Make a BigInt out of another BigInt, a fixed width integer or a string of decimal digits
+pub fn from_object(object: &ObjectBox) -> Result<BigInt, Fault> {
+    let object = object.borrow();
+    if let Some(big) = object.downcast_ref::<BigIntObject>() {
+        return Ok(big.value.clone());
+    }
+    if let Some(string) = object.downcast_ref::<StringObject>() {
+        return BigInt::parse(&string.value)
+            .ok_or(Fault::InvalidOperation(format!("BigInt: {:?} isn't an integer", string.value)));
+    }
+    Immediate::from_object(&*object).and_then(integer).map(BigInt::from)
+        .ok_or(Fault::InvalidType("BigInt: expected an integer or a string".to_string()))
+}
+
+
+pub struct BigIntObject {
+    super_object: Option<ObjectBox>,
+    vtable: VTable,
+    pub value: BigInt,
+}
+
+impl BigIntObject {
+    pub fn make_object(parent: ObjectBox, value: BigInt) -> ObjectBox {
+        ObjectBox::new(BigIntObject { super_object: Some(parent), vtable: VTable::new_empty(), value })
+    }
+    pub fn make_object_vtable() -> VTable {
+        let mut methods = HashMap::new();
+        methods.insert(Symbol::from("equals"), Arc::new(Method::RustMethod { fun: Box::new(bigint_equals) }));
+        methods.insert(Symbol::from("to_string"), Arc::new(Method::RustMethod { fun: Box::new(bigint_to_string) }));
+        methods.insert(Symbol::from("order"), Arc::new(Method::RustMethod { fun: Box::new(bigint_order) }));
+        VTable::new(methods)
+    }
+    pub fn make_number_vtable() -> VTable {
+        let mut methods = HashMap::new();
+        for (name, operation) in NUMBER_OPERATIONS.iter().copied() {
+            let fun = move |object: ObjectBox, context: &mut ContextData| bigint_arithmetic(object, context, operation, name);
+            methods.insert(Symbol::from(name), Arc::new(Method::RustMethod { fun: Box::new(fun) }));
+        }
+        methods.insert(Symbol::from("abs"), Arc::new(Method::RustMethod { fun: Box::new(bigint_abs) }));
+        methods.insert(Symbol::from("is_zero"), Arc::new(Method::RustMethod { fun: Box::new(bigint_is_zero) }));
+        VTable::new(methods)
+    }
+    pub fn make_integer_vtable() -> VTable {
+        let mut methods = HashMap::new();
+        methods.insert(Symbol::from("divides"), Arc::new(Method::RustMethod { fun: Box::new(bigint_divides) }));
+        methods.insert(Symbol::from("shift_right"), Arc::new(Method::RustMethod { fun: Box::new(bigint_shift_right) }));
+        methods.insert(Symbol::from("shift_left"), Arc::new(Method::RustMethod { fun: Box::new(bigint_shift_left) }));
+        methods.insert(Symbol::from("and"), Arc::new(Method::RustMethod { fun: Box::new(bigint_and) }));
+        methods.insert(Symbol::from("or"), Arc::new(Method::RustMethod { fun: Box::new(bigint_or) }));
+        methods.insert(Symbol::from("xor"), Arc::new(Method::RustMethod { fun: Box::new(bigint_xor) }));
+        VTable::new(methods)
+    }
+}
+
+impl Object for BigIntObject {
+    fn get_vtable(&self) -> &VTable {
+        &self.vtable
+    }
+    fn get_super_object(&self) -> Option<ObjectBox> {
+        self.super_object.clone()
+    }
+    fn get_field(&self, _index: usize) -> Option<ObjectBox> {
+        panic!("BigInt objects do not have fields")
+    }
+    fn set_field(&mut self, _index: usize, _value: ObjectBox) {
+        panic!("BigInt objects do not have fields")
+    }
+    fn size(&self) -> Option<usize> {
+        None
+    }
+    fn duplicate(&self) -> ObjectBox {
+        let big = BigIntObject::make_object(self.super_object.clone().unwrap(), self.value.clone());
+        let mut big_mut = big.borrow_mut();
+        big_mut.initialize(Vec::new(), self.vtable.clone());
+        drop(big_mut);
+        big
+    }
+    fn initialize(&mut self, _: Vec<ObjectBox>, vtable: VTable) {
+        self.vtable.extend(vtable);
+    }
+}
+
+fn with_value<T>(object: &ObjectBox, name: &str, fun: impl FnOnce(&mut BigInt) -> T) -> Result<T, Fault> {
+    let mut object = object.borrow_mut();
+    let object = object.downcast_mut::<BigIntObject>().ok_or(Fault::InvalidType(format!("BigInt {}: expected BigInt", name)))?;
+    Ok(fun(&mut object.value))
+}
+
+/// Arithmetic with an integer changes the receiver in place like it does for the fixed width
+/// integers. Arithmetic with a float or a fraction gives a float or a fraction that replaces the
+/// receiver.
+fn bigint_arithmetic(object: ObjectBox, context: &mut ContextData, operation: NumberOperation, name: &str) -> Result<Option<ObjectBox>, Fault> {
+    let fraction = Exact::from_fraction(&*context.arguments[0].borrow());
+    if let Some(argument) = fraction {
+        let result = Exact::from_object(&*object.borrow(), name)?.combine(operation, argument)?;
+        context.pop();
+        return Ok(Some(result.into_object()));
+    }
+    let value = with_value(&object, name, |value| value.clone())?;
+    match operand(&context.arguments[0], name)? {
+        Operand::Integer(other) => {
+            let result = apply(operation, &value, &other)?;
+            with_value(&object, name, |value| *value = result)?;
+            Ok(None)
+        }
+        Operand::Float(other) => {
+            let result = operation.apply_float(value.to_f64(), other)?;
+            context.pop();
+            Ok(Some(crate::object::create_f64(result)))
+        }
+    }
+}
+
+fn compare(object: &ObjectBox, context: &ContextData, name: &str) -> Result<Option<Ordering>, Fault> {
+    let fraction = Exact::from_fraction(&*context.arguments[0].borrow());
+    if let Some(argument) = fraction {
+        return Ok(Exact::from_object(&*object.borrow(), name)?.compare(argument));
+    }
+    let value = with_value(object, name, |value| value.clone())?;
+    match operand(&context.arguments[0], name)? {
+        Operand::Integer(other) => Ok(Some(value.cmp(&other))),
+        Operand::Float(other) => Ok(value.to_f64().partial_cmp(&other)),
+    }
+}
+
+fn bigint_equals(object: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let ordering = compare(&object, context, "equals")?;
+    Ok(Some(crate::object::create_boolean(ordering == Some(Ordering::Equal))))
+}
+
+fn bigint_order(object: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let ordering = compare(&object, context, "order")?
+        .ok_or(Fault::InvalidOperation("BigInt order: can't order NaN".to_string()))?;
+    Ok(Some(crate::object::create_i8(ordering as i8)))
+}
+
+fn bigint_to_string(object: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let string = with_value(&object, "to_string", |value| value.to_string())?;
+    Ok(Some(crate::object::create_string(string)))
+}
+
+fn bigint_abs(object: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    with_value(&object, "abs", |value| *value = value.abs())?;
+    Ok(None)
+}
+
+fn bigint_is_zero(object: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let zero = with_value(&object, "is_zero", |value| value.is_zero())?;
+    Ok(Some(crate::object::create_boolean(zero)))
+}
+
+fn bigint_divides(object: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let value = with_value(&object, "divides", |value| value.clone())?;
+    let Operand::Integer(other) = operand(&context.arguments[0], "divides")? else {
+        return Err(Fault::InvalidType("BigInt divides: expected an integer".to_string()));
+    };
+    let divides = if other.is_zero() { value.is_zero() } else { value.div_rem(&other)?.1.is_zero() };
+    Ok(Some(crate::object::create_boolean(divides)))
+}
+
+fn shift_amount(context: &ContextData, name: &str) -> Result<usize, Fault> {
+    match operand(&context.arguments[0], name)? {
+        Operand::Integer(bits) if !bits.is_negative() => bits.to_i128().and_then(|bits| usize::try_from(bits).ok())
+            .ok_or(Fault::Overflow(format!("BigInt {}: shift of {} is too large", name, bits))),
+        _ => Err(Fault::InvalidOperation(format!("BigInt {}: expected a positive integer", name))),
+    }
+}
+
+fn bigint_shift_right(object: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let bits = shift_amount(context, "shift_right")?;
+    with_value(&object, "shift_right", |value| *value = value.shift_right(bits))?;
+    Ok(None)
+}
+
+fn bigint_shift_left(object: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let bits = shift_amount(context, "shift_left")?;
+    with_value(&object, "shift_left", |value| *value = value.shift_left(bits))?;
+    Ok(None)
+}
+
+fn bitwise(object: ObjectBox, context: &mut ContextData, name: &str, operation: fn(u32, u32) -> u32) -> Result<Option<ObjectBox>, Fault> {
+    let Operand::Integer(other) = operand(&context.arguments[0], name)? else {
+        return Err(Fault::InvalidType(format!("BigInt {}: expected an integer", name)));
+    };
+    with_value(&object, name, |value| *value = value.bitwise(&other, operation))?;
+    Ok(None)
+}
+
+fn bigint_and(object: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    bitwise(object, context, "and", |a, b| a & b)
+}
+
+fn bigint_or(object: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    bitwise(object, context, "or", |a, b| a | b)
+}
+
+fn bigint_xor(object: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    bitwise(object, context, "xor", |a, b| a ^ b)
+}
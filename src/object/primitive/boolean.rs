@@ -2,6 +2,7 @@ use std::sync::Arc;
 use std::collections::HashMap;
 use crate::object::{Object, ObjectBox};
 use crate::object::VTable;
+use crate::object::symbol::Symbol;
 use super::PrimitiveObject;
 use crate::object::ContextData;
 use crate::object::Fault;
@@ -23,13 +24,16 @@ impl BooleanObject {
     }
     pub fn make_object_vtable() -> VTable {
         let mut methods = HashMap::new();
-        methods.insert("equals".to_string(), Arc::new(Method::RustMethod { fun: Box::new(boolean_equals) }));
-        methods.insert("to_string".to_string(), Arc::new(Method::RustMethod { fun: Box::new(boolean_to_string) }));
-        methods.insert("order".to_string(), Arc::new(Method::RustMethod { fun: Box::new(boolean_order) }));
+        methods.insert(Symbol::from("equals"), Arc::new(Method::RustMethod { fun: Box::new(boolean_equals) }));
+        methods.insert(Symbol::from("hash"), Arc::new(Method::RustMethod { fun: Box::new(boolean_hash) }));
+        methods.insert(Symbol::from("to_string"), Arc::new(Method::RustMethod { fun: Box::new(boolean_to_string) }));
+        methods.insert(Symbol::from("order"), Arc::new(Method::RustMethod { fun: Box::new(boolean_order) }));
         VTable::new(methods)
     }
     pub fn make_vtable() -> VTable {
-        let methods = HashMap::new();
+        let mut methods = HashMap::new();
+        super::convert::add_methods(&mut methods);
+        crate::object::compare::add_methods(&mut methods);
         VTable::new(methods)
     }
 }
@@ -51,19 +55,13 @@ impl Object for PrimitiveObject<bool> {
         None
     }
     fn duplicate(&self) -> ObjectBox {
-        let boolean = BooleanObject::make_object(self.super_object.clone().unwrap().borrow().duplicate(), self.data);
+        let boolean = BooleanObject::make_object(self.super_object.clone().unwrap(), self.data);
         let mut bln = boolean.borrow_mut();
         bln.initialize(Vec::new(), self.vtable.clone());
         drop(bln);
         boolean as ObjectBox
     }
     fn initialize(&mut self, _: Vec<ObjectBox>, vtable: VTable) {
-        let bool_vtable = BooleanObject::make_vtable();
-        let object_vtable = BooleanObject::make_object_vtable();
-        let parent = self.super_object.clone().unwrap();
-        let mut parent = parent.borrow_mut();
-        parent.initialize(Vec::new(), object_vtable);
-        self.vtable.extend(bool_vtable);
         self.vtable.extend(vtable);
     }
 }
@@ -76,7 +74,15 @@ fn boolean_equals(object: ObjectBox, context: &mut ContextData) -> Result<Option
     let other = other.borrow();
     match (object.downcast_ref::<PrimitiveObject<bool>>(), other.downcast_ref::<PrimitiveObject<bool>>()) {
         (Some(obj), Some(other)) => Ok(Some(crate::object::create_boolean(obj.data == other.data))),
-        _ => Err(Fault::InvalidType(format!("Boolean equals: Expected Boolean")))
+        _ => Err(Fault::InvalidType("Boolean equals: Expected Boolean".to_string()))
+    }
+}
+
+fn boolean_hash(object: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let object = object.borrow();
+    match object.downcast_ref::<PrimitiveObject<bool>>() {
+        Some(obj) => Ok(Some(crate::object::create_u64(crate::object::set::hash_of(&obj.data)))),
+        _ => Err(Fault::InvalidType("Boolean hash: Expected Boolean".to_string()))
     }
 }
 
@@ -84,7 +90,7 @@ fn boolean_to_string(object: ObjectBox, _: &mut ContextData) -> Result<Option<Ob
     let object = object.borrow();
     match object.downcast_ref::<PrimitiveObject<bool>>() {
         Some(obj) => Ok(Some(crate::object::create_string(obj.data.to_string()))),
-        _ => Err(Fault::InvalidType(format!("Boolean equals: Expected Boolean")))
+        _ => Err(Fault::InvalidType("Boolean equals: Expected Boolean".to_string()))
     }
 }
 
@@ -93,14 +99,8 @@ fn boolean_order(object: ObjectBox, context: &mut ContextData) -> Result<Option<
     let other = context.get_argument(0).unwrap();
     let other = other.borrow();
     match (object.downcast_ref::<PrimitiveObject<bool>>(), other.downcast_ref::<PrimitiveObject<bool>>()) {
-        (Some(obj), Some(other)) => if obj.data > other.data {
-                Ok(Some(crate::object::create_i8(1)))
-            } else if obj.data < other.data {
-                Ok(Some(crate::object::create_i8(-1)))
-            } else {
-                Ok(Some(crate::object::create_i8(0)))
-            },
-        _ => Err(Fault::InvalidType(format!("Boolean equals: Expected Boolean")))
+        (Some(obj), Some(other)) => Ok(Some(crate::object::create_i8(obj.data.cmp(&other.data) as i8))),
+        _ => Err(Fault::InvalidType("Boolean equals: Expected Boolean".to_string()))
     }
 }
 
@@ -5,6 +5,7 @@ use std::sync::Arc;
 use crate::object::primitive::PrimitiveObject;
 use crate::object::ObjectBox;
 use crate::object::VTable;
+use crate::object::symbol::Symbol;
 use crate::object::ContextData;
 use crate::object::Fault;
 
@@ -21,21 +22,25 @@ impl CharacterObject {
     }
     pub fn make_object_vtable() -> VTable {
         let mut methods = HashMap::new();
-        methods.insert("equals".to_string(), Arc::new(Method::RustMethod { fun: Box::new(character_equals) }));
-        methods.insert("to_string".to_string(), Arc::new(Method::RustMethod { fun: Box::new(character_to_string) }));
-        methods.insert("order".to_string(), Arc::new(Method::RustMethod { fun: Box::new(character_order) }));
+        methods.insert(Symbol::from("equals"), Arc::new(Method::RustMethod { fun: Box::new(character_equals) }));
+        methods.insert(Symbol::from("hash"), Arc::new(Method::RustMethod { fun: Box::new(character_hash) }));
+        methods.insert(Symbol::from("to_string"), Arc::new(Method::RustMethod { fun: Box::new(character_to_string) }));
+        methods.insert(Symbol::from("order"), Arc::new(Method::RustMethod { fun: Box::new(character_order) }));
         VTable::new(methods)
     }
     pub fn make_vtable() -> VTable {
         let mut methods = HashMap::new();
-        methods.insert("to_lowercase".to_string(), Arc::new(Method::RustMethod { fun: Box::new(char_to_lowercase) }));
-        methods.insert("to_uppercase".to_string(), Arc::new(Method::RustMethod { fun: Box::new(char_to_uppercase) }));
-        methods.insert("is_lowercase".to_string(), Arc::new(Method::RustMethod { fun: Box::new(char_is_lowercase) }));
-        methods.insert("is_uppercase".to_string(), Arc::new(Method::RustMethod { fun: Box::new(char_is_uppercase) }));
-        methods.insert("is_alphabetic".to_string(), Arc::new(Method::RustMethod { fun: Box::new(char_is_alphabetic) }));
-        methods.insert("is_alphanumeric".to_string(), Arc::new(Method::RustMethod { fun: Box::new(char_is_alphanumeric) }));
-        methods.insert("is_numeric".to_string(), Arc::new(Method::RustMethod { fun: Box::new(char_is_numeric) }));
-        methods.insert("is_whitespace".to_string(), Arc::new(Method::RustMethod { fun: Box::new(char_is_whitespace) }));
+        methods.insert(Symbol::from("to_lowercase"), Arc::new(Method::RustMethod { fun: Box::new(char_to_lowercase) }));
+        methods.insert(Symbol::from("to_uppercase"), Arc::new(Method::RustMethod { fun: Box::new(char_to_uppercase) }));
+        methods.insert(Symbol::from("is_lowercase"), Arc::new(Method::RustMethod { fun: Box::new(char_is_lowercase) }));
+        methods.insert(Symbol::from("is_uppercase"), Arc::new(Method::RustMethod { fun: Box::new(char_is_uppercase) }));
+        methods.insert(Symbol::from("is_alphabetic"), Arc::new(Method::RustMethod { fun: Box::new(char_is_alphabetic) }));
+        methods.insert(Symbol::from("is_alphanumeric"), Arc::new(Method::RustMethod { fun: Box::new(char_is_alphanumeric) }));
+        methods.insert(Symbol::from("is_numeric"), Arc::new(Method::RustMethod { fun: Box::new(char_is_numeric) }));
+        methods.insert(Symbol::from("is_whitespace"), Arc::new(Method::RustMethod { fun: Box::new(char_is_whitespace) }));
+        methods.insert(Symbol::from("to_int"), Arc::new(Method::RustMethod { fun: Box::new(char_to_int) }));
+        super::convert::add_methods(&mut methods);
+        crate::object::compare::add_methods(&mut methods);
         VTable::new(methods)
     }
 }
@@ -57,18 +62,13 @@ impl Object for PrimitiveObject<char> {
         None
     }
     fn duplicate(&self) -> ObjectBox {
-        let character = CharacterObject::make_object(self.super_object.clone().unwrap().borrow().duplicate(), self.data);
+        let character = CharacterObject::make_object(self.super_object.clone().unwrap(), self.data);
         let mut chr = character.borrow_mut();
         chr.initialize(Vec::new(), self.vtable.clone());
         drop(chr);
         character
     }
     fn initialize(&mut self, _: Vec<ObjectBox>, vtable: VTable) {
-        let object_vtable = CharacterObject::make_object_vtable();
-        let parent = self.super_object.clone().unwrap();
-        let mut parent = parent.borrow_mut();
-        parent.initialize(Vec::new(), object_vtable);
-        self.vtable.extend(CharacterObject::make_vtable());
         self.vtable.extend(vtable);
     }
 }
@@ -81,7 +81,15 @@ fn character_equals(object: ObjectBox, context: &mut ContextData) -> Result<Opti
     let other = other.borrow();
     match (object.downcast_ref::<PrimitiveObject<char>>(), other.downcast_ref::<PrimitiveObject<char>>()) {
         (Some(obj), Some(other)) => Ok(Some(crate::object::create_boolean(obj.data == other.data))),
-        _ => Err(Fault::InvalidType(format!("Char equals: Expected Char")))
+        _ => Err(Fault::InvalidType("Char equals: Expected Char".to_string()))
+    }
+}
+
+fn character_hash(object: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let object = object.borrow();
+    match object.downcast_ref::<PrimitiveObject<char>>() {
+        Some(obj) => Ok(Some(crate::object::create_u64(crate::object::set::hash_of(&obj.data)))),
+        _ => Err(Fault::InvalidType("Char hash: Expected Char".to_string()))
     }
 }
 
@@ -89,7 +97,7 @@ fn character_to_string(object: ObjectBox, _: &mut ContextData) -> Result<Option<
     let object = object.borrow();
     match object.downcast_ref::<PrimitiveObject<char>>() {
         Some(obj) => Ok(Some(crate::object::create_string(obj.data.to_string()))),
-        _ => Err(Fault::InvalidType(format!("Char equals: Expected Char")))
+        _ => Err(Fault::InvalidType("Char equals: Expected Char".to_string()))
     }
 }
 
@@ -105,7 +113,7 @@ fn character_order(object: ObjectBox, context: &mut ContextData) -> Result<Optio
             } else {
                 Ok(Some(crate::object::create_i8(0)))
             },
-        _ => Err(Fault::InvalidType(format!("Char equals: Expected Char")))
+        _ => Err(Fault::InvalidType("Char equals: Expected Char".to_string()))
     }
 }
 
@@ -113,7 +121,7 @@ fn char_to_lowercase(object: ObjectBox, _: &mut ContextData) -> Result<Option<Ob
     let object = object.borrow();
     match object.downcast_ref::<PrimitiveObject<char>>() {
         Some(obj) => Ok(Some(crate::object::create_character(obj.data.to_lowercase().next().unwrap_or(obj.data)))),
-        _ => Err(Fault::InvalidType(format!("Char equals: Expected Char")))
+        _ => Err(Fault::InvalidType("Char equals: Expected Char".to_string()))
     }
 }
 
@@ -121,7 +129,7 @@ fn char_to_uppercase(object: ObjectBox, _: &mut ContextData) -> Result<Option<Ob
     let object = object.borrow();
     match object.downcast_ref::<PrimitiveObject<char>>() {
         Some(obj) => Ok(Some(crate::object::create_character(obj.data.to_uppercase().next().unwrap_or(obj.data)))),
-        _ => Err(Fault::InvalidType(format!("Char equals: Expected Char")))
+        _ => Err(Fault::InvalidType("Char equals: Expected Char".to_string()))
     }
 }
 
@@ -129,7 +137,7 @@ fn char_is_lowercase(object: ObjectBox, _: &mut ContextData) -> Result<Option<Ob
     let object = object.borrow();
     match object.downcast_ref::<PrimitiveObject<char>>() {
         Some(obj) => Ok(Some(crate::object::create_boolean(obj.data.is_lowercase()))),
-        _ => Err(Fault::InvalidType(format!("Char equals: Expected Char")))
+        _ => Err(Fault::InvalidType("Char equals: Expected Char".to_string()))
     }
 }
 
@@ -137,7 +145,7 @@ fn char_is_uppercase(object: ObjectBox, _: &mut ContextData) -> Result<Option<Ob
     let object = object.borrow();
     match object.downcast_ref::<PrimitiveObject<char>>() {
         Some(obj) => Ok(Some(crate::object::create_boolean(obj.data.is_uppercase()))),
-        _ => Err(Fault::InvalidType(format!("Char equals: Expected Char")))
+        _ => Err(Fault::InvalidType("Char equals: Expected Char".to_string()))
     }
 }
 
@@ -145,7 +153,7 @@ fn char_is_alphabetic(object: ObjectBox, _: &mut ContextData) -> Result<Option<O
     let object = object.borrow();
     match object.downcast_ref::<PrimitiveObject<char>>() {
         Some(obj) => Ok(Some(crate::object::create_boolean(obj.data.is_alphabetic()))),
-        _ => Err(Fault::InvalidType(format!("Char equals: Expected Char")))
+        _ => Err(Fault::InvalidType("Char equals: Expected Char".to_string()))
     }
 }
 
@@ -153,7 +161,7 @@ fn char_is_alphanumeric(object: ObjectBox, _: &mut ContextData) -> Result<Option
     let object = object.borrow();
     match object.downcast_ref::<PrimitiveObject<char>>() {
         Some(obj) => Ok(Some(crate::object::create_boolean(obj.data.is_alphanumeric()))),
-        _ => Err(Fault::InvalidType(format!("Char equals: Expected Char")))
+        _ => Err(Fault::InvalidType("Char equals: Expected Char".to_string()))
     }
 }
 
@@ -161,7 +169,7 @@ fn char_is_numeric(object: ObjectBox, _: &mut ContextData) -> Result<Option<Obje
     let object = object.borrow();
     match object.downcast_ref::<PrimitiveObject<char>>() {
         Some(obj) => Ok(Some(crate::object::create_boolean(obj.data.is_numeric()))),
-        _ => Err(Fault::InvalidType(format!("Char equals: Expected Char")))
+        _ => Err(Fault::InvalidType("Char equals: Expected Char".to_string()))
     }
 }
 
@@ -169,7 +177,16 @@ fn char_is_whitespace(object: ObjectBox, _: &mut ContextData) -> Result<Option<O
     let object = object.borrow();
     match object.downcast_ref::<PrimitiveObject<char>>() {
         Some(obj) => Ok(Some(crate::object::create_boolean(obj.data.is_whitespace()))),
-        _ => Err(Fault::InvalidType(format!("Char equals: Expected Char")))
+        _ => Err(Fault::InvalidType("Char equals: Expected Char".to_string()))
     }
 }
 
+
+/// The code point of the character
+fn char_to_int(object: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let object = object.borrow();
+    match object.downcast_ref::<PrimitiveObject<char>>() {
+        Some(obj) => Ok(Some(crate::object::create_u32(obj.data as u32))),
+        _ => Err(Fault::InvalidType("Char to_int: Expected Char".to_string()))
+    }
+}
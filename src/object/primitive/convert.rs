@@ -0,0 +1,317 @@
+//! Conversions between the primitive types
+//! Every integer, float, character and boolean understands to_<type> for every other one of
+//! them in three variants:
+//! - to_<type> faults when the value can't be represented exactly
+//! - wrapping_to_<type> keeps the low bits of the value like an `as` cast between integers does
+//! - saturating_to_<type> clamps the value to the range of the type
+//!
+//! Floats are truncated toward zero before they are wrapped or saturated into an integer, NaN
+//! becomes 0. Characters convert as their code point and booleans as 0 or 1. A code point that
+//! wraps or saturates into a surrogate becomes the replacement character.
+//!
+//! A BigInt converts the same way and a Rational or a Decimal converts like a float unless it is a
+//! whole number. Every number understands to_bigint, which faults on one that isn't whole.
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use lazy_static::lazy_static;
+
+use crate::object::primitive::bigint::{BigInt, BigIntObject};
+use crate::object::primitive::exact::Exact;
+use crate::object::primitive::rational::Rational;
+use crate::object::symbol::Symbol;
+use crate::object::value::Immediate;
+use crate::object::{ContextData, Fault, Method, ObjectBox};
+
+/// Conversion
+/// What to do with a value that doesn't fit in the type it is converted to
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Conversion {
+    Checked,
+    Wrapping,
+    Saturating,
+}
+
+/// Target
+/// The type that a value gets converted to
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Target {
+    I8,
+    I16,
+    I32,
+    I64,
+    U8,
+    U16,
+    U32,
+    U64,
+    F32,
+    F64,
+    Char,
+    Boolean,
+}
+
+const TARGETS: &[(&str, Target)] = &[
+    ("i8", Target::I8),
+    ("i16", Target::I16),
+    ("i32", Target::I32),
+    ("i64", Target::I64),
+    ("u8", Target::U8),
+    ("u16", Target::U16),
+    ("u32", Target::U32),
+    ("u64", Target::U64),
+    ("f32", Target::F32),
+    ("f64", Target::F64),
+    ("char", Target::Char),
+    ("boolean", Target::Boolean),
+];
+
+lazy_static! {
+    static ref CONVERSIONS: HashMap<Symbol, (Target, Conversion)> = {
+        let mut conversions = HashMap::new();
+        for (name, target) in TARGETS {
+            conversions.insert(Symbol::from(format!("to_{}", name).as_str()), (*target, Conversion::Checked));
+            conversions.insert(Symbol::from(format!("wrapping_to_{}", name).as_str()), (*target, Conversion::Wrapping));
+            conversions.insert(Symbol::from(format!("saturating_to_{}", name).as_str()), (*target, Conversion::Saturating));
+        }
+        conversions
+    };
+}
+
+/// Find the conversion that a selector stands for
+pub fn conversion(selector: Symbol) -> Option<(Target, Conversion)> {
+    CONVERSIONS.get(&selector).copied()
+}
+
+/// Add a method for every conversion to a vtable
+pub fn add_methods(methods: &mut HashMap<Symbol, Arc<Method>>) {
+    for (selector, (target, conversion)) in CONVERSIONS.iter() {
+        let (target, conversion) = (*target, *conversion);
+        let fun = move |object: ObjectBox, _: &mut ContextData| -> Result<Option<ObjectBox>, Fault> {
+            let object = object.borrow();
+            let result = if let Some(big) = object.downcast_ref::<BigIntObject>() {
+                convert_bigint(&big.value, target, conversion)?
+            } else if let Some(fraction) = Exact::from_fraction(&*object) {
+                convert_fraction(fraction.to_rational()?, target, conversion)?
+            } else {
+                let value = Immediate::from_object(&*object)
+                    .ok_or(Fault::InvalidType("Conversion: expected a primitive".to_string()))?;
+                convert(value, target, conversion)?
+            };
+            Ok(Some(result.into_object()))
+        };
+        methods.insert(*selector, Arc::new(Method::RustMethod { fun: Box::new(fun) }));
+    }
+    methods.insert(Symbol::from("to_bigint"), Arc::new(Method::RustMethod { fun: Box::new(to_bigint) }));
+}
+
+fn to_bigint(object: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let object = object.borrow();
+    if let Some(big) = object.downcast_ref::<BigIntObject>() {
+        return Ok(Some(crate::object::create_bigint(big.value.clone())));
+    }
+    if let Some(fraction) = Exact::from_fraction(&*object) {
+        let fraction = fraction.to_rational()?;
+        return match fraction.is_integer() {
+            true => Ok(Some(crate::object::create_bigint(BigInt::from(fraction.numerator())))),
+            false => Err(Fault::InvalidOperation(format!("{} can't be converted to BigInt without loss", fraction))),
+        };
+    }
+    let value = Immediate::from_object(&*object)
+        .ok_or(Fault::InvalidType("Conversion: expected a primitive".to_string()))?;
+    let big = match Scalar::new(value) {
+        Scalar::Integer(value) => BigInt::from(value),
+        Scalar::Float(value) => BigInt::from_f64(value).filter(|_| value.fract() == 0.0)
+            .ok_or(Fault::InvalidOperation(format!("{} can't be converted to BigInt without loss", value)))?,
+    };
+    Ok(Some(crate::object::create_bigint(big)))
+}
+
+/// A primitive value widened so that every other one can be made from it
+#[derive(Clone, Copy)]
+enum Scalar {
+    Integer(i128),
+    Float(f64),
+}
+
+impl Scalar {
+    fn new(value: Immediate) -> Scalar {
+        match value {
+            Immediate::I8(value) => Scalar::Integer(value as i128),
+            Immediate::I16(value) => Scalar::Integer(value as i128),
+            Immediate::I32(value) => Scalar::Integer(value as i128),
+            Immediate::I64(value) => Scalar::Integer(value as i128),
+            Immediate::U8(value) => Scalar::Integer(value as i128),
+            Immediate::U16(value) => Scalar::Integer(value as i128),
+            Immediate::U32(value) => Scalar::Integer(value as i128),
+            Immediate::U64(value) => Scalar::Integer(value as i128),
+            Immediate::F32(value) => Scalar::Float(value as f64),
+            Immediate::F64(value) => Scalar::Float(value),
+            Immediate::Boolean(value) => Scalar::Integer(value as i128),
+            Immediate::Char(value) => Scalar::Integer(value as i128),
+        }
+    }
+
+    /// Get the value as an integer, a float that isn't integral is None unless it may be truncated
+    fn integer(self, conversion: Conversion) -> Option<i128> {
+        match self {
+            Scalar::Integer(value) => Some(value),
+            Scalar::Float(value) if conversion != Conversion::Checked => Some(value as i128),
+            Scalar::Float(value) => {
+                let integer = value as i128;
+                (integer as f64 == value).then_some(integer)
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for Scalar {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Scalar::Integer(value) => write!(f, "{}", value),
+            Scalar::Float(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+/// Convert a primitive value to another primitive type
+pub fn convert(value: Immediate, target: Target, conversion: Conversion) -> Result<Immediate, Fault> {
+    convert_scalar(Scalar::new(value), target, conversion)
+}
+
+/// Convert a BigInt to a primitive type
+/// A value too big for an i128 becomes a float approximately. Wrapping keeps the low 128 bits and saturating clamps to the
+/// range of an i128 first, both of which are wider than any of the integer types.
+pub fn convert_bigint(value: &BigInt, target: Target, conversion: Conversion) -> Result<Immediate, Fault> {
+    let scalar = match (value.to_i128(), target) {
+        (Some(integer), _) => Scalar::Integer(integer),
+        (None, Target::F32 | Target::F64) => Scalar::Float(value.to_f64()),
+        (None, _) => match conversion {
+            Conversion::Checked => return Err(Fault::InvalidOperation(format!("{} can't be converted to {:?} without loss", value, target))),
+            Conversion::Wrapping => Scalar::Integer(value.to_i128_wrapping()),
+            Conversion::Saturating if value.is_negative() => Scalar::Integer(i128::MIN),
+            Conversion::Saturating => Scalar::Integer(i128::MAX),
+        },
+    };
+    convert_scalar(scalar, target, conversion)
+}
+
+/// Convert a fraction to a primitive type, it converts like a float unless it is a whole number
+pub fn convert_fraction(value: Rational, target: Target, conversion: Conversion) -> Result<Immediate, Fault> {
+    let scalar = match value.is_integer() {
+        true => Scalar::Integer(value.numerator()),
+        false => Scalar::Float(value.to_f64()),
+    };
+    convert_scalar(scalar, target, conversion)
+}
+
+fn convert_scalar(scalar: Scalar, target: Target, conversion: Conversion) -> Result<Immediate, Fault> {
+    let lost = || Fault::InvalidOperation(format!("{} can't be converted to {:?} without loss", scalar, target));
+    macro_rules! integer {
+        ($type:ty, $variant:ident) => {{
+            let integer = scalar.integer(conversion).ok_or_else(lost)?;
+            let value = match conversion {
+                Conversion::Checked => <$type>::try_from(integer).map_err(|_| lost())?,
+                Conversion::Wrapping => integer as $type,
+                Conversion::Saturating => integer.clamp(<$type>::MIN as i128, <$type>::MAX as i128) as $type,
+            };
+            Immediate::$variant(value)
+        }};
+    }
+    let result = match target {
+        Target::I8 => integer!(i8, I8),
+        Target::I16 => integer!(i16, I16),
+        Target::I32 => integer!(i32, I32),
+        Target::I64 => integer!(i64, I64),
+        Target::U8 => integer!(u8, U8),
+        Target::U16 => integer!(u16, U16),
+        Target::U32 => integer!(u32, U32),
+        Target::U64 => integer!(u64, U64),
+        Target::F64 => match scalar {
+            Scalar::Integer(value) if conversion == Conversion::Checked && value as f64 as i128 != value => return Err(lost()),
+            Scalar::Integer(value) => Immediate::F64(value as f64),
+            Scalar::Float(value) => Immediate::F64(value),
+        },
+        Target::F32 => match scalar {
+            Scalar::Integer(value) if conversion == Conversion::Checked && value as f32 as i128 != value => return Err(lost()),
+            Scalar::Integer(value) => Immediate::F32(value as f32),
+            Scalar::Float(value) => {
+                let narrowed = value as f32;
+                match conversion {
+                    Conversion::Checked if !value.is_nan() && narrowed as f64 != value => return Err(lost()),
+                    Conversion::Saturating if value.is_finite() => Immediate::F32(value.clamp(f32::MIN as f64, f32::MAX as f64) as f32),
+                    _ => Immediate::F32(narrowed),
+                }
+            }
+        },
+        Target::Char => {
+            let integer = scalar.integer(conversion).ok_or_else(lost)?;
+            let code = match conversion {
+                Conversion::Checked => u32::try_from(integer).map_err(|_| lost())?,
+                Conversion::Wrapping => integer.rem_euclid(char::MAX as i128 + 1) as u32,
+                Conversion::Saturating => integer.clamp(0, char::MAX as i128) as u32,
+            };
+            match char::from_u32(code) {
+                Some(character) => Immediate::Char(character),
+                None if conversion == Conversion::Checked => return Err(lost()),
+                None => Immediate::Char(char::REPLACEMENT_CHARACTER),
+            }
+        }
+        Target::Boolean => {
+            let integer = scalar.integer(conversion).ok_or_else(lost)?;
+            match conversion {
+                Conversion::Checked if integer == 0 || integer == 1 => Immediate::Boolean(integer == 1),
+                Conversion::Checked => return Err(lost()),
+                Conversion::Wrapping => Immediate::Boolean(integer & 1 == 1),
+                Conversion::Saturating => Immediate::Boolean(integer > 0),
+            }
+        }
+    };
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Conversion::*;
+
+    #[test]
+    fn checked_conversions_fault_on_loss() {
+        assert_eq!(convert(Immediate::I64(127), Target::I8, Checked).unwrap(), Immediate::I8(127));
+        assert!(convert(Immediate::I64(128), Target::I8, Checked).is_err());
+        assert!(convert(Immediate::I8(-1), Target::U64, Checked).is_err());
+        assert!(convert(Immediate::F64(1.5), Target::I32, Checked).is_err());
+        assert_eq!(convert(Immediate::F64(-3.0), Target::I32, Checked).unwrap(), Immediate::I32(-3));
+        assert!(convert(Immediate::U64((1 << 53) + 1), Target::F64, Checked).is_err());
+        assert!(convert(Immediate::F64(0.1), Target::F32, Checked).is_err());
+        assert!(convert(Immediate::U32(0xD800), Target::Char, Checked).is_err());
+        assert!(convert(Immediate::I8(2), Target::Boolean, Checked).is_err());
+    }
+
+    #[test]
+    fn wrapping_conversions_keep_the_low_bits() {
+        assert_eq!(convert(Immediate::I64(300), Target::U8, Wrapping).unwrap(), Immediate::U8(44));
+        assert_eq!(convert(Immediate::I8(-1), Target::U16, Wrapping).unwrap(), Immediate::U16(u16::MAX));
+        assert_eq!(convert(Immediate::F64(-1.9), Target::I8, Wrapping).unwrap(), Immediate::I8(-1));
+        assert_eq!(convert(Immediate::U32(0xD800), Target::Char, Wrapping).unwrap(), Immediate::Char(char::REPLACEMENT_CHARACTER));
+        assert_eq!(convert(Immediate::I8(2), Target::Boolean, Wrapping).unwrap(), Immediate::Boolean(false));
+    }
+
+    #[test]
+    fn saturating_conversions_clamp() {
+        assert_eq!(convert(Immediate::I64(-5), Target::U32, Saturating).unwrap(), Immediate::U32(0));
+        assert_eq!(convert(Immediate::F64(1e300), Target::I16, Saturating).unwrap(), Immediate::I16(i16::MAX));
+        assert_eq!(convert(Immediate::F64(f64::NAN), Target::I64, Saturating).unwrap(), Immediate::I64(0));
+        assert_eq!(convert(Immediate::F64(1e40), Target::F32, Saturating).unwrap(), Immediate::F32(f32::MAX));
+        assert_eq!(convert(Immediate::I64(-7), Target::Char, Saturating).unwrap(), Immediate::Char('\0'));
+        assert_eq!(convert(Immediate::I64(-7), Target::Boolean, Saturating).unwrap(), Immediate::Boolean(false));
+    }
+
+    #[test]
+    fn big_integers_convert_past_the_range_of_i128() {
+        let big = BigInt::from(i128::MAX).mul(&BigInt::from(4));
+        assert!(convert_bigint(&big, Target::U64, Checked).is_err());
+        assert_eq!(convert_bigint(&big, Target::U64, Saturating).unwrap(), Immediate::U64(u64::MAX));
+        assert_eq!(convert_bigint(&big, Target::I8, Wrapping).unwrap(), Immediate::I8(-4));
+        assert_eq!(convert_bigint(&big, Target::F64, Checked).unwrap(), Immediate::F64(i128::MAX as f64 * 4.0));
+    }
+}
@@ -0,0 +1,408 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::object::primitive::bigint::BigInt;
+use crate::object::primitive::exact::{self, Exact};
+use crate::object::primitive::rational::Rational;
+use crate::object::primitive::NUMBER_OPERATIONS;
+use crate::object::symbol::Symbol;
+use crate::object::{ContextData, Fault, Method, Object, ObjectBox, VTable};
+
+/// The most digits a Decimal can have after the point, 10^38 is the largest power of ten in an i128
+pub const MAX_SCALE: u32 = 38;
+
+/// Decimal
+/// A fixed point number, the mantissa divided by 10 to the power of the scale. The result of an
+/// operation has the larger scale of the two sides and is rounded half to even when it has more
+/// digits than that, so 1.00 / 3 is 0.33 and 0.125 with a scale of 2 is 0.12.
+/// Two decimals are equal when they have the same value, whatever their scales are.
+#[derive(Clone, Copy, Debug)]
+pub struct Decimal {
+    mantissa: i128,
+    scale: u32,
+}
+
+fn overflow(name: &str) -> Fault {
+    Fault::Overflow(format!("Decimal {}: overflowed", name))
+}
+
+fn power_of_ten(exponent: u32) -> BigInt {
+    BigInt::from(10).pow(exponent)
+}
+
+/// Divide rounding half to even
+fn round_div(numerator: &BigInt, denominator: &BigInt) -> Result<BigInt, Fault> {
+    let (quotient, remainder) = numerator.div_rem(denominator)?;
+    let twice = remainder.abs().shift_left(1);
+    let round_away = match twice.cmp(&denominator.abs()) {
+        Ordering::Greater => true,
+        Ordering::Equal => !quotient.div_rem(&BigInt::from(2))?.1.is_zero(),
+        Ordering::Less => false,
+    };
+    if !round_away {
+        return Ok(quotient);
+    }
+    let step = if numerator.is_negative() != denominator.is_negative() { -1 } else { 1 };
+    Ok(quotient.add(&BigInt::from(step)))
+}
+
+// The arithmetic faults on overflow so it returns a Result instead of implementing std::ops
+#[allow(clippy::should_implement_trait)]
+impl Decimal {
+    pub fn new(mantissa: i128, scale: u32) -> Result<Decimal, Fault> {
+        if scale > MAX_SCALE {
+            return Err(Fault::InvalidOperation(format!("Decimal: a scale of {} is more than {}", scale, MAX_SCALE)));
+        }
+        Ok(Decimal { mantissa, scale })
+    }
+
+    pub fn from_integer(value: i128) -> Decimal {
+        Decimal { mantissa: value, scale: 0 }
+    }
+
+    pub fn zero() -> Decimal {
+        Decimal::from_integer(0)
+    }
+
+    fn from_big(mantissa: BigInt, scale: u32, name: &str) -> Result<Decimal, Fault> {
+        Decimal::new(mantissa.to_i128().ok_or_else(|| overflow(name))?, scale)
+    }
+
+    /// The Decimal closest to a fraction with the given scale
+    pub fn from_rational(value: Rational, scale: u32) -> Result<Decimal, Fault> {
+        let numerator = BigInt::from(value.numerator()).mul(&power_of_ten(scale));
+        Decimal::from_big(round_div(&numerator, &BigInt::from(value.denominator()))?, scale, "new")
+    }
+
+    /// Parse a number like -12.50, the scale is the number of digits after the point
+    pub fn parse(text: &str) -> Option<Decimal> {
+        let (whole, fraction) = text.split_once('.').unwrap_or((text, ""));
+        if !fraction.bytes().all(|digit| digit.is_ascii_digit()) || whole.trim_start_matches(['-', '+']).is_empty() {
+            return None;
+        }
+        let mantissa = format!("{}{}", whole, fraction).parse().ok()?;
+        Decimal::new(mantissa, u32::try_from(fraction.len()).ok()?).ok()
+    }
+
+    pub fn mantissa(&self) -> i128 {
+        self.mantissa
+    }
+
+    pub fn scale(&self) -> u32 {
+        self.scale
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.mantissa == 0
+    }
+
+    /// Change the scale, rounding half to even when digits are dropped
+    pub fn with_scale(self, scale: u32) -> Result<Decimal, Fault> {
+        if scale >= self.scale {
+            let mantissa = 10i128.checked_pow(scale - self.scale).and_then(|factor| self.mantissa.checked_mul(factor));
+            return Decimal::new(mantissa.ok_or_else(|| overflow("with_scale"))?, scale);
+        }
+        let mantissa = round_div(&BigInt::from(self.mantissa), &power_of_ten(self.scale - scale))?;
+        Decimal::from_big(mantissa, scale, "with_scale")
+    }
+
+    pub fn abs(self) -> Result<Decimal, Fault> {
+        let mantissa = self.mantissa.checked_abs().ok_or_else(|| overflow("abs"))?;
+        Ok(Decimal { mantissa, scale: self.scale })
+    }
+
+    pub fn add(self, other: Decimal) -> Result<Decimal, Fault> {
+        let scale = self.scale.max(other.scale);
+        let (left, right) = (self.with_scale(scale)?, other.with_scale(scale)?);
+        Decimal::new(left.mantissa.checked_add(right.mantissa).ok_or_else(|| overflow("add"))?, scale)
+    }
+
+    pub fn sub(self, other: Decimal) -> Result<Decimal, Fault> {
+        let scale = self.scale.max(other.scale);
+        let (left, right) = (self.with_scale(scale)?, other.with_scale(scale)?);
+        Decimal::new(left.mantissa.checked_sub(right.mantissa).ok_or_else(|| overflow("sub"))?, scale)
+    }
+
+    pub fn mul(self, other: Decimal) -> Result<Decimal, Fault> {
+        let scale = self.scale.max(other.scale);
+        let product = BigInt::from(self.mantissa).mul(&BigInt::from(other.mantissa));
+        let mantissa = round_div(&product, &power_of_ten(self.scale + other.scale - scale))?;
+        Decimal::from_big(mantissa, scale, "mul")
+    }
+
+    pub fn div(self, other: Decimal) -> Result<Decimal, Fault> {
+        let scale = self.scale.max(other.scale);
+        let numerator = BigInt::from(self.mantissa).mul(&power_of_ten(scale + other.scale - self.scale));
+        let mantissa = round_div(&numerator, &BigInt::from(other.mantissa))?;
+        Decimal::from_big(mantissa, scale, "div")
+    }
+
+    /// The remainder of dividing with the quotient truncated toward zero, like Rust's integers
+    pub fn rem(self, other: Decimal) -> Result<Decimal, Fault> {
+        let scale = self.scale.max(other.scale);
+        let (left, right) = (self.with_scale(scale)?, other.with_scale(scale)?);
+        if right.mantissa == 0 {
+            return Err(Fault::DivideByZero);
+        }
+        Decimal::new(left.mantissa.checked_rem(right.mantissa).unwrap_or(0), scale)
+    }
+
+    /// Raise to an integer power, the result keeps the scale
+    pub fn pow(self, exponent: i128) -> Result<Decimal, Fault> {
+        let power = u32::try_from(exponent.unsigned_abs()).map_err(|_| overflow("pow"))?;
+        let digits = self.scale.checked_mul(power).ok_or_else(|| overflow("pow"))?;
+        let (mut numerator, mut denominator) = (BigInt::from(self.mantissa).pow(power), power_of_ten(digits));
+        if exponent < 0 {
+            std::mem::swap(&mut numerator, &mut denominator);
+        }
+        let mantissa = round_div(&numerator.mul(&power_of_ten(self.scale)), &denominator)?;
+        Decimal::from_big(mantissa, self.scale, "pow")
+    }
+
+    pub fn to_rational(self) -> Result<Rational, Fault> {
+        Rational::new(self.mantissa, 10i128.pow(self.scale))
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        self.mantissa as f64 / 10f64.powi(self.scale as i32)
+    }
+}
+
+impl PartialEq for Decimal {
+    fn eq(&self, other: &Decimal) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Decimal {}
+
+impl Ord for Decimal {
+    fn cmp(&self, other: &Decimal) -> Ordering {
+        let scale = self.scale.max(other.scale);
+        let left = BigInt::from(self.mantissa).mul(&power_of_ten(scale - self.scale));
+        let right = BigInt::from(other.mantissa).mul(&power_of_ten(scale - other.scale));
+        left.cmp(&right)
+    }
+}
+
+impl PartialOrd for Decimal {
+    fn partial_cmp(&self, other: &Decimal) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl std::fmt::Display for Decimal {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let digits = format!("{:0width$}", self.mantissa.unsigned_abs(), width = self.scale as usize + 1);
+        let (whole, fraction) = digits.split_at(digits.len() - self.scale as usize);
+        let sign = if self.mantissa < 0 { "-" } else { "" };
+        if fraction.is_empty() {
+            write!(f, "{}{}", sign, whole)
+        } else {
+            write!(f, "{}{}.{}", sign, whole, fraction)
+        }
+    }
+}
+
+
+pub struct DecimalObject {
+    super_object: Option<ObjectBox>,
+    vtable: VTable,
+    pub value: Decimal,
+}
+
+impl DecimalObject {
+    pub fn make_object(parent: ObjectBox, value: Decimal) -> ObjectBox {
+        ObjectBox::new(DecimalObject { super_object: Some(parent), vtable: VTable::new_empty(), value })
+    }
+    pub fn make_object_vtable() -> VTable {
+        let mut methods = HashMap::new();
+        methods.insert(Symbol::from("equals"), Arc::new(Method::RustMethod { fun: Box::new(exact::exact_equals) }));
+        methods.insert(Symbol::from("order"), Arc::new(Method::RustMethod { fun: Box::new(exact::exact_order) }));
+        methods.insert(Symbol::from("to_string"), Arc::new(Method::RustMethod { fun: Box::new(decimal_to_string) }));
+        VTable::new(methods)
+    }
+    pub fn make_number_vtable() -> VTable {
+        let mut methods = HashMap::new();
+        for (name, operation) in NUMBER_OPERATIONS.iter().copied() {
+            let fun = move |object: ObjectBox, context: &mut ContextData| exact::exact_arithmetic(object, context, operation, name);
+            methods.insert(Symbol::from(name), Arc::new(Method::RustMethod { fun: Box::new(fun) }));
+        }
+        methods.insert(Symbol::from("abs"), Arc::new(Method::RustMethod { fun: Box::new(decimal_abs) }));
+        methods.insert(Symbol::from("is_zero"), Arc::new(Method::RustMethod { fun: Box::new(decimal_is_zero) }));
+        methods.insert(Symbol::from("scale"), Arc::new(Method::RustMethod { fun: Box::new(decimal_scale) }));
+        methods.insert(Symbol::from("with_scale"), Arc::new(Method::RustMethod { fun: Box::new(decimal_with_scale) }));
+        VTable::new(methods)
+    }
+}
+
+impl Object for DecimalObject {
+    fn get_vtable(&self) -> &VTable {
+        &self.vtable
+    }
+    fn get_super_object(&self) -> Option<ObjectBox> {
+        self.super_object.clone()
+    }
+    fn get_field(&self, _index: usize) -> Option<ObjectBox> {
+        panic!("Decimal objects do not have fields")
+    }
+    fn set_field(&mut self, _index: usize, _value: ObjectBox) {
+        panic!("Decimal objects do not have fields")
+    }
+    fn size(&self) -> Option<usize> {
+        None
+    }
+    fn duplicate(&self) -> ObjectBox {
+        let decimal = DecimalObject::make_object(self.super_object.clone().unwrap(), self.value);
+        let mut decimal_mut = decimal.borrow_mut();
+        decimal_mut.initialize(Vec::new(), self.vtable.clone());
+        drop(decimal_mut);
+        decimal
+    }
+    fn initialize(&mut self, _: Vec<ObjectBox>, vtable: VTable) {
+        self.vtable.extend(vtable);
+    }
+}
+
+/// Make a Decimal out of an integer, another Decimal or a string like 12.50
+/// A second argument gives the scale, which also lets a Rational or a float be rounded to a Decimal.
+pub fn from_arguments(arguments: &[ObjectBox]) -> Result<Decimal, Fault> {
+    let value = |object: &ObjectBox| -> Result<Exact, Fault> {
+        let object = object.borrow();
+        match object.downcast_ref::<crate::object::string::StringObject>() {
+            Some(string) => Decimal::parse(&string.value).map(Exact::Decimal)
+                .ok_or(Fault::InvalidOperation(format!("Decimal: {:?} isn't a decimal number", string.value))),
+            None => Exact::from_object(&*object, "new"),
+        }
+    };
+    match arguments {
+        [] => Ok(Decimal::zero()),
+        [argument] => match value(argument)? {
+            Exact::Integer(integer) => Ok(Decimal::from_integer(integer)),
+            Exact::Decimal(decimal) => Ok(decimal),
+            _ => Err(Fault::InvalidOperation("Decimal new: a fraction or a float needs a scale".to_string())),
+        },
+        [argument, scale] => {
+            let scale = u32::try_from(exact::integer_argument(scale, "Decimal new")?)
+                .map_err(|_| Fault::InvalidOperation("Decimal new: the scale must be positive".to_string()))?;
+            value(argument)?.to_decimal(scale)
+        }
+        _ => Err(Fault::InvalidType(format!("expected at most 2 arguments, got {}", arguments.len()))),
+    }
+}
+
+fn with_value<T>(object: &ObjectBox, name: &str, fun: impl FnOnce(&mut Decimal) -> T) -> Result<T, Fault> {
+    let mut object = object.borrow_mut();
+    let object = object.downcast_mut::<DecimalObject>().ok_or(Fault::InvalidType(format!("Decimal {}: expected Decimal", name)))?;
+    Ok(fun(&mut object.value))
+}
+
+fn decimal_to_string(object: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let string = with_value(&object, "to_string", |value| value.to_string())?;
+    Ok(Some(crate::object::create_string(string)))
+}
+
+fn decimal_abs(object: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    with_value(&object, "abs", |value| value.abs().map(|abs| *value = abs))??;
+    Ok(None)
+}
+
+fn decimal_is_zero(object: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let zero = with_value(&object, "is_zero", |value| value.is_zero())?;
+    Ok(Some(crate::object::create_boolean(zero)))
+}
+
+fn decimal_scale(object: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let scale = with_value(&object, "scale", |value| value.scale())?;
+    Ok(Some(crate::object::create_u32(scale)))
+}
+
+fn decimal_with_scale(object: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let scale = u32::try_from(exact::integer_argument(&context.arguments[0], "Decimal with_scale")?)
+        .map_err(|_| Fault::InvalidOperation("Decimal with_scale: the scale must be positive".to_string()))?;
+    with_value(&object, "with_scale", |value| value.with_scale(scale).map(|scaled| *value = scaled))??;
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::object::{create_decimal, create_i64, create_rational, create_string};
+    use crate::object::value::Immediate;
+
+    fn decimal(text: &str) -> Decimal {
+        Decimal::parse(text).unwrap()
+    }
+
+    fn send(receiver: &ObjectBox, selector: &str, arguments: Vec<ObjectBox>) -> Result<Option<ObjectBox>, Fault> {
+        let method = receiver.borrow().lookup_method(Symbol::from(selector)).unwrap();
+        let mut context = ContextData::new(crate::object::init_stack());
+        context.set_arguments(arguments);
+        context.push(receiver.clone());
+        method.call(receiver.clone(), &mut context)
+    }
+
+    #[test]
+    fn the_scale_is_the_digits_after_the_point() {
+        assert_eq!(decimal("12.50").scale(), 2);
+        assert_eq!(decimal("12.50").mantissa(), 1250);
+        assert_eq!(decimal("-3").scale(), 0);
+        assert_eq!(decimal("-0.05").to_string(), "-0.05");
+        assert_eq!(decimal("1.50").to_string(), "1.50");
+        assert_eq!(Decimal::parse("1.2.3"), None);
+        assert_eq!(Decimal::parse(".5"), None);
+        assert!(matches!(Decimal::new(1, MAX_SCALE + 1), Err(Fault::InvalidOperation(_))));
+    }
+
+    #[test]
+    fn results_have_the_larger_scale() {
+        let sum = decimal("1.5").add(decimal("0.25")).unwrap();
+        assert_eq!((sum.mantissa(), sum.scale()), (175, 2));
+        let quotient = decimal("1.00").div(Decimal::from_integer(3)).unwrap();
+        assert_eq!(quotient.to_string(), "0.33");
+        let product = decimal("0.5").mul(decimal("0.5")).unwrap();
+        assert_eq!(product.to_string(), "0.2");
+        assert_eq!(decimal("2.0").pow(-1).unwrap().to_string(), "0.5");
+    }
+
+    #[test]
+    fn dropped_digits_round_half_to_even() {
+        assert_eq!(decimal("0.125").with_scale(2).unwrap().to_string(), "0.12");
+        assert_eq!(decimal("0.135").with_scale(2).unwrap().to_string(), "0.14");
+        assert_eq!(decimal("-0.125").with_scale(2).unwrap().to_string(), "-0.12");
+        assert_eq!(decimal("0.126").with_scale(2).unwrap().to_string(), "0.13");
+        assert_eq!(decimal("7").with_scale(3).unwrap().to_string(), "7.000");
+    }
+
+    #[test]
+    fn equal_values_with_different_scales_are_equal() {
+        assert_eq!(decimal("1.50"), decimal("1.5"));
+        assert!(decimal("1.05") < decimal("1.5"));
+    }
+
+    #[test]
+    fn dividing_by_zero_faults() {
+        assert!(matches!(decimal("1.0").div(Decimal::zero()), Err(Fault::DivideByZero)));
+        assert!(matches!(decimal("1.0").rem(decimal("0.00")), Err(Fault::DivideByZero)));
+    }
+
+    #[test]
+    fn scale_messages() {
+        let price = create_decimal(decimal("19.99"));
+        let scale = send(&price, "scale", Vec::new()).unwrap().unwrap();
+        assert_eq!(Immediate::from_object(&*scale.borrow()), Some(Immediate::U32(2)));
+        send(&price, "with_scale", vec![create_i64(1)]).unwrap();
+        assert_eq!(price.borrow().downcast_ref::<DecimalObject>().unwrap().value.to_string(), "20.0");
+        assert!(send(&price, "with_scale", vec![create_i64(-1)]).is_err());
+
+        // An integer keeps the Decimal and a Rational makes the result a Rational
+        send(&price, "mul", vec![create_i64(3)]).unwrap();
+        assert_eq!(price.borrow().downcast_ref::<DecimalObject>().unwrap().value.to_string(), "60.0");
+        let result = send(&price, "div", vec![create_rational(Rational::new(1, 3).unwrap())]).unwrap().unwrap();
+        assert!(result.borrow().is::<crate::object::primitive::rational::RationalObject>());
+
+        let parsed = crate::object::create_object("Decimal", &[create_string("2.50".to_string())]).unwrap().unwrap();
+        assert_eq!(parsed.borrow().downcast_ref::<DecimalObject>().unwrap().value.scale(), 2);
+    }
+}
@@ -0,0 +1,243 @@
+//! Arithmetic between numbers of different types
+//! When a Rational or a Decimal meets another number both are widened to the more general of
+//! their two types: integers become decimals, decimals become rationals and anything with a float
+//! becomes a float. An integer power of an exact number stays exact.
+use std::cmp::Ordering;
+
+use crate::object::primitive::bigint::{BigInt, BigIntObject};
+use crate::object::primitive::decimal::{Decimal, DecimalObject};
+use crate::object::primitive::rational::{Rational, RationalObject};
+use crate::object::primitive::NumberOperation;
+use crate::object::value::Immediate;
+use crate::object::{ContextData, Fault, Object, ObjectBox};
+
+/// Exact
+/// A number taken out of an object, from the least to the most general type
+#[derive(Clone, Copy, Debug)]
+pub enum Exact {
+    Integer(i128),
+    Decimal(Decimal),
+    Rational(Rational),
+    Float(f64),
+}
+
+impl Exact {
+    /// Get the number that an object holds
+    pub fn from_object(object: &dyn Object, name: &str) -> Result<Exact, Fault> {
+        if let Some(exact) = Exact::from_fraction(object) {
+            return Ok(exact);
+        }
+        if let Some(big) = object.downcast_ref::<BigIntObject>() {
+            return big.value.to_i128().map(Exact::Integer)
+                .ok_or(Fault::Overflow(format!("Number {}: {} is too big to be combined with a fraction", name, big.value)));
+        }
+        let exact = match Immediate::from_object(object) {
+            Some(Immediate::I8(value)) => Exact::Integer(value as i128),
+            Some(Immediate::I16(value)) => Exact::Integer(value as i128),
+            Some(Immediate::I32(value)) => Exact::Integer(value as i128),
+            Some(Immediate::I64(value)) => Exact::Integer(value as i128),
+            Some(Immediate::U8(value)) => Exact::Integer(value as i128),
+            Some(Immediate::U16(value)) => Exact::Integer(value as i128),
+            Some(Immediate::U32(value)) => Exact::Integer(value as i128),
+            Some(Immediate::U64(value)) => Exact::Integer(value as i128),
+            Some(Immediate::F32(value)) => Exact::Float(value as f64),
+            Some(Immediate::F64(value)) => Exact::Float(value),
+            _ => return Err(Fault::InvalidType(format!("Number {}: Not a number", name))),
+        };
+        Ok(exact)
+    }
+
+    /// Get the number of a Rational or a Decimal object
+    pub fn from_fraction(object: &dyn Object) -> Option<Exact> {
+        if let Some(rational) = object.downcast_ref::<RationalObject>() {
+            Some(Exact::Rational(rational.value))
+        } else {
+            object.downcast_ref::<DecimalObject>().map(|decimal| Exact::Decimal(decimal.value))
+        }
+    }
+
+    fn rank(&self) -> u8 {
+        match self {
+            Exact::Integer(_) => 0,
+            Exact::Decimal(_) => 1,
+            Exact::Rational(_) => 2,
+            Exact::Float(_) => 3,
+        }
+    }
+
+    pub fn to_rational(self) -> Result<Rational, Fault> {
+        match self {
+            Exact::Integer(value) => Ok(Rational::from_integer(value)),
+            Exact::Decimal(value) => value.to_rational(),
+            Exact::Rational(value) => Ok(value),
+            Exact::Float(value) => Err(Fault::InvalidOperation(format!("{} can't be converted to Rational without loss", value))),
+        }
+    }
+
+    /// Get the Decimal with the given scale that is closest to the number
+    pub fn to_decimal(self, scale: u32) -> Result<Decimal, Fault> {
+        match self {
+            Exact::Integer(value) => Decimal::from_integer(value).with_scale(scale),
+            Exact::Decimal(value) => value.with_scale(scale),
+            Exact::Rational(value) => Decimal::from_rational(value, scale),
+            Exact::Float(value) if value.is_finite() => Decimal::parse(&format!("{:.*}", scale as usize, value))
+                .ok_or(Fault::Overflow(format!("Decimal: {} overflowed", value))),
+            Exact::Float(value) => Err(Fault::InvalidOperation(format!("{} can't be converted to Decimal", value))),
+        }
+    }
+
+    pub fn to_f64(self) -> f64 {
+        match self {
+            Exact::Integer(value) => value as f64,
+            Exact::Decimal(value) => value.to_f64(),
+            Exact::Rational(value) => value.to_f64(),
+            Exact::Float(value) => value,
+        }
+    }
+
+    /// Get the value if it is a whole number
+    pub fn integer(self) -> Option<i128> {
+        match self {
+            Exact::Integer(value) => Some(value),
+            Exact::Decimal(value) => value.to_rational().ok().filter(Rational::is_integer).map(|value| value.numerator()),
+            Exact::Rational(value) => value.is_integer().then(|| value.numerator()),
+            Exact::Float(_) => None,
+        }
+    }
+
+    pub fn combine(self, operation: NumberOperation, other: Exact) -> Result<Exact, Fault> {
+        if operation == NumberOperation::Pow {
+            if let (false, Some(exponent)) = (matches!(self, Exact::Float(_)), other.integer()) {
+                return match self {
+                    Exact::Decimal(base) => Ok(Exact::Decimal(base.pow(exponent)?)),
+                    base => Ok(Exact::Rational(base.to_rational()?.pow(exponent)?)),
+                };
+            }
+            return Ok(Exact::Float(operation.apply_float(self.to_f64(), other.to_f64())?));
+        }
+        match self.rank().max(other.rank()) {
+            3 => Ok(Exact::Float(operation.apply_float(self.to_f64(), other.to_f64())?)),
+            1 => {
+                let scale = |exact: Exact| match exact {
+                    Exact::Decimal(value) => value.scale(),
+                    _ => 0,
+                };
+                let scale = scale(self).max(scale(other));
+                let (a, b) = (self.to_decimal(scale)?, other.to_decimal(scale)?);
+                let result = match operation {
+                    NumberOperation::Add => a.add(b),
+                    NumberOperation::Sub => a.sub(b),
+                    NumberOperation::Mul => a.mul(b),
+                    NumberOperation::Div => a.div(b),
+                    NumberOperation::Mod => a.rem(b),
+                    NumberOperation::Pow => unreachable!("pow is handled above"),
+                };
+                Ok(Exact::Decimal(result?))
+            }
+            _ => {
+                let (a, b) = (self.to_rational()?, other.to_rational()?);
+                let result = match operation {
+                    NumberOperation::Add => a.add(b),
+                    NumberOperation::Sub => a.sub(b),
+                    NumberOperation::Mul => a.mul(b),
+                    NumberOperation::Div => a.div(b),
+                    NumberOperation::Mod => a.rem(b),
+                    NumberOperation::Pow => unreachable!("pow is handled above"),
+                };
+                Ok(Exact::Rational(result?))
+            }
+        }
+    }
+
+    pub fn compare(self, other: Exact) -> Option<Ordering> {
+        match (self, other) {
+            (Exact::Float(_), _) | (_, Exact::Float(_)) => self.to_f64().partial_cmp(&other.to_f64()),
+            (Exact::Integer(a), Exact::Integer(b)) => Some(a.cmp(&b)),
+            (Exact::Decimal(a), Exact::Decimal(b)) => Some(a.cmp(&b)),
+            (Exact::Decimal(a), Exact::Integer(b)) => Some(a.cmp(&Decimal::from_integer(b))),
+            (Exact::Integer(a), Exact::Decimal(b)) => Some(Decimal::from_integer(a).cmp(&b)),
+            _ => Some(self.to_rational().ok()?.cmp(&other.to_rational().ok()?)),
+        }
+    }
+
+    pub fn into_object(self) -> ObjectBox {
+        match self {
+            Exact::Integer(value) => match i64::try_from(value) {
+                Ok(value) => crate::object::create_i64(value),
+                Err(_) => crate::object::create_bigint(BigInt::from(value)),
+            },
+            Exact::Decimal(value) => crate::object::create_decimal(value),
+            Exact::Rational(value) => crate::object::create_rational(value),
+            Exact::Float(value) => crate::object::create_f64(value),
+        }
+    }
+
+    /// Put the number in an object of the same type, false if the types don't match
+    fn store(self, object: &mut dyn Object) -> bool {
+        match self {
+            Exact::Decimal(value) => object.downcast_mut::<DecimalObject>().map(|object| object.value = value).is_some(),
+            Exact::Rational(value) => object.downcast_mut::<RationalObject>().map(|object| object.value = value).is_some(),
+            _ => false,
+        }
+    }
+}
+
+impl From<f64> for Exact {
+    fn from(value: f64) -> Exact {
+        Exact::Float(value)
+    }
+}
+
+impl From<f32> for Exact {
+    fn from(value: f32) -> Exact {
+        Exact::Float(value as f64)
+    }
+}
+
+macro_rules! exact_from_integer {
+    ($($type:ty),*) => {
+        $(impl From<$type> for Exact {
+            fn from(value: $type) -> Exact {
+                Exact::Integer(value as i128)
+            }
+        })*
+    };
+}
+
+exact_from_integer!(i8, i16, i32, i64, u8, u16, u32, u64);
+
+/// Get an integer argument of a message
+pub fn integer_argument(object: &ObjectBox, name: &str) -> Result<i128, Fault> {
+    match Exact::from_object(&*object.borrow(), name)?.integer() {
+        Some(value) => Ok(value),
+        None => Err(Fault::InvalidType(format!("{}: expected an integer", name))),
+    }
+}
+
+/// Arithmetic on a Rational or a Decimal
+/// The receiver is changed in place when the result has its type like it is for the other
+/// numbers, otherwise the result replaces it.
+pub fn exact_arithmetic(object: ObjectBox, context: &mut ContextData, operation: NumberOperation, name: &str) -> Result<Option<ObjectBox>, Fault> {
+    let receiver = Exact::from_object(&*object.borrow(), name)?;
+    let argument = Exact::from_object(&*context.arguments[0].borrow(), name)?;
+    let result = receiver.combine(operation, argument)?;
+    if result.store(&mut *object.borrow_mut()) {
+        return Ok(None);
+    }
+    context.pop();
+    Ok(Some(result.into_object()))
+}
+
+pub fn exact_equals(object: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let receiver = Exact::from_object(&*object.borrow(), "equals")?;
+    let argument = Exact::from_object(&*context.arguments[0].borrow(), "equals")?;
+    Ok(Some(crate::object::create_boolean(receiver.compare(argument) == Some(Ordering::Equal))))
+}
+
+pub fn exact_order(object: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let receiver = Exact::from_object(&*object.borrow(), "order")?;
+    let argument = Exact::from_object(&*context.arguments[0].borrow(), "order")?;
+    let ordering = receiver.compare(argument)
+        .ok_or(Fault::InvalidOperation("Number order: can't order NaN".to_string()))?;
+    Ok(Some(crate::object::create_i8(ordering as i8)))
+}
@@ -1,6 +1,7 @@
 use super::{Method, ObjectBox};
 use std::collections::HashMap;
 use crate::object::{Object, VTable};
+use crate::object::symbol::Symbol;
 use std::sync::Arc;
 use super::Fault;
 use num_traits::Zero;
@@ -9,23 +10,6 @@ use crate::object::primitive::PrimitiveObject;
 use crate::object::ContextData;
 use crate::object::create_boolean;
 
-trait Pow {
-    fn pow(self, exponent: u32) -> Self;
-}
-
-impl Pow for f64 {
-    fn pow(self, exponent: u32) -> Self {
-        self.powf(exponent as f64)
-    }
-}
-
-impl Pow for f32 {
-    fn pow(self, exponent: u32) -> Self {
-        self.powf(exponent as f32)
-    }
-}
-
-
 pub struct FloatObject {
     super_object: Option<ObjectBox>,
     vtable: VTable,
@@ -33,27 +17,26 @@ pub struct FloatObject {
 
 impl FloatObject {
     pub fn make_object(parent: ObjectBox) -> ObjectBox {
-        let out = ObjectBox::new(FloatObject {super_object: Some(parent), vtable: VTable::new_empty()});
-        return out
+        ObjectBox::new(FloatObject {super_object: Some(parent), vtable: VTable::new_empty()})
     }
     pub fn make_vtable() -> VTable {
         let mut methods = HashMap::new();
-        methods.insert(String::from("is_nan"), Arc::new(Method::RustMethod { fun: Box::new(float_is_nan) }));
-        methods.insert(String::from("is_infinity"), Arc::new(Method::RustMethod { fun: Box::new(float_is_infinity) }));
-        methods.insert(String::from("is_negitive_infinity"), Arc::new(Method::RustMethod { fun: Box::new(float_is_neg_infinity) }));
-        methods.insert(String::from("is_finite"), Arc::new(Method::RustMethod { fun: Box::new(float_is_finite) }));
-        methods.insert(String::from("is_normal"), Arc::new(Method::RustMethod { fun: Box::new(float_is_normal) }));
-        methods.insert(String::from("floor"), Arc::new(Method::RustMethod { fun: Box::new(float_floor) }));
-        methods.insert(String::from("ceil"), Arc::new(Method::RustMethod { fun: Box::new(float_ceil) }));
-        methods.insert(String::from("nat_log"), Arc::new(Method::RustMethod { fun: Box::new(float_nat_log) }));
-        methods.insert(String::from("log"), Arc::new(Method::RustMethod { fun: Box::new(float_log) }));
-        methods.insert(String::from("hypotenuse"), Arc::new(Method::RustMethod { fun: Box::new(float_hypotenuse) }));
-        methods.insert(String::from("sin"), Arc::new(Method::RustMethod { fun: Box::new(float_sin) }));
-        methods.insert(String::from("cos"), Arc::new(Method::RustMethod { fun: Box::new(float_cos) }));
-        methods.insert(String::from("tan"), Arc::new(Method::RustMethod { fun: Box::new(float_tan) }));
-        methods.insert(String::from("arcsin"), Arc::new(Method::RustMethod { fun: Box::new(float_arcsin) }));
-        methods.insert(String::from("arccos"), Arc::new(Method::RustMethod { fun: Box::new(float_arccos) }));
-        methods.insert(String::from("arctan"), Arc::new(Method::RustMethod { fun: Box::new(float_arctan) }));
+        methods.insert(Symbol::from("is_nan"), Arc::new(Method::RustMethod { fun: Box::new(float_is_nan) }));
+        methods.insert(Symbol::from("is_infinity"), Arc::new(Method::RustMethod { fun: Box::new(float_is_infinity) }));
+        methods.insert(Symbol::from("is_negitive_infinity"), Arc::new(Method::RustMethod { fun: Box::new(float_is_neg_infinity) }));
+        methods.insert(Symbol::from("is_finite"), Arc::new(Method::RustMethod { fun: Box::new(float_is_finite) }));
+        methods.insert(Symbol::from("is_normal"), Arc::new(Method::RustMethod { fun: Box::new(float_is_normal) }));
+        methods.insert(Symbol::from("floor"), Arc::new(Method::RustMethod { fun: Box::new(float_floor) }));
+        methods.insert(Symbol::from("ceil"), Arc::new(Method::RustMethod { fun: Box::new(float_ceil) }));
+        methods.insert(Symbol::from("nat_log"), Arc::new(Method::RustMethod { fun: Box::new(float_nat_log) }));
+        methods.insert(Symbol::from("log"), Arc::new(Method::RustMethod { fun: Box::new(float_log) }));
+        methods.insert(Symbol::from("hypotenuse"), Arc::new(Method::RustMethod { fun: Box::new(float_hypotenuse) }));
+        methods.insert(Symbol::from("sin"), Arc::new(Method::RustMethod { fun: Box::new(float_sin) }));
+        methods.insert(Symbol::from("cos"), Arc::new(Method::RustMethod { fun: Box::new(float_cos) }));
+        methods.insert(Symbol::from("tan"), Arc::new(Method::RustMethod { fun: Box::new(float_tan) }));
+        methods.insert(Symbol::from("arcsin"), Arc::new(Method::RustMethod { fun: Box::new(float_arcsin) }));
+        methods.insert(Symbol::from("arccos"), Arc::new(Method::RustMethod { fun: Box::new(float_arccos) }));
+        methods.insert(Symbol::from("arctan"), Arc::new(Method::RustMethod { fun: Box::new(float_arctan) }));
         
         VTable::new(methods)
     }
@@ -76,87 +59,79 @@ impl Object for FloatObject {
         None
     }
     fn duplicate(&self) -> ObjectBox {
-        let obj = FloatObject::make_object(self.super_object.clone().unwrap().borrow().duplicate());
+        let obj = FloatObject::make_object(self.super_object.clone().unwrap());
         let mut obj_mut = obj.borrow_mut();
         obj_mut.initialize(Vec::new(), self.vtable.clone());
         drop(obj_mut);
-        return obj as ObjectBox;
+        obj as ObjectBox
     }
     fn initialize(&mut self, _: Vec<ObjectBox>, vtable: VTable) {
-        self.vtable.extend(FloatObject::make_vtable());
         self.vtable.extend(vtable);
-        match &mut self.super_object {
-            Some(super_object) => {
-                let mut super_object = super_object.borrow_mut();
-                super_object.initialize(vec![], VTable::new_empty());
-            }
-            None => {}
-        }
     }
 }
 
 fn float_is_nan(_: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
-    Err(Fault::NotImplemented(format!("Float is_nan")))
+    Err(Fault::NotImplemented("Float is_nan".to_string()))
 }
 
 fn float_is_infinity(_: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
-    Err(Fault::NotImplemented(format!("Float is_infinity")))
+    Err(Fault::NotImplemented("Float is_infinity".to_string()))
 }
 
 fn float_is_neg_infinity(_: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
-    Err(Fault::NotImplemented(format!("Float is_negative_infinity")))
+    Err(Fault::NotImplemented("Float is_negative_infinity".to_string()))
 }
 
 fn float_is_finite(_: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
-    Err(Fault::NotImplemented(format!("Float is_finite")))
+    Err(Fault::NotImplemented("Float is_finite".to_string()))
 }
 
 fn float_is_normal(_: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
-    Err(Fault::NotImplemented(format!("Float is_normal")))
+    Err(Fault::NotImplemented("Float is_normal".to_string()))
 }
 
 fn float_floor(_: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
-    Err(Fault::NotImplemented(format!("Float floor")))
+    Err(Fault::NotImplemented("Float floor".to_string()))
 }
 
 fn float_ceil(_: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
-    Err(Fault::NotImplemented(format!("Float ceil")))
+    Err(Fault::NotImplemented("Float ceil".to_string()))
 }
 
 fn float_nat_log(_: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
-    Err(Fault::NotImplemented(format!("Float nat_log")))
+    Err(Fault::NotImplemented("Float nat_log".to_string()))
 }
 
 fn float_log(_: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
-    Err(Fault::NotImplemented(format!("Float log")))
+    Err(Fault::NotImplemented("Float log".to_string()))
 }
 
 fn float_hypotenuse(_: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
-    Err(Fault::NotImplemented(format!("Float hypotenuse")))
+    Err(Fault::NotImplemented("Float hypotenuse".to_string()))
 }
 
 fn float_sin(_: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
-    Err(Fault::NotImplemented(format!("Float sin")))
+    Err(Fault::NotImplemented("Float sin".to_string()))
 }
 
 fn float_cos(_: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
-    Err(Fault::NotImplemented(format!("Float cos")))
+    Err(Fault::NotImplemented("Float cos".to_string()))
 }
 
 fn float_tan(_: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
-    Err(Fault::NotImplemented(format!("Float tan")))
+    Err(Fault::NotImplemented("Float tan".to_string()))
 }
 
 fn float_arcsin(_: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
-    Err(Fault::NotImplemented(format!("Float arcsin")))
+    Err(Fault::NotImplemented("Float arcsin".to_string()))
 }
 
 fn float_arccos(_: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
-    Err(Fault::NotImplemented(format!("Float arccos")))
+    Err(Fault::NotImplemented("Float arccos".to_string()))
 }
 
 fn float_arctan(_: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
-    Err(Fault::NotImplemented(format!("Float arctan")))
+    Err(Fault::NotImplemented("Float arctan".to_string()))
 }
 
 macro_rules! create_float_ops {
@@ -310,48 +285,46 @@ impl F64Object {
 
     pub fn make_object(parent: ObjectBox,
                        data: f64) -> ObjectBox {
-        let out = ObjectBox::new(PrimitiveObject::new(Some(parent), data));
-        return out;
+        ObjectBox::new(PrimitiveObject::new(Some(parent), data))
     }
     pub fn make_object_vtable() -> VTable {
         let mut methods = HashMap::new();
-        methods.insert(String::from("equals"), Arc::new(Method::RustMethod { fun: Box::new(f64_equals) }));
-        methods.insert(String::from("to_string"), Arc::new(Method::RustMethod { fun: Box::new(f64_to_string) }));
-        methods.insert(String::from("order"), Arc::new(Method::RustMethod { fun: Box::new(f64_order) }));
+        methods.insert(Symbol::from("equals"), Arc::new(Method::RustMethod { fun: Box::new(f64_equals) }));
+        methods.insert(Symbol::from("to_string"), Arc::new(Method::RustMethod { fun: Box::new(f64_to_string) }));
+        methods.insert(Symbol::from("order"), Arc::new(Method::RustMethod { fun: Box::new(f64_order) }));
         VTable::new(methods)
     }
     pub fn make_number_vtable() -> VTable {
         let mut number_vtable = HashMap::new();
-        number_vtable.insert(String::from("add"), Arc::new(Method::RustMethod { fun: Box::new(f64_add) }));
-        number_vtable.insert(String::from("sub"), Arc::new(Method::RustMethod { fun: Box::new(f64_sub) }));
-        number_vtable.insert(String::from("mul"), Arc::new(Method::RustMethod { fun: Box::new(f64_mul) }));
-        number_vtable.insert(String::from("div"), Arc::new(Method::RustMethod { fun: Box::new(f64_div) }));
-        number_vtable.insert(String::from("mod"), Arc::new(Method::RustMethod { fun: Box::new(f64_mod) }));
-        number_vtable.insert(String::from("abs"), Arc::new(Method::RustMethod { fun: Box::new(f64_abs) }));
-        number_vtable.insert(String::from("pow"), Arc::new(Method::RustMethod { fun: Box::new(f64_pow) }));
-        number_vtable.insert(String::from("is_zero"), Arc::new(Method::RustMethod { fun: Box::new(f64_is_zero) }));
+        number_vtable.insert(Symbol::from("add"), Arc::new(Method::RustMethod { fun: Box::new(f64_add) }));
+        number_vtable.insert(Symbol::from("sub"), Arc::new(Method::RustMethod { fun: Box::new(f64_sub) }));
+        number_vtable.insert(Symbol::from("mul"), Arc::new(Method::RustMethod { fun: Box::new(f64_mul) }));
+        number_vtable.insert(Symbol::from("div"), Arc::new(Method::RustMethod { fun: Box::new(f64_div) }));
+        number_vtable.insert(Symbol::from("mod"), Arc::new(Method::RustMethod { fun: Box::new(f64_mod) }));
+        number_vtable.insert(Symbol::from("abs"), Arc::new(Method::RustMethod { fun: Box::new(f64_abs) }));
+        number_vtable.insert(Symbol::from("pow"), Arc::new(Method::RustMethod { fun: Box::new(f64_pow) }));
+        number_vtable.insert(Symbol::from("is_zero"), Arc::new(Method::RustMethod { fun: Box::new(f64_is_zero) }));
         VTable::new(number_vtable)
     }
     pub fn make_float_vtable() -> VTable {
         let mut float_vtable = HashMap::new();
-        float_vtable.insert(String::from("is_nan"), Arc::new(Method::RustMethod { fun: Box::new(f64_is_nan) }));
-        float_vtable.insert(String::from("is_infinity"), Arc::new(Method::RustMethod { fun: Box::new(f64_is_infinity) }));
-        float_vtable.insert(String::from("is_negitive_infinity"), Arc::new(Method::RustMethod { fun: Box::new(f64_is_neg_infinity) }));
-        float_vtable.insert(String::from("is_finite"), Arc::new(Method::RustMethod { fun: Box::new(f64_is_finite) }));
-        float_vtable.insert(String::from("is_normal"), Arc::new(Method::RustMethod { fun: Box::new(f64_is_normal) }));
-        float_vtable.insert(String::from("floor"), Arc::new(Method::RustMethod { fun: Box::new(f64_floor) }));
-        float_vtable.insert(String::from("ceil"), Arc::new(Method::RustMethod { fun: Box::new(f64_ceil) }));
-        float_vtable.insert(String::from("nat_log"), Arc::new(Method::RustMethod { fun: Box::new(f64_nat_log) }));
-        float_vtable.insert(String::from("log"), Arc::new(Method::RustMethod { fun: Box::new(f64_log) }));
-        float_vtable.insert(String::from("hypotenuse"), Arc::new(Method::RustMethod { fun: Box::new(f64_hypotenuse) }));
-        float_vtable.insert(String::from("sin"), Arc::new(Method::RustMethod { fun: Box::new(f64_sin) }));
-        float_vtable.insert(String::from("cos"), Arc::new(Method::RustMethod { fun: Box::new(f64_cos) }));
-        float_vtable.insert(String::from("tan"), Arc::new(Method::RustMethod { fun: Box::new(f64_tan) }));
-        float_vtable.insert(String::from("arcsin"), Arc::new(Method::RustMethod { fun: Box::new(f64_arcsin) }));
-        float_vtable.insert(String::from("arccos"), Arc::new(Method::RustMethod { fun: Box::new(f64_arccos) }));
-        float_vtable.insert(String::from("arctan"), Arc::new(Method::RustMethod { fun: Box::new(f64_arctan) }));
-        let float_vtable = VTable::new(float_vtable);
-        float_vtable
+        float_vtable.insert(Symbol::from("is_nan"), Arc::new(Method::RustMethod { fun: Box::new(f64_is_nan) }));
+        float_vtable.insert(Symbol::from("is_infinity"), Arc::new(Method::RustMethod { fun: Box::new(f64_is_infinity) }));
+        float_vtable.insert(Symbol::from("is_negitive_infinity"), Arc::new(Method::RustMethod { fun: Box::new(f64_is_neg_infinity) }));
+        float_vtable.insert(Symbol::from("is_finite"), Arc::new(Method::RustMethod { fun: Box::new(f64_is_finite) }));
+        float_vtable.insert(Symbol::from("is_normal"), Arc::new(Method::RustMethod { fun: Box::new(f64_is_normal) }));
+        float_vtable.insert(Symbol::from("floor"), Arc::new(Method::RustMethod { fun: Box::new(f64_floor) }));
+        float_vtable.insert(Symbol::from("ceil"), Arc::new(Method::RustMethod { fun: Box::new(f64_ceil) }));
+        float_vtable.insert(Symbol::from("nat_log"), Arc::new(Method::RustMethod { fun: Box::new(f64_nat_log) }));
+        float_vtable.insert(Symbol::from("log"), Arc::new(Method::RustMethod { fun: Box::new(f64_log) }));
+        float_vtable.insert(Symbol::from("hypotenuse"), Arc::new(Method::RustMethod { fun: Box::new(f64_hypotenuse) }));
+        float_vtable.insert(Symbol::from("sin"), Arc::new(Method::RustMethod { fun: Box::new(f64_sin) }));
+        float_vtable.insert(Symbol::from("cos"), Arc::new(Method::RustMethod { fun: Box::new(f64_cos) }));
+        float_vtable.insert(Symbol::from("tan"), Arc::new(Method::RustMethod { fun: Box::new(f64_tan) }));
+        float_vtable.insert(Symbol::from("arcsin"), Arc::new(Method::RustMethod { fun: Box::new(f64_arcsin) }));
+        float_vtable.insert(Symbol::from("arccos"), Arc::new(Method::RustMethod { fun: Box::new(f64_arccos) }));
+        float_vtable.insert(Symbol::from("arctan"), Arc::new(Method::RustMethod { fun: Box::new(f64_arctan) }));
+        VTable::new(float_vtable)
     }
 }
 
@@ -373,27 +346,13 @@ impl Object for PrimitiveObject<f64> {
         None
     }
     fn duplicate(&self) -> ObjectBox {
-        let float = F64Object::make_object(self.super_object.clone().unwrap().borrow().duplicate(), self.data);
+        let float = F64Object::make_object(self.super_object.clone().unwrap(), self.data);
         let mut flt = float.borrow_mut();
         flt.initialize(Vec::new(), self.vtable.clone());
         drop(flt);
         float
     }
     fn initialize(&mut self, _: Vec<ObjectBox>, vtable: VTable) {
-        let object_vtable = F64Object::make_object_vtable();
-        let number_vtable = F64Object::make_number_vtable();
-        let float_vtable = F64Object::make_float_vtable();
-
-
-        let float_object = self.get_super_object().unwrap().clone();
-        let mut float_object = float_object.borrow_mut();
-        float_object.initialize(Vec::new(), float_vtable);
-        let number_object = float_object.get_super_object().unwrap().clone();
-        let mut number_object = number_object.borrow_mut();
-        number_object.initialize(Vec::new(), number_vtable);
-        let object_object = number_object.get_super_object().unwrap().clone();
-        let mut object_object = object_object.borrow_mut();
-        object_object.initialize(Vec::new(), object_vtable);
         self.vtable.extend(vtable);
     }
 }
@@ -407,49 +366,46 @@ pub struct F32Object {
 
 impl F32Object {
     pub fn make_object(parent: ObjectBox, data: f32) -> ObjectBox {
-        let out = ObjectBox::new(PrimitiveObject::new(Some(parent), data));
-        return out;
+        ObjectBox::new(PrimitiveObject::new(Some(parent), data))
     }
     pub fn make_object_vtable() -> VTable {
         let mut methods = HashMap::new();
-        methods.insert(String::from("equals"), Arc::new(Method::RustMethod { fun: Box::new(f32_equals) }));
-        methods.insert(String::from("to_string"), Arc::new(Method::RustMethod { fun: Box::new(f32_to_string) }));
-        methods.insert(String::from("order"), Arc::new(Method::RustMethod { fun: Box::new(f32_order) }));
+        methods.insert(Symbol::from("equals"), Arc::new(Method::RustMethod { fun: Box::new(f32_equals) }));
+        methods.insert(Symbol::from("to_string"), Arc::new(Method::RustMethod { fun: Box::new(f32_to_string) }));
+        methods.insert(Symbol::from("order"), Arc::new(Method::RustMethod { fun: Box::new(f32_order) }));
         VTable::new(methods)
     }
     pub fn make_number_vtable() -> VTable {
         let mut number_vtable = HashMap::new();
-        number_vtable.insert(String::from("add"), Arc::new(Method::RustMethod { fun: Box::new(f32_add) }));
-        number_vtable.insert(String::from("sub"), Arc::new(Method::RustMethod { fun: Box::new(f32_sub) }));
-        number_vtable.insert(String::from("mul"), Arc::new(Method::RustMethod { fun: Box::new(f32_mul) }));
-        number_vtable.insert(String::from("div"), Arc::new(Method::RustMethod { fun: Box::new(f32_div) }));
-        number_vtable.insert(String::from("mod"), Arc::new(Method::RustMethod { fun: Box::new(f32_mod) }));
-        number_vtable.insert(String::from("abs"), Arc::new(Method::RustMethod { fun: Box::new(f32_abs) }));
-        number_vtable.insert(String::from("pow"), Arc::new(Method::RustMethod { fun: Box::new(f32_pow) }));
-        number_vtable.insert(String::from("is_zero"), Arc::new(Method::RustMethod { fun: Box::new(f32_is_zero) }));
-        let number_vtable = VTable::new(number_vtable);
-        number_vtable
+        number_vtable.insert(Symbol::from("add"), Arc::new(Method::RustMethod { fun: Box::new(f32_add) }));
+        number_vtable.insert(Symbol::from("sub"), Arc::new(Method::RustMethod { fun: Box::new(f32_sub) }));
+        number_vtable.insert(Symbol::from("mul"), Arc::new(Method::RustMethod { fun: Box::new(f32_mul) }));
+        number_vtable.insert(Symbol::from("div"), Arc::new(Method::RustMethod { fun: Box::new(f32_div) }));
+        number_vtable.insert(Symbol::from("mod"), Arc::new(Method::RustMethod { fun: Box::new(f32_mod) }));
+        number_vtable.insert(Symbol::from("abs"), Arc::new(Method::RustMethod { fun: Box::new(f32_abs) }));
+        number_vtable.insert(Symbol::from("pow"), Arc::new(Method::RustMethod { fun: Box::new(f32_pow) }));
+        number_vtable.insert(Symbol::from("is_zero"), Arc::new(Method::RustMethod { fun: Box::new(f32_is_zero) }));
+        VTable::new(number_vtable)
     }
     pub fn make_float_vtable() -> VTable {
         let mut float_vtable = HashMap::new();
-        float_vtable.insert(String::from("is_nan"), Arc::new(Method::RustMethod { fun: Box::new(f32_is_nan) }));
-        float_vtable.insert(String::from("is_infinity"), Arc::new(Method::RustMethod { fun: Box::new(f32_is_infinity) }));
-        float_vtable.insert(String::from("is_negitive_infinity"), Arc::new(Method::RustMethod { fun: Box::new(f32_is_neg_infinity) }));
-        float_vtable.insert(String::from("is_finite"), Arc::new(Method::RustMethod { fun: Box::new(f32_is_finite) }));
-        float_vtable.insert(String::from("is_normal"), Arc::new(Method::RustMethod { fun: Box::new(f32_is_normal) }));
-        float_vtable.insert(String::from("floor"), Arc::new(Method::RustMethod { fun: Box::new(f32_floor) }));
-        float_vtable.insert(String::from("ceil"), Arc::new(Method::RustMethod { fun: Box::new(f32_ceil) }));
-        float_vtable.insert(String::from("nat_log"), Arc::new(Method::RustMethod { fun: Box::new(f32_nat_log) }));
-        float_vtable.insert(String::from("log"), Arc::new(Method::RustMethod { fun: Box::new(f32_log) }));
-        float_vtable.insert(String::from("hypotenuse"), Arc::new(Method::RustMethod { fun: Box::new(f32_hypotenuse) }));
-        float_vtable.insert(String::from("sin"), Arc::new(Method::RustMethod { fun: Box::new(f32_sin) }));
-        float_vtable.insert(String::from("cos"), Arc::new(Method::RustMethod { fun: Box::new(f32_cos) }));
-        float_vtable.insert(String::from("tan"), Arc::new(Method::RustMethod { fun: Box::new(f32_tan) }));
-        float_vtable.insert(String::from("arcsin"), Arc::new(Method::RustMethod { fun: Box::new(f32_arcsin) }));
-        float_vtable.insert(String::from("arccos"), Arc::new(Method::RustMethod { fun: Box::new(f32_arccos) }));
-        float_vtable.insert(String::from("arctan"), Arc::new(Method::RustMethod { fun: Box::new(f32_arctan) }));
-        let float_vtable = VTable::new(float_vtable);
-        float_vtable
+        float_vtable.insert(Symbol::from("is_nan"), Arc::new(Method::RustMethod { fun: Box::new(f32_is_nan) }));
+        float_vtable.insert(Symbol::from("is_infinity"), Arc::new(Method::RustMethod { fun: Box::new(f32_is_infinity) }));
+        float_vtable.insert(Symbol::from("is_negitive_infinity"), Arc::new(Method::RustMethod { fun: Box::new(f32_is_neg_infinity) }));
+        float_vtable.insert(Symbol::from("is_finite"), Arc::new(Method::RustMethod { fun: Box::new(f32_is_finite) }));
+        float_vtable.insert(Symbol::from("is_normal"), Arc::new(Method::RustMethod { fun: Box::new(f32_is_normal) }));
+        float_vtable.insert(Symbol::from("floor"), Arc::new(Method::RustMethod { fun: Box::new(f32_floor) }));
+        float_vtable.insert(Symbol::from("ceil"), Arc::new(Method::RustMethod { fun: Box::new(f32_ceil) }));
+        float_vtable.insert(Symbol::from("nat_log"), Arc::new(Method::RustMethod { fun: Box::new(f32_nat_log) }));
+        float_vtable.insert(Symbol::from("log"), Arc::new(Method::RustMethod { fun: Box::new(f32_log) }));
+        float_vtable.insert(Symbol::from("hypotenuse"), Arc::new(Method::RustMethod { fun: Box::new(f32_hypotenuse) }));
+        float_vtable.insert(Symbol::from("sin"), Arc::new(Method::RustMethod { fun: Box::new(f32_sin) }));
+        float_vtable.insert(Symbol::from("cos"), Arc::new(Method::RustMethod { fun: Box::new(f32_cos) }));
+        float_vtable.insert(Symbol::from("tan"), Arc::new(Method::RustMethod { fun: Box::new(f32_tan) }));
+        float_vtable.insert(Symbol::from("arcsin"), Arc::new(Method::RustMethod { fun: Box::new(f32_arcsin) }));
+        float_vtable.insert(Symbol::from("arccos"), Arc::new(Method::RustMethod { fun: Box::new(f32_arccos) }));
+        float_vtable.insert(Symbol::from("arctan"), Arc::new(Method::RustMethod { fun: Box::new(f32_arctan) }));
+        VTable::new(float_vtable)
     }
 }
 
@@ -470,27 +426,13 @@ impl Object for PrimitiveObject<f32> {
         None
     }
     fn duplicate(&self) -> ObjectBox {
-        let float = F32Object::make_object(self.super_object.clone().unwrap().borrow().duplicate(), self.data);
+        let float = F32Object::make_object(self.super_object.clone().unwrap(), self.data);
         let mut flt = float.borrow_mut();
         flt.initialize(Vec::new(), self.vtable.clone());
         drop(flt);
         float
     }
     fn initialize(&mut self, _: Vec<ObjectBox>, vtable: VTable) {
-        let object_vtable = F32Object::make_object_vtable();
-        let number_vtable = F32Object::make_number_vtable();
-        let float_vtable = F32Object::make_float_vtable();
-
-
-        let float_object = self.get_super_object().unwrap().clone();
-        let mut float_object = float_object.borrow_mut();
-        float_object.initialize(Vec::new(), float_vtable);
-        let number_object = float_object.get_super_object().unwrap().clone();
-        let mut number_object = number_object.borrow_mut();
-        number_object.initialize(Vec::new(), number_vtable);
-        let object_object = number_object.get_super_object().unwrap().clone();
-        let mut object_object = object_object.borrow_mut();
-        object_object.initialize(Vec::new(), object_vtable);
         self.vtable.extend(vtable);
     }
 }
@@ -10,32 +10,9 @@ use crate::object::primitive::PrimitiveObject;
 use num_integer::Integer;
 use crate::object::create_boolean;
 use crate::object::VTable;
-
-
-trait Abs {
-    fn abs(self) -> Self;
-}
-
-impl Abs for u64 {
-    fn abs(self) -> u64 {
-        self
-    }
-}
-impl Abs for u32 {
-    fn abs(self) -> u32 {
-        self
-    }
-}
-impl Abs for u16 {
-    fn abs(self) -> u16 {
-        self
-    }
-}
-impl Abs for u8 {
-    fn abs(self) -> u8 {
-        self
-    }
-}
+use crate::object::symbol::Symbol;
+use crate::object::value::Immediate;
+use crate::object::primitive::convert::{convert, Conversion, Target};
 
 
 pub struct IntegerObject {
@@ -45,17 +22,24 @@ pub struct IntegerObject {
 
 impl IntegerObject {
     pub fn make_object(parent: ObjectBox) -> ObjectBox {
-        let out = ObjectBox::new(IntegerObject{super_object: Some(parent), vtable: VTable::new_empty()});
-        return out;
+        ObjectBox::new(IntegerObject{super_object: Some(parent), vtable: VTable::new_empty()})
     }
     pub fn make_vtable() -> VTable {
         let mut methods = HashMap::new();
-        methods.insert(String::from("divides"), Arc::new(Method::RustMethod { fun: Box::new(integer_divides) }));
-        methods.insert(String::from("shift_right"), Arc::new(Method::RustMethod { fun: Box::new(integer_shift_right) }));
-        methods.insert(String::from("shift_left"), Arc::new(Method::RustMethod { fun: Box::new(integer_shift_left) }));
-        methods.insert(String::from("and"), Arc::new(Method::RustMethod { fun: Box::new(integer_bitwise_and) }));
-        methods.insert(String::from("or"), Arc::new(Method::RustMethod { fun: Box::new(integer_bitwise_or) }));
-        methods.insert(String::from("xor"), Arc::new(Method::RustMethod { fun: Box::new(integer_bitwise_xor) }));
+        methods.insert(Symbol::from("divides"), Arc::new(Method::RustMethod { fun: Box::new(integer_divides) }));
+        methods.insert(Symbol::from("shift_right"), Arc::new(Method::RustMethod { fun: Box::new(integer_shift_right) }));
+        methods.insert(Symbol::from("shift_left"), Arc::new(Method::RustMethod { fun: Box::new(integer_shift_left) }));
+        methods.insert(Symbol::from("and"), Arc::new(Method::RustMethod { fun: Box::new(integer_bitwise_and) }));
+        methods.insert(Symbol::from("or"), Arc::new(Method::RustMethod { fun: Box::new(integer_bitwise_or) }));
+        methods.insert(Symbol::from("xor"), Arc::new(Method::RustMethod { fun: Box::new(integer_bitwise_xor) }));
+        methods.insert(Symbol::from("to"), Arc::new(Method::RustMethod { fun: Box::new(crate::object::range::integer_to) }));
+        for (name, operation) in OPERATIONS {
+            for (prefix, overflow) in [("wrapping", Overflow::Wrapping), ("saturating", Overflow::Saturating), ("overflowing", Overflow::Overflowing)] {
+                let operation = *operation;
+                let fun = move |object: ObjectBox, context: &mut ContextData| integer_overflow_op(object, context, operation, overflow);
+                methods.insert(Symbol::from(format!("{}_{}", prefix, name).as_str()), Arc::new(Method::RustMethod { fun: Box::new(fun) }));
+            }
+        }
         VTable::new(methods)
     }
 }
@@ -78,49 +62,117 @@ impl Object for IntegerObject {
         None
     }
     fn duplicate(&self) -> ObjectBox {
-        let integer = IntegerObject::make_object(self.super_object.clone().unwrap().borrow().duplicate());
+        let integer = IntegerObject::make_object(self.super_object.clone().unwrap());
         let mut int = integer.borrow_mut();
         int.initialize(Vec::new(), self.vtable.clone());
         drop(int);
         integer
     }
     fn initialize(&mut self, _: Vec<ObjectBox>, vtable: crate::object::VTable) {
-        let integer_vtable = IntegerObject::make_vtable();
-        self.vtable.extend(integer_vtable);
         self.vtable.extend(vtable);
-        
-        match &mut self.super_object {
-            Some(super_object) => {
-                let mut super_object = super_object.borrow_mut();
-                super_object.initialize(vec![], VTable::new_empty());
-            }
-            None => {}
-        }
     }
 }
 
 fn integer_divides(_: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
-    Err(Fault::NotImplemented(format!("Integer divides")))
+    Err(Fault::NotImplemented("Integer divides".to_string()))
 }
 
 fn integer_shift_right(_: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
-    Err(Fault::NotImplemented(format!("Integer shift_right")))
+    Err(Fault::NotImplemented("Integer shift_right".to_string()))
 }
 
 fn integer_shift_left(_: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
-    Err(Fault::NotImplemented(format!("Integer shift_left")))
+    Err(Fault::NotImplemented("Integer shift_left".to_string()))
 }
 
 fn integer_bitwise_and(_: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
-    Err(Fault::NotImplemented(format!("Integer and")))
+    Err(Fault::NotImplemented("Integer and".to_string()))
 }
 
 fn integer_bitwise_or(_: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
-    Err(Fault::NotImplemented(format!("Integer or")))
+    Err(Fault::NotImplemented("Integer or".to_string()))
 }
 
 fn integer_bitwise_xor(_: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
-    Err(Fault::NotImplemented(format!("Integer xor")))
+    Err(Fault::NotImplemented("Integer xor".to_string()))
+}
+
+/// What the explicitly named arithmetic messages do when the result doesn't fit
+/// The plain messages fault with Fault::Overflow instead.
+#[derive(Clone, Copy, PartialEq)]
+enum Overflow {
+    /// Keep the low bits of the result
+    Wrapping,
+    /// Clamp the result to the range of the type
+    Saturating,
+    /// Give back a vector of the wrapped result and whether it overflowed
+    Overflowing,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Operation {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Pow,
+}
+
+const OPERATIONS: &[(&str, Operation)] = &[
+    ("add", Operation::Add),
+    ("sub", Operation::Sub),
+    ("mul", Operation::Mul),
+    ("div", Operation::Div),
+    ("pow", Operation::Pow),
+];
+
+/// Do arithmetic on an integer with defined overflow
+/// The argument has to fit in the type of the receiver, the exponent of pow in a U32.
+/// The result replaces the receiver like it does for the plain messages.
+fn integer_overflow_op(object: ObjectBox, context: &mut ContextData, operation: Operation, overflow: Overflow) -> Result<Option<ObjectBox>, Fault> {
+    let receiver = Immediate::from_object(&*object.borrow());
+    let argument = Immediate::from_object(&*context.arguments[0].borrow())
+        .ok_or(Fault::InvalidType("Integer arithmetic: Not a number".to_string()))?;
+    macro_rules! pick {
+        ($a:expr, $b:expr, $wrapping:ident, $saturating:ident, $overflowing:ident) => {
+            match overflow {
+                Overflow::Wrapping => ($a.$wrapping($b), false),
+                Overflow::Saturating => ($a.$saturating($b), false),
+                Overflow::Overflowing => $a.$overflowing($b),
+            }
+        };
+    }
+    macro_rules! apply {
+        ($($variant:ident),*) => {
+            match receiver {
+                $(Some(Immediate::$variant(a)) => {
+                    let (result, overflowed) = if operation == Operation::Pow {
+                        let Immediate::U32(exponent) = convert(argument, Target::U32, Conversion::Checked)? else { unreachable!() };
+                        pick!(a, exponent, wrapping_pow, saturating_pow, overflowing_pow)
+                    } else {
+                        let Immediate::$variant(b) = convert(argument, Target::$variant, Conversion::Checked)? else { unreachable!() };
+                        match operation {
+                            Operation::Add => pick!(a, b, wrapping_add, saturating_add, overflowing_add),
+                            Operation::Sub => pick!(a, b, wrapping_sub, saturating_sub, overflowing_sub),
+                            Operation::Mul => pick!(a, b, wrapping_mul, saturating_mul, overflowing_mul),
+                            Operation::Div if b == 0 => return Err(Fault::DivideByZero),
+                            Operation::Div => pick!(a, b, wrapping_div, saturating_div, overflowing_div),
+                            Operation::Pow => unreachable!(),
+                        }
+                    };
+                    (Immediate::$variant(result), overflowed)
+                })*
+                _ => return Err(Fault::InvalidType(format!("Integer arithmetic: expected an integer"))),
+            }
+        };
+    }
+    let (result, overflowed) = apply!(I8, I16, I32, I64, U8, U16, U32, U64);
+    let result = match overflow {
+        Overflow::Overflowing => crate::object::create_vector(vec![result.into_object(), create_boolean(overflowed)]),
+        _ => result.into_object(),
+    };
+    context.pop();
+    Ok(Some(result))
 }
 
 macro_rules! create_integer_ops {
@@ -130,49 +182,49 @@ macro_rules! create_integer_ops {
             let object = object.downcast_ref::<PrimitiveObject<$type>>().ok_or(Fault::InvalidType(format!("Integer divides: expected {}", stringify!($type))))?;
             let other = context.arguments[0].borrow();
             if let Some(other) = other.downcast_ref::<PrimitiveObject<i64>>() {
-                if object.data.is_multiple_of(&(other.data as $type)) {
+                if Integer::is_multiple_of(&object.data, &(other.data as $type)) {
                     Ok(Some(create_boolean(true)))
                 } else {
                     Ok(Some(create_boolean(false)))
                 }
             } else if let Some(other) = other.downcast_ref::<PrimitiveObject<u64>>() {
-                if object.data.is_multiple_of(&(other.data as $type)) {
+                if Integer::is_multiple_of(&object.data, &(other.data as $type)) {
                     Ok(Some(create_boolean(true)))
                 } else {
                     Ok(Some(create_boolean(false)))
                 }
             } else if let Some(other) = other.downcast_ref::<PrimitiveObject<i32>>() {
-                if object.data.is_multiple_of(&(other.data as $type)) {
+                if Integer::is_multiple_of(&object.data, &(other.data as $type)) {
                     Ok(Some(create_boolean(true)))
                 } else {
                     Ok(Some(create_boolean(false)))
                 }
             } else if let Some(other) = other.downcast_ref::<PrimitiveObject<u32>>() {
-                if object.data.is_multiple_of(&(other.data as $type)) {
+                if Integer::is_multiple_of(&object.data, &(other.data as $type)) {
                     Ok(Some(create_boolean(true)))
                 } else {
                     Ok(Some(create_boolean(false)))
                 }
             } else if let Some(other) = other.downcast_ref::<PrimitiveObject<i16>>() {
-                if object.data.is_multiple_of(&(other.data as $type)) {
+                if Integer::is_multiple_of(&object.data, &(other.data as $type)) {
                     Ok(Some(create_boolean(true)))
                 } else {
                     Ok(Some(create_boolean(false)))
                 }
             } else if let Some(other) = other.downcast_ref::<PrimitiveObject<u16>>() {
-                if object.data.is_multiple_of(&(other.data as $type)) {
+                if Integer::is_multiple_of(&object.data, &(other.data as $type)) {
                     Ok(Some(create_boolean(true)))
                 } else {
                     Ok(Some(create_boolean(false)))
                 }
             } else if let Some(other) = other.downcast_ref::<PrimitiveObject<i8>>() {
-                if object.data.is_multiple_of(&(other.data as $type)) {
+                if Integer::is_multiple_of(&object.data, &(other.data as $type)) {
                     Ok(Some(create_boolean(true)))
                 } else {
                     Ok(Some(create_boolean(false)))
                 }
             } else if let Some(other) = other.downcast_ref::<PrimitiveObject<u8>>() {
-                if object.data.is_multiple_of(&(other.data as $type)) {
+                if Integer::is_multiple_of(&object.data, &(other.data as $type)) {
                     Ok(Some(create_boolean(true)))
                 } else {
                     Ok(Some(create_boolean(false)))
@@ -323,36 +375,35 @@ pub struct I64Object {
 impl I64Object {
 
     pub fn make_object(parent: ObjectBox, data: i64) -> ObjectBox {
-        let out = ObjectBox::new(PrimitiveObject::new(Some(parent), data));
-        return out;
+        ObjectBox::new(PrimitiveObject::new(Some(parent), data))
     }
     pub fn make_object_vtable() -> VTable {
         let mut methods = HashMap::new();
-        methods.insert(String::from("equals"), Arc::new(Method::RustMethod { fun: Box::new(i64_equals) }));
-        methods.insert(String::from("to_string"), Arc::new(Method::RustMethod { fun: Box::new(i64_to_string) }));
-        methods.insert(String::from("order"), Arc::new(Method::RustMethod { fun: Box::new(i64_order) }));
-        return VTable::new(methods);
+        methods.insert(Symbol::from("equals"), Arc::new(Method::RustMethod { fun: Box::new(i64_equals) }));
+        methods.insert(Symbol::from("to_string"), Arc::new(Method::RustMethod { fun: Box::new(i64_to_string) }));
+        methods.insert(Symbol::from("order"), Arc::new(Method::RustMethod { fun: Box::new(i64_order) }));
+        VTable::new(methods)
     }
     pub fn make_number_vtable() -> VTable {
         let mut methods = HashMap::new();
-        methods.insert(String::from("add"), Arc::new(Method::RustMethod { fun: Box::new(i64_add) }));
-        methods.insert(String::from("sub"), Arc::new(Method::RustMethod { fun: Box::new(i64_sub) }));
-        methods.insert(String::from("mul"), Arc::new(Method::RustMethod { fun: Box::new(i64_mul) }));
-        methods.insert(String::from("div"), Arc::new(Method::RustMethod { fun: Box::new(i64_div) }));
-        methods.insert(String::from("mod"), Arc::new(Method::RustMethod { fun: Box::new(i64_mod) }));
-        methods.insert(String::from("abs"), Arc::new(Method::RustMethod { fun: Box::new(i64_abs) }));
-        methods.insert(String::from("pow"), Arc::new(Method::RustMethod { fun: Box::new(i64_pow) }));
-        methods.insert(String::from("is_zero"), Arc::new(Method::RustMethod { fun: Box::new(i64_is_zero) }));
+        methods.insert(Symbol::from("add"), Arc::new(Method::RustMethod { fun: Box::new(i64_add) }));
+        methods.insert(Symbol::from("sub"), Arc::new(Method::RustMethod { fun: Box::new(i64_sub) }));
+        methods.insert(Symbol::from("mul"), Arc::new(Method::RustMethod { fun: Box::new(i64_mul) }));
+        methods.insert(Symbol::from("div"), Arc::new(Method::RustMethod { fun: Box::new(i64_div) }));
+        methods.insert(Symbol::from("mod"), Arc::new(Method::RustMethod { fun: Box::new(i64_mod) }));
+        methods.insert(Symbol::from("abs"), Arc::new(Method::RustMethod { fun: Box::new(i64_abs) }));
+        methods.insert(Symbol::from("pow"), Arc::new(Method::RustMethod { fun: Box::new(i64_pow) }));
+        methods.insert(Symbol::from("is_zero"), Arc::new(Method::RustMethod { fun: Box::new(i64_is_zero) }));
         VTable::new(methods)
     }
     pub fn make_integer_vtable() -> VTable {
         let mut methods = HashMap::new();
-        methods.insert(String::from("divides"), Arc::new(Method::RustMethod { fun: Box::new(i64_divides) }));
-        methods.insert(String::from("shift_right"), Arc::new(Method::RustMethod { fun: Box::new(i64_shr) }));
-        methods.insert(String::from("shift_left"), Arc::new(Method::RustMethod { fun: Box::new(i64_shl) }));
-        methods.insert(String::from("and"), Arc::new(Method::RustMethod { fun: Box::new(i64_and) }));
-        methods.insert(String::from("or"), Arc::new(Method::RustMethod { fun: Box::new(i64_or) }));
-        methods.insert(String::from("xor"), Arc::new(Method::RustMethod { fun: Box::new(i64_xor) }));
+        methods.insert(Symbol::from("divides"), Arc::new(Method::RustMethod { fun: Box::new(i64_divides) }));
+        methods.insert(Symbol::from("shift_right"), Arc::new(Method::RustMethod { fun: Box::new(i64_shr) }));
+        methods.insert(Symbol::from("shift_left"), Arc::new(Method::RustMethod { fun: Box::new(i64_shl) }));
+        methods.insert(Symbol::from("and"), Arc::new(Method::RustMethod { fun: Box::new(i64_and) }));
+        methods.insert(Symbol::from("or"), Arc::new(Method::RustMethod { fun: Box::new(i64_or) }));
+        methods.insert(Symbol::from("xor"), Arc::new(Method::RustMethod { fun: Box::new(i64_xor) }));
         VTable::new(methods)
     }
 }
@@ -375,26 +426,13 @@ impl Object for PrimitiveObject<i64> {
         None
     }
     fn duplicate(&self) -> ObjectBox {
-        let integer = I64Object::make_object(self.super_object.clone().unwrap().borrow().duplicate(), self.data);
+        let integer = I64Object::make_object(self.super_object.clone().unwrap(), self.data);
         let mut int = integer.borrow_mut();
         int.initialize(Vec::new(), self.vtable.clone());
         drop(int);
         integer
     }
     fn initialize(&mut self, _: Vec<ObjectBox>, vtable: VTable) {
-        let object_vtable = I64Object::make_object_vtable();
-        let number_vtable = I64Object::make_number_vtable();
-        let integer_vtable = I64Object::make_integer_vtable();
-
-        let integer_object = self.get_super_object().unwrap().clone();
-        let mut integer_object = integer_object.borrow_mut();
-        integer_object.initialize(Vec::new(), integer_vtable);
-        let number_object = integer_object.get_super_object().unwrap().clone();
-        let mut number_object = number_object.borrow_mut();
-        number_object.initialize(Vec::new(), number_vtable);
-        let object_object = number_object.get_super_object().unwrap().clone();
-        let mut object_object = object_object.borrow_mut();
-        object_object.initialize(Vec::new(), object_vtable);
         self.vtable.extend(vtable);
     }
 }
@@ -408,36 +446,35 @@ pub struct U64Object {
 
 impl U64Object {
     pub fn make_object(parent: ObjectBox, data: u64) -> ObjectBox {
-        let out = ObjectBox::new(PrimitiveObject::new(Some(parent), data));
-        return out;
+        ObjectBox::new(PrimitiveObject::new(Some(parent), data))
     }
     pub fn make_object_vtable() -> VTable {
         let mut methods = HashMap::new();
-        methods.insert(String::from("equals"), Arc::new(Method::RustMethod { fun: Box::new(u64_equals) }));
-        methods.insert(String::from("to_string"), Arc::new(Method::RustMethod { fun: Box::new(u64_to_string) }));
-        methods.insert(String::from("order"), Arc::new(Method::RustMethod { fun: Box::new(u64_order) }));
-        return VTable::new(methods);
+        methods.insert(Symbol::from("equals"), Arc::new(Method::RustMethod { fun: Box::new(u64_equals) }));
+        methods.insert(Symbol::from("to_string"), Arc::new(Method::RustMethod { fun: Box::new(u64_to_string) }));
+        methods.insert(Symbol::from("order"), Arc::new(Method::RustMethod { fun: Box::new(u64_order) }));
+        VTable::new(methods)
     }
     pub fn make_number_vtable() -> VTable {
         let mut methods = HashMap::new();
-        methods.insert(String::from("add"), Arc::new(Method::RustMethod { fun: Box::new(u64_add) }));
-        methods.insert(String::from("sub"), Arc::new(Method::RustMethod { fun: Box::new(u64_sub) }));
-        methods.insert(String::from("mul"), Arc::new(Method::RustMethod { fun: Box::new(u64_mul) }));
-        methods.insert(String::from("div"), Arc::new(Method::RustMethod { fun: Box::new(u64_div) }));
-        methods.insert(String::from("mod"), Arc::new(Method::RustMethod { fun: Box::new(u64_mod) }));
-        methods.insert(String::from("abs"), Arc::new(Method::RustMethod { fun: Box::new(u64_abs) }));
-        methods.insert(String::from("pow"), Arc::new(Method::RustMethod { fun: Box::new(u64_pow) }));
-        methods.insert(String::from("is_zero"), Arc::new(Method::RustMethod { fun: Box::new(u64_is_zero) }));
+        methods.insert(Symbol::from("add"), Arc::new(Method::RustMethod { fun: Box::new(u64_add) }));
+        methods.insert(Symbol::from("sub"), Arc::new(Method::RustMethod { fun: Box::new(u64_sub) }));
+        methods.insert(Symbol::from("mul"), Arc::new(Method::RustMethod { fun: Box::new(u64_mul) }));
+        methods.insert(Symbol::from("div"), Arc::new(Method::RustMethod { fun: Box::new(u64_div) }));
+        methods.insert(Symbol::from("mod"), Arc::new(Method::RustMethod { fun: Box::new(u64_mod) }));
+        methods.insert(Symbol::from("abs"), Arc::new(Method::RustMethod { fun: Box::new(u64_abs) }));
+        methods.insert(Symbol::from("pow"), Arc::new(Method::RustMethod { fun: Box::new(u64_pow) }));
+        methods.insert(Symbol::from("is_zero"), Arc::new(Method::RustMethod { fun: Box::new(u64_is_zero) }));
         VTable::new(methods)
     }
     pub fn make_integer_vtable() -> VTable {
         let mut methods = HashMap::new();
-        methods.insert(String::from("divides"), Arc::new(Method::RustMethod { fun: Box::new(u64_divides) }));
-        methods.insert(String::from("shift_right"), Arc::new(Method::RustMethod { fun: Box::new(u64_shr) }));
-        methods.insert(String::from("shift_left"), Arc::new(Method::RustMethod { fun: Box::new(u64_shl) }));
-        methods.insert(String::from("and"), Arc::new(Method::RustMethod { fun: Box::new(u64_and) }));
-        methods.insert(String::from("or"), Arc::new(Method::RustMethod { fun: Box::new(u64_or) }));
-        methods.insert(String::from("xor"), Arc::new(Method::RustMethod { fun: Box::new(u64_xor) }));
+        methods.insert(Symbol::from("divides"), Arc::new(Method::RustMethod { fun: Box::new(u64_divides) }));
+        methods.insert(Symbol::from("shift_right"), Arc::new(Method::RustMethod { fun: Box::new(u64_shr) }));
+        methods.insert(Symbol::from("shift_left"), Arc::new(Method::RustMethod { fun: Box::new(u64_shl) }));
+        methods.insert(Symbol::from("and"), Arc::new(Method::RustMethod { fun: Box::new(u64_and) }));
+        methods.insert(Symbol::from("or"), Arc::new(Method::RustMethod { fun: Box::new(u64_or) }));
+        methods.insert(Symbol::from("xor"), Arc::new(Method::RustMethod { fun: Box::new(u64_xor) }));
         VTable::new(methods)
     }
 }
@@ -460,26 +497,13 @@ impl Object for PrimitiveObject<u64> {
         None
     }
     fn duplicate(&self) -> ObjectBox {
-        let integer = U64Object::make_object(self.super_object.clone().unwrap().borrow().duplicate(), self.data);
+        let integer = U64Object::make_object(self.super_object.clone().unwrap(), self.data);
         let mut int = integer.borrow_mut();
         int.initialize(Vec::new(), self.vtable.clone());
         drop(int);
         integer
     }
     fn initialize(&mut self, _: Vec<ObjectBox>, vtable: VTable) {
-        let object_vtable = U64Object::make_object_vtable();
-        let number_vtable = U64Object::make_number_vtable();
-        let integer_vtable = U64Object::make_integer_vtable();
-
-        let integer_object = self.get_super_object().unwrap().clone();
-        let mut integer_object = integer_object.borrow_mut();
-        integer_object.initialize(Vec::new(), integer_vtable);
-        let number_object = integer_object.get_super_object().unwrap().clone();
-        let mut number_object = number_object.borrow_mut();
-        number_object.initialize(Vec::new(), number_vtable);
-        let object_object = number_object.get_super_object().unwrap().clone();
-        let mut object_object = object_object.borrow_mut();
-        object_object.initialize(Vec::new(), object_vtable);
         self.vtable.extend(vtable);
     }
 }
@@ -493,36 +517,35 @@ pub struct I32Object {
 
 impl I32Object {
     pub fn make_object(parent: ObjectBox, data: i32) -> ObjectBox {
-        let out = ObjectBox::new(PrimitiveObject::new(Some(parent), data));
-        return out;
+        ObjectBox::new(PrimitiveObject::new(Some(parent), data))
     }
     pub fn make_object_vtable() -> VTable {
         let mut methods = HashMap::new();
-        methods.insert(String::from("equals"), Arc::new(Method::RustMethod { fun: Box::new(i32_equals) }));
-        methods.insert(String::from("to_string"), Arc::new(Method::RustMethod { fun: Box::new(i32_to_string) }));
-        methods.insert(String::from("order"), Arc::new(Method::RustMethod { fun: Box::new(i32_order) }));
-        return VTable::new(methods);
+        methods.insert(Symbol::from("equals"), Arc::new(Method::RustMethod { fun: Box::new(i32_equals) }));
+        methods.insert(Symbol::from("to_string"), Arc::new(Method::RustMethod { fun: Box::new(i32_to_string) }));
+        methods.insert(Symbol::from("order"), Arc::new(Method::RustMethod { fun: Box::new(i32_order) }));
+        VTable::new(methods)
     }
     pub fn make_number_vtable() -> VTable {
         let mut methods = HashMap::new();
-        methods.insert(String::from("add"), Arc::new(Method::RustMethod { fun: Box::new(i32_add) }));
-        methods.insert(String::from("sub"), Arc::new(Method::RustMethod { fun: Box::new(i32_sub) }));
-        methods.insert(String::from("mul"), Arc::new(Method::RustMethod { fun: Box::new(i32_mul) }));
-        methods.insert(String::from("div"), Arc::new(Method::RustMethod { fun: Box::new(i32_div) }));
-        methods.insert(String::from("mod"), Arc::new(Method::RustMethod { fun: Box::new(i32_mod) }));
-        methods.insert(String::from("abs"), Arc::new(Method::RustMethod { fun: Box::new(i32_abs) }));
-        methods.insert(String::from("pow"), Arc::new(Method::RustMethod { fun: Box::new(i32_pow) }));
-        methods.insert(String::from("is_zero"), Arc::new(Method::RustMethod { fun: Box::new(i32_is_zero) }));
+        methods.insert(Symbol::from("add"), Arc::new(Method::RustMethod { fun: Box::new(i32_add) }));
+        methods.insert(Symbol::from("sub"), Arc::new(Method::RustMethod { fun: Box::new(i32_sub) }));
+        methods.insert(Symbol::from("mul"), Arc::new(Method::RustMethod { fun: Box::new(i32_mul) }));
+        methods.insert(Symbol::from("div"), Arc::new(Method::RustMethod { fun: Box::new(i32_div) }));
+        methods.insert(Symbol::from("mod"), Arc::new(Method::RustMethod { fun: Box::new(i32_mod) }));
+        methods.insert(Symbol::from("abs"), Arc::new(Method::RustMethod { fun: Box::new(i32_abs) }));
+        methods.insert(Symbol::from("pow"), Arc::new(Method::RustMethod { fun: Box::new(i32_pow) }));
+        methods.insert(Symbol::from("is_zero"), Arc::new(Method::RustMethod { fun: Box::new(i32_is_zero) }));
         VTable::new(methods)
     }
     pub fn make_integer_vtable() -> VTable {
         let mut methods = HashMap::new();
-        methods.insert(String::from("divides"), Arc::new(Method::RustMethod { fun: Box::new(i32_divides) }));
-        methods.insert(String::from("shift_right"), Arc::new(Method::RustMethod { fun: Box::new(i32_shr) }));
-        methods.insert(String::from("shift_left"), Arc::new(Method::RustMethod { fun: Box::new(i32_shl) }));
-        methods.insert(String::from("and"), Arc::new(Method::RustMethod { fun: Box::new(i32_and) }));
-        methods.insert(String::from("or"), Arc::new(Method::RustMethod { fun: Box::new(i32_or) }));
-        methods.insert(String::from("xor"), Arc::new(Method::RustMethod { fun: Box::new(i32_xor) }));
+        methods.insert(Symbol::from("divides"), Arc::new(Method::RustMethod { fun: Box::new(i32_divides) }));
+        methods.insert(Symbol::from("shift_right"), Arc::new(Method::RustMethod { fun: Box::new(i32_shr) }));
+        methods.insert(Symbol::from("shift_left"), Arc::new(Method::RustMethod { fun: Box::new(i32_shl) }));
+        methods.insert(Symbol::from("and"), Arc::new(Method::RustMethod { fun: Box::new(i32_and) }));
+        methods.insert(Symbol::from("or"), Arc::new(Method::RustMethod { fun: Box::new(i32_or) }));
+        methods.insert(Symbol::from("xor"), Arc::new(Method::RustMethod { fun: Box::new(i32_xor) }));
         VTable::new(methods)
     }
 }
@@ -545,26 +568,13 @@ impl Object for PrimitiveObject<i32> {
         None
     }
     fn duplicate(&self) -> ObjectBox {
-        let integer = I32Object::make_object(self.super_object.clone().unwrap().borrow().duplicate(), self.data);
+        let integer = I32Object::make_object(self.super_object.clone().unwrap(), self.data);
         let mut int = integer.borrow_mut();
         int.initialize(Vec::new(), self.vtable.clone());
         drop(int);
         integer
     }
     fn initialize(&mut self, _: Vec<ObjectBox>, vtable: VTable) {
-        let object_vtable = I32Object::make_object_vtable();
-        let number_vtable = I32Object::make_number_vtable();
-        let integer_vtable = I32Object::make_integer_vtable();
-
-        let integer_object = self.get_super_object().unwrap().clone();
-        let mut integer_object = integer_object.borrow_mut();
-        integer_object.initialize(Vec::new(), integer_vtable);
-        let number_object = integer_object.get_super_object().unwrap().clone();
-        let mut number_object = number_object.borrow_mut();
-        number_object.initialize(Vec::new(), number_vtable);
-        let object_object = number_object.get_super_object().unwrap().clone();
-        let mut object_object = object_object.borrow_mut();
-        object_object.initialize(Vec::new(), object_vtable);
         self.vtable.extend(vtable);
     }
 }
@@ -578,36 +588,35 @@ pub struct U32Object {
 
 impl U32Object {
     pub fn make_object(parent: ObjectBox, data: u32) -> ObjectBox {
-        let out = ObjectBox::new(PrimitiveObject::new(Some(parent), data));
-        return out;
+        ObjectBox::new(PrimitiveObject::new(Some(parent), data))
     }
     pub fn make_object_vtable() -> VTable {
         let mut methods = HashMap::new();
-        methods.insert(String::from("equals"), Arc::new(Method::RustMethod { fun: Box::new(u32_equals) }));
-        methods.insert(String::from("to_string"), Arc::new(Method::RustMethod { fun: Box::new(u32_to_string) }));
-        methods.insert(String::from("order"), Arc::new(Method::RustMethod { fun: Box::new(u32_order) }));
-        return VTable::new(methods);
+        methods.insert(Symbol::from("equals"), Arc::new(Method::RustMethod { fun: Box::new(u32_equals) }));
+        methods.insert(Symbol::from("to_string"), Arc::new(Method::RustMethod { fun: Box::new(u32_to_string) }));
+        methods.insert(Symbol::from("order"), Arc::new(Method::RustMethod { fun: Box::new(u32_order) }));
+        VTable::new(methods)
     }
     pub fn make_number_vtable() -> VTable {
         let mut methods = HashMap::new();
-        methods.insert(String::from("add"), Arc::new(Method::RustMethod { fun: Box::new(u32_add) }));
-        methods.insert(String::from("sub"), Arc::new(Method::RustMethod { fun: Box::new(u32_sub) }));
-        methods.insert(String::from("mul"), Arc::new(Method::RustMethod { fun: Box::new(u32_mul) }));
-        methods.insert(String::from("div"), Arc::new(Method::RustMethod { fun: Box::new(u32_div) }));
-        methods.insert(String::from("mod"), Arc::new(Method::RustMethod { fun: Box::new(u32_mod) }));
-        methods.insert(String::from("abs"), Arc::new(Method::RustMethod { fun: Box::new(u32_abs) }));
-        methods.insert(String::from("pow"), Arc::new(Method::RustMethod { fun: Box::new(u32_pow) }));
-        methods.insert(String::from("is_zero"), Arc::new(Method::RustMethod { fun: Box::new(u32_is_zero) }));
+        methods.insert(Symbol::from("add"), Arc::new(Method::RustMethod { fun: Box::new(u32_add) }));
+        methods.insert(Symbol::from("sub"), Arc::new(Method::RustMethod { fun: Box::new(u32_sub) }));
+        methods.insert(Symbol::from("mul"), Arc::new(Method::RustMethod { fun: Box::new(u32_mul) }));
+        methods.insert(Symbol::from("div"), Arc::new(Method::RustMethod { fun: Box::new(u32_div) }));
+        methods.insert(Symbol::from("mod"), Arc::new(Method::RustMethod { fun: Box::new(u32_mod) }));
+        methods.insert(Symbol::from("abs"), Arc::new(Method::RustMethod { fun: Box::new(u32_abs) }));
+        methods.insert(Symbol::from("pow"), Arc::new(Method::RustMethod { fun: Box::new(u32_pow) }));
+        methods.insert(Symbol::from("is_zero"), Arc::new(Method::RustMethod { fun: Box::new(u32_is_zero) }));
         VTable::new(methods)
     }
     pub fn make_integer_vtable() -> VTable {
         let mut methods = HashMap::new();
-        methods.insert(String::from("divides"), Arc::new(Method::RustMethod { fun: Box::new(u32_divides) }));
-        methods.insert(String::from("shift_right"), Arc::new(Method::RustMethod { fun: Box::new(u32_shr) }));
-        methods.insert(String::from("shift_left"), Arc::new(Method::RustMethod { fun: Box::new(u32_shl) }));
-        methods.insert(String::from("and"), Arc::new(Method::RustMethod { fun: Box::new(u32_and) }));
-        methods.insert(String::from("or"), Arc::new(Method::RustMethod { fun: Box::new(u32_or) }));
-        methods.insert(String::from("xor"), Arc::new(Method::RustMethod { fun: Box::new(u32_xor) }));
+        methods.insert(Symbol::from("divides"), Arc::new(Method::RustMethod { fun: Box::new(u32_divides) }));
+        methods.insert(Symbol::from("shift_right"), Arc::new(Method::RustMethod { fun: Box::new(u32_shr) }));
+        methods.insert(Symbol::from("shift_left"), Arc::new(Method::RustMethod { fun: Box::new(u32_shl) }));
+        methods.insert(Symbol::from("and"), Arc::new(Method::RustMethod { fun: Box::new(u32_and) }));
+        methods.insert(Symbol::from("or"), Arc::new(Method::RustMethod { fun: Box::new(u32_or) }));
+        methods.insert(Symbol::from("xor"), Arc::new(Method::RustMethod { fun: Box::new(u32_xor) }));
         VTable::new(methods)
     }
 }
@@ -630,26 +639,13 @@ impl Object for PrimitiveObject<u32> {
         None
     }
     fn duplicate(&self) -> ObjectBox {
-        let integer = U32Object::make_object(self.super_object.clone().unwrap().borrow().duplicate(), self.data);
+        let integer = U32Object::make_object(self.super_object.clone().unwrap(), self.data);
         let mut int = integer.borrow_mut();
         int.initialize(Vec::new(), self.vtable.clone());
         drop(int);
         integer
     }
     fn initialize(&mut self, _: Vec<ObjectBox>, vtable: VTable) {
-        let object_vtable = U32Object::make_object_vtable();
-        let number_vtable = U32Object::make_number_vtable();
-        let integer_vtable = U32Object::make_integer_vtable();
-
-        let integer_object = self.get_super_object().unwrap().clone();
-        let mut integer_object = integer_object.borrow_mut();
-        integer_object.initialize(Vec::new(), integer_vtable);
-        let number_object = integer_object.get_super_object().unwrap().clone();
-        let mut number_object = number_object.borrow_mut();
-        number_object.initialize(Vec::new(), number_vtable);
-        let object_object = number_object.get_super_object().unwrap().clone();
-        let mut object_object = object_object.borrow_mut();
-        object_object.initialize(Vec::new(), object_vtable);
         self.vtable.extend(vtable);
     }
 }
@@ -663,36 +659,35 @@ pub struct I16Object {
 
 impl I16Object {
     pub fn make_object(parent: ObjectBox, data: i16) -> ObjectBox {
-        let out = ObjectBox::new(PrimitiveObject::new(Some(parent), data));
-        return out;
+        ObjectBox::new(PrimitiveObject::new(Some(parent), data))
     }
     pub fn make_object_vtable() -> VTable {
         let mut methods = HashMap::new();
-        methods.insert(String::from("equals"), Arc::new(Method::RustMethod { fun: Box::new(i16_equals) }));
-        methods.insert(String::from("to_string"), Arc::new(Method::RustMethod { fun: Box::new(i16_to_string) }));
-        methods.insert(String::from("order"), Arc::new(Method::RustMethod { fun: Box::new(i16_order) }));
-        return VTable::new(methods);
+        methods.insert(Symbol::from("equals"), Arc::new(Method::RustMethod { fun: Box::new(i16_equals) }));
+        methods.insert(Symbol::from("to_string"), Arc::new(Method::RustMethod { fun: Box::new(i16_to_string) }));
+        methods.insert(Symbol::from("order"), Arc::new(Method::RustMethod { fun: Box::new(i16_order) }));
+        VTable::new(methods)
     }
     pub fn make_number_vtable() -> VTable {
         let mut methods = HashMap::new();
-        methods.insert(String::from("add"), Arc::new(Method::RustMethod { fun: Box::new(i16_add) }));
-        methods.insert(String::from("sub"), Arc::new(Method::RustMethod { fun: Box::new(i16_sub) }));
-        methods.insert(String::from("mul"), Arc::new(Method::RustMethod { fun: Box::new(i16_mul) }));
-        methods.insert(String::from("div"), Arc::new(Method::RustMethod { fun: Box::new(i16_div) }));
-        methods.insert(String::from("mod"), Arc::new(Method::RustMethod { fun: Box::new(i16_mod) }));
-        methods.insert(String::from("abs"), Arc::new(Method::RustMethod { fun: Box::new(i16_abs) }));
-        methods.insert(String::from("pow"), Arc::new(Method::RustMethod { fun: Box::new(i16_pow) }));
-        methods.insert(String::from("is_zero"), Arc::new(Method::RustMethod { fun: Box::new(i16_is_zero) }));
+        methods.insert(Symbol::from("add"), Arc::new(Method::RustMethod { fun: Box::new(i16_add) }));
+        methods.insert(Symbol::from("sub"), Arc::new(Method::RustMethod { fun: Box::new(i16_sub) }));
+        methods.insert(Symbol::from("mul"), Arc::new(Method::RustMethod { fun: Box::new(i16_mul) }));
+        methods.insert(Symbol::from("div"), Arc::new(Method::RustMethod { fun: Box::new(i16_div) }));
+        methods.insert(Symbol::from("mod"), Arc::new(Method::RustMethod { fun: Box::new(i16_mod) }));
+        methods.insert(Symbol::from("abs"), Arc::new(Method::RustMethod { fun: Box::new(i16_abs) }));
+        methods.insert(Symbol::from("pow"), Arc::new(Method::RustMethod { fun: Box::new(i16_pow) }));
+        methods.insert(Symbol::from("is_zero"), Arc::new(Method::RustMethod { fun: Box::new(i16_is_zero) }));
         VTable::new(methods)
     }
     pub fn make_integer_vtable() -> VTable {
         let mut methods = HashMap::new();
-        methods.insert(String::from("divides"), Arc::new(Method::RustMethod { fun: Box::new(i16_divides) }));
-        methods.insert(String::from("shift_right"), Arc::new(Method::RustMethod { fun: Box::new(i16_shr) }));
-        methods.insert(String::from("shift_left"), Arc::new(Method::RustMethod { fun: Box::new(i16_shl) }));
-        methods.insert(String::from("and"), Arc::new(Method::RustMethod { fun: Box::new(i16_and) }));
-        methods.insert(String::from("or"), Arc::new(Method::RustMethod { fun: Box::new(i16_or) }));
-        methods.insert(String::from("xor"), Arc::new(Method::RustMethod { fun: Box::new(i16_xor) }));
+        methods.insert(Symbol::from("divides"), Arc::new(Method::RustMethod { fun: Box::new(i16_divides) }));
+        methods.insert(Symbol::from("shift_right"), Arc::new(Method::RustMethod { fun: Box::new(i16_shr) }));
+        methods.insert(Symbol::from("shift_left"), Arc::new(Method::RustMethod { fun: Box::new(i16_shl) }));
+        methods.insert(Symbol::from("and"), Arc::new(Method::RustMethod { fun: Box::new(i16_and) }));
+        methods.insert(Symbol::from("or"), Arc::new(Method::RustMethod { fun: Box::new(i16_or) }));
+        methods.insert(Symbol::from("xor"), Arc::new(Method::RustMethod { fun: Box::new(i16_xor) }));
         VTable::new(methods)
     }
 }
@@ -715,26 +710,13 @@ impl Object for PrimitiveObject<i16> {
         None
     }
     fn duplicate(&self) -> ObjectBox {
-        let integer = I16Object::make_object(self.super_object.clone().unwrap().borrow().duplicate(), self.data);
+        let integer = I16Object::make_object(self.super_object.clone().unwrap(), self.data);
         let mut int = integer.borrow_mut();
         int.initialize(Vec::new(), self.vtable.clone());
         drop(int);
         integer
     }
     fn initialize(&mut self, _: Vec<ObjectBox>, vtable: VTable) {
-        let object_vtable = I16Object::make_object_vtable();
-        let number_vtable = I16Object::make_number_vtable();
-        let integer_vtable = I16Object::make_integer_vtable();
-
-        let integer_object = self.get_super_object().unwrap().clone();
-        let mut integer_object = integer_object.borrow_mut();
-        integer_object.initialize(Vec::new(), integer_vtable);
-        let number_object = integer_object.get_super_object().unwrap().clone();
-        let mut number_object = number_object.borrow_mut();
-        number_object.initialize(Vec::new(), number_vtable);
-        let object_object = number_object.get_super_object().unwrap().clone();
-        let mut object_object = object_object.borrow_mut();
-        object_object.initialize(Vec::new(), object_vtable);
         self.vtable.extend(vtable);
     }
 }
@@ -748,36 +730,35 @@ pub struct U16Object {
 
 impl U16Object {
     pub fn make_object(parent: ObjectBox, data: u16) -> ObjectBox {
-        let out = ObjectBox::new(PrimitiveObject::new(Some(parent), data));
-        return out;
+        ObjectBox::new(PrimitiveObject::new(Some(parent), data))
     }
     pub fn make_object_vtable() -> VTable {
         let mut methods = HashMap::new();
-        methods.insert(String::from("equals"), Arc::new(Method::RustMethod { fun: Box::new(u16_equals) }));
-        methods.insert(String::from("to_string"), Arc::new(Method::RustMethod { fun: Box::new(u16_to_string) }));
-        methods.insert(String::from("order"), Arc::new(Method::RustMethod { fun: Box::new(u16_order) }));
-        return VTable::new(methods);
+        methods.insert(Symbol::from("equals"), Arc::new(Method::RustMethod { fun: Box::new(u16_equals) }));
+        methods.insert(Symbol::from("to_string"), Arc::new(Method::RustMethod { fun: Box::new(u16_to_string) }));
+        methods.insert(Symbol::from("order"), Arc::new(Method::RustMethod { fun: Box::new(u16_order) }));
+        VTable::new(methods)
     }
     pub fn make_number_vtable() -> VTable {
         let mut methods = HashMap::new();
-        methods.insert(String::from("add"), Arc::new(Method::RustMethod { fun: Box::new(u16_add) }));
-        methods.insert(String::from("sub"), Arc::new(Method::RustMethod { fun: Box::new(u16_sub) }));
-        methods.insert(String::from("mul"), Arc::new(Method::RustMethod { fun: Box::new(u16_mul) }));
-        methods.insert(String::from("div"), Arc::new(Method::RustMethod { fun: Box::new(u16_div) }));
-        methods.insert(String::from("mod"), Arc::new(Method::RustMethod { fun: Box::new(u16_mod) }));
-        methods.insert(String::from("abs"), Arc::new(Method::RustMethod { fun: Box::new(u16_abs) }));
-        methods.insert(String::from("pow"), Arc::new(Method::RustMethod { fun: Box::new(u16_pow) }));
-        methods.insert(String::from("is_zero"), Arc::new(Method::RustMethod { fun: Box::new(u16_is_zero) }));
+        methods.insert(Symbol::from("add"), Arc::new(Method::RustMethod { fun: Box::new(u16_add) }));
+        methods.insert(Symbol::from("sub"), Arc::new(Method::RustMethod { fun: Box::new(u16_sub) }));
+        methods.insert(Symbol::from("mul"), Arc::new(Method::RustMethod { fun: Box::new(u16_mul) }));
+        methods.insert(Symbol::from("div"), Arc::new(Method::RustMethod { fun: Box::new(u16_div) }));
+        methods.insert(Symbol::from("mod"), Arc::new(Method::RustMethod { fun: Box::new(u16_mod) }));
+        methods.insert(Symbol::from("abs"), Arc::new(Method::RustMethod { fun: Box::new(u16_abs) }));
+        methods.insert(Symbol::from("pow"), Arc::new(Method::RustMethod { fun: Box::new(u16_pow) }));
+        methods.insert(Symbol::from("is_zero"), Arc::new(Method::RustMethod { fun: Box::new(u16_is_zero) }));
         VTable::new(methods)
     }
     pub fn make_integer_vtable() -> VTable {
         let mut methods = HashMap::new();
-        methods.insert(String::from("divides"), Arc::new(Method::RustMethod { fun: Box::new(u16_divides) }));
-        methods.insert(String::from("shift_right"), Arc::new(Method::RustMethod { fun: Box::new(u16_shr) }));
-        methods.insert(String::from("shift_left"), Arc::new(Method::RustMethod { fun: Box::new(u16_shl) }));
-        methods.insert(String::from("and"), Arc::new(Method::RustMethod { fun: Box::new(u16_and) }));
-        methods.insert(String::from("or"), Arc::new(Method::RustMethod { fun: Box::new(u16_or) }));
-        methods.insert(String::from("xor"), Arc::new(Method::RustMethod { fun: Box::new(u16_xor) }));
+        methods.insert(Symbol::from("divides"), Arc::new(Method::RustMethod { fun: Box::new(u16_divides) }));
+        methods.insert(Symbol::from("shift_right"), Arc::new(Method::RustMethod { fun: Box::new(u16_shr) }));
+        methods.insert(Symbol::from("shift_left"), Arc::new(Method::RustMethod { fun: Box::new(u16_shl) }));
+        methods.insert(Symbol::from("and"), Arc::new(Method::RustMethod { fun: Box::new(u16_and) }));
+        methods.insert(Symbol::from("or"), Arc::new(Method::RustMethod { fun: Box::new(u16_or) }));
+        methods.insert(Symbol::from("xor"), Arc::new(Method::RustMethod { fun: Box::new(u16_xor) }));
         VTable::new(methods)
     }
 }
@@ -800,26 +781,13 @@ impl Object for PrimitiveObject<u16> {
         None
     }
     fn duplicate(&self) -> ObjectBox {
-        let integer = U16Object::make_object(self.super_object.clone().unwrap().borrow().duplicate(), self.data);
+        let integer = U16Object::make_object(self.super_object.clone().unwrap(), self.data);
         let mut int = integer.borrow_mut();
         int.initialize(Vec::new(), self.vtable.clone());
         drop(int);
         integer
     }
     fn initialize(&mut self, _: Vec<ObjectBox>, vtable: VTable) {
-        let object_vtable = U16Object::make_object_vtable();
-        let number_vtable = U16Object::make_number_vtable();
-        let integer_vtable = U16Object::make_integer_vtable();
-
-        let integer_object = self.get_super_object().unwrap().clone();
-        let mut integer_object = integer_object.borrow_mut();
-        integer_object.initialize(Vec::new(), integer_vtable);
-        let number_object = integer_object.get_super_object().unwrap().clone();
-        let mut number_object = number_object.borrow_mut();
-        number_object.initialize(Vec::new(), number_vtable);
-        let object_object = number_object.get_super_object().unwrap().clone();
-        let mut object_object = object_object.borrow_mut();
-        object_object.initialize(Vec::new(), object_vtable);
         self.vtable.extend(vtable);
     }
 }
@@ -833,36 +801,35 @@ pub struct I8Object {
 
 impl I8Object {
     pub fn make_object(parent: ObjectBox, data: i8) -> ObjectBox {
-        let out = ObjectBox::new(PrimitiveObject::new(Some(parent), data));
-        return out;
+        ObjectBox::new(PrimitiveObject::new(Some(parent), data))
     }
     pub fn make_object_vtable() -> VTable {
         let mut methods = HashMap::new();
-        methods.insert(String::from("equals"), Arc::new(Method::RustMethod { fun: Box::new(i8_equals) }));
-        methods.insert(String::from("to_string"), Arc::new(Method::RustMethod { fun: Box::new(i8_to_string) }));
-        methods.insert(String::from("order"), Arc::new(Method::RustMethod { fun: Box::new(i8_order) }));
-        return VTable::new(methods);
+        methods.insert(Symbol::from("equals"), Arc::new(Method::RustMethod { fun: Box::new(i8_equals) }));
+        methods.insert(Symbol::from("to_string"), Arc::new(Method::RustMethod { fun: Box::new(i8_to_string) }));
+        methods.insert(Symbol::from("order"), Arc::new(Method::RustMethod { fun: Box::new(i8_order) }));
+        VTable::new(methods)
     }
     pub fn make_number_vtable() -> VTable {
         let mut methods = HashMap::new();
-        methods.insert(String::from("add"), Arc::new(Method::RustMethod { fun: Box::new(i8_add) }));
-        methods.insert(String::from("sub"), Arc::new(Method::RustMethod { fun: Box::new(i8_sub) }));
-        methods.insert(String::from("mul"), Arc::new(Method::RustMethod { fun: Box::new(i8_mul) }));
-        methods.insert(String::from("div"), Arc::new(Method::RustMethod { fun: Box::new(i8_div) }));
-        methods.insert(String::from("mod"), Arc::new(Method::RustMethod { fun: Box::new(i8_mod) }));
-        methods.insert(String::from("abs"), Arc::new(Method::RustMethod { fun: Box::new(i8_abs) }));
-        methods.insert(String::from("pow"), Arc::new(Method::RustMethod { fun: Box::new(i8_pow) }));
-        methods.insert(String::from("is_zero"), Arc::new(Method::RustMethod { fun: Box::new(i8_is_zero) }));
+        methods.insert(Symbol::from("add"), Arc::new(Method::RustMethod { fun: Box::new(i8_add) }));
+        methods.insert(Symbol::from("sub"), Arc::new(Method::RustMethod { fun: Box::new(i8_sub) }));
+        methods.insert(Symbol::from("mul"), Arc::new(Method::RustMethod { fun: Box::new(i8_mul) }));
+        methods.insert(Symbol::from("div"), Arc::new(Method::RustMethod { fun: Box::new(i8_div) }));
+        methods.insert(Symbol::from("mod"), Arc::new(Method::RustMethod { fun: Box::new(i8_mod) }));
+        methods.insert(Symbol::from("abs"), Arc::new(Method::RustMethod { fun: Box::new(i8_abs) }));
+        methods.insert(Symbol::from("pow"), Arc::new(Method::RustMethod { fun: Box::new(i8_pow) }));
+        methods.insert(Symbol::from("is_zero"), Arc::new(Method::RustMethod { fun: Box::new(i8_is_zero) }));
         VTable::new(methods)
     }
     pub fn make_integer_vtable() -> VTable {
         let mut methods = HashMap::new();
-        methods.insert(String::from("divides"), Arc::new(Method::RustMethod { fun: Box::new(i8_divides) }));
-        methods.insert(String::from("shift_right"), Arc::new(Method::RustMethod { fun: Box::new(i8_shr) }));
-        methods.insert(String::from("shift_left"), Arc::new(Method::RustMethod { fun: Box::new(i8_shl) }));
-        methods.insert(String::from("and"), Arc::new(Method::RustMethod { fun: Box::new(i8_and) }));
-        methods.insert(String::from("or"), Arc::new(Method::RustMethod { fun: Box::new(i8_or) }));
-        methods.insert(String::from("xor"), Arc::new(Method::RustMethod { fun: Box::new(i8_xor) }));
+        methods.insert(Symbol::from("divides"), Arc::new(Method::RustMethod { fun: Box::new(i8_divides) }));
+        methods.insert(Symbol::from("shift_right"), Arc::new(Method::RustMethod { fun: Box::new(i8_shr) }));
+        methods.insert(Symbol::from("shift_left"), Arc::new(Method::RustMethod { fun: Box::new(i8_shl) }));
+        methods.insert(Symbol::from("and"), Arc::new(Method::RustMethod { fun: Box::new(i8_and) }));
+        methods.insert(Symbol::from("or"), Arc::new(Method::RustMethod { fun: Box::new(i8_or) }));
+        methods.insert(Symbol::from("xor"), Arc::new(Method::RustMethod { fun: Box::new(i8_xor) }));
         VTable::new(methods)
     }
 }
@@ -885,26 +852,13 @@ impl Object for PrimitiveObject<i8> {
         None
     }
     fn duplicate(&self) -> ObjectBox {
-        let integer = I8Object::make_object(self.super_object.clone().unwrap().borrow().duplicate(), self.data);
+        let integer = I8Object::make_object(self.super_object.clone().unwrap(), self.data);
         let mut int = integer.borrow_mut();
         int.initialize(Vec::new(), self.vtable.clone());
         drop(int);
         integer
     }
     fn initialize(&mut self, _: Vec<ObjectBox>, vtable: VTable) {
-        let object_vtable = I8Object::make_object_vtable();
-        let number_vtable = I8Object::make_number_vtable();
-        let integer_vtable = I8Object::make_integer_vtable();
-
-        let integer_object = self.get_super_object().unwrap().clone();
-        let mut integer_object = integer_object.borrow_mut();
-        integer_object.initialize(Vec::new(), integer_vtable);
-        let number_object = integer_object.get_super_object().unwrap().clone();
-        let mut number_object = number_object.borrow_mut();
-        number_object.initialize(Vec::new(), number_vtable);
-        let object_object = number_object.get_super_object().unwrap().clone();
-        let mut object_object = object_object.borrow_mut();
-        object_object.initialize(Vec::new(), object_vtable);
         self.vtable.extend(vtable);
     }
 }
@@ -918,36 +872,35 @@ pub struct U8Object {
 
 impl U8Object {
     pub fn make_object(parent: ObjectBox, data: u8) -> ObjectBox {
-        let out = ObjectBox::new(PrimitiveObject::new(Some(parent), data));
-        return out;
+        ObjectBox::new(PrimitiveObject::new(Some(parent), data))
     }
     pub fn make_object_vtable() -> VTable {
         let mut methods = HashMap::new();
-        methods.insert(String::from("equals"), Arc::new(Method::RustMethod { fun: Box::new(u8_equals) }));
-        methods.insert(String::from("to_string"), Arc::new(Method::RustMethod { fun: Box::new(u8_to_string) }));
-        methods.insert(String::from("order"), Arc::new(Method::RustMethod { fun: Box::new(u8_order) }));
-        return VTable::new(methods);
+        methods.insert(Symbol::from("equals"), Arc::new(Method::RustMethod { fun: Box::new(u8_equals) }));
+        methods.insert(Symbol::from("to_string"), Arc::new(Method::RustMethod { fun: Box::new(u8_to_string) }));
+        methods.insert(Symbol::from("order"), Arc::new(Method::RustMethod { fun: Box::new(u8_order) }));
+        VTable::new(methods)
     }
     pub fn make_number_vtable() -> VTable {
         let mut methods = HashMap::new();
-        methods.insert(String::from("add"), Arc::new(Method::RustMethod { fun: Box::new(u8_add) }));
-        methods.insert(String::from("sub"), Arc::new(Method::RustMethod { fun: Box::new(u8_sub) }));
-        methods.insert(String::from("mul"), Arc::new(Method::RustMethod { fun: Box::new(u8_mul) }));
-        methods.insert(String::from("div"), Arc::new(Method::RustMethod { fun: Box::new(u8_div) }));
-        methods.insert(String::from("mod"), Arc::new(Method::RustMethod { fun: Box::new(u8_mod) }));
-        methods.insert(String::from("abs"), Arc::new(Method::RustMethod { fun: Box::new(u8_abs) }));
-        methods.insert(String::from("pow"), Arc::new(Method::RustMethod { fun: Box::new(u8_pow) }));
-        methods.insert(String::from("is_zero"), Arc::new(Method::RustMethod { fun: Box::new(u8_is_zero) }));
+        methods.insert(Symbol::from("add"), Arc::new(Method::RustMethod { fun: Box::new(u8_add) }));
+        methods.insert(Symbol::from("sub"), Arc::new(Method::RustMethod { fun: Box::new(u8_sub) }));
+        methods.insert(Symbol::from("mul"), Arc::new(Method::RustMethod { fun: Box::new(u8_mul) }));
+        methods.insert(Symbol::from("div"), Arc::new(Method::RustMethod { fun: Box::new(u8_div) }));
+        methods.insert(Symbol::from("mod"), Arc::new(Method::RustMethod { fun: Box::new(u8_mod) }));
+        methods.insert(Symbol::from("abs"), Arc::new(Method::RustMethod { fun: Box::new(u8_abs) }));
+        methods.insert(Symbol::from("pow"), Arc::new(Method::RustMethod { fun: Box::new(u8_pow) }));
+        methods.insert(Symbol::from("is_zero"), Arc::new(Method::RustMethod { fun: Box::new(u8_is_zero) }));
         VTable::new(methods)
     }
     pub fn make_integer_vtable() -> VTable {
         let mut methods = HashMap::new();
-        methods.insert(String::from("divides"), Arc::new(Method::RustMethod { fun: Box::new(u8_divides) }));
-        methods.insert(String::from("shift_right"), Arc::new(Method::RustMethod { fun: Box::new(u8_shr) }));
-        methods.insert(String::from("shift_left"), Arc::new(Method::RustMethod { fun: Box::new(u8_shl) }));
-        methods.insert(String::from("and"), Arc::new(Method::RustMethod { fun: Box::new(u8_and) }));
-        methods.insert(String::from("or"), Arc::new(Method::RustMethod { fun: Box::new(u8_or) }));
-        methods.insert(String::from("xor"), Arc::new(Method::RustMethod { fun: Box::new(u8_xor) }));
+        methods.insert(Symbol::from("divides"), Arc::new(Method::RustMethod { fun: Box::new(u8_divides) }));
+        methods.insert(Symbol::from("shift_right"), Arc::new(Method::RustMethod { fun: Box::new(u8_shr) }));
+        methods.insert(Symbol::from("shift_left"), Arc::new(Method::RustMethod { fun: Box::new(u8_shl) }));
+        methods.insert(Symbol::from("and"), Arc::new(Method::RustMethod { fun: Box::new(u8_and) }));
+        methods.insert(Symbol::from("or"), Arc::new(Method::RustMethod { fun: Box::new(u8_or) }));
+        methods.insert(Symbol::from("xor"), Arc::new(Method::RustMethod { fun: Box::new(u8_xor) }));
         VTable::new(methods)
     }
 }
@@ -970,26 +923,13 @@ impl Object for PrimitiveObject<u8> {
         None
     }
     fn duplicate(&self) -> ObjectBox {
-        let integer = U8Object::make_object(self.super_object.clone().unwrap().borrow().duplicate(), self.data);
+        let integer = U8Object::make_object(self.super_object.clone().unwrap(), self.data);
         let mut int = integer.borrow_mut();
         int.initialize(Vec::new(), self.vtable.clone());
         drop(int);
         integer
     }
     fn initialize(&mut self, _: Vec<ObjectBox>, vtable: VTable) {
-        let object_vtable = U8Object::make_object_vtable();
-        let number_vtable = U8Object::make_number_vtable();
-        let integer_vtable = U8Object::make_integer_vtable();
-
-        let integer_object = self.get_super_object().unwrap().clone();
-        let mut integer_object = integer_object.borrow_mut();
-        integer_object.initialize(Vec::new(), integer_vtable);
-        let number_object = integer_object.get_super_object().unwrap().clone();
-        let mut number_object = number_object.borrow_mut();
-        number_object.initialize(Vec::new(), number_vtable);
-        let object_object = number_object.get_super_object().unwrap().clone();
-        let mut object_object = object_object.borrow_mut();
-        object_object.initialize(Vec::new(), object_vtable);
         self.vtable.extend(vtable);
     }
 }
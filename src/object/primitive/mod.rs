@@ -1,4 +1,5 @@
 use super::{Method, ObjectBox, VTable};
+use super::symbol::Symbol;
 use crate::object::Object;
 use super::Fault;
 use std::collections::HashMap;
@@ -10,6 +11,12 @@ pub mod integer;
 pub mod float;
 pub mod boolean;
 pub mod character;
+pub mod symbol;
+pub mod convert;
+pub mod bigint;
+pub mod rational;
+pub mod decimal;
+pub mod exact;
 
 #[derive(Clone)]
 pub struct PrimitiveObject<T: Copy + 'static> {
@@ -38,15 +45,17 @@ impl NumberObject {
     }
     pub fn make_vtable() -> VTable {
         let mut methods = HashMap::new();
-        methods.insert(String::from("add"), Arc::new(Method::RustMethod { fun: Box::new(number_add) }));
-        methods.insert(String::from("sub"), Arc::new(Method::RustMethod { fun: Box::new(number_subtract) }));
-        methods.insert(String::from("mul"), Arc::new(Method::RustMethod { fun: Box::new(number_multiply) }));
-        methods.insert(String::from("div"), Arc::new(Method::RustMethod { fun: Box::new(number_divide) }));
-        methods.insert(String::from("mod"), Arc::new(Method::RustMethod { fun: Box::new(number_modulo) }));
-        methods.insert(String::from("abs"), Arc::new(Method::RustMethod { fun: Box::new(number_abs) }));
-        methods.insert(String::from("pow"), Arc::new(Method::RustMethod { fun: Box::new(number_pow) }));
-        methods.insert(String::from("is_zero"), Arc::new(Method::RustMethod { fun: Box::new(number_is_zero) }));
-        
+        methods.insert(Symbol::from("add"), Arc::new(Method::RustMethod { fun: Box::new(number_add) }));
+        methods.insert(Symbol::from("sub"), Arc::new(Method::RustMethod { fun: Box::new(number_subtract) }));
+        methods.insert(Symbol::from("mul"), Arc::new(Method::RustMethod { fun: Box::new(number_multiply) }));
+        methods.insert(Symbol::from("div"), Arc::new(Method::RustMethod { fun: Box::new(number_divide) }));
+        methods.insert(Symbol::from("mod"), Arc::new(Method::RustMethod { fun: Box::new(number_modulo) }));
+        methods.insert(Symbol::from("abs"), Arc::new(Method::RustMethod { fun: Box::new(number_abs) }));
+        methods.insert(Symbol::from("pow"), Arc::new(Method::RustMethod { fun: Box::new(number_pow) }));
+        methods.insert(Symbol::from("is_zero"), Arc::new(Method::RustMethod { fun: Box::new(number_is_zero) }));
+        methods.insert(Symbol::from("hash"), Arc::new(Method::RustMethod { fun: Box::new(number_hash) }));
+        convert::add_methods(&mut methods);
+        crate::object::compare::add_methods(&mut methods);
         VTable::new(methods)
     }
 }
@@ -68,109 +77,280 @@ impl Object for NumberObject {
         None
     }
     fn duplicate(&self) -> ObjectBox {
-        let number = NumberObject {super_object: Some(self.super_object.clone().unwrap().borrow().duplicate()), vtable: self.vtable.clone()};
+        let number = NumberObject {super_object: Some(self.super_object.clone().unwrap()), vtable: self.vtable.clone()};
         ObjectBox::new(number)
     }
     fn initialize(&mut self, _: Vec<ObjectBox>, vtable: VTable) {
-        self.vtable.extend(NumberObject::make_vtable());
         self.vtable.extend(vtable);
-        match &mut self.super_object {
-            Some(super_object) => {
-                let mut super_object = super_object.borrow_mut();
-                super_object.initialize(vec![], VTable::new_empty());
-            }
-            None => {}
-        }
     }
 }
 
 fn number_add(_: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
-    Err(Fault::NotImplemented(format!("Number add")))
+    Err(Fault::NotImplemented("Number add".to_string()))
 }
 
 fn number_subtract(_: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
-    Err(Fault::NotImplemented(format!("Number sub")))
+    Err(Fault::NotImplemented("Number sub".to_string()))
 }
 
 fn number_multiply(_: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
-    Err(Fault::NotImplemented(format!("Number mul")))
+    Err(Fault::NotImplemented("Number mul".to_string()))
 }
 
 fn number_divide(_: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
-    Err(Fault::NotImplemented(format!("Number div")))
+    Err(Fault::NotImplemented("Number div".to_string()))
 }
 
 fn number_modulo(_: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
-    Err(Fault::NotImplemented(format!("Number mod")))
+    Err(Fault::NotImplemented("Number mod".to_string()))
 }
 
 fn number_abs(_: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
-    Err(Fault::NotImplemented(format!("Number abs")))
+    Err(Fault::NotImplemented("Number abs".to_string()))
 }
 
 fn number_pow(_: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
-    Err(Fault::NotImplemented(format!("Number pow")))
+    Err(Fault::NotImplemented("Number pow".to_string()))
 }
 
 fn number_is_zero(_: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
-    Err(Fault::NotImplemented(format!("Number is_zero")))
+    Err(Fault::NotImplemented("Number is_zero".to_string()))
+}
+
+/// Hash a number by its value so that equal numbers of different types hash the same
+/// A whole number hashes as an integer whatever its type and any other number as its nearest f64.
+fn number_hash(object: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let object = object.borrow();
+    if let Some(big) = object.downcast_ref::<bigint::BigIntObject>() {
+        if big.value.to_i128().is_none() {
+            return Ok(Some(crate::object::create_u64(crate::object::set::hash_of(&big.value.parts()))));
+        }
+    }
+    let number = exact::Exact::from_object(&*object, "hash")?;
+    let float = number.to_f64();
+    let whole = match number {
+        exact::Exact::Float(value) if value.fract() == 0.0 && value.abs() < 2f64.powi(127) => Some(value as i128),
+        number => number.integer(),
+    };
+    let hash = match whole {
+        Some(value) => crate::object::set::hash_of(&value),
+        // Zero is whole so this is never -0.0
+        None => crate::object::set::hash_of(&float.to_bits()),
+    };
+    Ok(Some(crate::object::create_u64(hash)))
+}
+
+/// NumberOperation
+/// The arithmetic messages that every number understands, used where numbers of different types
+/// are combined
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum NumberOperation {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Mod,
+    Pow,
+}
+
+/// The selectors of the operations
+pub const NUMBER_OPERATIONS: &[(&str, NumberOperation)] = &[
+    ("add", NumberOperation::Add),
+    ("sub", NumberOperation::Sub),
+    ("mul", NumberOperation::Mul),
+    ("div", NumberOperation::Div),
+    ("mod", NumberOperation::Mod),
+    ("pow", NumberOperation::Pow),
+];
+
+impl NumberOperation {
+    /// Apply the operation to two floats
+    pub fn apply_float(self, a: f64, b: f64) -> Result<f64, Fault> {
+        match self {
+            NumberOperation::Add => Ok(a + b),
+            NumberOperation::Sub => Ok(a - b),
+            NumberOperation::Mul => Ok(a * b),
+            NumberOperation::Div | NumberOperation::Mod if b == 0.0 => Err(Fault::DivideByZero),
+            NumberOperation::Div => Ok(a / b),
+            NumberOperation::Mod => Ok(a % b),
+            NumberOperation::Pow => Ok(a.powf(b)),
+        }
+    }
 }
 
+/// Arithmetic
+/// The arithmetic that the number messages are built on. It gives None when the result
+/// overflows instead of panicking in debug builds and wrapping in release builds.
+/// Floats don't overflow so they always give a result.
+pub trait Arithmetic: Sized {
+    fn checked_add(self, other: Self) -> Option<Self>;
+    fn checked_sub(self, other: Self) -> Option<Self>;
+    fn checked_mul(self, other: Self) -> Option<Self>;
+    fn checked_div(self, other: Self) -> Option<Self>;
+    fn checked_rem(self, other: Self) -> Option<Self>;
+    fn checked_pow(self, exponent: u32) -> Option<Self>;
+    fn checked_abs(self) -> Option<Self>;
+    /// Combine with an integer of any width. Integers are widened to i128 first so that neither
+    /// side gets truncated and the result is narrowed back, giving None when it doesn't fit.
+    fn checked_wide(self, operation: NumberOperation, other: i128) -> Option<Self>;
+    /// The value as an i128, None for floats
+    fn widen(self) -> Option<i128>;
+}
+
+/// Apply an operation to two integers that have been widened to i128
+pub fn wide_operation(a: i128, operation: NumberOperation, b: i128) -> Option<i128> {
+    match operation {
+        NumberOperation::Add => a.checked_add(b),
+        NumberOperation::Sub => a.checked_sub(b),
+        NumberOperation::Mul => a.checked_mul(b),
+        NumberOperation::Div => a.checked_div(b),
+        NumberOperation::Mod => a.checked_rem(b),
+        NumberOperation::Pow => u32::try_from(b).ok().and_then(|exponent| a.checked_pow(exponent)),
+    }
+}
+
+/// Multiplying or dividing an integer by a signed integer gives a result of the argument's type.
+/// The outer None means the receiver is a float, which keeps its own type instead.
+pub fn into_argument<T: Arithmetic, R: Into<i128> + TryFrom<i128>>(receiver: T, operation: NumberOperation, argument: R) -> Option<Option<R>> {
+    let receiver = receiver.widen()?;
+    Some(wide_operation(receiver, operation, argument.into()).and_then(|result| R::try_from(result).ok()))
+}
+
+macro_rules! integer_arithmetic {
+    ($type:ty, $abs:expr) => {
+        impl Arithmetic for $type {
+            fn checked_add(self, other: Self) -> Option<Self> {
+                <$type>::checked_add(self, other)
+            }
+            fn checked_sub(self, other: Self) -> Option<Self> {
+                <$type>::checked_sub(self, other)
+            }
+            fn checked_mul(self, other: Self) -> Option<Self> {
+                <$type>::checked_mul(self, other)
+            }
+            fn checked_div(self, other: Self) -> Option<Self> {
+                <$type>::checked_div(self, other)
+            }
+            fn checked_rem(self, other: Self) -> Option<Self> {
+                <$type>::checked_rem(self, other)
+            }
+            fn checked_pow(self, exponent: u32) -> Option<Self> {
+                <$type>::checked_pow(self, exponent)
+            }
+            fn checked_abs(self) -> Option<Self> {
+                $abs(self)
+            }
+            fn checked_wide(self, operation: NumberOperation, other: i128) -> Option<Self> {
+                wide_operation(self as i128, operation, other).and_then(|result| <$type>::try_from(result).ok())
+            }
+            fn widen(self) -> Option<i128> {
+                Some(self as i128)
+            }
+        }
+    };
+}
+
+macro_rules! float_arithmetic {
+    ($type:ty) => {
+        impl Arithmetic for $type {
+            fn checked_add(self, other: Self) -> Option<Self> {
+                Some(self + other)
+            }
+            fn checked_sub(self, other: Self) -> Option<Self> {
+                Some(self - other)
+            }
+            fn checked_mul(self, other: Self) -> Option<Self> {
+                Some(self * other)
+            }
+            fn checked_div(self, other: Self) -> Option<Self> {
+                Some(self / other)
+            }
+            fn checked_rem(self, other: Self) -> Option<Self> {
+                Some(self % other)
+            }
+            fn checked_pow(self, exponent: u32) -> Option<Self> {
+                Some(self.powf(exponent as $type))
+            }
+            fn checked_abs(self) -> Option<Self> {
+                Some(self.abs())
+            }
+            fn checked_wide(self, operation: NumberOperation, other: i128) -> Option<Self> {
+                let other = other as $type;
+                Some(match operation {
+                    NumberOperation::Add => self + other,
+                    NumberOperation::Sub => self - other,
+                    NumberOperation::Mul => self * other,
+                    NumberOperation::Div => self / other,
+                    NumberOperation::Mod => self % other,
+                    NumberOperation::Pow => self.powf(other),
+                })
+            }
+            fn widen(self) -> Option<i128> {
+                None
+            }
+        }
+    };
+}
+
+integer_arithmetic!(i8, i8::checked_abs);
+integer_arithmetic!(i16, i16::checked_abs);
+integer_arithmetic!(i32, i32::checked_abs);
+integer_arithmetic!(i64, i64::checked_abs);
+integer_arithmetic!(u8, Some);
+integer_arithmetic!(u16, Some);
+integer_arithmetic!(u32, Some);
+integer_arithmetic!(u64, Some);
+float_arithmetic!(f32);
+float_arithmetic!(f64);
 
 #[macro_export]
 macro_rules! create_type_ops {
     ($type:ty, $add_name:ident, $sub_name:ident, $mul_name:ident, $div_name:ident, $mod_name:ident, $abs:ident, $pow:ident, $is_zero:ident) => {
 
-        fn $add_name(object: ObjectBox, context: &mut crate::object::ContextData) -> Result<Option<ObjectBox>, Fault> {
+        fn $add_name(object: ObjectBox, context: &mut $crate::object::ContextData) -> Result<Option<ObjectBox>, Fault> {
             {
                 let mut object = object.borrow_mut();
-                let object = object.downcast_mut::<crate::object::primitive::PrimitiveObject<$type>>();
+                let object = object.downcast_mut::<$crate::object::primitive::PrimitiveObject<$type>>();
                 let original_arg = context.arguments[0].clone();
                 let mut arg_mut = original_arg.borrow_mut();
                 match object {
                     Some(object) => {
-                        if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<i64>>() {
-                            if arg.data.is_negative() {
-                                object.data -= arg.data.abs() as $type;
-                            } else {
-                                object.data += arg.data as $type;
-                            }
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<i32>>() {
-                            if arg.data.is_negative() {
-                                object.data -= arg.data.abs() as $type;
-                            } else {
-                                object.data += arg.data as $type;
-                            }
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<i16>>() {
-                            if arg.data.is_negative() {
-                                object.data -= arg.data.abs() as $type;
-                            } else {
-                                object.data += arg.data as $type;
-                            }
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<i8>>() {
-                            if arg.data.is_negative() {
-                                object.data -= arg.data.abs() as $type;
-                            } else {
-                                object.data += arg.data as $type;
-                            }
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<u64>>() {
-                            object.data += arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<u32>>() {
-                            object.data += arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<u16>>() {
-                            object.data += arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<u8>>() {
-                            object.data += arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_mut::<crate::object::primitive::PrimitiveObject<f64>>() {
+                        if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<i64>>() {
+                            object.data = $crate::object::primitive::Arithmetic::checked_wide(object.data, $crate::object::primitive::NumberOperation::Add, arg.data as i128).ok_or(Fault::Overflow(format!("Number add: {} overflowed", stringify!($type))))?;
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<i32>>() {
+                            object.data = $crate::object::primitive::Arithmetic::checked_wide(object.data, $crate::object::primitive::NumberOperation::Add, arg.data as i128).ok_or(Fault::Overflow(format!("Number add: {} overflowed", stringify!($type))))?;
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<i16>>() {
+                            object.data = $crate::object::primitive::Arithmetic::checked_wide(object.data, $crate::object::primitive::NumberOperation::Add, arg.data as i128).ok_or(Fault::Overflow(format!("Number add: {} overflowed", stringify!($type))))?;
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<i8>>() {
+                            object.data = $crate::object::primitive::Arithmetic::checked_wide(object.data, $crate::object::primitive::NumberOperation::Add, arg.data as i128).ok_or(Fault::Overflow(format!("Number add: {} overflowed", stringify!($type))))?;
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<u64>>() {
+                            object.data = $crate::object::primitive::Arithmetic::checked_wide(object.data, $crate::object::primitive::NumberOperation::Add, arg.data as i128).ok_or(Fault::Overflow(format!("Number add: {} overflowed", stringify!($type))))?;
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<u32>>() {
+                            object.data = $crate::object::primitive::Arithmetic::checked_wide(object.data, $crate::object::primitive::NumberOperation::Add, arg.data as i128).ok_or(Fault::Overflow(format!("Number add: {} overflowed", stringify!($type))))?;
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<u16>>() {
+                            object.data = $crate::object::primitive::Arithmetic::checked_wide(object.data, $crate::object::primitive::NumberOperation::Add, arg.data as i128).ok_or(Fault::Overflow(format!("Number add: {} overflowed", stringify!($type))))?;
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<u8>>() {
+                            object.data = $crate::object::primitive::Arithmetic::checked_wide(object.data, $crate::object::primitive::NumberOperation::Add, arg.data as i128).ok_or(Fault::Overflow(format!("Number add: {} overflowed", stringify!($type))))?;
+                        } else if let Some(arg) = arg_mut.downcast_mut::<$crate::object::primitive::PrimitiveObject<f64>>() {
                             arg.data += object.data as f64;
                             drop(arg_mut);
                             context.pop();
                             return Ok(Some(original_arg));
-                        } else if let Some(arg) = arg_mut.downcast_mut::<crate::object::primitive::PrimitiveObject<f32>>() {
+                        } else if let Some(arg) = arg_mut.downcast_mut::<$crate::object::primitive::PrimitiveObject<f32>>() {
                             arg.data += object.data as f32;
                             drop(arg_mut);
                             context.pop();
                             return Ok(Some(original_arg));
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::bigint::BigIntObject>() {
+                            let result = $crate::object::primitive::bigint::Promote::promote(object.data, $crate::object::primitive::NumberOperation::Add, &arg.value)?;
+                            drop(arg_mut);
+                            context.pop();
+                            return Ok(Some(result));
+                        } else if let Some(arg) = $crate::object::primitive::exact::Exact::from_fraction(&*arg_mut) {
+                            let result = $crate::object::primitive::exact::Exact::from(object.data).combine($crate::object::primitive::NumberOperation::Add, arg)?;
+                            drop(arg_mut);
+                            context.pop();
+                            return Ok(Some(result.into_object()));
                         } else {
                             return Err(Fault::InvalidType(format!("Number add: Not a number")));
                         }
@@ -183,56 +363,50 @@ macro_rules! create_type_ops {
             Ok(None)
         }
 
-        fn $sub_name(object: ObjectBox, context: &mut crate::object::ContextData) -> Result<Option<ObjectBox>, Fault> {
+        fn $sub_name(object: ObjectBox, context: &mut $crate::object::ContextData) -> Result<Option<ObjectBox>, Fault> {
             {
                 let mut object = object.borrow_mut();
-                let object = object.downcast_mut::<crate::object::primitive::PrimitiveObject<$type>>();
+                let object = object.downcast_mut::<$crate::object::primitive::PrimitiveObject<$type>>();
                 let original_arg = context.arguments[0].clone();
                 let mut arg_mut = original_arg.borrow_mut();
                 match object {
                     Some(object) => {
-                        if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<i64>>() {
-                            if arg.data.is_negative() {
-                                object.data += arg.data.abs() as $type;
-                            } else {
-                                object.data -= arg.data as $type;
-                            }
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<i32>>() {
-                            if arg.data.is_negative() {
-                                object.data += arg.data.abs() as $type;
-                            } else {
-                                object.data -= arg.data as $type;
-                            }
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<i16>>() {
-                            if arg.data.is_negative() {
-                                object.data += arg.data.abs() as $type;
-                            } else {
-                                object.data -= arg.data as $type;
-                            }
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<i8>>() {
-                            if arg.data.is_negative() {
-                                object.data += arg.data.abs() as $type;
-                            } else {
-                                object.data -= arg.data as $type;
-                            }
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<u64>>() {
-                            object.data -= arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<u32>>() {
-                            object.data -= arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<u16>>() {
-                            object.data -= arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<u8>>() {
-                            object.data -= arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_mut::<crate::object::primitive::PrimitiveObject<f64>>() {
+                        if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<i64>>() {
+                            object.data = $crate::object::primitive::Arithmetic::checked_wide(object.data, $crate::object::primitive::NumberOperation::Sub, arg.data as i128).ok_or(Fault::Overflow(format!("Number sub: {} overflowed", stringify!($type))))?;
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<i32>>() {
+                            object.data = $crate::object::primitive::Arithmetic::checked_wide(object.data, $crate::object::primitive::NumberOperation::Sub, arg.data as i128).ok_or(Fault::Overflow(format!("Number sub: {} overflowed", stringify!($type))))?;
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<i16>>() {
+                            object.data = $crate::object::primitive::Arithmetic::checked_wide(object.data, $crate::object::primitive::NumberOperation::Sub, arg.data as i128).ok_or(Fault::Overflow(format!("Number sub: {} overflowed", stringify!($type))))?;
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<i8>>() {
+                            object.data = $crate::object::primitive::Arithmetic::checked_wide(object.data, $crate::object::primitive::NumberOperation::Sub, arg.data as i128).ok_or(Fault::Overflow(format!("Number sub: {} overflowed", stringify!($type))))?;
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<u64>>() {
+                            object.data = $crate::object::primitive::Arithmetic::checked_wide(object.data, $crate::object::primitive::NumberOperation::Sub, arg.data as i128).ok_or(Fault::Overflow(format!("Number sub: {} overflowed", stringify!($type))))?;
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<u32>>() {
+                            object.data = $crate::object::primitive::Arithmetic::checked_wide(object.data, $crate::object::primitive::NumberOperation::Sub, arg.data as i128).ok_or(Fault::Overflow(format!("Number sub: {} overflowed", stringify!($type))))?;
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<u16>>() {
+                            object.data = $crate::object::primitive::Arithmetic::checked_wide(object.data, $crate::object::primitive::NumberOperation::Sub, arg.data as i128).ok_or(Fault::Overflow(format!("Number sub: {} overflowed", stringify!($type))))?;
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<u8>>() {
+                            object.data = $crate::object::primitive::Arithmetic::checked_wide(object.data, $crate::object::primitive::NumberOperation::Sub, arg.data as i128).ok_or(Fault::Overflow(format!("Number sub: {} overflowed", stringify!($type))))?;
+                        } else if let Some(arg) = arg_mut.downcast_mut::<$crate::object::primitive::PrimitiveObject<f64>>() {
                             arg.data -= object.data as f64;
                             drop(arg_mut);
                             context.pop();
                             return Ok(Some(original_arg));
-                        } else if let Some(arg) = arg_mut.downcast_mut::<crate::object::primitive::PrimitiveObject<f32>>() {
+                        } else if let Some(arg) = arg_mut.downcast_mut::<$crate::object::primitive::PrimitiveObject<f32>>() {
                             arg.data -= object.data as f32;
                             drop(arg_mut);
                             context.pop();
                             return Ok(Some(original_arg));
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::bigint::BigIntObject>() {
+                            let result = $crate::object::primitive::bigint::Promote::promote(object.data, $crate::object::primitive::NumberOperation::Sub, &arg.value)?;
+                            drop(arg_mut);
+                            context.pop();
+                            return Ok(Some(result));
+                        } else if let Some(arg) = $crate::object::primitive::exact::Exact::from_fraction(&*arg_mut) {
+                            let result = $crate::object::primitive::exact::Exact::from(object.data).combine($crate::object::primitive::NumberOperation::Sub, arg)?;
+                            drop(arg_mut);
+                            context.pop();
+                            return Ok(Some(result.into_object()));
                         } else {
                             return Err(Fault::InvalidType(format!("Number sub: Not a number")));
                         }
@@ -246,52 +420,82 @@ macro_rules! create_type_ops {
         }
         
 
-        fn $mul_name(object: ObjectBox, context: &mut crate::object::ContextData) -> Result<Option<ObjectBox>, Fault> {
+        fn $mul_name(object: ObjectBox, context: &mut $crate::object::ContextData) -> Result<Option<ObjectBox>, Fault> {
             {
                 let mut object = object.borrow_mut();
-                let object = object.downcast_mut::<crate::object::primitive::PrimitiveObject<$type>>();
+                let object = object.downcast_mut::<$crate::object::primitive::PrimitiveObject<$type>>();
                 let original_arg = context.arguments[0].clone();
                 let mut arg_mut = original_arg.borrow_mut();
                 match object {
                     Some(object) => {
-                        if let Some(arg) = arg_mut.downcast_mut::<crate::object::primitive::PrimitiveObject<i64>>() {
-                            arg.data = object.data as i64 * arg.data;
+                        if let Some(arg) = arg_mut.downcast_mut::<$crate::object::primitive::PrimitiveObject<i64>>() {
+                            match $crate::object::primitive::into_argument(object.data, $crate::object::primitive::NumberOperation::Mul, arg.data) {
+                                Some(result) => {
+                                    arg.data = result.ok_or(Fault::Overflow(format!("Number mul: {} overflowed", stringify!($type))))?;
+                                    drop(arg_mut);
+                                    context.pop();
+                                    return Ok(Some(original_arg));
+                                }
+                                None => object.data = $crate::object::primitive::Arithmetic::checked_wide(object.data, $crate::object::primitive::NumberOperation::Mul, arg.data as i128).ok_or(Fault::Overflow(format!("Number mul: {} overflowed", stringify!($type))))?,
+                            }
+                        } else if let Some(arg) = arg_mut.downcast_mut::<$crate::object::primitive::PrimitiveObject<i32>>() {
+                            match $crate::object::primitive::into_argument(object.data, $crate::object::primitive::NumberOperation::Mul, arg.data) {
+                                Some(result) => {
+                                    arg.data = result.ok_or(Fault::Overflow(format!("Number mul: {} overflowed", stringify!($type))))?;
+                                    drop(arg_mut);
+                                    context.pop();
+                                    return Ok(Some(original_arg));
+                                }
+                                None => object.data = $crate::object::primitive::Arithmetic::checked_wide(object.data, $crate::object::primitive::NumberOperation::Mul, arg.data as i128).ok_or(Fault::Overflow(format!("Number mul: {} overflowed", stringify!($type))))?,
+                            }
+                        } else if let Some(arg) = arg_mut.downcast_mut::<$crate::object::primitive::PrimitiveObject<i16>>() {
+                            match $crate::object::primitive::into_argument(object.data, $crate::object::primitive::NumberOperation::Mul, arg.data) {
+                                Some(result) => {
+                                    arg.data = result.ok_or(Fault::Overflow(format!("Number mul: {} overflowed", stringify!($type))))?;
+                                    drop(arg_mut);
+                                    context.pop();
+                                    return Ok(Some(original_arg));
+                                }
+                                None => object.data = $crate::object::primitive::Arithmetic::checked_wide(object.data, $crate::object::primitive::NumberOperation::Mul, arg.data as i128).ok_or(Fault::Overflow(format!("Number mul: {} overflowed", stringify!($type))))?,
+                            }
+                        } else if let Some(arg) = arg_mut.downcast_mut::<$crate::object::primitive::PrimitiveObject<i8>>() {
+                            match $crate::object::primitive::into_argument(object.data, $crate::object::primitive::NumberOperation::Mul, arg.data) {
+                                Some(result) => {
+                                    arg.data = result.ok_or(Fault::Overflow(format!("Number mul: {} overflowed", stringify!($type))))?;
+                                    drop(arg_mut);
+                                    context.pop();
+                                    return Ok(Some(original_arg));
+                                }
+                                None => object.data = $crate::object::primitive::Arithmetic::checked_wide(object.data, $crate::object::primitive::NumberOperation::Mul, arg.data as i128).ok_or(Fault::Overflow(format!("Number mul: {} overflowed", stringify!($type))))?,
+                            }
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<u64>>() {
+                            object.data = $crate::object::primitive::Arithmetic::checked_wide(object.data, $crate::object::primitive::NumberOperation::Mul, arg.data as i128).ok_or(Fault::Overflow(format!("Number mul: {} overflowed", stringify!($type))))?;
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<u32>>() {
+                            object.data = $crate::object::primitive::Arithmetic::checked_wide(object.data, $crate::object::primitive::NumberOperation::Mul, arg.data as i128).ok_or(Fault::Overflow(format!("Number mul: {} overflowed", stringify!($type))))?;
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<u16>>() {
+                            object.data = $crate::object::primitive::Arithmetic::checked_wide(object.data, $crate::object::primitive::NumberOperation::Mul, arg.data as i128).ok_or(Fault::Overflow(format!("Number mul: {} overflowed", stringify!($type))))?;
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<u8>>() {
+                            object.data = $crate::object::primitive::Arithmetic::checked_wide(object.data, $crate::object::primitive::NumberOperation::Mul, arg.data as i128).ok_or(Fault::Overflow(format!("Number mul: {} overflowed", stringify!($type))))?;
+                        } else if let Some(arg) = arg_mut.downcast_mut::<$crate::object::primitive::PrimitiveObject<f64>>() {
+                            arg.data *= object.data as f64;
                             drop(arg_mut);
                             context.pop();
                             return Ok(Some(original_arg));
-                        } else if let Some(arg) = arg_mut.downcast_mut::<crate::object::primitive::PrimitiveObject<i32>>() {
-                            arg.data = object.data as i32 * arg.data;
+                        } else if let Some(arg) = arg_mut.downcast_mut::<$crate::object::primitive::PrimitiveObject<f32>>() {
+                            arg.data *= object.data as f32;
                             drop(arg_mut);
                             context.pop();
                             return Ok(Some(original_arg));
-                        } else if let Some(arg) = arg_mut.downcast_mut::<crate::object::primitive::PrimitiveObject<i16>>() {
-                            arg.data = object.data as i16 * arg.data;
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::bigint::BigIntObject>() {
+                            let result = $crate::object::primitive::bigint::Promote::promote(object.data, $crate::object::primitive::NumberOperation::Mul, &arg.value)?;
                             drop(arg_mut);
                             context.pop();
-                            return Ok(Some(original_arg));
-                        } else if let Some(arg) = arg_mut.downcast_mut::<crate::object::primitive::PrimitiveObject<i8>>() {
-                            arg.data = object.data as i8 * arg.data;
+                            return Ok(Some(result));
+                        } else if let Some(arg) = $crate::object::primitive::exact::Exact::from_fraction(&*arg_mut) {
+                            let result = $crate::object::primitive::exact::Exact::from(object.data).combine($crate::object::primitive::NumberOperation::Mul, arg)?;
                             drop(arg_mut);
                             context.pop();
-                            return Ok(Some(original_arg));
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<u64>>() {
-                            object.data *= arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<u32>>() {
-                            object.data *= arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<u16>>() {
-                            object.data *= arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<u8>>() {
-                            object.data *= arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_mut::<crate::object::primitive::PrimitiveObject<f64>>() {
-                            arg.data *= object.data as f64;
-                            drop(arg_mut);
-                            context.pop();
-                            return Ok(Some(original_arg));
-                        } else if let Some(arg) = arg_mut.downcast_mut::<crate::object::primitive::PrimitiveObject<f32>>() {
-                            arg.data *= object.data as f32;
-                            drop(arg_mut);
-                            context.pop();
-                            return Ok(Some(original_arg));
+                            return Ok(Some(result.into_object()));
                         } else {
                             return Err(Fault::InvalidType(format!("Number mul: Not a number")));
                         }
@@ -304,67 +508,87 @@ macro_rules! create_type_ops {
             Ok(None)
         }
 
-        fn $div_name(object: ObjectBox, context: &mut crate::object::ContextData) -> Result<Option<ObjectBox>, Fault> {
+        fn $div_name(object: ObjectBox, context: &mut $crate::object::ContextData) -> Result<Option<ObjectBox>, Fault> {
             {
                 let mut object = object.borrow_mut();
-                let object = object.downcast_mut::<crate::object::primitive::PrimitiveObject<$type>>();
+                let object = object.downcast_mut::<$crate::object::primitive::PrimitiveObject<$type>>();
                 let original_arg = context.arguments[0].clone();
                 let mut arg_mut = original_arg.borrow_mut();
                 match object {
                     Some(object) => {
-                        if let Some(arg) = arg_mut.downcast_mut::<crate::object::primitive::PrimitiveObject<i64>>() {
+                        if let Some(arg) = arg_mut.downcast_mut::<$crate::object::primitive::PrimitiveObject<i64>>() {
                             if arg.data.is_zero() {
                                 return Err(Fault::DivideByZero);
                             }
-                            arg.data = object.data as i64 / arg.data;
-                            drop(arg_mut);
-                            context.pop();
-                            return Ok(Some(original_arg));
-                        } else if let Some(arg) = arg_mut.downcast_mut::<crate::object::primitive::PrimitiveObject<i32>>() {
+                            match $crate::object::primitive::into_argument(object.data, $crate::object::primitive::NumberOperation::Div, arg.data) {
+                                Some(result) => {
+                                    arg.data = result.ok_or(Fault::Overflow(format!("Number div: {} overflowed", stringify!($type))))?;
+                                    drop(arg_mut);
+                                    context.pop();
+                                    return Ok(Some(original_arg));
+                                }
+                                None => object.data = $crate::object::primitive::Arithmetic::checked_wide(object.data, $crate::object::primitive::NumberOperation::Div, arg.data as i128).ok_or(Fault::Overflow(format!("Number div: {} overflowed", stringify!($type))))?,
+                            }
+                        } else if let Some(arg) = arg_mut.downcast_mut::<$crate::object::primitive::PrimitiveObject<i32>>() {
                             if arg.data.is_zero() {
                                 return Err(Fault::DivideByZero);
                             }
-                            arg.data = object.data as i32 / arg.data;
-                            drop(arg_mut);
-                            context.pop();
-                            return Ok(Some(original_arg));
-                        } else if let Some(arg) = arg_mut.downcast_mut::<crate::object::primitive::PrimitiveObject<i16>>() {
+                            match $crate::object::primitive::into_argument(object.data, $crate::object::primitive::NumberOperation::Div, arg.data) {
+                                Some(result) => {
+                                    arg.data = result.ok_or(Fault::Overflow(format!("Number div: {} overflowed", stringify!($type))))?;
+                                    drop(arg_mut);
+                                    context.pop();
+                                    return Ok(Some(original_arg));
+                                }
+                                None => object.data = $crate::object::primitive::Arithmetic::checked_wide(object.data, $crate::object::primitive::NumberOperation::Div, arg.data as i128).ok_or(Fault::Overflow(format!("Number div: {} overflowed", stringify!($type))))?,
+                            }
+                        } else if let Some(arg) = arg_mut.downcast_mut::<$crate::object::primitive::PrimitiveObject<i16>>() {
                             if arg.data.is_zero() {
                                 return Err(Fault::DivideByZero);
                             }
-                            arg.data = object.data as i16 / arg.data;
-                            drop(arg_mut);
-                            context.pop();
-                            return Ok(Some(original_arg));
-                        } else if let Some(arg) = arg_mut.downcast_mut::<crate::object::primitive::PrimitiveObject<i8>>() {
+                            match $crate::object::primitive::into_argument(object.data, $crate::object::primitive::NumberOperation::Div, arg.data) {
+                                Some(result) => {
+                                    arg.data = result.ok_or(Fault::Overflow(format!("Number div: {} overflowed", stringify!($type))))?;
+                                    drop(arg_mut);
+                                    context.pop();
+                                    return Ok(Some(original_arg));
+                                }
+                                None => object.data = $crate::object::primitive::Arithmetic::checked_wide(object.data, $crate::object::primitive::NumberOperation::Div, arg.data as i128).ok_or(Fault::Overflow(format!("Number div: {} overflowed", stringify!($type))))?,
+                            }
+                        } else if let Some(arg) = arg_mut.downcast_mut::<$crate::object::primitive::PrimitiveObject<i8>>() {
                             if arg.data.is_zero() {
                                 return Err(Fault::DivideByZero);
                             }
-                            arg.data = object.data as i8 / arg.data;
-                            drop(arg_mut);
-                            context.pop();
-                            return Ok(Some(original_arg));
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<u64>>() {
+                            match $crate::object::primitive::into_argument(object.data, $crate::object::primitive::NumberOperation::Div, arg.data) {
+                                Some(result) => {
+                                    arg.data = result.ok_or(Fault::Overflow(format!("Number div: {} overflowed", stringify!($type))))?;
+                                    drop(arg_mut);
+                                    context.pop();
+                                    return Ok(Some(original_arg));
+                                }
+                                None => object.data = $crate::object::primitive::Arithmetic::checked_wide(object.data, $crate::object::primitive::NumberOperation::Div, arg.data as i128).ok_or(Fault::Overflow(format!("Number div: {} overflowed", stringify!($type))))?,
+                            }
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<u64>>() {
                             if arg.data.is_zero() {
                                 return Err(Fault::DivideByZero);
                             }
-                            object.data /= arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<u32>>() {
+                            object.data = $crate::object::primitive::Arithmetic::checked_wide(object.data, $crate::object::primitive::NumberOperation::Div, arg.data as i128).ok_or(Fault::Overflow(format!("Number div: {} overflowed", stringify!($type))))?;
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<u32>>() {
                             if arg.data.is_zero() {
                                 return Err(Fault::DivideByZero);
                             }
-                            object.data /= arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<u16>>() {
+                            object.data = $crate::object::primitive::Arithmetic::checked_wide(object.data, $crate::object::primitive::NumberOperation::Div, arg.data as i128).ok_or(Fault::Overflow(format!("Number div: {} overflowed", stringify!($type))))?;
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<u16>>() {
                             if arg.data.is_zero() {
                                 return Err(Fault::DivideByZero);
                             }
-                            object.data /= arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<u8>>() {
+                            object.data = $crate::object::primitive::Arithmetic::checked_wide(object.data, $crate::object::primitive::NumberOperation::Div, arg.data as i128).ok_or(Fault::Overflow(format!("Number div: {} overflowed", stringify!($type))))?;
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<u8>>() {
                             if arg.data.is_zero() {
                                 return Err(Fault::DivideByZero);
                             }
-                            object.data /= arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_mut::<crate::object::primitive::PrimitiveObject<f64>>() {
+                            object.data = $crate::object::primitive::Arithmetic::checked_wide(object.data, $crate::object::primitive::NumberOperation::Div, arg.data as i128).ok_or(Fault::Overflow(format!("Number div: {} overflowed", stringify!($type))))?;
+                        } else if let Some(arg) = arg_mut.downcast_mut::<$crate::object::primitive::PrimitiveObject<f64>>() {
                             if arg.data.is_zero() {
                                 return Err(Fault::DivideByZero);
                             }
@@ -372,7 +596,7 @@ macro_rules! create_type_ops {
                             drop(arg_mut);
                             context.pop();
                             return Ok(Some(original_arg));
-                        } else if let Some(arg) = arg_mut.downcast_mut::<crate::object::primitive::PrimitiveObject<f32>>() {
+                        } else if let Some(arg) = arg_mut.downcast_mut::<$crate::object::primitive::PrimitiveObject<f32>>() {
                             if arg.data.is_zero() {
                                 return Err(Fault::DivideByZero);
                             }
@@ -380,6 +604,16 @@ macro_rules! create_type_ops {
                             drop(arg_mut);
                             context.pop();
                             return Ok(Some(original_arg));
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::bigint::BigIntObject>() {
+                            let result = $crate::object::primitive::bigint::Promote::promote(object.data, $crate::object::primitive::NumberOperation::Div, &arg.value)?;
+                            drop(arg_mut);
+                            context.pop();
+                            return Ok(Some(result));
+                        } else if let Some(arg) = $crate::object::primitive::exact::Exact::from_fraction(&*arg_mut) {
+                            let result = $crate::object::primitive::exact::Exact::from(object.data).combine($crate::object::primitive::NumberOperation::Div, arg)?;
+                            drop(arg_mut);
+                            context.pop();
+                            return Ok(Some(result.into_object()));
                         } else {
                             return Err(Fault::InvalidType(format!("Number div: Not a number")));
                         }
@@ -392,55 +626,55 @@ macro_rules! create_type_ops {
             Ok(None)
         }
 
-        fn $mod_name(object: ObjectBox, context: &mut crate::object::ContextData) -> Result<Option<ObjectBox>, Fault> {
+        fn $mod_name(object: ObjectBox, context: &mut $crate::object::ContextData) -> Result<Option<ObjectBox>, Fault> {
             {
                 let mut object = object.borrow_mut();
-                let object = object.downcast_mut::<crate::object::primitive::PrimitiveObject<$type>>();
+                let object = object.downcast_mut::<$crate::object::primitive::PrimitiveObject<$type>>();
                 let original_arg = context.arguments[0].clone();
                 let mut arg_mut = original_arg.borrow_mut();
                 match object {
                     Some(object) => {
-                        if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<i64>>() {
+                        if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<i64>>() {
                             if arg.data.is_zero() {
                                 return Err(Fault::DivideByZero);
                             }
-                            object.data = (object.data as i64 % arg.data) as $type;
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<i32>>() {
+                            object.data = $crate::object::primitive::Arithmetic::checked_wide(object.data, $crate::object::primitive::NumberOperation::Mod, arg.data as i128).ok_or(Fault::Overflow(format!("Number mod: {} overflowed", stringify!($type))))?;
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<i32>>() {
                             if arg.data.is_zero() {
                                 return Err(Fault::DivideByZero);
                             }
-                            object.data = (object.data as i32 % arg.data) as $type;
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<i16>>() {
+                            object.data = $crate::object::primitive::Arithmetic::checked_wide(object.data, $crate::object::primitive::NumberOperation::Mod, arg.data as i128).ok_or(Fault::Overflow(format!("Number mod: {} overflowed", stringify!($type))))?;
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<i16>>() {
                             if arg.data.is_zero() {
                                 return Err(Fault::DivideByZero);
                             }
-                            object.data = (object.data as i16 % arg.data) as $type;
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<i8>>() {
+                            object.data = $crate::object::primitive::Arithmetic::checked_wide(object.data, $crate::object::primitive::NumberOperation::Mod, arg.data as i128).ok_or(Fault::Overflow(format!("Number mod: {} overflowed", stringify!($type))))?;
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<i8>>() {
                             if arg.data.is_zero() {
                                 return Err(Fault::DivideByZero);
                             }
-                            object.data = (object.data as i8 % arg.data) as $type;
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<u64>>() {
+                            object.data = $crate::object::primitive::Arithmetic::checked_wide(object.data, $crate::object::primitive::NumberOperation::Mod, arg.data as i128).ok_or(Fault::Overflow(format!("Number mod: {} overflowed", stringify!($type))))?;
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<u64>>() {
                             if arg.data.is_zero() {
                                 return Err(Fault::DivideByZero);
                             }
-                            object.data %= arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<u32>>() {
+                            object.data = $crate::object::primitive::Arithmetic::checked_wide(object.data, $crate::object::primitive::NumberOperation::Mod, arg.data as i128).ok_or(Fault::Overflow(format!("Number mod: {} overflowed", stringify!($type))))?;
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<u32>>() {
                             if arg.data.is_zero() {
                                 return Err(Fault::DivideByZero);
                             }
-                            object.data %= arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<u16>>() {
+                            object.data = $crate::object::primitive::Arithmetic::checked_wide(object.data, $crate::object::primitive::NumberOperation::Mod, arg.data as i128).ok_or(Fault::Overflow(format!("Number mod: {} overflowed", stringify!($type))))?;
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<u16>>() {
                             if arg.data.is_zero() {
                                 return Err(Fault::DivideByZero);
                             }
-                            object.data %= arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<u8>>() {
+                            object.data = $crate::object::primitive::Arithmetic::checked_wide(object.data, $crate::object::primitive::NumberOperation::Mod, arg.data as i128).ok_or(Fault::Overflow(format!("Number mod: {} overflowed", stringify!($type))))?;
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<u8>>() {
                             if arg.data.is_zero() {
                                 return Err(Fault::DivideByZero);
                             }
-                            object.data %= arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_mut::<crate::object::primitive::PrimitiveObject<f64>>() {
+                            object.data = $crate::object::primitive::Arithmetic::checked_wide(object.data, $crate::object::primitive::NumberOperation::Mod, arg.data as i128).ok_or(Fault::Overflow(format!("Number mod: {} overflowed", stringify!($type))))?;
+                        } else if let Some(arg) = arg_mut.downcast_mut::<$crate::object::primitive::PrimitiveObject<f64>>() {
                             if arg.data.is_zero() {
                                 return Err(Fault::DivideByZero);
                             }
@@ -448,7 +682,7 @@ macro_rules! create_type_ops {
                             drop(arg_mut);
                             context.pop();
                             return Ok(Some(original_arg));
-                        } else if let Some(arg) = arg_mut.downcast_mut::<crate::object::primitive::PrimitiveObject<f32>>() {
+                        } else if let Some(arg) = arg_mut.downcast_mut::<$crate::object::primitive::PrimitiveObject<f32>>() {
                             if arg.data.is_zero() {
                                 return Err(Fault::DivideByZero);
                             }
@@ -456,6 +690,16 @@ macro_rules! create_type_ops {
                             drop(arg_mut);
                             context.pop();
                             return Ok(Some(original_arg));
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::bigint::BigIntObject>() {
+                            let result = $crate::object::primitive::bigint::Promote::promote(object.data, $crate::object::primitive::NumberOperation::Mod, &arg.value)?;
+                            drop(arg_mut);
+                            context.pop();
+                            return Ok(Some(result));
+                        } else if let Some(arg) = $crate::object::primitive::exact::Exact::from_fraction(&*arg_mut) {
+                            let result = $crate::object::primitive::exact::Exact::from(object.data).combine($crate::object::primitive::NumberOperation::Mod, arg)?;
+                            drop(arg_mut);
+                            context.pop();
+                            return Ok(Some(result.into_object()));
                         } else {
                             return Err(Fault::InvalidType(format!("Number mod: Not a number")));
                         }
@@ -468,50 +712,60 @@ macro_rules! create_type_ops {
             Ok(None)
         }
         
-        fn $abs(object: ObjectBox, _: &mut crate::object::ContextData) -> Result<Option<ObjectBox>, Fault> {
+        fn $abs(object: ObjectBox, _: &mut $crate::object::ContextData) -> Result<Option<ObjectBox>, Fault> {
             let mut object = object.borrow_mut();
-            if let Some(object) = object.downcast_mut::<crate::object::primitive::PrimitiveObject<$type>>() {
-                object.data = object.data.abs();
+            if let Some(object) = object.downcast_mut::<$crate::object::primitive::PrimitiveObject<$type>>() {
+                object.data = $crate::object::primitive::Arithmetic::checked_abs(object.data).ok_or(Fault::Overflow(format!("Number abs: {} overflowed", stringify!($type))))?;
             } else {
                 return Err(Fault::InvalidType(format!("Number abs: expected {}", stringify!($type))))
             }
             Ok(None)
         }
 
-        fn $pow(object: ObjectBox, context: &mut crate::object::ContextData) -> Result<Option<ObjectBox>, Fault> {
+        fn $pow(object: ObjectBox, context: &mut $crate::object::ContextData) -> Result<Option<ObjectBox>, Fault> {
             {
                 let mut object = object.borrow_mut();
-                let object = object.downcast_mut::<crate::object::primitive::PrimitiveObject<$type>>();
+                let object = object.downcast_mut::<$crate::object::primitive::PrimitiveObject<$type>>();
                 let original_arg = context.arguments[0].clone();
                 let mut arg_mut = original_arg.borrow_mut();
                 match object {
                     Some(object) => {
-                        if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<i64>>() {
-                            object.data = object.data.pow(arg.data as u32);
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<i32>>() {
-                            object.data = object.data.pow(arg.data as u32);
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<i16>>() {
-                            object.data = object.data.pow(arg.data as u32);
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<i8>>() {
-                            object.data = object.data.pow(arg.data as u32);
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<u64>>() {
-                            object.data = object.data.pow(arg.data as u32);
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<u32>>() {
-                            object.data = object.data.pow(arg.data as u32);
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<u16>>() {
-                            object.data = object.data.pow(arg.data as u32);
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<u8>>() {
-                            object.data = object.data.pow(arg.data as u32);
-                        } else if let Some(arg) = arg_mut.downcast_mut::<crate::object::primitive::PrimitiveObject<f64>>() {
+                        if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<i64>>() {
+                            object.data = $crate::object::primitive::Arithmetic::checked_wide(object.data, $crate::object::primitive::NumberOperation::Pow, arg.data as i128).ok_or(Fault::Overflow(format!("Number pow: {} overflowed", stringify!($type))))?;
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<i32>>() {
+                            object.data = $crate::object::primitive::Arithmetic::checked_wide(object.data, $crate::object::primitive::NumberOperation::Pow, arg.data as i128).ok_or(Fault::Overflow(format!("Number pow: {} overflowed", stringify!($type))))?;
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<i16>>() {
+                            object.data = $crate::object::primitive::Arithmetic::checked_wide(object.data, $crate::object::primitive::NumberOperation::Pow, arg.data as i128).ok_or(Fault::Overflow(format!("Number pow: {} overflowed", stringify!($type))))?;
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<i8>>() {
+                            object.data = $crate::object::primitive::Arithmetic::checked_wide(object.data, $crate::object::primitive::NumberOperation::Pow, arg.data as i128).ok_or(Fault::Overflow(format!("Number pow: {} overflowed", stringify!($type))))?;
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<u64>>() {
+                            object.data = $crate::object::primitive::Arithmetic::checked_wide(object.data, $crate::object::primitive::NumberOperation::Pow, arg.data as i128).ok_or(Fault::Overflow(format!("Number pow: {} overflowed", stringify!($type))))?;
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<u32>>() {
+                            object.data = $crate::object::primitive::Arithmetic::checked_wide(object.data, $crate::object::primitive::NumberOperation::Pow, arg.data as i128).ok_or(Fault::Overflow(format!("Number pow: {} overflowed", stringify!($type))))?;
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<u16>>() {
+                            object.data = $crate::object::primitive::Arithmetic::checked_wide(object.data, $crate::object::primitive::NumberOperation::Pow, arg.data as i128).ok_or(Fault::Overflow(format!("Number pow: {} overflowed", stringify!($type))))?;
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<u8>>() {
+                            object.data = $crate::object::primitive::Arithmetic::checked_wide(object.data, $crate::object::primitive::NumberOperation::Pow, arg.data as i128).ok_or(Fault::Overflow(format!("Number pow: {} overflowed", stringify!($type))))?;
+                        } else if let Some(arg) = arg_mut.downcast_mut::<$crate::object::primitive::PrimitiveObject<f64>>() {
                             arg.data = (object.data as f64).powf(arg.data);
                             drop(arg_mut);
                             context.pop();
                             return Ok(Some(original_arg));
-                        } else if let Some(arg) = arg_mut.downcast_mut::<crate::object::primitive::PrimitiveObject<f32>>() {
+                        } else if let Some(arg) = arg_mut.downcast_mut::<$crate::object::primitive::PrimitiveObject<f32>>() {
                             arg.data = (object.data as f32).powf(arg.data);
                             drop(arg_mut);
                             context.pop();
                             return Ok(Some(original_arg));
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::bigint::BigIntObject>() {
+                            let result = $crate::object::primitive::bigint::Promote::promote(object.data, $crate::object::primitive::NumberOperation::Pow, &arg.value)?;
+                            drop(arg_mut);
+                            context.pop();
+                            return Ok(Some(result));
+                        } else if let Some(arg) = $crate::object::primitive::exact::Exact::from_fraction(&*arg_mut) {
+                            let result = $crate::object::primitive::exact::Exact::from(object.data).combine($crate::object::primitive::NumberOperation::Pow, arg)?;
+                            drop(arg_mut);
+                            context.pop();
+                            return Ok(Some(result.into_object()));
                         } else {
                             return Err(Fault::InvalidType(format!("Number pow: Not a number")));
                         }
@@ -524,13 +778,13 @@ macro_rules! create_type_ops {
             Ok(None)
         }
 
-        fn $is_zero(object: ObjectBox, _: &mut crate::object::ContextData) -> Result<Option<ObjectBox>, Fault> {
+        fn $is_zero(object: ObjectBox, _: &mut $crate::object::ContextData) -> Result<Option<ObjectBox>, Fault> {
             let object = object.borrow();
-            if let Some(object) = object.downcast_ref::<crate::object::primitive::PrimitiveObject<$type>>() {
+            if let Some(object) = object.downcast_ref::<$crate::object::primitive::PrimitiveObject<$type>>() {
                 if object.data.is_zero() {
-                    return Ok(Some(crate::object::create_boolean(true)));
+                    return Ok(Some($crate::object::create_boolean(true)));
                 } else {
-                    return Ok(Some(crate::object::create_boolean(false)));
+                    return Ok(Some($crate::object::create_boolean(false)));
                 }
             } else {
                 return Err(Fault::InvalidType(format!("Number is_zero: expected {}", stringify!($type))))
@@ -539,40 +793,50 @@ macro_rules! create_type_ops {
     };
     (f32, $add_name:ident, $sub_name:ident, $mul_name:ident, $div_name:ident, $mod_name:ident, $abs:ident, $pow:ident, $is_zero:ident) => {
 
-        fn $add_name(object: ObjectBox, context: &mut crate::object::ContextData) -> Result<Option<ObjectBox>, Fault> {
+        fn $add_name(object: ObjectBox, context: &mut $crate::object::ContextData) -> Result<Option<ObjectBox>, Fault> {
             {
                 let mut object = object.borrow_mut();
-                let object = object.downcast_mut::<crate::object::primitive::PrimitiveObject<$type>>();
+                let object = object.downcast_mut::<$crate::object::primitive::PrimitiveObject<$type>>();
                 let original_arg = context.arguments[0].clone();
                 let mut arg_mut = original_arg.borrow_mut();
                 match object {
                     Some(object) => {
-                        if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<i64>>() {
+                        if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<i64>>() {
                             object.data += arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<i32>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<i32>>() {
                             object.data += arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<i16>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<i16>>() {
                             object.data += arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<i8>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<i8>>() {
                             object.data += arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<u64>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<u64>>() {
                             object.data += arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<u32>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<u32>>() {
                             object.data += arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<u16>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<u16>>() {
                             object.data += arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<u8>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<u8>>() {
                             object.data += arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_mut::<crate::object::primitive::PrimitiveObject<f64>>() {
+                        } else if let Some(arg) = arg_mut.downcast_mut::<$crate::object::primitive::PrimitiveObject<f64>>() {
                             arg.data += object.data as f64;
                             drop(arg_mut);
                             context.pop();
                             return Ok(Some(original_arg));
-                        } else if let Some(arg) = arg_mut.downcast_mut::<crate::object::primitive::PrimitiveObject<f32>>() {
+                        } else if let Some(arg) = arg_mut.downcast_mut::<$crate::object::primitive::PrimitiveObject<f32>>() {
                             arg.data += object.data as f32;
                             drop(arg_mut);
                             context.pop();
                             return Ok(Some(original_arg));
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::bigint::BigIntObject>() {
+                            let result = $crate::object::primitive::bigint::Promote::promote(object.data, $crate::object::primitive::NumberOperation::Add, &arg.value)?;
+                            drop(arg_mut);
+                            context.pop();
+                            return Ok(Some(result));
+                        } else if let Some(arg) = $crate::object::primitive::exact::Exact::from_fraction(&*arg_mut) {
+                            let result = $crate::object::primitive::exact::Exact::from(object.data).combine($crate::object::primitive::NumberOperation::Add, arg)?;
+                            drop(arg_mut);
+                            context.pop();
+                            return Ok(Some(result.into_object()));
                         } else {
                             return Err(Fault::InvalidType(format!("Number add: Not a number")));
                         }
@@ -585,40 +849,50 @@ macro_rules! create_type_ops {
             Ok(None)
         }
 
-        fn $sub_name(object: ObjectBox, context: &mut crate::object::ContextData) -> Result<Option<ObjectBox>, Fault> {
+        fn $sub_name(object: ObjectBox, context: &mut $crate::object::ContextData) -> Result<Option<ObjectBox>, Fault> {
             {
                 let mut object = object.borrow_mut();
-                let object = object.downcast_mut::<crate::object::primitive::PrimitiveObject<$type>>();
+                let object = object.downcast_mut::<$crate::object::primitive::PrimitiveObject<$type>>();
                 let original_arg = context.arguments[0].clone();
                 let mut arg_mut = original_arg.borrow_mut();
                 match object {
                     Some(object) => {
-                        if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<i64>>() {
+                        if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<i64>>() {
                             object.data -= arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<i32>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<i32>>() {
                             object.data -= arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<i16>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<i16>>() {
                             object.data -= arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<i8>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<i8>>() {
                             object.data -= arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<u64>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<u64>>() {
                             object.data -= arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<u32>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<u32>>() {
                             object.data -= arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<u16>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<u16>>() {
                             object.data -= arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<u8>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<u8>>() {
                             object.data -= arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_mut::<crate::object::primitive::PrimitiveObject<f64>>() {
+                        } else if let Some(arg) = arg_mut.downcast_mut::<$crate::object::primitive::PrimitiveObject<f64>>() {
                             arg.data -= object.data as f64;
                             drop(arg_mut);
                             context.pop();
                             return Ok(Some(original_arg));
-                        } else if let Some(arg) = arg_mut.downcast_mut::<crate::object::primitive::PrimitiveObject<f32>>() {
+                        } else if let Some(arg) = arg_mut.downcast_mut::<$crate::object::primitive::PrimitiveObject<f32>>() {
                             arg.data -= object.data as f32;
                             drop(arg_mut);
                             context.pop();
                             return Ok(Some(original_arg));
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::bigint::BigIntObject>() {
+                            let result = $crate::object::primitive::bigint::Promote::promote(object.data, $crate::object::primitive::NumberOperation::Sub, &arg.value)?;
+                            drop(arg_mut);
+                            context.pop();
+                            return Ok(Some(result));
+                        } else if let Some(arg) = $crate::object::primitive::exact::Exact::from_fraction(&*arg_mut) {
+                            let result = $crate::object::primitive::exact::Exact::from(object.data).combine($crate::object::primitive::NumberOperation::Sub, arg)?;
+                            drop(arg_mut);
+                            context.pop();
+                            return Ok(Some(result.into_object()));
                         } else {
                             return Err(Fault::InvalidType(format!("Number sub: Not a number")));
                         }
@@ -632,40 +906,50 @@ macro_rules! create_type_ops {
         }
         
 
-        fn $mul_name(object: ObjectBox, context: &mut crate::object::ContextData) -> Result<Option<ObjectBox>, Fault> {
+        fn $mul_name(object: ObjectBox, context: &mut $crate::object::ContextData) -> Result<Option<ObjectBox>, Fault> {
             {
                 let mut object = object.borrow_mut();
-                let object = object.downcast_mut::<crate::object::primitive::PrimitiveObject<$type>>();
+                let object = object.downcast_mut::<$crate::object::primitive::PrimitiveObject<$type>>();
                 let original_arg = context.arguments[0].clone();
                 let mut arg_mut = original_arg.borrow_mut();
                 match object {
                     Some(object) => {
-                        if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<i64>>() {
+                        if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<i64>>() {
                             object.data *= arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<i32>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<i32>>() {
                             object.data *= arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<i16>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<i16>>() {
                             object.data *= arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<i8>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<i8>>() {
                             object.data *= arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<u64>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<u64>>() {
                             object.data *= arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<u32>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<u32>>() {
                             object.data *= arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<u16>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<u16>>() {
                             object.data *= arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<u8>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<u8>>() {
                             object.data *= arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_mut::<crate::object::primitive::PrimitiveObject<f64>>() {
+                        } else if let Some(arg) = arg_mut.downcast_mut::<$crate::object::primitive::PrimitiveObject<f64>>() {
                             arg.data *= object.data as f64;
                             drop(arg_mut);
                             context.pop();
                             return Ok(Some(original_arg));
-                        } else if let Some(arg) = arg_mut.downcast_mut::<crate::object::primitive::PrimitiveObject<f32>>() {
+                        } else if let Some(arg) = arg_mut.downcast_mut::<$crate::object::primitive::PrimitiveObject<f32>>() {
                             arg.data *= object.data as f32;
                             drop(arg_mut);
                             context.pop();
                             return Ok(Some(original_arg));
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::bigint::BigIntObject>() {
+                            let result = $crate::object::primitive::bigint::Promote::promote(object.data, $crate::object::primitive::NumberOperation::Mul, &arg.value)?;
+                            drop(arg_mut);
+                            context.pop();
+                            return Ok(Some(result));
+                        } else if let Some(arg) = $crate::object::primitive::exact::Exact::from_fraction(&*arg_mut) {
+                            let result = $crate::object::primitive::exact::Exact::from(object.data).combine($crate::object::primitive::NumberOperation::Mul, arg)?;
+                            drop(arg_mut);
+                            context.pop();
+                            return Ok(Some(result.into_object()));
                         } else {
                             return Err(Fault::InvalidType(format!("Number mul: Not a number")));
                         }
@@ -678,55 +962,55 @@ macro_rules! create_type_ops {
             Ok(None)
         }
 
-        fn $div_name(object: ObjectBox, context: &mut crate::object::ContextData) -> Result<Option<ObjectBox>, Fault> {
+        fn $div_name(object: ObjectBox, context: &mut $crate::object::ContextData) -> Result<Option<ObjectBox>, Fault> {
             {
                 let mut object = object.borrow_mut();
-                let object = object.downcast_mut::<crate::object::primitive::PrimitiveObject<$type>>();
+                let object = object.downcast_mut::<$crate::object::primitive::PrimitiveObject<$type>>();
                 let original_arg = context.arguments[0].clone();
                 let mut arg_mut = original_arg.borrow_mut();
                 match object {
                     Some(object) => {
-                        if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<i64>>() {
+                        if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<i64>>() {
                             if arg.data.is_zero() {
                                 return Err(Fault::DivideByZero);
                             }
                             object.data /= arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<i32>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<i32>>() {
                             if arg.data.is_zero() {
                                 return Err(Fault::DivideByZero);
                             }
                             object.data /= arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<i16>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<i16>>() {
                             if arg.data.is_zero() {
                                 return Err(Fault::DivideByZero);
                             }
                             object.data /= arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<i8>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<i8>>() {
                             if arg.data.is_zero() {
                                 return Err(Fault::DivideByZero);
                             }
                             object.data /= arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<u64>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<u64>>() {
                             if arg.data.is_zero() {
                                 return Err(Fault::DivideByZero);
                             }
                             object.data /= arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<u32>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<u32>>() {
                             if arg.data.is_zero() {
                                 return Err(Fault::DivideByZero);
                             }
                             object.data /= arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<u16>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<u16>>() {
                             if arg.data.is_zero() {
                                 return Err(Fault::DivideByZero);
                             }
                             object.data /= arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<u8>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<u8>>() {
                             if arg.data.is_zero() {
                                 return Err(Fault::DivideByZero);
                             }
                             object.data /= arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_mut::<crate::object::primitive::PrimitiveObject<f64>>() {
+                        } else if let Some(arg) = arg_mut.downcast_mut::<$crate::object::primitive::PrimitiveObject<f64>>() {
                             if arg.data.is_zero() {
                                 return Err(Fault::DivideByZero);
                             }
@@ -734,7 +1018,7 @@ macro_rules! create_type_ops {
                             drop(arg_mut);
                             context.pop();
                             return Ok(Some(original_arg));
-                        } else if let Some(arg) = arg_mut.downcast_mut::<crate::object::primitive::PrimitiveObject<f32>>() {
+                        } else if let Some(arg) = arg_mut.downcast_mut::<$crate::object::primitive::PrimitiveObject<f32>>() {
                             if arg.data.is_zero() {
                                 return Err(Fault::DivideByZero);
                             }
@@ -742,6 +1026,16 @@ macro_rules! create_type_ops {
                             drop(arg_mut);
                             context.pop();
                             return Ok(Some(original_arg));
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::bigint::BigIntObject>() {
+                            let result = $crate::object::primitive::bigint::Promote::promote(object.data, $crate::object::primitive::NumberOperation::Div, &arg.value)?;
+                            drop(arg_mut);
+                            context.pop();
+                            return Ok(Some(result));
+                        } else if let Some(arg) = $crate::object::primitive::exact::Exact::from_fraction(&*arg_mut) {
+                            let result = $crate::object::primitive::exact::Exact::from(object.data).combine($crate::object::primitive::NumberOperation::Div, arg)?;
+                            drop(arg_mut);
+                            context.pop();
+                            return Ok(Some(result.into_object()));
                         } else {
                             return Err(Fault::InvalidType(format!("Number div: Not a number")));
                         }
@@ -754,55 +1048,55 @@ macro_rules! create_type_ops {
             Ok(None)
         }
 
-        fn $mod_name(object: ObjectBox, context: &mut crate::object::ContextData) -> Result<Option<ObjectBox>, Fault> {
+        fn $mod_name(object: ObjectBox, context: &mut $crate::object::ContextData) -> Result<Option<ObjectBox>, Fault> {
             {
                 let mut object = object.borrow_mut();
-                let object = object.downcast_mut::<crate::object::primitive::PrimitiveObject<$type>>();
+                let object = object.downcast_mut::<$crate::object::primitive::PrimitiveObject<$type>>();
                 let original_arg = context.arguments[0].clone();
                 let mut arg_mut = original_arg.borrow_mut();
                 match object {
                     Some(object) => {
-                        if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<i64>>() {
+                        if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<i64>>() {
                             if arg.data.is_zero() {
                                 return Err(Fault::DivideByZero);
                             }
                             object.data %= arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<i32>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<i32>>() {
                             if arg.data.is_zero() {
                                 return Err(Fault::DivideByZero);
                             }
                             object.data %= arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<i16>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<i16>>() {
                             if arg.data.is_zero() {
                                 return Err(Fault::DivideByZero);
                             }
                             object.data %= arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<i8>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<i8>>() {
                             if arg.data.is_zero() {
                                 return Err(Fault::DivideByZero);
                             }
                             object.data %= arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<u64>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<u64>>() {
                             if arg.data.is_zero() {
                                 return Err(Fault::DivideByZero);
                             }
                             object.data %= arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<u32>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<u32>>() {
                             if arg.data.is_zero() {
                                 return Err(Fault::DivideByZero);
                             }
                             object.data %= arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<u16>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<u16>>() {
                             if arg.data.is_zero() {
                                 return Err(Fault::DivideByZero);
                             }
                             object.data %= arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<u8>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<u8>>() {
                             if arg.data.is_zero() {
                                 return Err(Fault::DivideByZero);
                             }
                             object.data %= arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_mut::<crate::object::primitive::PrimitiveObject<f64>>() {
+                        } else if let Some(arg) = arg_mut.downcast_mut::<$crate::object::primitive::PrimitiveObject<f64>>() {
                             if arg.data.is_zero() {
                                 return Err(Fault::DivideByZero);
                             }
@@ -810,7 +1104,7 @@ macro_rules! create_type_ops {
                             drop(arg_mut);
                             context.pop();
                             return Ok(Some(original_arg));
-                        } else if let Some(arg) = arg_mut.downcast_mut::<crate::object::primitive::PrimitiveObject<f32>>() {
+                        } else if let Some(arg) = arg_mut.downcast_mut::<$crate::object::primitive::PrimitiveObject<f32>>() {
                             if arg.data.is_zero() {
                                 return Err(Fault::DivideByZero);
                             }
@@ -818,6 +1112,16 @@ macro_rules! create_type_ops {
                             drop(arg_mut);
                             context.pop();
                             return Ok(Some(original_arg));
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::bigint::BigIntObject>() {
+                            let result = $crate::object::primitive::bigint::Promote::promote(object.data, $crate::object::primitive::NumberOperation::Mod, &arg.value)?;
+                            drop(arg_mut);
+                            context.pop();
+                            return Ok(Some(result));
+                        } else if let Some(arg) = $crate::object::primitive::exact::Exact::from_fraction(&*arg_mut) {
+                            let result = $crate::object::primitive::exact::Exact::from(object.data).combine($crate::object::primitive::NumberOperation::Mod, arg)?;
+                            drop(arg_mut);
+                            context.pop();
+                            return Ok(Some(result.into_object()));
                         } else {
                             return Err(Fault::InvalidType(format!("Number mod: Not a number")));
                         }
@@ -830,9 +1134,9 @@ macro_rules! create_type_ops {
             Ok(None)
         }
         
-        fn $abs(object: ObjectBox, _: &mut crate::object::ContextData) -> Result<Option<ObjectBox>, Fault> {
+        fn $abs(object: ObjectBox, _: &mut $crate::object::ContextData) -> Result<Option<ObjectBox>, Fault> {
             let mut object = object.borrow_mut();
-            if let Some(object) = object.downcast_mut::<crate::object::primitive::PrimitiveObject<$type>>() {
+            if let Some(object) = object.downcast_mut::<$crate::object::primitive::PrimitiveObject<$type>>() {
                 object.data = object.data.abs();
             } else {
                 return Err(Fault::InvalidType(format!("Number abs: expected {}", stringify!($type))))
@@ -840,40 +1144,50 @@ macro_rules! create_type_ops {
             Ok(None)
         }
 
-        fn $pow(object: ObjectBox, context: &mut crate::object::ContextData) -> Result<Option<ObjectBox>, Fault> {
+        fn $pow(object: ObjectBox, context: &mut $crate::object::ContextData) -> Result<Option<ObjectBox>, Fault> {
             {
                 let mut object = object.borrow_mut();
-                let object = object.downcast_mut::<crate::object::primitive::PrimitiveObject<$type>>();
+                let object = object.downcast_mut::<$crate::object::primitive::PrimitiveObject<$type>>();
                 let original_arg = context.arguments[0].clone();
                 let mut arg_mut = original_arg.borrow_mut();
                 match object {
                     Some(object) => {
-                        if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<i64>>() {
+                        if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<i64>>() {
                             object.data = object.data.pow(arg.data as u32);
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<i32>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<i32>>() {
                             object.data = object.data.pow(arg.data as u32);
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<i16>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<i16>>() {
                             object.data = object.data.pow(arg.data as u32);
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<i8>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<i8>>() {
                             object.data = object.data.pow(arg.data as u32);
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<u64>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<u64>>() {
                             object.data = object.data.pow(arg.data as u32);
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<u32>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<u32>>() {
                             object.data = object.data.pow(arg.data as u32);
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<u16>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<u16>>() {
                             object.data = object.data.pow(arg.data as u32);
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<u8>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<u8>>() {
                             object.data = object.data.pow(arg.data as u32);
-                        } else if let Some(arg) = arg_mut.downcast_mut::<crate::object::primitive::PrimitiveObject<f64>>() {
+                        } else if let Some(arg) = arg_mut.downcast_mut::<$crate::object::primitive::PrimitiveObject<f64>>() {
                             arg.data = (object.data as f64).powf(arg.data);
                             drop(arg_mut);
                             context.pop();
                             return Ok(Some(original_arg));
-                        } else if let Some(arg) = arg_mut.downcast_mut::<crate::object::primitive::PrimitiveObject<f32>>() {
+                        } else if let Some(arg) = arg_mut.downcast_mut::<$crate::object::primitive::PrimitiveObject<f32>>() {
                             arg.data = (object.data as f32).powf(arg.data);
                             drop(arg_mut);
                             context.pop();
                             return Ok(Some(original_arg));
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::bigint::BigIntObject>() {
+                            let result = $crate::object::primitive::bigint::Promote::promote(object.data, $crate::object::primitive::NumberOperation::Pow, &arg.value)?;
+                            drop(arg_mut);
+                            context.pop();
+                            return Ok(Some(result));
+                        } else if let Some(arg) = $crate::object::primitive::exact::Exact::from_fraction(&*arg_mut) {
+                            let result = $crate::object::primitive::exact::Exact::from(object.data).combine($crate::object::primitive::NumberOperation::Pow, arg)?;
+                            drop(arg_mut);
+                            context.pop();
+                            return Ok(Some(result.into_object()));
                         } else {
                             return Err(Fault::InvalidType(format!("Number pow: Not a number")));
                         }
@@ -886,13 +1200,13 @@ macro_rules! create_type_ops {
             Ok(None)
         }
 
-        fn $is_zero(object: ObjectBox, _: &mut crate::object::ContextData) -> Result<Option<ObjectBox>, Fault> {
+        fn $is_zero(object: ObjectBox, _: &mut $crate::object::ContextData) -> Result<Option<ObjectBox>, Fault> {
             let object = object.borrow();
-            if let Some(object) = object.downcast_ref::<crate::object::primitive::PrimitiveObject<$type>>() {
+            if let Some(object) = object.downcast_ref::<$crate::object::primitive::PrimitiveObject<$type>>() {
                 if object.data.is_zero() {
-                    return Ok(Some(crate::object::create_boolean(true)));
+                    return Ok(Some($crate::object::create_boolean(true)));
                 } else {
-                    return Ok(Some(crate::object::create_boolean(false)));
+                    return Ok(Some($crate::object::create_boolean(false)));
                 }
             } else {
                 return Err(Fault::InvalidType(format!("Number is_zero: expected {}", stringify!($type))))
@@ -901,40 +1215,50 @@ macro_rules! create_type_ops {
     };
     (f64, $add_name:ident, $sub_name:ident, $mul_name:ident, $div_name:ident, $mod_name:ident, $abs:ident, $pow:ident, $is_zero:ident) => {
 
-        fn $add_name(object: ObjectBox, context: &mut crate::object::ContextData) -> Result<Option<ObjectBox>, Fault> {
+        fn $add_name(object: ObjectBox, context: &mut $crate::object::ContextData) -> Result<Option<ObjectBox>, Fault> {
             {
                 let mut object = object.borrow_mut();
-                let object = object.downcast_mut::<crate::object::primitive::PrimitiveObject<$type>>();
+                let object = object.downcast_mut::<$crate::object::primitive::PrimitiveObject<$type>>();
                 let original_arg = context.arguments[0].clone();
                 let mut arg_mut = original_arg.borrow_mut();
                 match object {
                     Some(object) => {
-                        if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<i64>>() {
+                        if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<i64>>() {
                             object.data += arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<i32>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<i32>>() {
                             object.data += arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<i16>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<i16>>() {
                             object.data += arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<i8>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<i8>>() {
                             object.data += arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<u64>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<u64>>() {
                             object.data += arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<u32>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<u32>>() {
                             object.data += arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<u16>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<u16>>() {
                             object.data += arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<u8>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<u8>>() {
                             object.data += arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_mut::<crate::object::primitive::PrimitiveObject<f64>>() {
+                        } else if let Some(arg) = arg_mut.downcast_mut::<$crate::object::primitive::PrimitiveObject<f64>>() {
                             arg.data += object.data as f64;
                             drop(arg_mut);
                             context.pop();
                             return Ok(Some(original_arg));
-                        } else if let Some(arg) = arg_mut.downcast_mut::<crate::object::primitive::PrimitiveObject<f32>>() {
+                        } else if let Some(arg) = arg_mut.downcast_mut::<$crate::object::primitive::PrimitiveObject<f32>>() {
                             arg.data += object.data as f32;
                             drop(arg_mut);
                             context.pop();
                             return Ok(Some(original_arg));
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::bigint::BigIntObject>() {
+                            let result = $crate::object::primitive::bigint::Promote::promote(object.data, $crate::object::primitive::NumberOperation::Add, &arg.value)?;
+                            drop(arg_mut);
+                            context.pop();
+                            return Ok(Some(result));
+                        } else if let Some(arg) = $crate::object::primitive::exact::Exact::from_fraction(&*arg_mut) {
+                            let result = $crate::object::primitive::exact::Exact::from(object.data).combine($crate::object::primitive::NumberOperation::Add, arg)?;
+                            drop(arg_mut);
+                            context.pop();
+                            return Ok(Some(result.into_object()));
                         } else {
                             return Err(Fault::InvalidType(format!("Number add: Not a number")));
                         }
@@ -947,40 +1271,50 @@ macro_rules! create_type_ops {
             Ok(None)
         }
 
-        fn $sub_name(object: ObjectBox, context: &mut crate::object::ContextData) -> Result<Option<ObjectBox>, Fault> {
+        fn $sub_name(object: ObjectBox, context: &mut $crate::object::ContextData) -> Result<Option<ObjectBox>, Fault> {
             {
                 let mut object = object.borrow_mut();
-                let object = object.downcast_mut::<crate::object::primitive::PrimitiveObject<$type>>();
+                let object = object.downcast_mut::<$crate::object::primitive::PrimitiveObject<$type>>();
                 let original_arg = context.arguments[0].clone();
                 let mut arg_mut = original_arg.borrow_mut();
                 match object {
                     Some(object) => {
-                        if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<i64>>() {
+                        if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<i64>>() {
                             object.data -= arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<i32>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<i32>>() {
                             object.data -= arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<i16>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<i16>>() {
                             object.data -= arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<i8>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<i8>>() {
                             object.data -= arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<u64>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<u64>>() {
                             object.data -= arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<u32>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<u32>>() {
                             object.data -= arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<u16>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<u16>>() {
                             object.data -= arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<u8>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<u8>>() {
                             object.data -= arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_mut::<crate::object::primitive::PrimitiveObject<f64>>() {
+                        } else if let Some(arg) = arg_mut.downcast_mut::<$crate::object::primitive::PrimitiveObject<f64>>() {
                             arg.data -= object.data as f64;
                             drop(arg_mut);
                             context.pop();
                             return Ok(Some(original_arg));
-                        } else if let Some(arg) = arg_mut.downcast_mut::<crate::object::primitive::PrimitiveObject<f32>>() {
+                        } else if let Some(arg) = arg_mut.downcast_mut::<$crate::object::primitive::PrimitiveObject<f32>>() {
                             arg.data -= object.data as f32;
                             drop(arg_mut);
                             context.pop();
                             return Ok(Some(original_arg));
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::bigint::BigIntObject>() {
+                            let result = $crate::object::primitive::bigint::Promote::promote(object.data, $crate::object::primitive::NumberOperation::Sub, &arg.value)?;
+                            drop(arg_mut);
+                            context.pop();
+                            return Ok(Some(result));
+                        } else if let Some(arg) = $crate::object::primitive::exact::Exact::from_fraction(&*arg_mut) {
+                            let result = $crate::object::primitive::exact::Exact::from(object.data).combine($crate::object::primitive::NumberOperation::Sub, arg)?;
+                            drop(arg_mut);
+                            context.pop();
+                            return Ok(Some(result.into_object()));
                         } else {
                             return Err(Fault::InvalidType(format!("Number sub: Not a number")));
                         }
@@ -994,40 +1328,50 @@ macro_rules! create_type_ops {
         }
         
 
-        fn $mul_name(object: ObjectBox, context: &mut crate::object::ContextData) -> Result<Option<ObjectBox>, Fault> {
+        fn $mul_name(object: ObjectBox, context: &mut $crate::object::ContextData) -> Result<Option<ObjectBox>, Fault> {
             {
                 let mut object = object.borrow_mut();
-                let object = object.downcast_mut::<crate::object::primitive::PrimitiveObject<$type>>();
+                let object = object.downcast_mut::<$crate::object::primitive::PrimitiveObject<$type>>();
                 let original_arg = context.arguments[0].clone();
                 let mut arg_mut = original_arg.borrow_mut();
                 match object {
                     Some(object) => {
-                        if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<i64>>() {
+                        if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<i64>>() {
                             object.data *= arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<i32>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<i32>>() {
                             object.data *= arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<i16>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<i16>>() {
                             object.data *= arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<i8>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<i8>>() {
                             object.data *= arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<u64>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<u64>>() {
                             object.data *= arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<u32>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<u32>>() {
                             object.data *= arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<u16>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<u16>>() {
                             object.data *= arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<u8>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<u8>>() {
                             object.data *= arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_mut::<crate::object::primitive::PrimitiveObject<f64>>() {
+                        } else if let Some(arg) = arg_mut.downcast_mut::<$crate::object::primitive::PrimitiveObject<f64>>() {
                             arg.data *= object.data as f64;
                             drop(arg_mut);
                             context.pop();
                             return Ok(Some(original_arg));
-                        } else if let Some(arg) = arg_mut.downcast_mut::<crate::object::primitive::PrimitiveObject<f32>>() {
+                        } else if let Some(arg) = arg_mut.downcast_mut::<$crate::object::primitive::PrimitiveObject<f32>>() {
                             arg.data *= object.data as f32;
                             drop(arg_mut);
                             context.pop();
                             return Ok(Some(original_arg));
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::bigint::BigIntObject>() {
+                            let result = $crate::object::primitive::bigint::Promote::promote(object.data, $crate::object::primitive::NumberOperation::Mul, &arg.value)?;
+                            drop(arg_mut);
+                            context.pop();
+                            return Ok(Some(result));
+                        } else if let Some(arg) = $crate::object::primitive::exact::Exact::from_fraction(&*arg_mut) {
+                            let result = $crate::object::primitive::exact::Exact::from(object.data).combine($crate::object::primitive::NumberOperation::Mul, arg)?;
+                            drop(arg_mut);
+                            context.pop();
+                            return Ok(Some(result.into_object()));
                         } else {
                             return Err(Fault::InvalidType(format!("Number mul: Not a number")));
                         }
@@ -1040,55 +1384,55 @@ macro_rules! create_type_ops {
             Ok(None)
         }
 
-        fn $div_name(object: ObjectBox, context: &mut crate::object::ContextData) -> Result<Option<ObjectBox>, Fault> {
+        fn $div_name(object: ObjectBox, context: &mut $crate::object::ContextData) -> Result<Option<ObjectBox>, Fault> {
             {
                 let mut object = object.borrow_mut();
-                let object = object.downcast_mut::<crate::object::primitive::PrimitiveObject<$type>>();
+                let object = object.downcast_mut::<$crate::object::primitive::PrimitiveObject<$type>>();
                 let original_arg = context.arguments[0].clone();
                 let mut arg_mut = original_arg.borrow_mut();
                 match object {
                     Some(object) => {
-                        if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<i64>>() {
+                        if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<i64>>() {
                             if arg.data.is_zero() {
                                 return Err(Fault::DivideByZero);
                             }
                             object.data /= arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<i32>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<i32>>() {
                             if arg.data.is_zero() {
                                 return Err(Fault::DivideByZero);
                             }
                             object.data /= arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<i16>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<i16>>() {
                             if arg.data.is_zero() {
                                 return Err(Fault::DivideByZero);
                             }
                             object.data /= arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<i8>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<i8>>() {
                             if arg.data.is_zero() {
                                 return Err(Fault::DivideByZero);
                             }
                             object.data /= arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<u64>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<u64>>() {
                             if arg.data.is_zero() {
                                 return Err(Fault::DivideByZero);
                             }
                             object.data /= arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<u32>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<u32>>() {
                             if arg.data.is_zero() {
                                 return Err(Fault::DivideByZero);
                             }
                             object.data /= arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<u16>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<u16>>() {
                             if arg.data.is_zero() {
                                 return Err(Fault::DivideByZero);
                             }
                             object.data /= arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<u8>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<u8>>() {
                             if arg.data.is_zero() {
                                 return Err(Fault::DivideByZero);
                             }
                             object.data /= arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_mut::<crate::object::primitive::PrimitiveObject<f64>>() {
+                        } else if let Some(arg) = arg_mut.downcast_mut::<$crate::object::primitive::PrimitiveObject<f64>>() {
                             if arg.data.is_zero() {
                                 return Err(Fault::DivideByZero);
                             }
@@ -1096,7 +1440,7 @@ macro_rules! create_type_ops {
                             drop(arg_mut);
                             context.pop();
                             return Ok(Some(original_arg));
-                        } else if let Some(arg) = arg_mut.downcast_mut::<crate::object::primitive::PrimitiveObject<f32>>() {
+                        } else if let Some(arg) = arg_mut.downcast_mut::<$crate::object::primitive::PrimitiveObject<f32>>() {
                             if arg.data.is_zero() {
                                 return Err(Fault::DivideByZero);
                             }
@@ -1104,6 +1448,16 @@ macro_rules! create_type_ops {
                             drop(arg_mut);
                             context.pop();
                             return Ok(Some(original_arg));
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::bigint::BigIntObject>() {
+                            let result = $crate::object::primitive::bigint::Promote::promote(object.data, $crate::object::primitive::NumberOperation::Div, &arg.value)?;
+                            drop(arg_mut);
+                            context.pop();
+                            return Ok(Some(result));
+                        } else if let Some(arg) = $crate::object::primitive::exact::Exact::from_fraction(&*arg_mut) {
+                            let result = $crate::object::primitive::exact::Exact::from(object.data).combine($crate::object::primitive::NumberOperation::Div, arg)?;
+                            drop(arg_mut);
+                            context.pop();
+                            return Ok(Some(result.into_object()));
                         } else {
                             return Err(Fault::InvalidType(format!("Number div: Not a number")));
                         }
@@ -1116,55 +1470,55 @@ macro_rules! create_type_ops {
             Ok(None)
         }
 
-        fn $mod_name(object: ObjectBox, context: &mut crate::object::ContextData) -> Result<Option<ObjectBox>, Fault> {
+        fn $mod_name(object: ObjectBox, context: &mut $crate::object::ContextData) -> Result<Option<ObjectBox>, Fault> {
             {
                 let mut object = object.borrow_mut();
-                let object = object.downcast_mut::<crate::object::primitive::PrimitiveObject<$type>>();
+                let object = object.downcast_mut::<$crate::object::primitive::PrimitiveObject<$type>>();
                 let original_arg = context.arguments[0].clone();
                 let mut arg_mut = original_arg.borrow_mut();
                 match object {
                     Some(object) => {
-                        if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<i64>>() {
+                        if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<i64>>() {
                             if arg.data.is_zero() {
                                 return Err(Fault::DivideByZero);
                             }
                             object.data %= arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<i32>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<i32>>() {
                             if arg.data.is_zero() {
                                 return Err(Fault::DivideByZero);
                             }
                             object.data %= arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<i16>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<i16>>() {
                             if arg.data.is_zero() {
                                 return Err(Fault::DivideByZero);
                             }
                             object.data %= arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<i8>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<i8>>() {
                             if arg.data.is_zero() {
                                 return Err(Fault::DivideByZero);
                             }
                             object.data %= arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<u64>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<u64>>() {
                             if arg.data.is_zero() {
                                 return Err(Fault::DivideByZero);
                             }
                             object.data %= arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<u32>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<u32>>() {
                             if arg.data.is_zero() {
                                 return Err(Fault::DivideByZero);
                             }
                             object.data %= arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<u16>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<u16>>() {
                             if arg.data.is_zero() {
                                 return Err(Fault::DivideByZero);
                             }
                             object.data %= arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<u8>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<u8>>() {
                             if arg.data.is_zero() {
                                 return Err(Fault::DivideByZero);
                             }
                             object.data %= arg.data as $type;
-                        } else if let Some(arg) = arg_mut.downcast_mut::<crate::object::primitive::PrimitiveObject<f64>>() {
+                        } else if let Some(arg) = arg_mut.downcast_mut::<$crate::object::primitive::PrimitiveObject<f64>>() {
                             if arg.data.is_zero() {
                                 return Err(Fault::DivideByZero);
                             }
@@ -1172,7 +1526,7 @@ macro_rules! create_type_ops {
                             drop(arg_mut);
                             context.pop();
                             return Ok(Some(original_arg));
-                        } else if let Some(arg) = arg_mut.downcast_mut::<crate::object::primitive::PrimitiveObject<f32>>() {
+                        } else if let Some(arg) = arg_mut.downcast_mut::<$crate::object::primitive::PrimitiveObject<f32>>() {
                             if arg.data.is_zero() {
                                 return Err(Fault::DivideByZero);
                             }
@@ -1180,6 +1534,16 @@ macro_rules! create_type_ops {
                             drop(arg_mut);
                             context.pop();
                             return Ok(Some(original_arg));
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::bigint::BigIntObject>() {
+                            let result = $crate::object::primitive::bigint::Promote::promote(object.data, $crate::object::primitive::NumberOperation::Mod, &arg.value)?;
+                            drop(arg_mut);
+                            context.pop();
+                            return Ok(Some(result));
+                        } else if let Some(arg) = $crate::object::primitive::exact::Exact::from_fraction(&*arg_mut) {
+                            let result = $crate::object::primitive::exact::Exact::from(object.data).combine($crate::object::primitive::NumberOperation::Mod, arg)?;
+                            drop(arg_mut);
+                            context.pop();
+                            return Ok(Some(result.into_object()));
                         } else {
                             return Err(Fault::InvalidType(format!("Number mod: Not a number")));
                         }
@@ -1192,9 +1556,9 @@ macro_rules! create_type_ops {
             Ok(None)
         }
         
-        fn $abs(object: ObjectBox, _: &mut crate::object::ContextData) -> Result<Option<ObjectBox>, Fault> {
+        fn $abs(object: ObjectBox, _: &mut $crate::object::ContextData) -> Result<Option<ObjectBox>, Fault> {
             let mut object = object.borrow_mut();
-            if let Some(object) = object.downcast_mut::<crate::object::primitive::PrimitiveObject<$type>>() {
+            if let Some(object) = object.downcast_mut::<$crate::object::primitive::PrimitiveObject<$type>>() {
                 object.data = object.data.abs();
             } else {
                 return Err(Fault::InvalidType(format!("Number abs: expected {}", stringify!($type))))
@@ -1202,40 +1566,50 @@ macro_rules! create_type_ops {
             Ok(None)
         }
 
-        fn $pow(object: ObjectBox, context: &mut crate::object::ContextData) -> Result<Option<ObjectBox>, Fault> {
+        fn $pow(object: ObjectBox, context: &mut $crate::object::ContextData) -> Result<Option<ObjectBox>, Fault> {
             {
                 let mut object = object.borrow_mut();
-                let object = object.downcast_mut::<crate::object::primitive::PrimitiveObject<$type>>();
+                let object = object.downcast_mut::<$crate::object::primitive::PrimitiveObject<$type>>();
                 let original_arg = context.arguments[0].clone();
                 let mut arg_mut = original_arg.borrow_mut();
                 match object {
                     Some(object) => {
-                        if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<i64>>() {
+                        if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<i64>>() {
                             object.data = object.data.pow(arg.data as u32);
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<i32>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<i32>>() {
                             object.data = object.data.pow(arg.data as u32);
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<i16>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<i16>>() {
                             object.data = object.data.pow(arg.data as u32);
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<i8>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<i8>>() {
                             object.data = object.data.pow(arg.data as u32);
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<u64>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<u64>>() {
                             object.data = object.data.pow(arg.data as u32);
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<u32>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<u32>>() {
                             object.data = object.data.pow(arg.data as u32);
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<u16>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<u16>>() {
                             object.data = object.data.pow(arg.data as u32);
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<u8>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<u8>>() {
                             object.data = object.data.pow(arg.data as u32);
-                        } else if let Some(arg) = arg_mut.downcast_mut::<crate::object::primitive::PrimitiveObject<f64>>() {
+                        } else if let Some(arg) = arg_mut.downcast_mut::<$crate::object::primitive::PrimitiveObject<f64>>() {
                             arg.data = (object.data as f64).powf(arg.data);
                             drop(arg_mut);
                             context.pop();
                             return Ok(Some(original_arg));
-                        } else if let Some(arg) = arg_mut.downcast_mut::<crate::object::primitive::PrimitiveObject<f32>>() {
+                        } else if let Some(arg) = arg_mut.downcast_mut::<$crate::object::primitive::PrimitiveObject<f32>>() {
                             arg.data = (object.data as f32).powf(arg.data);
                             drop(arg_mut);
                             context.pop();
                             return Ok(Some(original_arg));
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::bigint::BigIntObject>() {
+                            let result = $crate::object::primitive::bigint::Promote::promote(object.data, $crate::object::primitive::NumberOperation::Pow, &arg.value)?;
+                            drop(arg_mut);
+                            context.pop();
+                            return Ok(Some(result));
+                        } else if let Some(arg) = $crate::object::primitive::exact::Exact::from_fraction(&*arg_mut) {
+                            let result = $crate::object::primitive::exact::Exact::from(object.data).combine($crate::object::primitive::NumberOperation::Pow, arg)?;
+                            drop(arg_mut);
+                            context.pop();
+                            return Ok(Some(result.into_object()));
                         } else {
                             return Err(Fault::InvalidType(format!("Number pow: Not a number")));
                         }
@@ -1248,13 +1622,13 @@ macro_rules! create_type_ops {
             Ok(None)
         }
 
-        fn $is_zero(object: ObjectBox, _: &mut crate::object::ContextData) -> Result<Option<ObjectBox>, Fault> {
+        fn $is_zero(object: ObjectBox, _: &mut $crate::object::ContextData) -> Result<Option<ObjectBox>, Fault> {
             let object = object.borrow();
-            if let Some(object) = object.downcast_ref::<crate::object::primitive::PrimitiveObject<$type>>() {
+            if let Some(object) = object.downcast_ref::<$crate::object::primitive::PrimitiveObject<$type>>() {
                 if object.data.is_zero() {
-                    return Ok(Some(crate::object::create_boolean(true)));
+                    return Ok(Some($crate::object::create_boolean(true)));
                 } else {
-                    return Ok(Some(crate::object::create_boolean(false)));
+                    return Ok(Some($crate::object::create_boolean(false)));
                 }
             } else {
                 return Err(Fault::InvalidType(format!("Number is_zero: expected {}", stringify!($type))))
@@ -1267,74 +1641,80 @@ macro_rules! create_type_ops {
 #[macro_export]
 macro_rules! primitive_base_ops {
     ($type:ty, $equals:ident, $to_string:ident, $order:ident) => {
-        fn $equals(object: ObjectBox, context: &mut crate::object::ContextData) -> Result<Option<ObjectBox>, Fault> {
+        fn $equals(object: ObjectBox, context: &mut $crate::object::ContextData) -> Result<Option<ObjectBox>, Fault> {
             {
                 let mut object = object.borrow_mut();
-                let object = object.downcast_mut::<crate::object::primitive::PrimitiveObject<$type>>();
+                let object = object.downcast_mut::<$crate::object::primitive::PrimitiveObject<$type>>();
                 let original_arg = context.arguments[0].clone();
                 let mut arg_mut = original_arg.borrow_mut();
                 match object {
                     Some(object) => {
-                        if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<i64>>() {
+                        if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<i64>>() {
                             if object.data == arg.data as $type {
-                                return Ok(Some(crate::object::create_boolean(true)));
+                                return Ok(Some($crate::object::create_boolean(true)));
                             } else {
-                                return Ok(Some(crate::object::create_boolean(false)));
+                                return Ok(Some($crate::object::create_boolean(false)));
                             }
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<i32>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<i32>>() {
                             if object.data == arg.data as $type {
-                                return Ok(Some(crate::object::create_boolean(true)));
+                                return Ok(Some($crate::object::create_boolean(true)));
                             } else {
-                                return Ok(Some(crate::object::create_boolean(false)));
+                                return Ok(Some($crate::object::create_boolean(false)));
                             }
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<i16>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<i16>>() {
                             if object.data == arg.data as $type {
-                                return Ok(Some(crate::object::create_boolean(true)));
+                                return Ok(Some($crate::object::create_boolean(true)));
                             } else {
-                                return Ok(Some(crate::object::create_boolean(false)));
+                                return Ok(Some($crate::object::create_boolean(false)));
                             }
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<i8>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<i8>>() {
                             if object.data == arg.data as $type {
-                                return Ok(Some(crate::object::create_boolean(true)));
+                                return Ok(Some($crate::object::create_boolean(true)));
                             } else {
-                                return Ok(Some(crate::object::create_boolean(false)));
+                                return Ok(Some($crate::object::create_boolean(false)));
                             }
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<u64>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<u64>>() {
                             if object.data == arg.data as $type {
-                                return Ok(Some(crate::object::create_boolean(true)));
+                                return Ok(Some($crate::object::create_boolean(true)));
                             } else {
-                                return Ok(Some(crate::object::create_boolean(false)));
+                                return Ok(Some($crate::object::create_boolean(false)));
                             }
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<u32>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<u32>>() {
                             if object.data == arg.data as $type {
-                                return Ok(Some(crate::object::create_boolean(true)));
+                                return Ok(Some($crate::object::create_boolean(true)));
                             } else {
-                                return Ok(Some(crate::object::create_boolean(false)));
+                                return Ok(Some($crate::object::create_boolean(false)));
                             }
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<u16>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<u16>>() {
                             if object.data == arg.data as $type {
-                                return Ok(Some(crate::object::create_boolean(true)));
+                                return Ok(Some($crate::object::create_boolean(true)));
                             } else {
-                                return Ok(Some(crate::object::create_boolean(false)));
+                                return Ok(Some($crate::object::create_boolean(false)));
                             }
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<u8>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<u8>>() {
                             if object.data == arg.data as $type {
-                                return Ok(Some(crate::object::create_boolean(true)));
+                                return Ok(Some($crate::object::create_boolean(true)));
                             } else {
-                                return Ok(Some(crate::object::create_boolean(false)));
+                                return Ok(Some($crate::object::create_boolean(false)));
                             }
-                        } else if let Some(arg) = arg_mut.downcast_mut::<crate::object::primitive::PrimitiveObject<f64>>() {
+                        } else if let Some(arg) = arg_mut.downcast_mut::<$crate::object::primitive::PrimitiveObject<f64>>() {
                             if object.data as f64 == arg.data as f64 {
-                                return Ok(Some(crate::object::create_boolean(true)));
+                                return Ok(Some($crate::object::create_boolean(true)));
                             } else {
-                                return Ok(Some(crate::object::create_boolean(false)));
+                                return Ok(Some($crate::object::create_boolean(false)));
                             }
-                        } else if let Some(arg) = arg_mut.downcast_mut::<crate::object::primitive::PrimitiveObject<f32>>() {
+                        } else if let Some(arg) = arg_mut.downcast_mut::<$crate::object::primitive::PrimitiveObject<f32>>() {
                             if object.data as f32 == arg.data as f32 {
-                                return Ok(Some(crate::object::create_boolean(true)));
+                                return Ok(Some($crate::object::create_boolean(true)));
                             } else {
-                                return Ok(Some(crate::object::create_boolean(false)));
-                            }
+                                return Ok(Some($crate::object::create_boolean(false)));
+                            }
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::bigint::BigIntObject>() {
+                            let ordering = $crate::object::primitive::bigint::Promote::compare(object.data, &arg.value);
+                            return Ok(Some($crate::object::create_boolean(ordering == Some(std::cmp::Ordering::Equal))));
+                        } else if let Some(arg) = $crate::object::primitive::exact::Exact::from_fraction(&*arg_mut) {
+                            let ordering = $crate::object::primitive::exact::Exact::from(object.data).compare(arg);
+                            return Ok(Some($crate::object::create_boolean(ordering == Some(std::cmp::Ordering::Equal))));
                         } else {
                             return Err(Fault::InvalidType(format!("Number equals: Not a number")));
                         }
@@ -1346,105 +1726,113 @@ macro_rules! primitive_base_ops {
             }
         }
         
-        fn $to_string(object: ObjectBox, _: &mut crate::object::ContextData) -> Result<Option<ObjectBox>, Fault> {
+        fn $to_string(object: ObjectBox, _: &mut $crate::object::ContextData) -> Result<Option<ObjectBox>, Fault> {
             let object = object.borrow();
-            if let Some(object) = object.downcast_ref::<crate::object::primitive::PrimitiveObject<$type>>() {
+            if let Some(object) = object.downcast_ref::<$crate::object::primitive::PrimitiveObject<$type>>() {
                 let string = object.data.to_string();
-                return Ok(Some(crate::object::create_string(string)));
+                return Ok(Some($crate::object::create_string(string)));
             } else {
                 return Err(Fault::InvalidType(format!("Number to_string: expected {}", stringify!($type))))
             }
         }
 
 
-        fn $order(object: ObjectBox, context: &mut crate::object::ContextData) -> Result<Option<ObjectBox>, Fault> {
+        fn $order(object: ObjectBox, context: &mut $crate::object::ContextData) -> Result<Option<ObjectBox>, Fault> {
             {
                 let mut object = object.borrow_mut();
-                let object = object.downcast_mut::<crate::object::primitive::PrimitiveObject<$type>>();
+                let object = object.downcast_mut::<$crate::object::primitive::PrimitiveObject<$type>>();
                 let original_arg = context.arguments[0].clone();
                 let mut arg_mut = original_arg.borrow_mut();
                 match object {
                     Some(object) => {
-                        if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<i64>>() {
+                        if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<i64>>() {
                             if object.data < arg.data as $type {
-                                return Ok(Some(crate::object::create_i8(-1)));
+                                return Ok(Some($crate::object::create_i8(-1)));
                             } else if object.data > arg.data as $type {
-                                return Ok(Some(crate::object::create_i8(1)));
+                                return Ok(Some($crate::object::create_i8(1)));
                             } else {
-                                return Ok(Some(crate::object::create_i8(0)));
+                                return Ok(Some($crate::object::create_i8(0)));
                             }
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<i32>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<i32>>() {
                             if object.data < arg.data as $type {
-                                return Ok(Some(crate::object::create_i8(-1)));
+                                return Ok(Some($crate::object::create_i8(-1)));
                             } else if object.data > arg.data as $type {
-                                return Ok(Some(crate::object::create_i8(1)));
+                                return Ok(Some($crate::object::create_i8(1)));
                             } else {
-                                return Ok(Some(crate::object::create_i8(0)));
+                                return Ok(Some($crate::object::create_i8(0)));
                             }
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<i16>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<i16>>() {
                             if object.data < arg.data as $type {
-                                return Ok(Some(crate::object::create_i8(-1)));
+                                return Ok(Some($crate::object::create_i8(-1)));
                             } else if object.data > arg.data as $type {
-                                return Ok(Some(crate::object::create_i8(1)));
+                                return Ok(Some($crate::object::create_i8(1)));
                             } else {
-                                return Ok(Some(crate::object::create_i8(0)));
+                                return Ok(Some($crate::object::create_i8(0)));
                             }
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<i8>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<i8>>() {
                             if object.data < arg.data as $type {
-                                return Ok(Some(crate::object::create_i8(-1)));
+                                return Ok(Some($crate::object::create_i8(-1)));
                             } else if object.data > arg.data as $type {
-                                return Ok(Some(crate::object::create_i8(1)));
+                                return Ok(Some($crate::object::create_i8(1)));
                             } else {
-                                return Ok(Some(crate::object::create_i8(0)));
+                                return Ok(Some($crate::object::create_i8(0)));
                             }
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<u64>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<u64>>() {
                             if object.data < arg.data as $type {
-                                return Ok(Some(crate::object::create_i8(-1)));
+                                return Ok(Some($crate::object::create_i8(-1)));
                             } else if object.data > arg.data as $type {
-                                return Ok(Some(crate::object::create_i8(1)));
+                                return Ok(Some($crate::object::create_i8(1)));
                             } else {
-                                return Ok(Some(crate::object::create_i8(0)));
+                                return Ok(Some($crate::object::create_i8(0)));
                             }
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<u32>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<u32>>() {
                             if object.data < arg.data as $type {
-                                return Ok(Some(crate::object::create_i8(-1)));
+                                return Ok(Some($crate::object::create_i8(-1)));
                             } else if object.data > arg.data as $type {
-                                return Ok(Some(crate::object::create_i8(1)));
+                                return Ok(Some($crate::object::create_i8(1)));
                             } else {
-                                return Ok(Some(crate::object::create_i8(0)));
+                                return Ok(Some($crate::object::create_i8(0)));
                             }
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<u16>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<u16>>() {
                             if object.data < arg.data as $type {
-                                return Ok(Some(crate::object::create_i8(-1)));
+                                return Ok(Some($crate::object::create_i8(-1)));
                             } else if object.data > arg.data as $type {
-                                return Ok(Some(crate::object::create_i8(1)));
+                                return Ok(Some($crate::object::create_i8(1)));
                             } else {
-                                return Ok(Some(crate::object::create_i8(0)));
+                                return Ok(Some($crate::object::create_i8(0)));
                             }
-                        } else if let Some(arg) = arg_mut.downcast_ref::<crate::object::primitive::PrimitiveObject<u8>>() {
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::PrimitiveObject<u8>>() {
                             if object.data < arg.data as $type {
-                                return Ok(Some(crate::object::create_i8(-1)));
+                                return Ok(Some($crate::object::create_i8(-1)));
                             } else if object.data > arg.data as $type {
-                                return Ok(Some(crate::object::create_i8(1)));
+                                return Ok(Some($crate::object::create_i8(1)));
                             } else {
-                                return Ok(Some(crate::object::create_i8(0)));
+                                return Ok(Some($crate::object::create_i8(0)));
                             }
-                        } else if let Some(arg) = arg_mut.downcast_mut::<crate::object::primitive::PrimitiveObject<f64>>() {
+                        } else if let Some(arg) = arg_mut.downcast_mut::<$crate::object::primitive::PrimitiveObject<f64>>() {
                             if (object.data as f64) < arg.data {
-                                return Ok(Some(crate::object::create_i8(-1)));
+                                return Ok(Some($crate::object::create_i8(-1)));
                             } else if object.data > arg.data as $type {
-                                return Ok(Some(crate::object::create_i8(1)));
+                                return Ok(Some($crate::object::create_i8(1)));
                             } else {
-                                return Ok(Some(crate::object::create_i8(0)));
+                                return Ok(Some($crate::object::create_i8(0)));
                             }
-                        } else if let Some(arg) = arg_mut.downcast_mut::<crate::object::primitive::PrimitiveObject<f32>>() {
+                        } else if let Some(arg) = arg_mut.downcast_mut::<$crate::object::primitive::PrimitiveObject<f32>>() {
                             if (object.data as f32) < arg.data {
-                                return Ok(Some(crate::object::create_i8(-1)));
+                                return Ok(Some($crate::object::create_i8(-1)));
                             } else if object.data > arg.data as $type {
-                                return Ok(Some(crate::object::create_i8(1)));
+                                return Ok(Some($crate::object::create_i8(1)));
                             } else {
-                                return Ok(Some(crate::object::create_i8(0)));
-                            }
+                                return Ok(Some($crate::object::create_i8(0)));
+                            }
+                        } else if let Some(arg) = arg_mut.downcast_ref::<$crate::object::primitive::bigint::BigIntObject>() {
+                            let ordering = $crate::object::primitive::bigint::Promote::compare(object.data, &arg.value)
+                                .ok_or(Fault::InvalidOperation(format!("Number order: can't order NaN")))?;
+                            return Ok(Some($crate::object::create_i8(ordering as i8)));
+                        } else if let Some(arg) = $crate::object::primitive::exact::Exact::from_fraction(&*arg_mut) {
+                            let ordering = $crate::object::primitive::exact::Exact::from(object.data).compare(arg)
+                                .ok_or(Fault::InvalidOperation(format!("Number order: can't order NaN")))?;
+                            return Ok(Some($crate::object::create_i8(ordering as i8)));
                         } else {
                             return Err(Fault::InvalidType(format!("Number order: Not a number")));
                         }
@@ -1457,3 +1845,50 @@ macro_rules! primitive_base_ops {
         }
     };
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::object::value::Immediate;
+    use crate::object::{create_i64, create_i8, create_u64, create_u8};
+
+    /// Send an arithmetic message and get the receiver back when the result replaced it in place
+    fn send(receiver: ObjectBox, selector: &str, argument: ObjectBox) -> Result<Immediate, Fault> {
+        let method = receiver.borrow().lookup_method(Symbol::from(selector)).unwrap();
+        let mut context = ContextData::new(crate::object::init_stack());
+        context.set_arguments(vec![argument]);
+        context.push(receiver.clone());
+        let result = method.call(receiver.clone(), &mut context)?.unwrap_or(receiver);
+        let result = Immediate::from_object(&*result.borrow()).unwrap();
+        Ok(result)
+    }
+
+    #[test]
+    fn mixed_width_arguments_are_not_truncated() {
+        assert!(matches!(send(create_i8(0), "sub", create_i64(-200)), Err(Fault::Overflow(_))));
+        assert!(matches!(send(create_i8(0), "add", create_u64(300)), Err(Fault::Overflow(_))));
+        assert!(matches!(send(create_u8(1), "add", create_i64(-2)), Err(Fault::Overflow(_))));
+        assert!(matches!(send(create_i8(100), "mul", create_i64(1 << 40)), Ok(Immediate::I64(value)) if value == 100 << 40));
+        assert!(matches!(send(create_u64(u64::MAX), "div", create_i8(-1)), Err(Fault::Overflow(_))));
+        assert!(matches!(send(create_i8(7), "mod", create_i64(300)), Ok(Immediate::I8(7))));
+        assert!(matches!(send(create_i8(2), "pow", create_i64(-1)), Err(Fault::Overflow(_))));
+    }
+
+    #[test]
+    fn mixed_width_arguments_that_fit() {
+        assert!(matches!(send(create_i8(10), "add", create_i64(-20)), Ok(Immediate::I8(-10))));
+        assert!(matches!(send(create_u8(200), "sub", create_i64(-55)), Ok(Immediate::U8(255))));
+        assert!(matches!(send(create_i8(-128), "div", create_i64(-1)), Ok(Immediate::I64(128))));
+        assert!(matches!(send(create_u8(2), "pow", create_u64(7)), Ok(Immediate::U8(128))));
+    }
+
+    #[test]
+    fn wide_operation_boundaries() {
+        assert_eq!(wide_operation(i128::MAX, NumberOperation::Add, 1), None);
+        assert_eq!(wide_operation(i128::MIN, NumberOperation::Div, -1), None);
+        assert_eq!(wide_operation(2, NumberOperation::Pow, u32::MAX as i128 + 1), None);
+        assert_eq!(<i8 as Arithmetic>::checked_wide(-100, NumberOperation::Sub, 28), Some(-128));
+        assert_eq!(<i8 as Arithmetic>::checked_wide(-100, NumberOperation::Sub, 29), None);
+        assert_eq!(<f64 as Arithmetic>::checked_wide(1.5, NumberOperation::Mul, 2), Some(3.0));
+    }
+}
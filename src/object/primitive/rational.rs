@@ -0,0 +1,340 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use num_integer::Integer;
+
+use crate::object::primitive::bigint::BigInt;
+use crate::object::primitive::exact::{self, Exact};
+use crate::object::primitive::NUMBER_OPERATIONS;
+use crate::object::symbol::Symbol;
+use crate::object::{ContextData, Fault, Method, Object, ObjectBox, VTable};
+
+/// Rational
+/// An exact fraction. It is always in lowest terms with a positive denominator so every value has
+/// exactly one representation. Arithmetic that doesn't fit in an i128 faults with an Overflow.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Rational {
+    numerator: i128,
+    denominator: i128,
+}
+
+fn overflow(name: &str) -> Fault {
+    Fault::Overflow(format!("Rational {}: overflowed", name))
+}
+
+// The arithmetic faults on overflow so it returns a Result instead of implementing std::ops
+#[allow(clippy::should_implement_trait)]
+impl Rational {
+    pub fn new(numerator: i128, denominator: i128) -> Result<Rational, Fault> {
+        if denominator == 0 {
+            return Err(Fault::DivideByZero);
+        }
+        // The gcd of i128::MIN can't be negated so it is left to overflow
+        if numerator == i128::MIN || denominator == i128::MIN {
+            return Err(overflow("new"));
+        }
+        let divisor = numerator.gcd(&denominator);
+        let (numerator, denominator) = (numerator / divisor, denominator / divisor);
+        if denominator < 0 {
+            Ok(Rational { numerator: -numerator, denominator: -denominator })
+        } else {
+            Ok(Rational { numerator, denominator })
+        }
+    }
+
+    pub fn from_integer(value: i128) -> Rational {
+        Rational { numerator: value, denominator: 1 }
+    }
+
+    pub fn zero() -> Rational {
+        Rational::from_integer(0)
+    }
+
+    /// Parse a fraction like -3/4 or an integer
+    pub fn parse(text: &str) -> Option<Rational> {
+        let (numerator, denominator) = text.split_once('/').unwrap_or((text, "1"));
+        Rational::new(numerator.trim().parse().ok()?, denominator.trim().parse().ok()?).ok()
+    }
+
+    pub fn numerator(&self) -> i128 {
+        self.numerator
+    }
+
+    pub fn denominator(&self) -> i128 {
+        self.denominator
+    }
+
+    pub fn is_integer(&self) -> bool {
+        self.denominator == 1
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.numerator == 0
+    }
+
+    pub fn neg(self) -> Result<Rational, Fault> {
+        let numerator = self.numerator.checked_neg().ok_or_else(|| overflow("neg"))?;
+        Ok(Rational { numerator, denominator: self.denominator })
+    }
+
+    pub fn abs(self) -> Result<Rational, Fault> {
+        if self.numerator < 0 { self.neg() } else { Ok(self) }
+    }
+
+    pub fn recip(self) -> Result<Rational, Fault> {
+        Rational::new(self.denominator, self.numerator)
+    }
+
+    pub fn add(self, other: Rational) -> Result<Rational, Fault> {
+        // Only scale by the part of the denominators that they don't share to keep the numbers small
+        let divisor = self.denominator.gcd(&other.denominator);
+        let left = self.numerator.checked_mul(other.denominator / divisor);
+        let right = other.numerator.checked_mul(self.denominator / divisor);
+        let numerator = left.zip(right).and_then(|(left, right)| left.checked_add(right));
+        let denominator = (self.denominator / divisor).checked_mul(other.denominator);
+        match numerator.zip(denominator) {
+            Some((numerator, denominator)) => Rational::new(numerator, denominator),
+            None => Err(overflow("add")),
+        }
+    }
+
+    pub fn sub(self, other: Rational) -> Result<Rational, Fault> {
+        self.add(other.neg()?)
+    }
+
+    pub fn mul(self, other: Rational) -> Result<Rational, Fault> {
+        // Cancel across before multiplying so that the product stays in lowest terms
+        let first = self.numerator.gcd(&other.denominator).max(1);
+        let second = other.numerator.gcd(&self.denominator).max(1);
+        let numerator = (self.numerator / first).checked_mul(other.numerator / second);
+        let denominator = (self.denominator / second).checked_mul(other.denominator / first);
+        match numerator.zip(denominator) {
+            Some((numerator, denominator)) => Rational::new(numerator, denominator),
+            None => Err(overflow("mul")),
+        }
+    }
+
+    pub fn div(self, other: Rational) -> Result<Rational, Fault> {
+        self.mul(other.recip()?)
+    }
+
+    /// The remainder of dividing with the quotient truncated toward zero, like Rust's integers
+    pub fn rem(self, other: Rational) -> Result<Rational, Fault> {
+        let quotient = self.div(other)?;
+        let truncated = Rational::from_integer(quotient.numerator / quotient.denominator);
+        self.sub(other.mul(truncated)?)
+    }
+
+    pub fn pow(self, exponent: i128) -> Result<Rational, Fault> {
+        if exponent < 0 {
+            return self.recip()?.pow(exponent.checked_neg().ok_or_else(|| overflow("pow"))?);
+        }
+        let exponent = u32::try_from(exponent).map_err(|_| overflow("pow"))?;
+        let numerator = self.numerator.checked_pow(exponent);
+        let denominator = self.denominator.checked_pow(exponent);
+        match numerator.zip(denominator) {
+            Some((numerator, denominator)) => Ok(Rational { numerator, denominator }),
+            None => Err(overflow("pow")),
+        }
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        self.numerator as f64 / self.denominator as f64
+    }
+}
+
+impl Ord for Rational {
+    fn cmp(&self, other: &Rational) -> Ordering {
+        // The cross products can be too big for an i128
+        let left = BigInt::from(self.numerator).mul(&BigInt::from(other.denominator));
+        let right = BigInt::from(other.numerator).mul(&BigInt::from(self.denominator));
+        left.cmp(&right)
+    }
+}
+
+impl PartialOrd for Rational {
+    fn partial_cmp(&self, other: &Rational) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl std::fmt::Display for Rational {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}/{}", self.numerator, self.denominator)
+    }
+}
+
+
+pub struct RationalObject {
+    super_object: Option<ObjectBox>,
+    vtable: VTable,
+    pub value: Rational,
+}
+
+impl RationalObject {
+    pub fn make_object(parent: ObjectBox, value: Rational) -> ObjectBox {
+        ObjectBox::new(RationalObject { super_object: Some(parent), vtable: VTable::new_empty(), value })
+    }
+    pub fn make_object_vtable() -> VTable {
+        let mut methods = HashMap::new();
+        methods.insert(Symbol::from("equals"), Arc::new(Method::RustMethod { fun: Box::new(exact::exact_equals) }));
+        methods.insert(Symbol::from("order"), Arc::new(Method::RustMethod { fun: Box::new(exact::exact_order) }));
+        methods.insert(Symbol::from("to_string"), Arc::new(Method::RustMethod { fun: Box::new(rational_to_string) }));
+        VTable::new(methods)
+    }
+    pub fn make_number_vtable() -> VTable {
+        let mut methods = HashMap::new();
+        for (name, operation) in NUMBER_OPERATIONS.iter().copied() {
+            let fun = move |object: ObjectBox, context: &mut ContextData| exact::exact_arithmetic(object, context, operation, name);
+            methods.insert(Symbol::from(name), Arc::new(Method::RustMethod { fun: Box::new(fun) }));
+        }
+        methods.insert(Symbol::from("abs"), Arc::new(Method::RustMethod { fun: Box::new(rational_abs) }));
+        methods.insert(Symbol::from("is_zero"), Arc::new(Method::RustMethod { fun: Box::new(rational_is_zero) }));
+        methods.insert(Symbol::from("numerator"), Arc::new(Method::RustMethod { fun: Box::new(rational_numerator) }));
+        methods.insert(Symbol::from("denominator"), Arc::new(Method::RustMethod { fun: Box::new(rational_denominator) }));
+        VTable::new(methods)
+    }
+}
+
+impl Object for RationalObject {
+    fn get_vtable(&self) -> &VTable {
+        &self.vtable
+    }
+    fn get_super_object(&self) -> Option<ObjectBox> {
+        self.super_object.clone()
+    }
+    fn get_field(&self, _index: usize) -> Option<ObjectBox> {
+        panic!("Rational objects do not have fields")
+    }
+    fn set_field(&mut self, _index: usize, _value: ObjectBox) {
+        panic!("Rational objects do not have fields")
+    }
+    fn size(&self) -> Option<usize> {
+        None
+    }
+    fn duplicate(&self) -> ObjectBox {
+        let rational = RationalObject::make_object(self.super_object.clone().unwrap(), self.value);
+        let mut rational_mut = rational.borrow_mut();
+        rational_mut.initialize(Vec::new(), self.vtable.clone());
+        drop(rational_mut);
+        rational
+    }
+    fn initialize(&mut self, _: Vec<ObjectBox>, vtable: VTable) {
+        self.vtable.extend(vtable);
+    }
+}
+
+/// Make a Rational out of an integer, a Decimal, a Rational or a string like 3/4
+/// Two integers make the fraction of the first over the second.
+pub fn from_arguments(arguments: &[ObjectBox]) -> Result<Rational, Fault> {
+    match arguments {
+        [] => Ok(Rational::zero()),
+        [value] => {
+            let value = value.borrow();
+            if let Some(string) = value.downcast_ref::<crate::object::string::StringObject>() {
+                return Rational::parse(&string.value)
+                    .ok_or(Fault::InvalidOperation(format!("Rational: {:?} isn't a fraction", string.value)));
+            }
+            Exact::from_object(&*value, "new")?.to_rational()
+        }
+        [numerator, denominator] => Rational::new(exact::integer_argument(numerator, "Rational new")?, exact::integer_argument(denominator, "Rational new")?),
+        _ => Err(Fault::InvalidType(format!("expected at most 2 arguments, got {}", arguments.len()))),
+    }
+}
+
+fn with_value<T>(object: &ObjectBox, name: &str, fun: impl FnOnce(&mut Rational) -> T) -> Result<T, Fault> {
+    let mut object = object.borrow_mut();
+    let object = object.downcast_mut::<RationalObject>().ok_or(Fault::InvalidType(format!("Rational {}: expected Rational", name)))?;
+    Ok(fun(&mut object.value))
+}
+
+fn rational_to_string(object: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let string = with_value(&object, "to_string", |value| value.to_string())?;
+    Ok(Some(crate::object::create_string(string)))
+}
+
+fn rational_abs(object: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    with_value(&object, "abs", |value| value.abs().map(|abs| *value = abs))??;
+    Ok(None)
+}
+
+fn rational_is_zero(object: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let zero = with_value(&object, "is_zero", |value| value.is_zero())?;
+    Ok(Some(crate::object::create_boolean(zero)))
+}
+
+fn rational_numerator(object: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let numerator = with_value(&object, "numerator", |value| value.numerator())?;
+    Ok(Some(Exact::Integer(numerator).into_object()))
+}
+
+fn rational_denominator(object: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let denominator = with_value(&object, "denominator", |value| value.denominator())?;
+    Ok(Some(Exact::Integer(denominator).into_object()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::object::primitive::decimal::Decimal;
+    use crate::object::{create_decimal, create_i64, create_rational};
+
+    fn rational(numerator: i128, denominator: i128) -> Rational {
+        Rational::new(numerator, denominator).unwrap()
+    }
+
+    fn send(receiver: &ObjectBox, selector: &str, arguments: Vec<ObjectBox>) -> Result<Option<ObjectBox>, Fault> {
+        let method = receiver.borrow().lookup_method(Symbol::from(selector)).unwrap();
+        let mut context = ContextData::new(crate::object::init_stack());
+        context.set_arguments(arguments);
+        context.push(receiver.clone());
+        method.call(receiver.clone(), &mut context)
+    }
+
+    #[test]
+    fn fractions_are_kept_in_lowest_terms() {
+        let value = rational(6, -8);
+        assert_eq!((value.numerator(), value.denominator()), (-3, 4));
+        assert_eq!(rational(0, -5), Rational::zero());
+        assert_eq!(Rational::parse("10/4"), Some(rational(5, 2)));
+        assert_eq!(Rational::parse(" -7 "), Some(Rational::from_integer(-7)));
+        assert_eq!(rational(1, 6).add(rational(1, 3)).unwrap(), rational(1, 2));
+        assert_eq!(rational(2, 3).mul(rational(9, 4)).unwrap(), rational(3, 2));
+        assert_eq!(rational(-7, 2).rem(rational(1, 1)).unwrap(), rational(-1, 2));
+        assert_eq!(rational(2, 3).pow(-2).unwrap(), rational(9, 4));
+        assert_eq!(rational(3, 6).to_string(), "1/2");
+        assert!(rational(1, 3) < rational(1, 2));
+    }
+
+    #[test]
+    fn dividing_by_zero_faults() {
+        assert!(matches!(Rational::new(1, 0), Err(Fault::DivideByZero)));
+        assert!(matches!(rational(1, 2).div(Rational::zero()), Err(Fault::DivideByZero)));
+        assert!(matches!(rational(1, 2).rem(Rational::zero()), Err(Fault::DivideByZero)));
+        assert!(matches!(Rational::zero().pow(-1), Err(Fault::DivideByZero)));
+        assert_eq!(Rational::parse("1/0"), None);
+    }
+
+    #[test]
+    fn results_too_big_for_an_i128_overflow() {
+        assert!(matches!(Rational::new(i128::MIN, 1), Err(Fault::Overflow(_))));
+        assert!(matches!(Rational::from_integer(i128::MAX).add(Rational::from_integer(1)), Err(Fault::Overflow(_))));
+        assert!(matches!(rational(1, 2).pow(128), Err(Fault::Overflow(_))));
+    }
+
+    #[test]
+    fn arithmetic_messages_widen_to_the_more_general_type() {
+        let half = create_rational(rational(1, 2));
+        assert!(send(&half, "add", vec![create_i64(1)]).unwrap().is_none());
+        assert_eq!(half.borrow().downcast_ref::<RationalObject>().unwrap().value, rational(3, 2));
+
+        // A Decimal becomes a Rational so a third stays exact
+        let third = create_rational(rational(1, 3));
+        send(&third, "add", vec![create_decimal(Decimal::parse("0.5").unwrap())]).unwrap();
+        assert_eq!(third.borrow().downcast_ref::<RationalObject>().unwrap().value, rational(5, 6));
+
+        assert!(matches!(send(&half, "div", vec![create_i64(0)]), Err(Fault::DivideByZero)));
+        assert!(matches!(crate::object::create_object("Rational", &[create_i64(1), create_i64(0)]), Err(Fault::DivideByZero)));
+    }
+}
@@ -0,0 +1,106 @@
+use crate::object::Method;
+use crate::object::Object;
+use std::collections::HashMap;
+use std::sync::Arc;
+use crate::object::primitive::PrimitiveObject;
+use crate::object::ObjectBox;
+use crate::object::VTable;
+use crate::object::symbol::Symbol;
+use crate::object::ContextData;
+use crate::object::Fault;
+
+
+/// SymbolObject
+/// A Symbol is an interned name. Comparing two symbols only compares their ids.
+pub struct SymbolObject {}
+
+impl SymbolObject {
+    pub fn make_object(parent: ObjectBox, value: Symbol) -> ObjectBox {
+        ObjectBox::new(PrimitiveObject::new(Some(parent), value))
+    }
+    pub fn make_object_vtable() -> VTable {
+        let mut methods = HashMap::new();
+        methods.insert(Symbol::from("equals"), Arc::new(Method::RustMethod { fun: Box::new(symbol_equals) }));
+        methods.insert(Symbol::from("to_string"), Arc::new(Method::RustMethod { fun: Box::new(symbol_to_string) }));
+        methods.insert(Symbol::from("order"), Arc::new(Method::RustMethod { fun: Box::new(symbol_order) }));
+        VTable::new(methods)
+    }
+    pub fn make_vtable() -> VTable {
+        let mut methods = HashMap::new();
+        methods.insert(Symbol::from("hash"), Arc::new(Method::RustMethod { fun: Box::new(symbol_hash) }));
+        VTable::new(methods)
+    }
+}
+
+impl Object for PrimitiveObject<Symbol> {
+    fn get_vtable(&self) -> &VTable {
+        &self.vtable
+    }
+    fn get_super_object(&self) -> Option<ObjectBox> {
+        self.super_object.clone()
+    }
+    fn get_field(&self, _index: usize) -> Option<ObjectBox> {
+        panic!("Symbol objects do not have fields")
+    }
+    fn set_field(&mut self, _index: usize, _value: ObjectBox) {
+        panic!("Symbol objects do not have fields")
+    }
+    fn size(&self) -> Option<usize> {
+        None
+    }
+    fn duplicate(&self) -> ObjectBox {
+        let symbol = SymbolObject::make_object(self.super_object.clone().unwrap(), self.data);
+        let mut sym = symbol.borrow_mut();
+        sym.initialize(Vec::new(), self.vtable.clone());
+        drop(sym);
+        symbol
+    }
+    fn initialize(&mut self, _: Vec<ObjectBox>, vtable: VTable) {
+        self.vtable.extend(vtable);
+    }
+}
+
+
+fn symbol_equals(object: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let object = object.borrow();
+    let other = context.get_argument(0).unwrap();
+    let other = other.borrow();
+    match (object.downcast_ref::<PrimitiveObject<Symbol>>(), other.downcast_ref::<PrimitiveObject<Symbol>>()) {
+        (Some(obj), Some(other)) => Ok(Some(crate::object::create_boolean(obj.data == other.data))),
+        (Some(_), None) => Ok(Some(crate::object::create_boolean(false))),
+        _ => Err(Fault::InvalidType("Symbol equals: Expected Symbol".to_string()))
+    }
+}
+
+fn symbol_to_string(object: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let object = object.borrow();
+    match object.downcast_ref::<PrimitiveObject<Symbol>>() {
+        Some(obj) => Ok(Some(crate::object::create_string(obj.data.to_string()))),
+        _ => Err(Fault::InvalidType("Symbol to_string: Expected Symbol".to_string()))
+    }
+}
+
+/// Symbols are ordered by their names so that sorting them is stable between runs
+fn symbol_order(object: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let object = object.borrow();
+    let other = context.get_argument(0).unwrap();
+    let other = other.borrow();
+    match (object.downcast_ref::<PrimitiveObject<Symbol>>(), other.downcast_ref::<PrimitiveObject<Symbol>>()) {
+        (Some(obj), Some(other)) => if obj.data == other.data {
+                Ok(Some(crate::object::create_i8(0)))
+            } else if obj.data.as_str() > other.data.as_str() {
+                Ok(Some(crate::object::create_i8(1)))
+            } else {
+                Ok(Some(crate::object::create_i8(-1)))
+            },
+        _ => Err(Fault::InvalidType("Symbol order: Expected Symbol".to_string()))
+    }
+}
+
+fn symbol_hash(object: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let object = object.borrow();
+    match object.downcast_ref::<PrimitiveObject<Symbol>>() {
+        Some(obj) => Ok(Some(crate::object::create_u64(obj.data.id() as u64))),
+        _ => Err(Fault::InvalidType("Symbol hash: Expected Symbol".to_string()))
+    }
+}
@@ -0,0 +1,376 @@
+//! Range
+//! The integers from a start towards an end in steps, counting down when the step is negative.
+//! The elements have the type of the start so a range of U8 gives U8s, and a range is checked when
+//! it is made so that its last element fits that type.
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use super::block::Block;
+use super::primitive::bigint::{BigInt, BigIntObject};
+use super::primitive::exact::{integer_argument, Exact};
+use super::symbol::Symbol;
+use super::value::Immediate;
+use super::{ContextData, Fault, Method, Object, ObjectBox, VTable};
+
+
+/// The integer type of the elements of a Range
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Kind {
+    I8,
+    I16,
+    I32,
+    I64,
+    U8,
+    U16,
+    U32,
+    U64,
+    BigInt,
+}
+
+impl Kind {
+    /// Get the type of an integer object
+    pub fn of(object: &ObjectBox, name: &str) -> Result<Kind, Fault> {
+        let object = object.borrow();
+        if object.is::<BigIntObject>() {
+            return Ok(Kind::BigInt);
+        }
+        match Immediate::from_object(&*object) {
+            Some(Immediate::I8(_)) => Ok(Kind::I8),
+            Some(Immediate::I16(_)) => Ok(Kind::I16),
+            Some(Immediate::I32(_)) => Ok(Kind::I32),
+            Some(Immediate::I64(_)) => Ok(Kind::I64),
+            Some(Immediate::U8(_)) => Ok(Kind::U8),
+            Some(Immediate::U16(_)) => Ok(Kind::U16),
+            Some(Immediate::U32(_)) => Ok(Kind::U32),
+            Some(Immediate::U64(_)) => Ok(Kind::U64),
+            _ => Err(Fault::InvalidType(format!("{}: expected an integer", name))),
+        }
+    }
+
+    /// The tag of the type in an image, the immediate tags with 8 for a BigInt
+    pub fn tag(self) -> u8 {
+        self as u8
+    }
+
+    pub fn from_tag(tag: u8) -> Option<Kind> {
+        [Kind::I8, Kind::I16, Kind::I32, Kind::I64, Kind::U8, Kind::U16, Kind::U32, Kind::U64, Kind::BigInt].get(tag as usize).copied()
+    }
+
+    /// Make an object of this type, an Overflow fault if the value doesn't fit
+    pub fn create(self, value: i128) -> Result<ObjectBox, Fault> {
+        let overflow = |_| Fault::Overflow(format!("Range: {} does not fit in {:?}", value, self));
+        let object = match self {
+            Kind::I8 => super::create_i8(i8::try_from(value).map_err(overflow)?),
+            Kind::I16 => super::create_i16(i16::try_from(value).map_err(overflow)?),
+            Kind::I32 => super::create_i32(i32::try_from(value).map_err(overflow)?),
+            Kind::I64 => super::create_i64(i64::try_from(value).map_err(overflow)?),
+            Kind::U8 => super::create_u8(u8::try_from(value).map_err(overflow)?),
+            Kind::U16 => super::create_u16(u16::try_from(value).map_err(overflow)?),
+            Kind::U32 => super::create_u32(u32::try_from(value).map_err(overflow)?),
+            Kind::U64 => super::create_u64(u64::try_from(value).map_err(overflow)?),
+            Kind::BigInt => super::create_bigint(BigInt::from(value)),
+        };
+        Ok(object)
+    }
+}
+
+/// Range
+/// The bounds of a range and the number of elements they give, which is worked out once
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Range {
+    pub start: i128,
+    pub end: i128,
+    pub step: i128,
+    pub inclusive: bool,
+    pub kind: Kind,
+    length: u128,
+}
+
+impl Range {
+    pub fn new(start: i128, end: i128, step: i128, inclusive: bool, kind: Kind) -> Result<Range, Fault> {
+        if step == 0 {
+            return Err(Fault::InvalidOperation("Range: the step can't be 0".to_string()));
+        }
+        let overflow = || Fault::Overflow(format!("Range: {} to {} is too long", start, end));
+        // The distance is counted in the direction of the step
+        let distance = if step > 0 { end.checked_sub(start) } else { start.checked_sub(end) }.ok_or_else(overflow)?;
+        let stride = step.unsigned_abs();
+        let length = match (distance, inclusive) {
+            (distance, true) if distance >= 0 => distance.unsigned_abs() / stride + 1,
+            (distance, false) if distance > 0 => (distance.unsigned_abs() - 1) / stride + 1,
+            _ => 0,
+        };
+        let range = Range { start, end, step, inclusive, kind, length };
+        if let Some(last) = range.last() {
+            kind.create(last)?;
+        }
+        Ok(range)
+    }
+
+    pub fn len(&self) -> u128 {
+        self.length
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    /// The element at an index, None when the range isn't that long
+    pub fn get(&self, index: u128) -> Option<i128> {
+        // Every element is between the start and the last element so this can't overflow
+        (index < self.length).then(|| self.start + index as i128 * self.step)
+    }
+
+    pub fn last(&self) -> Option<i128> {
+        self.length.checked_sub(1).and_then(|index| self.get(index))
+    }
+
+    pub fn contains(&self, value: i128) -> bool {
+        // Only i128::MIN divided by -1 can overflow and it is never an element then
+        let index = value.checked_sub(self.start)
+            .filter(|offset| offset.checked_rem(self.step) == Some(0))
+            .and_then(|offset| offset.checked_div(self.step));
+        index.is_some_and(|index| index >= 0 && index.unsigned_abs() < self.length)
+    }
+
+    /// The same elements from the last to the first
+    pub fn reverse(&self) -> Range {
+        match self.last() {
+            Some(last) => Range { start: last, end: self.start, step: -self.step, inclusive: true, kind: self.kind, length: self.length },
+            None => *self,
+        }
+    }
+}
+
+pub struct RangeObject {
+    super_object: Option<ObjectBox>,
+    vtable: VTable,
+    pub value: Range,
+}
+
+impl RangeObject {
+    pub fn make_object(parent: ObjectBox, value: Range) -> ObjectBox {
+        ObjectBox::new(RangeObject { super_object: Some(parent), vtable: VTable::new_empty(), value })
+    }
+    pub fn make_vtable() -> VTable {
+        let mut methods = HashMap::new();
+        methods.insert(Symbol::from("do"), Arc::new(Method::RustMethod { fun: Box::new(range_do) }));
+        methods.insert(Symbol::from("contains"), Arc::new(Method::RustMethod { fun: Box::new(range_contains) }));
+        methods.insert(Symbol::from("length"), Arc::new(Method::RustMethod { fun: Box::new(range_length) }));
+        methods.insert(Symbol::from("to_vector"), Arc::new(Method::RustMethod { fun: Box::new(range_to_vector) }));
+        methods.insert(Symbol::from("reverse"), Arc::new(Method::RustMethod { fun: Box::new(range_reverse) }));
+        methods.insert(Symbol::from("iterator"), Arc::new(Method::RustMethod { fun: Box::new(range_iterator) }));
+        VTable::new(methods)
+    }
+}
+
+impl Object for RangeObject {
+    fn get_vtable(&self) -> &VTable {
+        &self.vtable
+    }
+    fn get_super_object(&self) -> Option<ObjectBox> {
+        self.super_object.clone()
+    }
+    fn get_field(&self, _index: usize) -> Option<ObjectBox> {
+        panic!("Range does not have fields");
+    }
+    fn set_field(&mut self, _index: usize, _value: ObjectBox) {
+        panic!("Range does not have fields");
+    }
+    fn size(&self) -> Option<usize> {
+        None
+    }
+    fn duplicate(&self) -> ObjectBox {
+        let range = RangeObject::make_object(self.super_object.clone().unwrap(), self.value);
+        let mut range_mut = range.borrow_mut();
+        range_mut.initialize(Vec::new(), self.vtable.clone());
+        drop(range_mut);
+        range
+    }
+    fn initialize(&mut self, _: Vec<ObjectBox>, vtable: VTable) {
+        self.vtable.extend(vtable);
+    }
+}
+
+/// Make a Range from a start, an end, a step and whether the end is included
+/// The step defaults to 1 and the end is left out unless the fourth argument is true. With no
+/// arguments the range is empty.
+pub fn from_arguments(arguments: &[ObjectBox]) -> Result<Range, Fault> {
+    if arguments.is_empty() {
+        return Range::new(0, 0, 1, false, Kind::I64);
+    }
+    if arguments.len() < 2 || arguments.len() > 4 {
+        return Err(Fault::InvalidType(format!("expected 2 to 4 arguments, got {}", arguments.len())));
+    }
+    let kind = Kind::of(&arguments[0], "Range")?;
+    let start = integer_argument(&arguments[0], "Range")?;
+    let end = integer_argument(&arguments[1], "Range")?;
+    let step = arguments.get(2).map(|step| integer_argument(step, "Range")).transpose()?.unwrap_or(1);
+    let inclusive = match arguments.get(3).map(|inclusive| Immediate::from_object(&*inclusive.borrow())) {
+        None => false,
+        Some(Some(Immediate::Boolean(inclusive))) => inclusive,
+        Some(_) => return Err(Fault::InvalidType("Range: expected a Boolean for inclusive".to_string())),
+    };
+    Range::new(start, end, step, inclusive, kind)
+}
+
+/// The range from an integer to the argument including both ends, sent as to: on any integer
+pub fn integer_to(object: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let kind = Kind::of(&object, "Integer to")?;
+    let start = integer_argument(&object, "Integer to")?;
+    let end = integer_argument(&context.arguments[0], "Integer to")?;
+    Ok(Some(super::create_range(Range::new(start, end, 1, true, kind)?)))
+}
+
+fn range(object: &ObjectBox, name: &str) -> Result<Range, Fault> {
+    let object = object.borrow();
+    let object = object.downcast_ref::<RangeObject>().ok_or(Fault::InvalidType(format!("Range {}: Expected Range", name)))?;
+    Ok(object.value)
+}
+
+/// Evaluate a block with each element in order
+fn range_do(object: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let value = range(&object, "do")?;
+    let block = context.arguments[0].clone();
+    if !block.borrow().is::<Block>() {
+        return Err(Fault::InvalidType("Range do: Expected Block".to_string()));
+    }
+    for index in 0..value.len() {
+        let element = value.kind.create(value.get(index).unwrap())?;
+        Block::evaluate(&block, None, vec![element])?;
+    }
+    Ok(None)
+}
+
+/// Tell if a number is one of the elements, whatever its type
+fn range_contains(object: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let value = range(&object, "contains")?;
+    // A float equals an integer when it has no fraction part
+    let number = match Exact::from_object(&*context.arguments[0].borrow(), "Range contains") {
+        Ok(Exact::Float(value)) if value.fract() == 0.0 && value.abs() < 2f64.powi(127) => Some(value as i128),
+        Ok(number) => number.integer(),
+        Err(_) => None,
+    };
+    Ok(Some(super::create_boolean(number.is_some_and(|number| value.contains(number)))))
+}
+
+fn range_length(object: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let value = range(&object, "length")?;
+    let length = u64::try_from(value.len()).map_err(|_| Fault::Overflow(format!("Range length: {} elements is too many", value.len())))?;
+    Ok(Some(super::create_u64(length)))
+}
+
+fn range_to_vector(object: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let value = range(&object, "to_vector")?;
+    let length = usize::try_from(value.len()).map_err(|_| Fault::Overflow(format!("Range to_vector: {} elements is too many", value.len())))?;
+    let mut elements = Vec::new();
+    elements.try_reserve(length).map_err(|_| Fault::Overflow(format!("Range to_vector: {} elements is too many", length)))?;
+    for index in 0..value.len() {
+        elements.push(value.kind.create(value.get(index).unwrap())?);
+    }
+    Ok(Some(super::create_vector(elements)))
+}
+
+fn range_reverse(object: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let value = range(&object, "reverse")?;
+    Ok(Some(super::create_range(value.reverse())))
+}
+
+/// Iterate over the elements without making them all first
+fn range_iterator(object: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let value = range(&object, "iterator")?;
+    Ok(Some(super::create_iterator(super::iterator::Source::Range { range: value, index: 0 })))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::primitive::rational::Rational;
+    use super::super::vector::VectorObject;
+    use super::super::{create_boolean, create_f64, create_i64, create_object, create_rational, create_string, create_u8};
+
+    fn elements(range: &Range) -> Vec<i128> {
+        (0..range.len()).map(|index| range.get(index).unwrap()).collect()
+    }
+
+    fn send(receiver: &ObjectBox, selector: &str, arguments: Vec<ObjectBox>) -> Result<Option<ObjectBox>, Fault> {
+        let method = receiver.borrow().lookup_method(Symbol::from(selector)).unwrap();
+        method.evaluate(receiver.clone(), arguments)
+    }
+
+    #[test]
+    fn the_end_is_only_included_when_asked_for() {
+        assert_eq!(elements(&Range::new(0, 10, 3, false, Kind::I64).unwrap()), [0, 3, 6, 9]);
+        assert_eq!(elements(&Range::new(0, 9, 3, false, Kind::I64).unwrap()), [0, 3, 6]);
+        assert_eq!(elements(&Range::new(0, 9, 3, true, Kind::I64).unwrap()), [0, 3, 6, 9]);
+        assert_eq!(elements(&Range::new(4, 4, 1, true, Kind::I64).unwrap()), [4]);
+        assert!(Range::new(4, 4, 1, false, Kind::I64).unwrap().is_empty());
+    }
+
+    #[test]
+    fn negative_steps_count_down() {
+        assert_eq!(elements(&Range::new(10, 0, -3, false, Kind::I64).unwrap()), [10, 7, 4, 1]);
+        assert_eq!(elements(&Range::new(10, 1, -3, false, Kind::I64).unwrap()), [10, 7, 4]);
+        assert_eq!(elements(&Range::new(10, 1, -3, true, Kind::I64).unwrap()), [10, 7, 4, 1]);
+        // A step away from the end gives nothing
+        assert!(Range::new(0, 10, -1, true, Kind::I64).unwrap().is_empty());
+        assert!(Range::new(10, 0, 1, true, Kind::I64).unwrap().is_empty());
+        assert_eq!(elements(&Range::new(0, 10, 3, false, Kind::I64).unwrap().reverse()), [9, 6, 3, 0]);
+    }
+
+    #[test]
+    fn a_zero_step_faults() {
+        assert!(matches!(Range::new(0, 10, 0, false, Kind::I64), Err(Fault::InvalidOperation(_))));
+        assert!(matches!(create_object("Range", &[create_i64(0), create_i64(10), create_i64(0)]), Err(Fault::InvalidOperation(_))));
+    }
+
+    #[test]
+    fn the_last_element_has_to_fit_the_type() {
+        assert_eq!(Range::new(250, 255, 1, true, Kind::U8).unwrap().len(), 6);
+        assert!(matches!(Range::new(250, 256, 1, true, Kind::U8), Err(Fault::Overflow(_))));
+        // The end isn't an element so it doesn't have to fit
+        assert_eq!(Range::new(0, 256, 1, false, Kind::U8).unwrap().last(), Some(255));
+        assert!(matches!(Range::new(i128::MIN, i128::MAX, 1, false, Kind::BigInt), Err(Fault::Overflow(_))));
+    }
+
+    #[test]
+    fn contains_follows_the_step_and_the_end() {
+        let range = Range::new(1, 10, 3, false, Kind::I64).unwrap();
+        assert!(range.contains(1) && range.contains(4) && range.contains(7));
+        assert!(!range.contains(10) && !range.contains(5) && !range.contains(-2) && !range.contains(0));
+        let range = Range::new(10, 1, -3, true, Kind::I64).unwrap();
+        assert!(range.contains(10) && range.contains(1));
+        assert!(!range.contains(13) && !range.contains(-2) && !range.contains(2));
+        assert!(!Range::new(0, 0, 1, false, Kind::I64).unwrap().contains(0));
+        let range = Range::new(0, -10, -1, false, Kind::BigInt).unwrap();
+        assert!(!range.contains(i128::MIN) && !Range::new(-1, 10, 1, false, Kind::I64).unwrap().contains(i128::MAX));
+        // The longest range has more elements than an i128 can count
+        let range = Range::new(-1, i128::MAX - 1, 1, true, Kind::BigInt).unwrap();
+        assert!(range.contains(-1) && range.contains(i128::MAX - 1) && !range.contains(i128::MAX));
+    }
+
+    #[test]
+    fn ranges_are_made_and_asked_with_messages() {
+        let range = create_object("Range", &[create_u8(2), create_i64(8), create_i64(2), create_boolean(true)]).unwrap().unwrap();
+        let contains = |value: ObjectBox| {
+            let result = send(&range, "contains", vec![value]).unwrap().unwrap();
+            let result = Immediate::from_object(&*result.borrow());
+            result
+        };
+        assert_eq!(contains(create_i64(8)), Some(Immediate::Boolean(true)));
+        assert_eq!(contains(create_f64(6.0)), Some(Immediate::Boolean(true)));
+        assert_eq!(contains(create_f64(6.5)), Some(Immediate::Boolean(false)));
+        assert_eq!(contains(create_rational(Rational::new(8, 2).unwrap())), Some(Immediate::Boolean(true)));
+        assert_eq!(contains(create_string("4".to_string())), Some(Immediate::Boolean(false)));
+
+        let vector = send(&range, "to_vector", Vec::new()).unwrap().unwrap();
+        let vector: Vec<_> = vector.borrow().downcast_ref::<VectorObject>().unwrap().value.iter()
+            .map(|element| Immediate::from_object(&*element.borrow())).collect();
+        assert_eq!(vector, [2, 4, 6, 8].map(|value| Some(Immediate::U8(value))));
+
+        let range = send(&create_i64(3), "to", vec![create_i64(1)]).unwrap().unwrap();
+        let length = send(&range, "length", Vec::new()).unwrap().unwrap();
+        assert_eq!(Immediate::from_object(&*length.borrow()), Some(Immediate::U64(0)));
+        assert!(create_object("Range", &[create_i64(0), create_i64(1), create_i64(1), create_i64(1)]).is_err());
+    }
+}
@@ -0,0 +1,367 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use ::regex::{Captures, Regex};
+
+use super::block::Block;
+use super::primitive::exact::integer_argument;
+use super::string::StringObject;
+use super::symbol::Symbol;
+use super::{ContextData, Fault, Method, Nil, Object, ObjectBox, VTable};
+
+
+/// RegexObject
+/// A compiled regular expression. The positions of its matches count chars like the String
+/// methods do so they can be used to index and slice the string that was searched.
+pub struct RegexObject {
+    super_object: Option<ObjectBox>,
+    vtable: VTable,
+    pub value: Regex,
+}
+
+impl RegexObject {
+    pub fn make_object(parent: ObjectBox, value: Regex) -> ObjectBox {
+        ObjectBox::new(RegexObject { super_object: Some(parent), vtable: VTable::new_empty(), value })
+    }
+    pub fn make_vtable() -> VTable {
+        let mut methods = HashMap::new();
+        methods.insert(Symbol::from("matches"), Arc::new(Method::RustMethod { fun: Box::new(regex_matches) }));
+        methods.insert(Symbol::from("find"), Arc::new(Method::RustMethod { fun: Box::new(regex_find) }));
+        methods.insert(Symbol::from("find_all"), Arc::new(Method::RustMethod { fun: Box::new(regex_find_all) }));
+        methods.insert(Symbol::from("replace_all"), Arc::new(Method::RustMethod { fun: Box::new(regex_replace_all) }));
+        methods.insert(Symbol::from("split"), Arc::new(Method::RustMethod { fun: Box::new(regex_split) }));
+        methods.insert(Symbol::from("pattern"), Arc::new(Method::RustMethod { fun: Box::new(regex_pattern) }));
+        methods.insert(Symbol::from("to_string"), Arc::new(Method::RustMethod { fun: Box::new(regex_pattern) }));
+        VTable::new(methods)
+    }
+}
+
+impl Object for RegexObject {
+    fn get_vtable(&self) -> &VTable {
+        &self.vtable
+    }
+    fn get_super_object(&self) -> Option<ObjectBox> {
+        self.super_object.clone()
+    }
+    fn get_field(&self, _index: usize) -> Option<ObjectBox> {
+        panic!("Regex objects do not have fields")
+    }
+    fn set_field(&mut self, _index: usize, _value: ObjectBox) {
+        panic!("Regex objects do not have fields")
+    }
+    fn size(&self) -> Option<usize> {
+        None
+    }
+    fn duplicate(&self) -> ObjectBox {
+        let regex = RegexObject::make_object(self.super_object.clone().unwrap(), self.value.clone());
+        let mut regex_mut = regex.borrow_mut();
+        regex_mut.initialize(Vec::new(), self.vtable.clone());
+        drop(regex_mut);
+        regex
+    }
+    fn initialize(&mut self, _: Vec<ObjectBox>, vtable: VTable) {
+        self.vtable.extend(vtable);
+    }
+}
+
+/// Match
+/// Where a Regex matched and the text of each of its groups, the whole match is group 0
+#[derive(Clone, Debug, Default)]
+pub struct Match {
+    pub start: usize,
+    pub end: usize,
+    pub groups: Vec<Option<String>>,
+    pub names: Vec<Option<String>>,
+}
+
+pub struct MatchObject {
+    super_object: Option<ObjectBox>,
+    vtable: VTable,
+    pub value: Match,
+}
+
+impl MatchObject {
+    pub fn make_object(parent: ObjectBox, value: Match) -> ObjectBox {
+        ObjectBox::new(MatchObject { super_object: Some(parent), vtable: VTable::new_empty(), value })
+    }
+    pub fn make_vtable() -> VTable {
+        let mut methods = HashMap::new();
+        methods.insert(Symbol::from("start"), Arc::new(Method::RustMethod { fun: Box::new(match_start) }));
+        methods.insert(Symbol::from("end"), Arc::new(Method::RustMethod { fun: Box::new(match_end) }));
+        methods.insert(Symbol::from("text"), Arc::new(Method::RustMethod { fun: Box::new(match_text) }));
+        methods.insert(Symbol::from("group"), Arc::new(Method::RustMethod { fun: Box::new(match_group) }));
+        methods.insert(Symbol::from("groups"), Arc::new(Method::RustMethod { fun: Box::new(match_groups) }));
+        methods.insert(Symbol::from("to_string"), Arc::new(Method::RustMethod { fun: Box::new(match_text) }));
+        VTable::new(methods)
+    }
+}
+
+impl Object for MatchObject {
+    fn get_vtable(&self) -> &VTable {
+        &self.vtable
+    }
+    fn get_super_object(&self) -> Option<ObjectBox> {
+        self.super_object.clone()
+    }
+    fn get_field(&self, _index: usize) -> Option<ObjectBox> {
+        panic!("Match objects do not have fields")
+    }
+    fn set_field(&mut self, _index: usize, _value: ObjectBox) {
+        panic!("Match objects do not have fields")
+    }
+    fn size(&self) -> Option<usize> {
+        None
+    }
+    fn duplicate(&self) -> ObjectBox {
+        let found = MatchObject::make_object(self.super_object.clone().unwrap(), self.value.clone());
+        let mut found_mut = found.borrow_mut();
+        found_mut.initialize(Vec::new(), self.vtable.clone());
+        drop(found_mut);
+        found
+    }
+    fn initialize(&mut self, _: Vec<ObjectBox>, vtable: VTable) {
+        self.vtable.extend(vtable);
+    }
+}
+
+/// Compile a pattern, a bad pattern faults with the reason it couldn't be compiled
+pub fn compile(pattern: &str) -> Result<Regex, Fault> {
+    Regex::new(pattern).map_err(|error| Fault::InvalidOperation(format!("Regex: {}", error)))
+}
+
+/// Make a Regex out of the String with its pattern
+pub fn from_arguments(arguments: &[ObjectBox]) -> Result<Regex, Fault> {
+    match arguments {
+        [pattern] => {
+            let pattern = pattern.borrow();
+            let pattern = pattern.downcast_ref::<StringObject>().ok_or(Fault::InvalidType("Regex: expected a String pattern".to_string()))?;
+            compile(&pattern.value)
+        }
+        _ => Err(Fault::InvalidType(format!("expected 1 argument, got {}", arguments.len()))),
+    }
+}
+
+/// Turns byte offsets into char indexes, the offsets have to be asked for in increasing order
+struct CharIndexes<'a> {
+    text: &'a str,
+    byte: usize,
+    index: usize,
+}
+
+impl<'a> CharIndexes<'a> {
+    fn new(text: &'a str) -> CharIndexes<'a> {
+        CharIndexes { text, byte: 0, index: 0 }
+    }
+
+    fn get(&mut self, byte: usize) -> usize {
+        self.index += self.text[self.byte..byte].chars().count();
+        self.byte = byte;
+        self.index
+    }
+
+    fn make_match(&mut self, regex: &Regex, captures: &Captures) -> Match {
+        let whole = captures.get(0).expect("a match always has group 0");
+        Match {
+            start: self.get(whole.start()),
+            end: self.get(whole.end()),
+            groups: captures.iter().map(|group| group.map(|group| group.as_str().to_string())).collect(),
+            names: regex.capture_names().map(|name| name.map(str::to_string)).collect(),
+        }
+    }
+}
+
+/// Get the Regex of the receiver
+/// It is cloned so that nothing is borrowed while a replacer block runs.
+fn receiver(object: &ObjectBox, name: &str) -> Result<Regex, Fault> {
+    let object = object.borrow();
+    let object = object.downcast_ref::<RegexObject>().ok_or(Fault::InvalidType(format!("Regex {}: Expected Regex", name)))?;
+    Ok(object.value.clone())
+}
+
+fn string_argument(context: &ContextData, position: usize, name: &str) -> Result<String, Fault> {
+    let argument = context.arguments[position].borrow();
+    let argument = argument.downcast_ref::<StringObject>().ok_or(Fault::InvalidType(format!("Regex {}: Expected String", name)))?;
+    Ok(argument.value.clone())
+}
+
+/// Whether the pattern matches anywhere in the string, anchors make it match the whole string
+fn regex_matches(object: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let regex = receiver(&object, "matches")?;
+    let text = string_argument(context, 0, "matches")?;
+    Ok(Some(super::create_boolean(regex.is_match(&text))))
+}
+
+/// The first Match in the string, Nil if there isn't one
+fn regex_find(object: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let regex = receiver(&object, "find")?;
+    let text = string_argument(context, 0, "find")?;
+    match regex.captures(&text) {
+        Some(captures) => Ok(Some(super::create_match(CharIndexes::new(&text).make_match(&regex, &captures)))),
+        None => Ok(Some(Nil::new())),
+    }
+}
+
+fn regex_find_all(object: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let regex = receiver(&object, "find_all")?;
+    let text = string_argument(context, 0, "find_all")?;
+    let mut indexes = CharIndexes::new(&text);
+    let matches: Vec<ObjectBox> = regex.captures_iter(&text)
+        .map(|captures| super::create_match(indexes.make_match(&regex, &captures)))
+        .collect();
+    Ok(Some(super::create_vector(matches)))
+}
+
+/// Replace every match with a String or with what a Block gives for the Match
+/// A String replacement can refer to groups with $1 or ${name}.
+fn regex_replace_all(object: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let regex = receiver(&object, "replace_all")?;
+    let text = string_argument(context, 0, "replace_all")?;
+    let replacer = context.arguments[1].clone();
+    if replacer.borrow().downcast_ref::<Block>().is_none() {
+        let replacement = string_argument(context, 1, "replace_all")?;
+        return Ok(Some(super::create_string(regex.replace_all(&text, replacement.as_str()).into_owned())));
+    }
+    let mut indexes = CharIndexes::new(&text);
+    let mut output = String::with_capacity(text.len());
+    let mut last = 0;
+    for captures in regex.captures_iter(&text) {
+        let whole = captures.get(0).expect("a match always has group 0");
+        output.push_str(&text[last..whole.start()]);
+        last = whole.end();
+        let found = super::create_match(indexes.make_match(&regex, &captures));
+        let replacement = Block::evaluate(&replacer, None, vec![found])?
+            .ok_or(Fault::InvalidOperation("Regex replace_all: the block didn't give a replacement".to_string()))?;
+        let replacement = replacement.borrow();
+        let replacement = replacement.downcast_ref::<StringObject>()
+            .ok_or(Fault::InvalidType("Regex replace_all: the block has to give a String".to_string()))?;
+        output.push_str(&replacement.value);
+    }
+    output.push_str(&text[last..]);
+    Ok(Some(super::create_string(output)))
+}
+
+fn regex_split(object: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let regex = receiver(&object, "split")?;
+    let text = string_argument(context, 0, "split")?;
+    let parts: Vec<ObjectBox> = regex.split(&text).map(|part| super::create_string(part.to_string())).collect();
+    Ok(Some(super::create_vector(parts)))
+}
+
+fn regex_pattern(object: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let regex = receiver(&object, "pattern")?;
+    Ok(Some(super::create_string(regex.as_str().to_string())))
+}
+
+fn with_match<T>(object: &ObjectBox, name: &str, fun: impl FnOnce(&Match) -> T) -> Result<T, Fault> {
+    let object = object.borrow();
+    let object = object.downcast_ref::<MatchObject>().ok_or(Fault::InvalidType(format!("Match {}: Expected Match", name)))?;
+    Ok(fun(&object.value))
+}
+
+fn group_object(group: Option<&String>) -> ObjectBox {
+    match group {
+        Some(group) => super::create_string(group.clone()),
+        None => Nil::new(),
+    }
+}
+
+fn match_start(object: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let start = with_match(&object, "start", |found| found.start)?;
+    Ok(Some(super::create_u64(start as u64)))
+}
+
+fn match_end(object: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let end = with_match(&object, "end", |found| found.end)?;
+    Ok(Some(super::create_u64(end as u64)))
+}
+
+fn match_text(object: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let text = with_match(&object, "text", |found| group_object(found.groups[0].as_ref()))?;
+    Ok(Some(text))
+}
+
+/// The text of a group by its number or its name, Nil if the group didn't take part in the match
+fn match_group(object: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let name = context.arguments[0].borrow().downcast_ref::<StringObject>().map(|name| name.value.clone());
+    let index = match name {
+        Some(name) => with_match(&object, "group", |found| found.names.iter().position(|other| other.as_deref() == Some(name.as_str())))?
+            .ok_or(Fault::InvalidOperation(format!("Match group: there is no group named {}", name)))?,
+        None => {
+            let index = integer_argument(&context.arguments[0], "Match group")?;
+            usize::try_from(index).map_err(|_| Fault::InvalidOperation(format!("Match group: group {} is negative", index)))?
+        }
+    };
+    with_match(&object, "group", |found| found.groups.get(index).map(|group| group_object(group.as_ref())))?
+        .map(Some)
+        .ok_or(Fault::InvalidOperation(format!("Match group: there is no group {}", index)))
+}
+
+/// The text of every group after the whole match
+fn match_groups(object: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let groups = with_match(&object, "groups", |found| found.groups[1..].iter().map(|group| group_object(group.as_ref())).collect())?;
+    Ok(Some(super::create_vector(groups)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::object::value::Immediate;
+    use crate::object::vector::VectorObject;
+    use crate::object::{create_i64, create_object, create_string};
+
+    fn send(receiver: &ObjectBox, selector: &str, arguments: Vec<ObjectBox>) -> Result<ObjectBox, Fault> {
+        let method = receiver.borrow().lookup_method(Symbol::from(selector)).unwrap();
+        Ok(method.evaluate(receiver.clone(), arguments)?.unwrap())
+    }
+
+    fn regex(pattern: &str) -> ObjectBox {
+        create_object("Regex", &[string(pattern)]).unwrap().unwrap()
+    }
+
+    fn string(value: &str) -> ObjectBox {
+        create_string(value.to_string())
+    }
+
+    fn text(object: &ObjectBox) -> String {
+        object.borrow().downcast_ref::<StringObject>().unwrap().value.clone()
+    }
+
+    fn texts(object: &ObjectBox) -> Vec<String> {
+        let vector = object.borrow();
+        let vector = vector.downcast_ref::<VectorObject>().unwrap();
+        vector.value.iter().map(text).collect()
+    }
+
+    #[test]
+    fn match_positions_count_chars() {
+        let found = send(&regex("b+"), "find", vec![string("ébbc")]).unwrap();
+        let found = found.borrow();
+        let found = &found.downcast_ref::<MatchObject>().unwrap().value;
+        assert_eq!((found.start, found.end), (1, 3));
+        assert_eq!(found.groups, vec![Some("bb".to_string())]);
+    }
+
+    #[test]
+    fn groups_are_found_by_number_or_name() {
+        let found = send(&regex("(?<key>\\w+)=(\\d+)?"), "find", vec![string("size=")]).unwrap();
+        assert_eq!(text(&send(&found, "group", vec![string("key")]).unwrap()), "size");
+        assert!(Immediate::from_object(&*send(&found, "group", vec![create_i64(2)]).unwrap().borrow()).is_none());
+        assert!(send(&found, "group", vec![string("value")]).is_err());
+        assert!(send(&found, "group", vec![create_i64(3)]).is_err());
+    }
+
+    #[test]
+    fn replacing_and_splitting() {
+        let replaced = send(&regex("(\\w+)@"), "replace_all", vec![string("a@ b@"), string("<$1>")]).unwrap();
+        assert_eq!(text(&replaced), "<a> <b>");
+        let parts = send(&regex(",\\s*"), "split", vec![string("x, y,z")]).unwrap();
+        assert_eq!(texts(&parts), vec!["x", "y", "z"]);
+        let matches = send(&regex("\\d"), "matches", vec![string("abc")]).unwrap();
+        assert!(matches!(Immediate::from_object(&*matches.borrow()), Some(Immediate::Boolean(false))));
+    }
+
+    #[test]
+    fn bad_patterns_fault() {
+        assert!(matches!(compile("(unclosed"), Err(Fault::InvalidOperation(_))));
+    }
+}
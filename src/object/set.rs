@@ -0,0 +1,450 @@
+//! Set
+//! A Set holds objects that are not equal to each other. It sends hash to an object to find the
+//! bucket it belongs in and equals to tell it apart from the others in that bucket, so a class
+//! that overrides equals and hash gets the same behaviour as the builtin types.
+//! An object without hash that keeps the equals of Object is only equal to itself so it is hashed
+//! by its address. One that overrides equals without hash can't be put in a Set since equal
+//! objects could end up in different buckets. Objects that can't be compared, like a String and a
+//! number, are not equal.
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::sync::Arc;
+
+use super::block::Block;
+use super::primitive::exact::integer_argument;
+use super::symbol::predefined::EQUALS;
+use super::symbol::Symbol;
+use super::value::Immediate;
+use super::vector::VectorObject;
+use super::{is_identity_equals, ContextData, Fault, Method, Nil, Object, ObjectBox, VTable};
+
+
+/// Hash a Rust value for a hash method
+pub fn hash_of<T: Hash + ?Sized>(value: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// ObjectSet
+/// The elements of a Set with their hashes. Elements that haven't been hashed yet, because the
+/// Set was made from a Vector or loaded from an image, wait in pending until the Set is next used.
+#[derive(Clone, Default)]
+pub struct ObjectSet {
+    entries: Vec<(u64, ObjectBox)>,
+    buckets: HashMap<u64, Vec<usize>>,
+    pub pending: Vec<ObjectBox>,
+}
+
+impl ObjectSet {
+    pub fn pending(elements: Vec<ObjectBox>) -> Self {
+        ObjectSet { pending: elements, ..ObjectSet::default() }
+    }
+
+    /// Every element, the ones that are still pending last
+    pub fn elements(&self) -> Vec<ObjectBox> {
+        self.entries.iter().map(|(_, element)| element.clone()).chain(self.pending.iter().cloned()).collect()
+    }
+
+    fn bucket(&self, hash: u64) -> Vec<ObjectBox> {
+        self.buckets.get(&hash).into_iter().flatten().map(|&index| self.entries[index].1.clone()).collect()
+    }
+
+    fn insert(&mut self, hash: u64, element: ObjectBox) {
+        self.buckets.entry(hash).or_default().push(self.entries.len());
+        self.entries.push((hash, element));
+    }
+
+    /// Remove the element that is this very object
+    fn remove(&mut self, hash: u64, element: &ObjectBox) {
+        let Some(bucket) = self.buckets.get_mut(&hash) else { return };
+        let Some(position) = bucket.iter().position(|&index| std::ptr::eq(self.entries[index].1.as_ptr(), element.as_ptr())) else { return };
+        let index = bucket.swap_remove(position);
+        if bucket.is_empty() {
+            self.buckets.remove(&hash);
+        }
+        self.entries.swap_remove(index);
+        // The last entry took the place of the removed one
+        if let Some((moved, _)) = self.entries.get(index) {
+            let moved_from = self.entries.len();
+            for slot in self.buckets.get_mut(moved).into_iter().flatten() {
+                if *slot == moved_from {
+                    *slot = index;
+                }
+            }
+        }
+    }
+
+    /// Add an object if nothing equal to it is there yet
+    fn add(&mut self, object: ObjectBox) -> Result<(), Fault> {
+        let hash = hash(&object)?;
+        if find_equal(self.bucket(hash), &object)?.is_none() {
+            self.insert(hash, object);
+        }
+        Ok(())
+    }
+}
+
+pub struct SetObject {
+    super_object: Option<ObjectBox>,
+    vtable: VTable,
+    pub value: ObjectSet,
+}
+
+impl SetObject {
+    pub fn make_object(parent: ObjectBox, value: ObjectSet) -> ObjectBox {
+        ObjectBox::new(SetObject { super_object: Some(parent), vtable: VTable::new_empty(), value })
+    }
+    pub fn make_vtable() -> VTable {
+        let mut methods = HashMap::new();
+        methods.insert(Symbol::from("add"), Arc::new(Method::RustMethod { fun: Box::new(set_add) }));
+        methods.insert(Symbol::from("remove"), Arc::new(Method::RustMethod { fun: Box::new(set_remove) }));
+        methods.insert(Symbol::from("contains"), Arc::new(Method::RustMethod { fun: Box::new(set_contains) }));
+        methods.insert(Symbol::from("length"), Arc::new(Method::RustMethod { fun: Box::new(set_length) }));
+        methods.insert(Symbol::from("union"), Arc::new(Method::RustMethod { fun: Box::new(set_union) }));
+        methods.insert(Symbol::from("intersection"), Arc::new(Method::RustMethod { fun: Box::new(set_intersection) }));
+        methods.insert(Symbol::from("difference"), Arc::new(Method::RustMethod { fun: Box::new(set_difference) }));
+        methods.insert(Symbol::from("is_subset"), Arc::new(Method::RustMethod { fun: Box::new(set_is_subset) }));
+        methods.insert(Symbol::from("each"), Arc::new(Method::RustMethod { fun: Box::new(set_each) }));
+        methods.insert(Symbol::from("to_vector"), Arc::new(Method::RustMethod { fun: Box::new(set_to_vector) }));
+        methods.insert(Symbol::from("iterator"), Arc::new(Method::RustMethod { fun: Box::new(set_iterator) }));
+        VTable::new(methods)
+    }
+}
+
+impl Object for SetObject {
+    fn get_vtable(&self) -> &VTable {
+        &self.vtable
+    }
+    fn get_super_object(&self) -> Option<ObjectBox> {
+        self.super_object.clone()
+    }
+    fn get_field(&self, _index: usize) -> Option<ObjectBox> {
+        panic!("Set does not have fields");
+    }
+    fn set_field(&mut self, _index: usize, _value: ObjectBox) {
+        panic!("Set does not have fields");
+    }
+    fn size(&self) -> Option<usize> {
+        None
+    }
+    fn duplicate(&self) -> ObjectBox {
+        let set = SetObject::make_object(self.super_object.clone().unwrap(), self.value.clone());
+        let mut set_mut = set.borrow_mut();
+        set_mut.initialize(Vec::new(), self.vtable.clone());
+        drop(set_mut);
+        set
+    }
+    fn initialize(&mut self, _: Vec<ObjectBox>, vtable: VTable) {
+        self.vtable.extend(vtable);
+    }
+    fn children(&self) -> Vec<ObjectBox> {
+        self.value.elements().into_iter().chain(self.super_object.clone()).collect()
+    }
+    fn clear_children(&mut self) {
+        self.value = ObjectSet::default();
+    }
+}
+
+/// Make the elements of a new Set, a Vector argument gives its elements
+pub fn from_arguments(arguments: &[ObjectBox]) -> Result<ObjectSet, Fault> {
+    match arguments {
+        [] => Ok(ObjectSet::default()),
+        [argument] => {
+            let elements = argument.borrow().downcast_ref::<VectorObject>().map(|vector| vector.value.to_vec())
+                .ok_or(Fault::InvalidType("Set: Expected Vector".to_string()))?;
+            Ok(ObjectSet::pending(elements))
+        }
+        _ => Err(Fault::InvalidType(format!("expected at most 1 argument, got {}", arguments.len()))),
+    }
+}
+
+/// Get the hash of an object by sending it hash
+fn hash(object: &ObjectBox) -> Result<u64, Fault> {
+    if object.borrow().is::<Nil>() {
+        return Ok(0);
+    }
+    let hash = object.borrow().lookup_method(Symbol::from("hash"));
+    let Some(hash) = hash else {
+        let equals = object.borrow().lookup_method(EQUALS);
+        return match equals {
+            Some(equals) if !is_identity_equals(&equals) => {
+                Err(Fault::InvalidOperation("Set: an object that overrides equals needs a hash method too".to_string()))
+            }
+            _ => Ok(object.as_ptr() as usize as u64),
+        };
+    };
+    let result = hash.evaluate(object.clone(), Vec::new())?
+        .ok_or(Fault::InvalidOperation("Set: hash didn't give a result".to_string()))?;
+    let result = integer_argument(&result, "Set hash")?;
+    Ok(result as u64)
+}
+
+/// Tell if two objects are equal by sending equals to the first
+pub fn equal(object: &ObjectBox, other: &ObjectBox) -> Result<bool, Fault> {
+    if std::ptr::eq(object.as_ptr(), other.as_ptr()) {
+        return Ok(true);
+    }
+    let (object_nil, other_nil) = (object.borrow().is::<Nil>(), other.borrow().is::<Nil>());
+    if object_nil || other_nil {
+        return Ok(object_nil && other_nil);
+    }
+    let Some(equals) = object.borrow().lookup_method(EQUALS) else {
+        return Ok(false);
+    };
+    let result = match equals.evaluate(object.clone(), vec![other.clone()]) {
+        Ok(result) => result.ok_or(Fault::InvalidOperation("Set: equals didn't give a result".to_string()))?,
+        Err(Fault::InvalidType(_)) => return Ok(false),
+        Err(fault) => return Err(fault),
+    };
+    let result = Immediate::from_object(&*result.borrow());
+    match result {
+        Some(Immediate::Boolean(result)) => Ok(result),
+        _ => Err(Fault::InvalidType("Set: equals didn't give a Boolean".to_string())),
+    }
+}
+
+fn find_equal(candidates: Vec<ObjectBox>, object: &ObjectBox) -> Result<Option<ObjectBox>, Fault> {
+    for candidate in candidates {
+        if equal(&candidate, object)? {
+            return Ok(Some(candidate));
+        }
+    }
+    Ok(None)
+}
+
+fn with_set<T>(object: &ObjectBox, name: &str, fun: impl FnOnce(&mut ObjectSet) -> T) -> Result<T, Fault> {
+    let mut object = object.borrow_mut();
+    let set = object.downcast_mut::<SetObject>().ok_or(Fault::InvalidType(format!("Set {}: Expected Set", name)))?;
+    Ok(fun(&mut set.value))
+}
+
+// The Set is only borrowed between calls to hash and equals since they can run code that uses it
+
+/// Hash the elements that are still pending
+fn settle(object: &ObjectBox, name: &str) -> Result<(), Fault> {
+    let pending = with_set(object, name, |set| std::mem::take(&mut set.pending))?;
+    for element in pending {
+        add(object, name, element)?;
+    }
+    Ok(())
+}
+
+/// Find the element of a Set equal to an object and its hash
+fn find(object: &ObjectBox, name: &str, element: &ObjectBox) -> Result<Option<(u64, ObjectBox)>, Fault> {
+    let hash = hash(element)?;
+    let bucket = with_set(object, name, |set| set.bucket(hash))?;
+    Ok(find_equal(bucket, element)?.map(|found| (hash, found)))
+}
+
+fn add(object: &ObjectBox, name: &str, element: ObjectBox) -> Result<(), Fault> {
+    let hash = hash(&element)?;
+    let bucket = with_set(object, name, |set| set.bucket(hash))?;
+    if find_equal(bucket, &element)?.is_none() {
+        with_set(object, name, |set| set.insert(hash, element))?;
+    }
+    Ok(())
+}
+
+/// Get the hashed elements of a Set
+fn entries(object: &ObjectBox, name: &str) -> Result<Vec<(u64, ObjectBox)>, Fault> {
+    settle(object, name)?;
+    with_set(object, name, |set| set.entries.clone())
+}
+
+/// Get the argument that has to be a Set ready to be searched
+fn set_argument(context: &ContextData, name: &str) -> Result<ObjectBox, Fault> {
+    let argument = context.arguments[0].clone();
+    if !argument.borrow().is::<SetObject>() {
+        return Err(Fault::InvalidType(format!("Set {}: Expected Set", name)));
+    }
+    settle(&argument, name)?;
+    Ok(argument)
+}
+
+fn set_add(object: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    settle(&object, "add")?;
+    add(&object, "add", context.arguments[0].clone())?;
+    Ok(None)
+}
+
+fn set_remove(object: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    settle(&object, "remove")?;
+    if let Some((hash, element)) = find(&object, "remove", &context.arguments[0])? {
+        with_set(&object, "remove", |set| set.remove(hash, &element))?;
+    }
+    Ok(None)
+}
+
+fn set_contains(object: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    settle(&object, "contains")?;
+    let found = find(&object, "contains", &context.arguments[0])?;
+    Ok(Some(super::create_boolean(found.is_some())))
+}
+
+fn set_length(object: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    settle(&object, "length")?;
+    let length = with_set(&object, "length", |set| set.entries.len())?;
+    Ok(Some(super::create_u64(length as u64)))
+}
+
+/// The elements of the receiver and then the ones of the argument that aren't in it
+fn set_union(object: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let mut result = ObjectSet::default();
+    for (hash, element) in entries(&object, "union")? {
+        result.insert(hash, element);
+    }
+    let other = set_argument(context, "union")?;
+    for (_, element) in entries(&other, "union")? {
+        result.add(element)?;
+    }
+    Ok(Some(super::create_set(result)))
+}
+
+/// Keep the elements of the receiver that are in the argument or the ones that aren't
+fn filter(object: &ObjectBox, context: &ContextData, name: &str, keep_found: bool) -> Result<ObjectSet, Fault> {
+    let other = set_argument(context, name)?;
+    let mut result = ObjectSet::default();
+    for (hash, element) in entries(object, name)? {
+        if find(&other, name, &element)?.is_some() == keep_found {
+            result.insert(hash, element);
+        }
+    }
+    Ok(result)
+}
+
+/// The elements of the receiver that are also in the argument
+fn set_intersection(object: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    Ok(Some(super::create_set(filter(&object, context, "intersection", true)?)))
+}
+
+/// The elements of the receiver that are not in the argument
+fn set_difference(object: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    Ok(Some(super::create_set(filter(&object, context, "difference", false)?)))
+}
+
+/// Tell if every element of the receiver is in the argument
+fn set_is_subset(object: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let missing = filter(&object, context, "is_subset", false)?;
+    Ok(Some(super::create_boolean(missing.entries.is_empty())))
+}
+
+/// Evaluate a block with each element
+fn set_each(object: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let block = context.arguments[0].clone();
+    if !block.borrow().is::<Block>() {
+        return Err(Fault::InvalidType("Set each: Expected Block".to_string()));
+    }
+    for (_, element) in entries(&object, "each")? {
+        Block::evaluate(&block, None, vec![element])?;
+    }
+    Ok(None)
+}
+
+fn set_to_vector(object: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let elements = entries(&object, "to_vector")?.into_iter().map(|(_, element)| element).collect();
+    Ok(Some(super::create_vector(elements)))
+}
+
+/// Iterate over the elements the set has now
+fn set_iterator(object: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let elements = entries(&object, "iterator")?.into_iter().map(|(_, element)| element).collect();
+    Ok(Some(super::iterator::over_elements(elements)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::object::primitive::bigint::BigInt;
+    use crate::object::{create_base_object, create_bigint, create_boolean, create_f64, create_i64, create_object, create_set, create_string, create_u8, Class, ObjectStruct};
+
+    fn send(receiver: &ObjectBox, selector: &str, arguments: Vec<ObjectBox>) -> Result<Option<ObjectBox>, Fault> {
+        let method = receiver.borrow().lookup_method(Symbol::from(selector)).unwrap();
+        method.evaluate(receiver.clone(), arguments)
+    }
+
+    fn value(object: Option<ObjectBox>) -> Option<Immediate> {
+        Immediate::from_object(&*object.unwrap().borrow())
+    }
+
+    fn length(set: &ObjectBox) -> Option<Immediate> {
+        value(send(set, "length", Vec::new()).unwrap())
+    }
+
+    fn contains(set: &ObjectBox, element: ObjectBox) -> Option<Immediate> {
+        value(send(set, "contains", vec![element]).unwrap())
+    }
+
+    fn instance(vtable: VTable) -> ObjectBox {
+        let class = Arc::new(Class::new(Some("Object"), vtable, Vec::new()));
+        let object = ObjectStruct::new(Some(class), Some(create_base_object()));
+        object.borrow_mut().initialize(Vec::new(), VTable::new_empty());
+        object
+    }
+
+    #[test]
+    fn adding_contains_and_removing() {
+        let set = create_set(ObjectSet::default());
+        send(&set, "add", vec![create_string("a".to_string())]).unwrap();
+        send(&set, "add", vec![create_string("b".to_string())]).unwrap();
+        assert_eq!(length(&set), Some(Immediate::U64(2)));
+        assert_eq!(contains(&set, create_string("a".to_string())), Some(Immediate::Boolean(true)));
+        assert_eq!(contains(&set, create_string("c".to_string())), Some(Immediate::Boolean(false)));
+
+        send(&set, "remove", vec![create_string("a".to_string())]).unwrap();
+        send(&set, "remove", vec![create_string("c".to_string())]).unwrap();
+        assert_eq!(length(&set), Some(Immediate::U64(1)));
+        assert_eq!(contains(&set, create_string("a".to_string())), Some(Immediate::Boolean(false)));
+        assert_eq!(contains(&set, create_string("b".to_string())), Some(Immediate::Boolean(true)));
+    }
+
+    #[test]
+    fn equal_elements_are_only_kept_once() {
+        let elements = ["x", "y", "x", "x"].iter().map(|value| create_string(value.to_string())).collect();
+        let set = create_object("Set", &[crate::object::create_vector(elements)]).unwrap().unwrap();
+        assert_eq!(length(&set), Some(Immediate::U64(2)));
+        send(&set, "add", vec![create_string("y".to_string())]).unwrap();
+        send(&set, "add", vec![create_boolean(true)]).unwrap();
+        send(&set, "add", vec![create_boolean(true)]).unwrap();
+        assert_eq!(length(&set), Some(Immediate::U64(3)));
+    }
+
+    #[test]
+    fn equal_numbers_of_different_types_are_the_same_element() {
+        let set = create_set(ObjectSet::default());
+        let half = || create_object("Rational", &[create_i64(3), create_i64(2)]).unwrap().unwrap();
+        let decimal = create_object("Decimal", &[create_string("1.50".to_string())]).unwrap().unwrap();
+        for element in [create_i64(1), create_u8(1), create_f64(1.0), create_bigint(BigInt::from(1)), half(), decimal] {
+            send(&set, "add", vec![element]).unwrap();
+        }
+        assert_eq!(length(&set), Some(Immediate::U64(2)));
+        assert_eq!(contains(&set, create_f64(1.5)), Some(Immediate::Boolean(true)));
+        assert_eq!(contains(&set, create_bigint(BigInt::from(1))), Some(Immediate::Boolean(true)));
+        send(&set, "remove", vec![half()]).unwrap();
+        assert_eq!(length(&set), Some(Immediate::U64(1)));
+        assert_eq!(contains(&set, create_i64(2)), Some(Immediate::Boolean(false)));
+    }
+
+    #[test]
+    fn instances_without_hash_are_hashed_by_identity() {
+        let set = create_set(ObjectSet::default());
+        let first = instance(VTable::new_empty());
+        let second = instance(VTable::new_empty());
+        assert_ne!(hash(&first).unwrap(), hash(&second).unwrap());
+        for element in [&first, &second, &first] {
+            send(&set, "add", vec![element.clone()]).unwrap();
+        }
+        assert_eq!(length(&set), Some(Immediate::U64(2)));
+        send(&set, "remove", vec![first.clone()]).unwrap();
+        assert_eq!(contains(&set, first), Some(Immediate::Boolean(false)));
+        assert_eq!(contains(&set, second), Some(Immediate::Boolean(true)));
+    }
+
+    #[test]
+    fn overriding_equals_without_hash_faults() {
+        let equals: Arc<Method> = Arc::new(Method::RustMethod { fun: Box::new(|_, _| Ok(Some(create_boolean(true)))) });
+        let object = instance(VTable::new(HashMap::from([(EQUALS, equals)])));
+        let set = create_set(ObjectSet::default());
+        assert!(matches!(send(&set, "add", vec![object]), Err(Fault::InvalidOperation(_))));
+    }
+}
@@ -2,24 +2,27 @@ use super::ContextData;
 use super::Object;
 use super::ObjectBox;
 use super::VTable;
+use crate::object::symbol::Symbol;
 use std::collections::HashMap;
 use crate::object::Method;
 use std::sync::Arc;
 use super::Fault;
+use super::value::{Immediate, Value};
 
 
 
 pub struct Stack {
     super_object: Option<ObjectBox>,
     vtable: VTable,
-    pub data: Vec<ObjectBox>,
+    pub data: Vec<Value>,
 }
 
 impl Stack {
     pub fn make_vtable() -> VTable {
         let mut methods = HashMap::new();
-        methods.insert(String::from("push"), Arc::new(Method::RustMethod { fun: Box::new(stack_push) }));
-        methods.insert(String::from("pop"), Arc::new(Method::RustMethod { fun: Box::new(stack_pop) }));
+        methods.insert(Symbol::from("push"), Arc::new(Method::RustMethod { fun: Box::new(stack_push) }));
+        methods.insert(Symbol::from("pop"), Arc::new(Method::RustMethod { fun: Box::new(stack_pop) }));
+        methods.insert(Symbol::from("iterator"), Arc::new(Method::RustMethod { fun: Box::new(stack_iterator) }));
         VTable::new(methods)
     }
 
@@ -29,23 +32,56 @@ impl Stack {
 
     pub fn make_object_with_stack(parent: ObjectBox,
                                   data: Vec<ObjectBox>) -> ObjectBox {
+        let data = data.into_iter().map(Value::Object).collect();
         ObjectBox::new(Stack {super_object: Some(parent), data, vtable: VTable::new_empty()})
     }
     
     pub fn push(&mut self, value: ObjectBox) {
+        self.data.push(Value::Object(value));
+    }
+
+    pub fn push_value(&mut self, value: Value) {
         self.data.push(value);
     }
 
     pub fn pop(&mut self) -> Option<ObjectBox> {
+        self.data.pop().map(Value::into_object)
+    }
+
+    pub fn pop_value(&mut self) -> Option<Value> {
         self.data.pop()
     }
 
+    /// Get the top of the stack as an object
+    /// An immediate on top gets boxed in place so that every later access sees the same object.
+    pub fn top(&mut self) -> Option<ObjectBox> {
+        let top = self.data.last_mut()?;
+        if let Value::Immediate(immediate) = top {
+            *top = Value::Object(immediate.into_object());
+        }
+        match top {
+            Value::Object(object) => Some(object.clone()),
+            Value::Immediate(_) => unreachable!(),
+        }
+    }
+
+    /// Get the immediate at the index from the top of the stack if that slot is unboxed
+    pub fn peek_immediate(&self, index: usize) -> Option<Immediate> {
+        let position = self.data.len().checked_sub(index + 1)?;
+        self.data[position].as_immediate()
+    }
+
+    pub fn replace_top(&mut self, value: Value) {
+        *self.data.last_mut().expect("Stack::replace_top: stack was empty") = value;
+    }
+
+    /// Immediates are values so this gives a boxed copy of them
     pub fn index(&self, index: usize) -> Option<ObjectBox> {
         let mut iter = self.data.iter().rev();
         for _ in 0..index {
             iter.next();
         }
-        iter.next().map(|x| x.clone())
+        iter.next().map(|x| x.clone().into_object())
     }
 }
 
@@ -64,13 +100,17 @@ impl Object for Stack {
         for _ in 0..index {
             iter.next();
         }
-        *iter.next().unwrap() = value;
+        *iter.next().unwrap() = Value::Object(value);
     }
     fn size(&self) -> Option<usize> {
         Some(self.data.len())
     }
     fn duplicate(&self) -> ObjectBox {
-        let stack = Stack::make_object_with_stack(self.super_object.clone().unwrap().borrow().duplicate(), self.data.clone());
+        let stack = ObjectBox::new(Stack {
+            super_object: Some(self.super_object.clone().unwrap()),
+            data: self.data.clone(),
+            vtable: VTable::new_empty(),
+        });
         let mut stk = stack.borrow_mut();
         stk.initialize(Vec::new(), self.vtable.clone());
         drop(stk);
@@ -80,15 +120,19 @@ impl Object for Stack {
         for arg in arguments {
             self.push(arg);
         }
-        self.vtable.extend(Stack::make_vtable());
         self.vtable.extend(vtable);
-        match &mut self.super_object {
-            Some(super_object) => {
-                let mut super_object = super_object.borrow_mut();
-                super_object.initialize(vec![], VTable::new_empty());
-            }
-            None => {}
-        }
+    }
+    fn children(&self) -> Vec<ObjectBox> {
+        self.data.iter()
+            .filter_map(|value| match value {
+                Value::Object(object) => Some(object.clone()),
+                Value::Immediate(_) => None,
+            })
+            .chain(self.super_object.clone())
+            .collect()
+    }
+    fn clear_children(&mut self) {
+        self.data.clear();
     }
 }
 
@@ -111,3 +155,63 @@ fn stack_pop(object: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>
     }
 }
 
+/// Iterate over a copy of the stack from the top down, the order pop would give
+fn stack_iterator(object: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let object = object.borrow();
+    let object = object.downcast_ref::<Stack>().ok_or(Fault::InvalidType("Stack iterator: Expected Stack".to_string()))?;
+    let elements = object.data.iter().rev().cloned().map(Value::into_object).collect();
+    Ok(Some(super::iterator::over_elements(elements)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::object::Nil;
+    use crate::object::primitive::PrimitiveObject;
+
+    fn stack(values: &[Immediate]) -> Stack {
+        let mut stack = Stack { super_object: Some(Nil::new()), data: Vec::new(), vtable: VTable::new_empty() };
+        for value in values {
+            stack.push_value(Value::Immediate(*value));
+        }
+        stack
+    }
+
+    /// The value of a boxed primitive
+    fn primitive<T: Copy>(object: &ObjectBox) -> T where PrimitiveObject<T>: Object {
+        object.borrow().downcast_ref::<PrimitiveObject<T>>().unwrap().data
+    }
+
+    #[test]
+    fn the_top_is_boxed_in_place() {
+        let mut stack = stack(&[Immediate::I64(1), Immediate::I64(2)]);
+        assert_eq!(stack.peek_immediate(0), Some(Immediate::I64(2)));
+        let top = stack.top().unwrap();
+        assert!(stack.peek_immediate(0).is_none());
+        assert_eq!(top.as_ptr(), stack.top().unwrap().as_ptr());
+        assert_eq!(primitive::<i64>(&top), 2);
+        // The slot below stays unboxed
+        assert_eq!(stack.peek_immediate(1), Some(Immediate::I64(1)));
+        assert!(stack.peek_immediate(2).is_none());
+    }
+
+    #[test]
+    fn indexing_gives_boxed_copies_of_immediates() {
+        let stack = stack(&[Immediate::I64(7), Immediate::Boolean(true)]);
+        let first = stack.index(1).unwrap();
+        assert_ne!(first.as_ptr(), stack.index(1).unwrap().as_ptr());
+        assert_eq!(primitive::<i64>(&first), 7);
+        assert_eq!(stack.peek_immediate(1), Some(Immediate::I64(7)));
+    }
+
+    #[test]
+    fn immediates_are_not_children() {
+        let mut stack = stack(&[Immediate::U8(1)]);
+        let object = Nil::new();
+        stack.push(object.clone());
+        let children = stack.children();
+        // The object and the parent
+        assert_eq!(children.len(), 2);
+        assert_eq!(children[0].as_ptr(), object.as_ptr());
+    }
+}
@@ -2,11 +2,16 @@ use std::collections::HashMap;
 use std::sync::Arc;
 use super::vector::VectorObject;
 use super::{Object, ObjectBox, VTable, Method, ContextData, Fault};
+use super::symbol::Symbol;
 use crate::object::primitive::PrimitiveObject;
+use crate::object::primitive::exact::integer_argument;
+use unicode_segmentation::UnicodeSegmentation;
 
 
 
-
+/// StringObject
+/// Indexes, lengths and slices count chars so they never split a character. The bytes and the
+/// grapheme clusters of a string have their own methods.
 pub struct StringObject {
     super_object: Option<ObjectBox>,
     vtable: VTable,
@@ -24,30 +29,47 @@ impl StringObject {
         };
         ObjectBox::new(string)
     }
-    fn make_object_vtable() -> VTable {
+    pub fn make_object_vtable() -> VTable {
         let mut methods = HashMap::new();
-        methods.insert(String::from("equals"), Arc::new(Method::RustMethod { fun: Box::new(string_equals) }));
-        methods.insert(String::from("to_string"), Arc::new(Method::RustMethod { fun: Box::new(string_to_string) }));
-        methods.insert(String::from("order"), Arc::new(Method::RustMethod { fun: Box::new(string_order) }));
+        methods.insert(Symbol::from("equals"), Arc::new(Method::RustMethod { fun: Box::new(string_equals) }));
+        methods.insert(Symbol::from("hash"), Arc::new(Method::RustMethod { fun: Box::new(string_hash) }));
+        methods.insert(Symbol::from("to_string"), Arc::new(Method::RustMethod { fun: Box::new(string_to_string) }));
+        methods.insert(Symbol::from("order"), Arc::new(Method::RustMethod { fun: Box::new(string_order) }));
         VTable::new(methods)
     }
     pub fn make_vtable() -> VTable {
         let mut methods = HashMap::new();
-        methods.insert(String::from("length"), Arc::new(Method::RustMethod { fun: Box::new(string_length) }));
-        methods.insert(String::from("to_lowercase"), Arc::new(Method::RustMethod { fun: Box::new(string_to_lowercase) }));
-        methods.insert(String::from("to_uppercase"), Arc::new(Method::RustMethod { fun: Box::new(string_to_uppercase) }));
-        methods.insert(String::from("trim"), Arc::new(Method::RustMethod { fun: Box::new(string_trim) }));
-        methods.insert(String::from("trim_start"), Arc::new(Method::RustMethod { fun: Box::new(string_trim_start) }));
-        methods.insert(String::from("trim_end"), Arc::new(Method::RustMethod { fun: Box::new(string_trim_end) }));
-        methods.insert(String::from("contains"), Arc::new(Method::RustMethod { fun: Box::new(string_contains) }));
-        methods.insert(String::from("to_vector"), Arc::new(Method::RustMethod { fun: Box::new(string_to_vector) }));
-        methods.insert(String::from("split"), Arc::new(Method::RustMethod { fun: Box::new(string_split) }));
-        methods.insert(String::from("get"), Arc::new(Method::RustMethod { fun: Box::new(string_get) }));
-        methods.insert(String::from("set"), Arc::new(Method::RustMethod { fun: Box::new(string_set) }));
-        methods.insert(String::from("push"), Arc::new(Method::RustMethod { fun: Box::new(string_push) }));
-        methods.insert(String::from("pop"), Arc::new(Method::RustMethod { fun: Box::new(string_pop) }));
-        methods.insert(String::from("push_char"), Arc::new(Method::RustMethod { fun: Box::new(string_push_char) }));
-        methods.insert(String::from("concat"), Arc::new(Method::RustMethod { fun: Box::new(string_concat) }));
+        methods.insert(Symbol::from("length"), Arc::new(Method::RustMethod { fun: Box::new(string_length) }));
+        methods.insert(Symbol::from("byte_length"), Arc::new(Method::RustMethod { fun: Box::new(string_byte_length) }));
+        methods.insert(Symbol::from("bytes"), Arc::new(Method::RustMethod { fun: Box::new(string_bytes) }));
+        methods.insert(Symbol::from("encode_utf8"), Arc::new(Method::RustMethod { fun: Box::new(string_encode_utf8) }));
+        methods.insert(Symbol::from("decode_hex"), Arc::new(Method::RustMethod { fun: Box::new(string_decode_hex) }));
+        methods.insert(Symbol::from("decode_base64"), Arc::new(Method::RustMethod { fun: Box::new(string_decode_base64) }));
+        methods.insert(Symbol::from("graphemes"), Arc::new(Method::RustMethod { fun: Box::new(string_graphemes) }));
+        methods.insert(Symbol::from("to_lowercase"), Arc::new(Method::RustMethod { fun: Box::new(string_to_lowercase) }));
+        methods.insert(Symbol::from("to_uppercase"), Arc::new(Method::RustMethod { fun: Box::new(string_to_uppercase) }));
+        methods.insert(Symbol::from("trim"), Arc::new(Method::RustMethod { fun: Box::new(string_trim) }));
+        methods.insert(Symbol::from("trim_start"), Arc::new(Method::RustMethod { fun: Box::new(string_trim_start) }));
+        methods.insert(Symbol::from("trim_end"), Arc::new(Method::RustMethod { fun: Box::new(string_trim_end) }));
+        methods.insert(Symbol::from("contains"), Arc::new(Method::RustMethod { fun: Box::new(string_contains) }));
+        methods.insert(Symbol::from("to_vector"), Arc::new(Method::RustMethod { fun: Box::new(string_to_vector) }));
+        methods.insert(Symbol::from("iterator"), Arc::new(Method::RustMethod { fun: Box::new(string_iterator) }));
+        methods.insert(Symbol::from("split"), Arc::new(Method::RustMethod { fun: Box::new(string_split) }));
+        methods.insert(Symbol::from("get"), Arc::new(Method::RustMethod { fun: Box::new(string_get) }));
+        methods.insert(Symbol::from("set"), Arc::new(Method::RustMethod { fun: Box::new(string_set) }));
+        methods.insert(Symbol::from("push"), Arc::new(Method::RustMethod { fun: Box::new(string_push) }));
+        methods.insert(Symbol::from("pop"), Arc::new(Method::RustMethod { fun: Box::new(string_pop) }));
+        methods.insert(Symbol::from("push_char"), Arc::new(Method::RustMethod { fun: Box::new(string_push_char) }));
+        methods.insert(Symbol::from("concat"), Arc::new(Method::RustMethod { fun: Box::new(string_concat) }));
+        methods.insert(Symbol::from("to_symbol"), Arc::new(Method::RustMethod { fun: Box::new(string_to_symbol) }));
+        methods.insert(Symbol::from("slice"), Arc::new(Method::RustMethod { fun: Box::new(string_slice) }));
+        methods.insert(Symbol::from("index_of"), Arc::new(Method::RustMethod { fun: Box::new(string_index_of) }));
+        methods.insert(Symbol::from("starts_with"), Arc::new(Method::RustMethod { fun: Box::new(string_starts_with) }));
+        methods.insert(Symbol::from("ends_with"), Arc::new(Method::RustMethod { fun: Box::new(string_ends_with) }));
+        methods.insert(Symbol::from("replace"), Arc::new(Method::RustMethod { fun: Box::new(string_replace) }));
+        methods.insert(Symbol::from("reverse"), Arc::new(Method::RustMethod { fun: Box::new(string_reverse) }));
+        methods.insert(Symbol::from("format"), Arc::new(Method::RustMethod { fun: Box::new(string_format) }));
+        super::compare::add_methods(&mut methods);
         VTable::new(methods)
     }
 }
@@ -70,23 +92,15 @@ impl Object for StringObject {
         Some(self.value.len())
     }
     fn duplicate(&self) -> ObjectBox {
-        let string = StringObject::make_object(self.super_object.clone().unwrap().borrow().duplicate(), self.value.clone());
+        let string = StringObject::make_object(self.super_object.clone().unwrap(), self.value.clone());
         let mut str_obj = string.borrow_mut();
         str_obj.initialize(Vec::new(), self.vtable.clone());
         drop(str_obj);
         string
     }
     fn initialize(&mut self, args: Vec<ObjectBox>, vtable: VTable) {
-        self.vtable.extend(StringObject::make_vtable());
         self.vtable.extend(vtable);
-        match &mut self.super_object {
-            Some(super_object) => {
-                let mut super_object = super_object.borrow_mut();
-                super_object.initialize(vec![], StringObject::make_object_vtable());
-            }
-            None => {}
-        }
-        if let Some(arg) = args.get(0) {
+        if let Some(arg) = args.first() {
             let arg = arg.borrow();
             if let Some(arg) = arg.downcast_ref::<VectorObject>() {
                 for value in arg.value.iter() {
@@ -104,7 +118,15 @@ fn string_equals(object: ObjectBox, context: &mut ContextData) -> Result<Option<
     let other = other.borrow();
     match (object.downcast_ref::<StringObject>(), other.downcast_ref::<StringObject>()) {
         (Some(obj), Some(other)) => Ok(Some(crate::object::create_boolean(obj.value == other.value))),
-        _ => Err(Fault::InvalidType(format!("String equals: Expected String")))
+        _ => Err(Fault::InvalidType("String equals: Expected String".to_string()))
+    }
+}
+
+fn string_hash(object: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let object = object.borrow();
+    match object.downcast_ref::<StringObject>() {
+        Some(obj) => Ok(Some(crate::object::create_u64(super::set::hash_of(&obj.value)))),
+        _ => Err(Fault::InvalidType("String hash: Expected String".to_string()))
     }
 }
 
@@ -112,7 +134,15 @@ fn string_to_string(object: ObjectBox, _: &mut ContextData) -> Result<Option<Obj
     let object = object.borrow();
     match object.downcast_ref::<StringObject>() {
         Some(obj) => Ok(Some(crate::object::create_string(obj.value.clone()))),
-        _ => Err(Fault::InvalidType(format!("String equals: Expected String")))
+        _ => Err(Fault::InvalidType("String equals: Expected String".to_string()))
+    }
+}
+
+fn string_to_symbol(object: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let object = object.borrow();
+    match object.downcast_ref::<StringObject>() {
+        Some(obj) => Ok(Some(crate::object::create_symbol(Symbol::intern(&obj.value)))),
+        _ => Err(Fault::InvalidType("String to_symbol: Expected String".to_string()))
     }
 }
 
@@ -121,24 +151,65 @@ fn string_order(object: ObjectBox, context: &mut ContextData) -> Result<Option<O
     let other = context.get_argument(0).unwrap();
     let other = other.borrow();
     match (object.downcast_ref::<StringObject>(), other.downcast_ref::<StringObject>()) {
-        (Some(obj), Some(other)) => Ok(Some(crate::object::create_boolean(obj.value < other.value))),
-        _ => Err(Fault::InvalidType(format!("String equals: Expected String")))
+        (Some(obj), Some(other)) => Ok(Some(crate::object::create_i8(obj.value.cmp(&other.value) as i8))),
+        _ => Err(Fault::InvalidType("String order: Expected String".to_string()))
     }
 }
 
 fn string_length(object: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
     let object = object.borrow();
     match object.downcast_ref::<StringObject>() {
-        Some(obj) => Ok(Some(crate::object::create_u64(obj.value.len() as u64))),
-        _ => Err(Fault::InvalidType(format!("String equals: Expected String")))
+        Some(obj) => Ok(Some(crate::object::create_u64(obj.value.chars().count() as u64))),
+        _ => Err(Fault::InvalidType("String length: Expected String".to_string()))
     }
 }
 
+fn string_byte_length(object: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let object = object.borrow();
+    let obj = object.downcast_ref::<StringObject>().ok_or(Fault::InvalidType("String byte_length: Expected String".to_string()))?;
+    Ok(Some(crate::object::create_u64(obj.value.len() as u64)))
+}
+
+fn string_bytes(object: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let object = object.borrow();
+    let obj = object.downcast_ref::<StringObject>().ok_or(Fault::InvalidType("String bytes: Expected String".to_string()))?;
+    let vec: Vec<ObjectBox> = obj.value.bytes().map(crate::object::create_u8).collect();
+    Ok(Some(crate::object::create_vector(vec)))
+}
+
+/// The UTF-8 bytes of the string as a ByteArray
+fn string_encode_utf8(object: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let object = object.borrow();
+    let obj = object.downcast_ref::<StringObject>().ok_or(Fault::InvalidType("String encode_utf8: Expected String".to_string()))?;
+    Ok(Some(crate::object::create_byte_array(obj.value.as_bytes().to_vec())))
+}
+
+/// The bytes written in the string as hex digits
+fn string_decode_hex(object: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let object = object.borrow();
+    let obj = object.downcast_ref::<StringObject>().ok_or(Fault::InvalidType("String decode_hex: Expected String".to_string()))?;
+    Ok(Some(crate::object::create_byte_array(super::bytes::from_hex(&obj.value, "String decode_hex")?)))
+}
+
+/// The bytes written in the string as base64
+fn string_decode_base64(object: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let object = object.borrow();
+    let obj = object.downcast_ref::<StringObject>().ok_or(Fault::InvalidType("String decode_base64: Expected String".to_string()))?;
+    Ok(Some(crate::object::create_byte_array(super::bytes::from_base64(&obj.value, "String decode_base64")?)))
+}
+
+fn string_graphemes(object: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let object = object.borrow();
+    let obj = object.downcast_ref::<StringObject>().ok_or(Fault::InvalidType("String graphemes: Expected String".to_string()))?;
+    let vec: Vec<ObjectBox> = obj.value.graphemes(true).map(|g| crate::object::create_string(g.to_string())).collect();
+    Ok(Some(crate::object::create_vector(vec)))
+}
+
 fn string_to_lowercase(object: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
     let object = object.borrow();
     match object.downcast_ref::<StringObject>() {
         Some(obj) => Ok(Some(crate::object::create_string(obj.value.to_lowercase()))),
-        _ => Err(Fault::InvalidType(format!("String equals: Expected String")))
+        _ => Err(Fault::InvalidType("String equals: Expected String".to_string()))
     }
 }
 
@@ -146,7 +217,7 @@ fn string_to_uppercase(object: ObjectBox, _: &mut ContextData) -> Result<Option<
     let object = object.borrow();
     match object.downcast_ref::<StringObject>() {
         Some(obj) => Ok(Some(crate::object::create_string(obj.value.to_uppercase()))),
-        _ => Err(Fault::InvalidType(format!("String equals: Expected String")))
+        _ => Err(Fault::InvalidType("String equals: Expected String".to_string()))
     }
 }
 
@@ -154,7 +225,7 @@ fn string_trim(object: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBo
     let object = object.borrow();
     match object.downcast_ref::<StringObject>() {
         Some(obj) => Ok(Some(crate::object::create_string(obj.value.trim().to_string()))),
-        _ => Err(Fault::InvalidType(format!("String equals: Expected String")))
+        _ => Err(Fault::InvalidType("String equals: Expected String".to_string()))
     }
 }
 
@@ -162,7 +233,7 @@ fn string_trim_start(object: ObjectBox, _: &mut ContextData) -> Result<Option<Ob
     let object = object.borrow();
     match object.downcast_ref::<StringObject>() {
         Some(obj) => Ok(Some(crate::object::create_string(obj.value.trim_start().to_string()))),
-        _ => Err(Fault::InvalidType(format!("String equals: Expected String")))
+        _ => Err(Fault::InvalidType("String equals: Expected String".to_string()))
     }
 }
 
@@ -170,7 +241,7 @@ fn string_trim_end(object: ObjectBox, _: &mut ContextData) -> Result<Option<Obje
     let object = object.borrow();
     match object.downcast_ref::<StringObject>() {
         Some(obj) => Ok(Some(crate::object::create_string(obj.value.trim_end().to_string()))),
-        _ => Err(Fault::InvalidType(format!("String equals: Expected String")))
+        _ => Err(Fault::InvalidType("String equals: Expected String".to_string()))
     }
 }
 
@@ -180,7 +251,7 @@ fn string_contains(object: ObjectBox, context: &mut ContextData) -> Result<Optio
     let other = other.borrow();
     match (object.downcast_ref::<StringObject>(), other.downcast_ref::<StringObject>()) {
         (Some(obj), Some(other)) => Ok(Some(crate::object::create_boolean(obj.value.contains(&other.value)))),
-        _ => Err(Fault::InvalidType(format!("String equals: Expected String")))
+        _ => Err(Fault::InvalidType("String equals: Expected String".to_string()))
     }
 }
 
@@ -188,14 +259,22 @@ fn string_to_vector(object: ObjectBox, context: &mut ContextData) -> Result<Opti
     let object = object.borrow();
     match object.downcast_ref::<StringObject>() {
         Some(obj) => {
-            let vec: Vec<ObjectBox> = obj.value.chars().map(|c| crate::object::create_character(c)).collect();
+            let vec: Vec<ObjectBox> = obj.value.chars().map(crate::object::create_character).collect();
             context.pop();
             Ok(Some(crate::object::create_vector(vec)))
         },
-        _ => Err(Fault::InvalidType(format!("String equals: Expected String")))
+        _ => Err(Fault::InvalidType("String equals: Expected String".to_string()))
     }
 }
 
+/// Iterate over the chars of the string
+fn string_iterator(object: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    if !object.borrow().is::<StringObject>() {
+        return Err(Fault::InvalidType("String iterator: Expected String".to_string()));
+    }
+    Ok(Some(super::iterator::over_chars(object)))
+}
+
 fn string_split(object: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
     let object = object.borrow();
     let separator = context.get_argument(0).unwrap();
@@ -205,47 +284,27 @@ fn string_split(object: ObjectBox, context: &mut ContextData) -> Result<Option<O
             let vec: Vec<ObjectBox> = obj.value.split(&separator.value).map(|s| crate::object::create_string(s.to_string())).collect();
             Ok(Some(crate::object::create_vector(vec)))
         },
-        _ => Err(Fault::InvalidType(format!("String equals: Expected String")))
+        _ => Err(Fault::InvalidType("String equals: Expected String".to_string()))
     }
 }
 
 fn string_get(object: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let index = index_argument(context, 0, "get")?;
     let object = object.borrow();
-    let index = context.get_argument(0).unwrap();
-    let index = index.borrow();
-    match (object.downcast_ref::<StringObject>(), index.downcast_ref::<PrimitiveObject<u64>>()) {
-        (Some(obj), Some(index)) => {
-            let index = index.data as usize;
-            if index < obj.value.len() {
-                Ok(Some(crate::object::create_character(obj.value.chars().nth(index).unwrap())))
-            } else {
-                Ok(None)
-            }
-        },
-        _ => Err(Fault::InvalidType(format!("String equals: Expected String")))
-    }
+    let obj = object.downcast_ref::<StringObject>().ok_or(Fault::InvalidType("String get: Expected String".to_string()))?;
+    let c = obj.value.chars().nth(index).ok_or_else(|| out_of_range("get", index, &obj.value))?;
+    Ok(Some(crate::object::create_character(c)))
 }
 
 fn string_set(object: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let index = index_argument(context, 0, "set")?;
+    let value = context.arguments[1].borrow().downcast_ref::<PrimitiveObject<char>>().map(|value| value.data)
+        .ok_or(Fault::InvalidType("String set: Expected Char".to_string()))?;
     let mut object = object.borrow_mut();
-    let index = context.get_argument(0).unwrap();
-    let index = index.borrow();
-    let value = context.get_argument(1).unwrap();
-    let value = value.borrow();
-    match (object.downcast_mut::<StringObject>(), index.downcast_ref::<PrimitiveObject<u64>>(), value.downcast_ref::<PrimitiveObject<char>>()) {
-        (Some(obj), Some(index), Some(value)) => {
-            let index = index.data as usize;
-            if index < obj.value.len() {
-                let mut new_string = obj.value.clone();
-                new_string.replace_range(index..index+1, &value.data.to_string());
-                obj.value = new_string;
-                Ok(None)
-            } else {
-                Ok(None)
-            }
-        },
-        _ => Err(Fault::InvalidType(format!("String equals: Expected String")))
-    }
+    let obj = object.downcast_mut::<StringObject>().ok_or(Fault::InvalidType("String set: Expected String".to_string()))?;
+    let (offset, old) = obj.value.char_indices().nth(index).ok_or_else(|| out_of_range("set", index, &obj.value))?;
+    obj.value.replace_range(offset..offset + old.len_utf8(), value.encode_utf8(&mut [0; 4]));
+    Ok(None)
 }
 
 fn string_push(object: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
@@ -257,7 +316,7 @@ fn string_push(object: ObjectBox, context: &mut ContextData) -> Result<Option<Ob
             obj.value.push(value.data);
             Ok(None)
         },
-        _ => Err(Fault::InvalidType(format!("String equals: Expected String")))
+        _ => Err(Fault::InvalidType("String equals: Expected String".to_string()))
     }
 }
 
@@ -271,7 +330,7 @@ fn string_pop(object: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox
                 Ok(None)
             }
         },
-        _ => Err(Fault::InvalidType(format!("String equals: Expected String")))
+        _ => Err(Fault::InvalidType("String equals: Expected String".to_string()))
     }
 }
 
@@ -284,7 +343,7 @@ fn string_push_char(object: ObjectBox, context: &mut ContextData) -> Result<Opti
             obj.value.push(value.data);
             Ok(None)
         },
-        _ => Err(Fault::InvalidType(format!("String equals: Expected String")))
+        _ => Err(Fault::InvalidType("String equals: Expected String".to_string()))
     }
 }
 
@@ -294,9 +353,174 @@ fn string_concat(object: ObjectBox, context: &mut ContextData) -> Result<Option<
     let other = other.borrow();
     match (object.downcast_ref::<StringObject>(), other.downcast_ref::<StringObject>()) {
         (Some(obj), Some(other)) => Ok(Some(crate::object::create_string(obj.value.clone() + &other.value))),
-        _ => Err(Fault::InvalidType(format!("String equals: Expected String")))
+        _ => Err(Fault::InvalidType("String equals: Expected String".to_string()))
     }
 }
 
 
 
+
+/// Get the argument at a position as a char index
+fn index_argument(context: &ContextData, position: usize, name: &str) -> Result<usize, Fault> {
+    let index = integer_argument(&context.arguments[position], &format!("String {}", name))?;
+    usize::try_from(index).map_err(|_| Fault::InvalidOperation(format!("String {}: index {} is negative", name, index)))
+}
+
+/// Get the argument at a position as text, a Char is a string of one char
+fn text_argument(context: &ContextData, position: usize, name: &str) -> Result<String, Fault> {
+    let argument = context.arguments[position].borrow();
+    if let Some(string) = argument.downcast_ref::<StringObject>() {
+        Ok(string.value.clone())
+    } else if let Some(c) = argument.downcast_ref::<PrimitiveObject<char>>() {
+        Ok(c.data.to_string())
+    } else {
+        Err(Fault::InvalidType(format!("String {}: Expected String or Char", name)))
+    }
+}
+
+fn out_of_range(name: &str, index: usize, value: &str) -> Fault {
+    Fault::InvalidOperation(format!("String {}: index {} is out of range for length {}", name, index, value.chars().count()))
+}
+
+/// Get the byte offset of a char index, the length of the string is the offset of its end
+fn byte_offset(name: &str, index: usize, value: &str) -> Result<usize, Fault> {
+    value.char_indices().map(|(offset, _)| offset).chain(std::iter::once(value.len())).nth(index)
+        .ok_or_else(|| out_of_range(name, index, value))
+}
+
+/// The chars from the first index up to but not including the second
+fn string_slice(object: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let start = index_argument(context, 0, "slice")?;
+    let end = index_argument(context, 1, "slice")?;
+    if start > end {
+        return Err(Fault::InvalidOperation(format!("String slice: start {} is after end {}", start, end)));
+    }
+    let object = object.borrow();
+    let obj = object.downcast_ref::<StringObject>().ok_or(Fault::InvalidType("String slice: Expected String".to_string()))?;
+    let (start, end) = (byte_offset("slice", start, &obj.value)?, byte_offset("slice", end, &obj.value)?);
+    Ok(Some(crate::object::create_string(obj.value[start..end].to_string())))
+}
+
+/// The char index of the first occurrence of a string or a char, Nil if there isn't one
+fn string_index_of(object: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let pattern = text_argument(context, 0, "index_of")?;
+    let object = object.borrow();
+    let obj = object.downcast_ref::<StringObject>().ok_or(Fault::InvalidType("String index_of: Expected String".to_string()))?;
+    match obj.value.find(&pattern) {
+        Some(offset) => Ok(Some(crate::object::create_u64(obj.value[..offset].chars().count() as u64))),
+        None => Ok(Some(super::Nil::new())),
+    }
+}
+
+fn string_starts_with(object: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let pattern = text_argument(context, 0, "starts_with")?;
+    let object = object.borrow();
+    let obj = object.downcast_ref::<StringObject>().ok_or(Fault::InvalidType("String starts_with: Expected String".to_string()))?;
+    Ok(Some(crate::object::create_boolean(obj.value.starts_with(&pattern))))
+}
+
+fn string_ends_with(object: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let pattern = text_argument(context, 0, "ends_with")?;
+    let object = object.borrow();
+    let obj = object.downcast_ref::<StringObject>().ok_or(Fault::InvalidType("String ends_with: Expected String".to_string()))?;
+    Ok(Some(crate::object::create_boolean(obj.value.ends_with(&pattern))))
+}
+
+/// Replace every occurrence of the first argument with the second
+fn string_replace(object: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let from = text_argument(context, 0, "replace")?;
+    let to = text_argument(context, 1, "replace")?;
+    let object = object.borrow();
+    let obj = object.downcast_ref::<StringObject>().ok_or(Fault::InvalidType("String replace: Expected String".to_string()))?;
+    Ok(Some(crate::object::create_string(obj.value.replace(&from, &to))))
+}
+
+/// Reverse the grapheme clusters so that combining marks stay on their characters
+fn string_reverse(object: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let object = object.borrow();
+    let obj = object.downcast_ref::<StringObject>().ok_or(Fault::InvalidType("String reverse: Expected String".to_string()))?;
+    Ok(Some(crate::object::create_string(obj.value.graphemes(true).rev().collect())))
+}
+
+/// Put the elements of a Vector into the placeholders of the string
+fn string_format(object: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let arguments = context.arguments[0].borrow().downcast_ref::<VectorObject>().map(|vector| vector.value.to_vec())
+        .ok_or(Fault::InvalidType("String format: Expected Vector".to_string()))?;
+    let template = object.borrow().downcast_ref::<StringObject>().map(|string| string.value.clone())
+        .ok_or(Fault::InvalidType("String format: Expected String".to_string()))?;
+    Ok(Some(crate::object::create_string(super::format::format(&template, &arguments)?)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::object::value::Immediate;
+    use crate::object::{create_character, create_i64, create_string};
+
+    fn send(receiver: &ObjectBox, selector: &str, arguments: Vec<ObjectBox>) -> Result<Option<ObjectBox>, Fault> {
+        let method = receiver.borrow().lookup_method(Symbol::from(selector)).unwrap();
+        method.evaluate(receiver.clone(), arguments)
+    }
+
+    fn text(object: &ObjectBox) -> String {
+        object.borrow().downcast_ref::<StringObject>().unwrap().value.clone()
+    }
+
+    fn texts(vector: &ObjectBox) -> Vec<String> {
+        vector.borrow().downcast_ref::<VectorObject>().unwrap().value.iter().map(text).collect()
+    }
+
+    fn immediate(object: Option<ObjectBox>) -> Option<Immediate> {
+        Immediate::from_object(&*object.unwrap().borrow())
+    }
+
+    #[test]
+    fn indexes_and_lengths_count_chars() {
+        let string = create_string("naïve ☕".to_string());
+        assert_eq!(immediate(send(&string, "length", Vec::new()).unwrap()), Some(Immediate::U64(7)));
+        assert_eq!(immediate(send(&string, "byte_length", Vec::new()).unwrap()), Some(Immediate::U64(10)));
+        assert_eq!(immediate(send(&string, "get", vec![create_i64(2)]).unwrap()), Some(Immediate::Char('ï')));
+        assert_eq!(immediate(send(&string, "get", vec![create_i64(6)]).unwrap()), Some(Immediate::Char('☕')));
+        assert_eq!(immediate(send(&string, "index_of", vec![create_character('☕')]).unwrap()), Some(Immediate::U64(6)));
+    }
+
+    #[test]
+    fn setting_a_char_can_change_its_width() {
+        let string = create_string("año".to_string());
+        send(&string, "set", vec![create_i64(1), create_character('n')]).unwrap();
+        assert_eq!(text(&string), "ano");
+        send(&string, "set", vec![create_i64(2), create_character('😀')]).unwrap();
+        assert_eq!(text(&string), "an😀");
+    }
+
+    #[test]
+    fn slices_are_by_char() {
+        let string = create_string("日本語です".to_string());
+        let slice = send(&string, "slice", vec![create_i64(1), create_i64(3)]).unwrap().unwrap();
+        assert_eq!(text(&slice), "本語");
+        let slice = send(&string, "slice", vec![create_i64(5), create_i64(5)]).unwrap().unwrap();
+        assert_eq!(text(&slice), "");
+        assert!(send(&string, "slice", vec![create_i64(3), create_i64(1)]).is_err());
+        assert!(send(&string, "slice", vec![create_i64(0), create_i64(6)]).is_err());
+    }
+
+    #[test]
+    fn indexes_out_of_range_fault() {
+        let string = create_string("é".to_string());
+        assert!(matches!(send(&string, "get", vec![create_i64(1)]), Err(Fault::InvalidOperation(_))));
+        assert!(matches!(send(&string, "get", vec![create_i64(-1)]), Err(Fault::InvalidOperation(_))));
+        assert!(matches!(send(&string, "set", vec![create_i64(1), create_character('e')]), Err(Fault::InvalidOperation(_))));
+        assert_eq!(text(&string), "é");
+    }
+
+    #[test]
+    fn graphemes_keep_combining_marks() {
+        // An e with a combining acute accent is two chars but one grapheme
+        let string = create_string("ne\u{301}e 👍🏽".to_string());
+        let graphemes = send(&string, "graphemes", Vec::new()).unwrap().unwrap();
+        assert_eq!(texts(&graphemes), ["n", "e\u{301}", "e", " ", "👍🏽"]);
+        assert_eq!(immediate(send(&string, "length", Vec::new()).unwrap()), Some(Immediate::U64(7)));
+        let reversed = send(&string, "reverse", Vec::new()).unwrap().unwrap();
+        assert_eq!(text(&reversed), "👍🏽 ee\u{301}n");
+    }
+}
@@ -0,0 +1,122 @@
+//! Interned symbols
+//! Selectors and other names are interned once into a global table so that they can be compared
+//! and hashed as integers instead of strings.
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use lazy_static::lazy_static;
+
+/// Symbol
+/// An interned string. Two symbols are equal exactly when their names are equal.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Symbol(u32);
+
+macro_rules! predefined_symbols {
+    ($($name:ident = $string:literal),* $(,)?) => {
+        /// Symbols that are known at compile time so that Rust code can match on them
+        pub mod predefined {
+            use super::Symbol;
+            predefined_symbols!(@consts 0u32, $($name,)*);
+        }
+        const PREDEFINED: &[&str] = &[$($string),*];
+    };
+    (@consts $index:expr, $name:ident, $($rest:ident,)*) => {
+        pub const $name: Symbol = Symbol($index);
+        predefined_symbols!(@consts $index + 1u32, $($rest,)*);
+    };
+    (@consts $index:expr,) => {};
+}
+
+predefined_symbols! {
+    ADD = "add",
+    SUB = "sub",
+    MUL = "mul",
+    DIV = "div",
+    MOD = "mod",
+    POW = "pow",
+    ABS = "abs",
+    IS_ZERO = "is_zero",
+    EQUALS = "equals",
+    ORDER = "order",
+    NOT_EQUALS = "not_equals",
+    LESS_THAN = "less_than",
+    LESS_EQUAL = "less_equal",
+    GREATER_THAN = "greater_than",
+    GREATER_EQUAL = "greater_equal",
+    MIN = "min",
+    MAX = "max",
+}
+
+lazy_static! {
+    static ref SYMBOLS: RwLock<SymbolTable> = RwLock::new(SymbolTable::new());
+}
+
+/// SymbolTable
+/// The names are leaked since symbols live for the rest of the program anyway.
+struct SymbolTable {
+    names: Vec<&'static str>,
+    symbols: HashMap<&'static str, Symbol>,
+}
+
+impl SymbolTable {
+    fn new() -> SymbolTable {
+        let mut table = SymbolTable {
+            names: Vec::new(),
+            symbols: HashMap::new(),
+        };
+        for name in PREDEFINED {
+            table.insert(name);
+        }
+        table
+    }
+
+    fn insert(&mut self, name: &str) -> Symbol {
+        let name: &'static str = Box::leak(name.to_string().into_boxed_str());
+        let symbol = Symbol(self.names.len() as u32);
+        self.names.push(name);
+        self.symbols.insert(name, symbol);
+        symbol
+    }
+}
+
+impl Symbol {
+    /// Get the symbol for a name, adding it to the table if it isn't there yet
+    pub fn intern(name: &str) -> Symbol {
+        if let Some(symbol) = SYMBOLS.read().expect("Symbol::intern: lock poisoned").symbols.get(name) {
+            return *symbol;
+        }
+        let mut table = SYMBOLS.write().expect("Symbol::intern: lock poisoned");
+        match table.symbols.get(name) {
+            Some(symbol) => *symbol,
+            None => table.insert(name),
+        }
+    }
+
+    /// Get the name of the symbol
+    pub fn as_str(&self) -> &'static str {
+        SYMBOLS.read().expect("Symbol::as_str: lock poisoned").names[self.0 as usize]
+    }
+
+    /// The number that identifies the symbol
+    pub fn id(&self) -> u32 {
+        self.0
+    }
+}
+
+impl From<&str> for Symbol {
+    fn from(name: &str) -> Self {
+        Symbol::intern(name)
+    }
+}
+
+impl std::fmt::Display for Symbol {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl std::fmt::Debug for Symbol {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "#{}", self.as_str())
+    }
+}
@@ -6,6 +6,7 @@ use crate::SEND_CHANNEL;
 
 use super::{Fault, Object, ObjectBox};
 use crate::object::VTable;
+use crate::object::symbol::Symbol;
 
 
 
@@ -24,7 +25,12 @@ impl System {
     }
     pub fn make_vtable() -> VTable {
         let mut methods = HashMap::new();
-        methods.insert("spawn".to_string(), Arc::new(Method::RustMethod { fun: Box::new(system_spawn)}));
+        methods.insert(Symbol::from("spawn"), Arc::new(Method::RustMethod { fun: Box::new(system_spawn)}));
+        methods.insert(Symbol::from("collect_garbage"), Arc::new(Method::RustMethod { fun: Box::new(system_collect_garbage)}));
+        methods.insert(Symbol::from("heap_size"), Arc::new(Method::RustMethod { fun: Box::new(system_heap_size)}));
+        methods.insert(Symbol::from("collected_objects"), Arc::new(Method::RustMethod { fun: Box::new(system_collected_objects)}));
+        methods.insert(Symbol::from("register_finalizer"), Arc::new(Method::RustMethod { fun: Box::new(system_register_finalizer)}));
+        methods.insert(Symbol::from("save_image"), Arc::new(Method::RustMethod { fun: Box::new(system_save_image)}));
         VTable::new(methods)
     }
 }
@@ -46,23 +52,14 @@ impl Object for System {
         None
     }
     fn duplicate(&self) -> ObjectBox {
-        let object = System::make_object(self.super_object.clone().unwrap().borrow().duplicate());
+        let object = System::make_object(self.super_object.clone().unwrap());
         let mut object_mut = object.borrow_mut();
         object_mut.initialize(vec![], self.vtable.clone());
         drop(object_mut);
         object
     }
     fn initialize(&mut self, _args: Vec<ObjectBox>, vtable: VTable) {
-        self.vtable.extend(System::make_vtable());
         self.vtable.extend(vtable);
-        match self.super_object.clone() {
-            Some(super_object) => {
-                let mut super_object = super_object.borrow_mut();
-                super_object.initialize(vec![], self.vtable.clone());
-                drop(super_object);
-            }
-            None => {}
-        }
     }
 }
 
@@ -70,7 +67,7 @@ impl Object for System {
 fn system_spawn(_: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
     let block = context.arguments[0].clone();
     let block = block.borrow();
-    let block = block.downcast_ref::<Block>().ok_or(Fault::InvalidType(format!("System spawn: argument was not a Block")))?;
+    let block = block.downcast_ref::<Block>().ok_or(Fault::InvalidType("System spawn: argument was not a Block".to_string()))?;
     let mut new_context = ContextData::new(super::init_stack());
     for (i, capture) in block.captures.iter().enumerate() {
         new_context.set_argument(i, capture.clone())
@@ -81,3 +78,34 @@ fn system_spawn(_: ObjectBox, context: &mut ContextData) -> Result<Option<Object
     Ok(None)
 }
 
+
+/// Collection can't happen while other cores are running so this only asks the scheduler to
+/// collect at the next context switch.
+fn system_collect_garbage(_: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    super::gc::request_collection();
+    Ok(None)
+}
+
+fn system_heap_size(_: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    Ok(Some(super::create_u64(super::gc::heap_size() as u64)))
+}
+
+fn system_collected_objects(_: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    Ok(Some(super::create_u64(super::gc::total_freed() as u64)))
+}
+
+/// The argument gets sent finalize in its own task once nothing else refers to it
+fn system_register_finalizer(_: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let object = context.get_argument(0).ok_or(Fault::InvalidOperation("System register_finalizer: Expected object".to_string()))?;
+    super::gc::register_finalizer(&object);
+    Ok(None)
+}
+
+/// The image is saved the next time the scheduler stops every core
+fn system_save_image(_: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let path = context.get_argument(0).ok_or(Fault::InvalidOperation("System save_image: Expected path".to_string()))?;
+    let path = path.borrow();
+    let path = path.downcast_ref::<super::string::StringObject>().ok_or(Fault::InvalidType("System save_image: Expected String".to_string()))?;
+    crate::vm::image::request_save(path.value.clone());
+    Ok(None)
+}
@@ -0,0 +1,319 @@
+//! Unboxed values
+//! Integers, floats, booleans and characters are small enough to live directly on the runtime
+//! stack. They only get turned into an ObjectBox when they escape it, e.g. when they are stored
+//! in a field or a temporary, or when a message is sent to them that can't be handled inline.
+use num_traits::{CheckedRem, Float, PrimInt};
+
+use super::{Fault, Object, ObjectBox};
+use super::primitive::PrimitiveObject;
+use super::primitive::convert;
+use super::symbol::Symbol;
+use super::symbol::predefined::*;
+
+/// Immediate
+/// A primitive value that is stored inline instead of behind an ObjectBox.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Immediate {
+    I8(i8),
+    I16(i16),
+    I32(i32),
+    I64(i64),
+    U8(u8),
+    U16(u16),
+    U32(u32),
+    U64(u64),
+    F32(f32),
+    F64(f64),
+    Boolean(bool),
+    Char(char),
+}
+
+/// Value
+/// A slot on the runtime stack.
+#[derive(Clone)]
+pub enum Value {
+    Object(ObjectBox),
+    Immediate(Immediate),
+}
+
+impl Value {
+    /// Get the value as an object, boxing it if it is an immediate
+    pub fn into_object(self) -> ObjectBox {
+        match self {
+            Value::Object(object) => object,
+            Value::Immediate(immediate) => immediate.into_object(),
+        }
+    }
+
+    pub fn as_immediate(&self) -> Option<Immediate> {
+        match self {
+            Value::Object(_) => None,
+            Value::Immediate(immediate) => Some(*immediate),
+        }
+    }
+}
+
+impl From<ObjectBox> for Value {
+    fn from(object: ObjectBox) -> Self {
+        Value::Object(object)
+    }
+}
+
+impl From<Immediate> for Value {
+    fn from(immediate: Immediate) -> Self {
+        Value::Immediate(immediate)
+    }
+}
+
+/// Reply
+/// What to do with the result of a message that was handled inline.
+/// This follows what the boxed methods do with their results.
+pub enum Reply {
+    /// The receiver gets replaced with the result like arithmetic does in place
+    Replace(Immediate),
+    /// The result gets pushed on top of the receiver
+    Push(Immediate),
+}
+
+impl Immediate {
+    /// Get the value of a boxed primitive
+    pub fn from_object(object: &dyn Object) -> Option<Immediate> {
+        macro_rules! try_immediate {
+            ($($type:ty => $variant:ident),*) => {
+                $(if let Some(object) = object.downcast_ref::<PrimitiveObject<$type>>() {
+                    return Some(Immediate::$variant(object.data));
+                })*
+            };
+        }
+        try_immediate!(i8 => I8, i16 => I16, i32 => I32, i64 => I64, u8 => U8, u16 => U16, u32 => U32,
+                       u64 => U64, f32 => F32, f64 => F64, bool => Boolean, char => Char);
+        None
+    }
+
+    /// Box the value into a heap object
+    pub fn into_object(self) -> ObjectBox {
+        match self {
+            Immediate::I8(value) => super::create_i8(value),
+            Immediate::I16(value) => super::create_i16(value),
+            Immediate::I32(value) => super::create_i32(value),
+            Immediate::I64(value) => super::create_i64(value),
+            Immediate::U8(value) => super::create_u8(value),
+            Immediate::U16(value) => super::create_u16(value),
+            Immediate::U32(value) => super::create_u32(value),
+            Immediate::U64(value) => super::create_u64(value),
+            Immediate::F32(value) => super::create_f32(value),
+            Immediate::F64(value) => super::create_f64(value),
+            Immediate::Boolean(value) => super::create_boolean(value),
+            Immediate::Char(value) => super::create_character(value),
+        }
+    }
+
+    /// Handle a message without boxing the receiver or the argument.
+    /// This only covers messages where both sides have the same type.
+    /// None means the message has to go through the regular method lookup.
+    pub fn send(self, selector: Symbol, argument: Option<Immediate>) -> Option<Result<Reply, Fault>> {
+        use Immediate::*;
+        let reply = match (self, argument) {
+            (I8(a), None) => integer_unary(a, selector),
+            (I16(a), None) => integer_unary(a, selector),
+            (I32(a), None) => integer_unary(a, selector),
+            (I64(a), None) => integer_unary(a, selector),
+            (U8(a), None) => integer_unary(a, selector),
+            (U16(a), None) => integer_unary(a, selector),
+            (U32(a), None) => integer_unary(a, selector),
+            (U64(a), None) => integer_unary(a, selector),
+            (F32(a), None) => float_unary(a, selector),
+            (F64(a), None) => float_unary(a, selector),
+            (I8(a), Some(I8(b))) => integer_binary(a, b, selector),
+            (I16(a), Some(I16(b))) => integer_binary(a, b, selector),
+            (I32(a), Some(I32(b))) => integer_binary(a, b, selector),
+            (I64(a), Some(I64(b))) => integer_binary(a, b, selector),
+            (U8(a), Some(U8(b))) => integer_binary(a, b, selector),
+            (U16(a), Some(U16(b))) => integer_binary(a, b, selector),
+            (U32(a), Some(U32(b))) => integer_binary(a, b, selector),
+            (U64(a), Some(U64(b))) => integer_binary(a, b, selector),
+            (F32(a), Some(F32(b))) => float_binary(a, b, selector),
+            (F64(a), Some(F64(b))) => float_binary(a, b, selector),
+            (Boolean(a), Some(Boolean(b))) => base_binary(a, b, selector),
+            (Char(a), Some(Char(b))) => base_binary(a, b, selector),
+            _ => None,
+        };
+        if reply.is_some() || argument.is_some() {
+            return reply;
+        }
+        let (target, conversion) = convert::conversion(selector)?;
+        Some(convert::convert(self, target, conversion).map(Reply::Push))
+    }
+}
+
+trait Inline: Copy + PartialOrd {
+    fn wrap(self) -> Immediate;
+}
+
+trait InlineInteger: Inline + PrimInt + CheckedRem {
+    fn magnitude(self) -> Option<Self>;
+}
+
+macro_rules! inline_impl {
+    ($type:ty, $variant:ident) => {
+        impl Inline for $type {
+            fn wrap(self) -> Immediate {
+                Immediate::$variant(self)
+            }
+        }
+    };
+}
+
+macro_rules! inline_integer_impl {
+    ($type:ty, $variant:ident, signed) => {
+        inline_impl!($type, $variant);
+        impl InlineInteger for $type {
+            fn magnitude(self) -> Option<Self> {
+                self.checked_abs()
+            }
+        }
+    };
+    ($type:ty, $variant:ident, unsigned) => {
+        inline_impl!($type, $variant);
+        impl InlineInteger for $type {
+            fn magnitude(self) -> Option<Self> {
+                Some(self)
+            }
+        }
+    };
+}
+
+inline_integer_impl!(i8, I8, signed);
+inline_integer_impl!(i16, I16, signed);
+inline_integer_impl!(i32, I32, signed);
+inline_integer_impl!(i64, I64, signed);
+inline_integer_impl!(u8, U8, unsigned);
+inline_integer_impl!(u16, U16, unsigned);
+inline_integer_impl!(u32, U32, unsigned);
+inline_integer_impl!(u64, U64, unsigned);
+inline_impl!(f32, F32);
+inline_impl!(f64, F64);
+inline_impl!(bool, Boolean);
+inline_impl!(char, Char);
+
+fn base_binary<T: Inline>(a: T, b: T, selector: Symbol) -> Option<Result<Reply, Fault>> {
+    // Unordered floats count as equal like they do for order
+    let ordering = if a < b {
+        std::cmp::Ordering::Less
+    } else if a > b {
+        std::cmp::Ordering::Greater
+    } else {
+        std::cmp::Ordering::Equal
+    };
+    let result = match selector {
+        EQUALS => Immediate::Boolean(a == b),
+        NOT_EQUALS => Immediate::Boolean(a != b),
+        ORDER => Immediate::I8(ordering as i8),
+        LESS_THAN => Immediate::Boolean(ordering.is_lt()),
+        LESS_EQUAL => Immediate::Boolean(ordering.is_le()),
+        GREATER_THAN => Immediate::Boolean(ordering.is_gt()),
+        GREATER_EQUAL => Immediate::Boolean(ordering.is_ge()),
+        MIN => if ordering.is_gt() { b.wrap() } else { a.wrap() },
+        MAX => if ordering.is_lt() { b.wrap() } else { a.wrap() },
+        _ => return None,
+    };
+    Some(Ok(Reply::Push(result)))
+}
+
+fn integer_unary<T: InlineInteger>(a: T, selector: Symbol) -> Option<Result<Reply, Fault>> {
+    match selector {
+        ABS => Some(a.magnitude().map(|a| Reply::Replace(a.wrap())).ok_or_else(|| overflow::<T>("abs"))),
+        IS_ZERO => Some(Ok(Reply::Push(Immediate::Boolean(a.is_zero())))),
+        _ => None,
+    }
+}
+
+fn overflow<T>(name: &str) -> Fault {
+    Fault::Overflow(format!("Number {}: {} overflowed", name, std::any::type_name::<T>()))
+}
+
+fn integer_binary<T: InlineInteger>(a: T, b: T, selector: Symbol) -> Option<Result<Reply, Fault>> {
+    let result = match selector {
+        ADD => a.checked_add(&b).ok_or_else(|| overflow::<T>("add")),
+        SUB => a.checked_sub(&b).ok_or_else(|| overflow::<T>("sub")),
+        MUL => a.checked_mul(&b).ok_or_else(|| overflow::<T>("mul")),
+        DIV | MOD if b.is_zero() => return Some(Err(Fault::DivideByZero)),
+        DIV => a.checked_div(&b).ok_or_else(|| overflow::<T>("div")),
+        MOD => a.checked_rem(&b).ok_or_else(|| overflow::<T>("mod")),
+        POW => match b.to_u32() {
+            Some(exponent) => num_traits::checked_pow(a, exponent as usize).ok_or_else(|| overflow::<T>("pow")),
+            None => Err(Fault::Overflow(format!("Number pow: exponent {} is not between 0 and {}", b.to_i128().unwrap_or_default(), u32::MAX))),
+        },
+        _ => return base_binary(a, b, selector),
+    };
+    Some(result.map(|result| Reply::Replace(result.wrap())))
+}
+
+fn float_unary<T: Inline + Float>(a: T, selector: Symbol) -> Option<Result<Reply, Fault>> {
+    match selector {
+        ABS => Some(Ok(Reply::Replace(a.abs().wrap()))),
+        IS_ZERO => Some(Ok(Reply::Push(Immediate::Boolean(a.is_zero())))),
+        _ => None,
+    }
+}
+
+fn float_binary<T: Inline + Float>(a: T, b: T, selector: Symbol) -> Option<Result<Reply, Fault>> {
+    let result = match selector {
+        ADD => a + b,
+        MUL => a * b,
+        DIV | MOD if b.is_zero() => return Some(Err(Fault::DivideByZero)),
+        DIV => a / b,
+        MOD => a % b,
+        POW => a.powf(b),
+        // The boxed float sub puts the argument on the left so leave it to that
+        SUB => return None,
+        _ => return base_binary(a, b, selector),
+    };
+    Some(Ok(Reply::Replace(result.wrap())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Immediate::*;
+
+    fn send(receiver: Immediate, selector: Symbol, argument: Option<Immediate>) -> Result<Immediate, Fault> {
+        match receiver.send(selector, argument).expect("handled inline")? {
+            Reply::Replace(result) | Reply::Push(result) => Ok(result),
+        }
+    }
+
+    #[test]
+    fn integer_arithmetic_stops_at_the_edges_of_its_type() {
+        assert_eq!(send(I8(i8::MAX - 1), ADD, Some(I8(1))).unwrap(), I8(i8::MAX));
+        assert!(matches!(send(I8(i8::MAX), ADD, Some(I8(1))), Err(Fault::Overflow(_))));
+        assert_eq!(send(U8(1), SUB, Some(U8(1))).unwrap(), U8(0));
+        assert!(matches!(send(U8(0), SUB, Some(U8(1))), Err(Fault::Overflow(_))));
+        assert!(matches!(send(I64(i64::MAX / 2 + 1), MUL, Some(I64(2))), Err(Fault::Overflow(_))));
+        assert!(matches!(send(I32(i32::MIN), DIV, Some(I32(-1))), Err(Fault::Overflow(_))));
+        assert!(matches!(send(I32(i32::MIN), MOD, Some(I32(-1))), Err(Fault::Overflow(_))));
+        assert!(matches!(send(I16(i16::MIN), ABS, None), Err(Fault::Overflow(_))));
+        assert_eq!(send(I16(i16::MIN + 1), ABS, None).unwrap(), I16(i16::MAX));
+    }
+
+    #[test]
+    fn powers_check_the_result_and_the_exponent() {
+        assert_eq!(send(U16(2), POW, Some(U16(15))).unwrap(), U16(1 << 15));
+        assert!(matches!(send(U16(2), POW, Some(U16(16))), Err(Fault::Overflow(_))));
+        assert!(matches!(send(I64(1), POW, Some(I64(-1))), Err(Fault::Overflow(_))));
+        assert!(matches!(send(U64(1), POW, Some(U64(u32::MAX as u64 + 1))), Err(Fault::Overflow(_))));
+    }
+
+    #[test]
+    fn dividing_by_zero_is_not_an_overflow() {
+        assert!(matches!(send(U32(1), DIV, Some(U32(0))), Err(Fault::DivideByZero)));
+        assert!(matches!(send(I8(1), MOD, Some(I8(0))), Err(Fault::DivideByZero)));
+        assert!(matches!(send(F64(1.0), DIV, Some(F64(0.0))), Err(Fault::DivideByZero)));
+    }
+
+    #[test]
+    fn mixed_types_go_through_the_method_lookup() {
+        assert!(I8(1).send(ADD, Some(I64(1))).is_none());
+        assert!(F64(1.0).send(SUB, Some(F64(1.0))).is_none());
+    }
+}
@@ -1,6 +1,8 @@
+use std::cmp::Ordering;
 use std::collections::HashMap;
 use std::sync::Arc;
-use super::{block::Block, ContextData, Fault, Object, ObjectBox, PrimitiveObject, VTable, Method};
+use super::{block::Block, ContextData, Fault, Object, ObjectBox, PrimitiveObject, VTable, Method, Nil};
+use super::symbol::Symbol;
 
 
 
@@ -24,13 +26,25 @@ impl VectorObject {
     }
     pub fn make_vtable() -> VTable {
         let mut methods = HashMap::new();
-        methods.insert(String::from("length"), Arc::new(Method::RustMethod { fun: Box::new(vector_length) }));
-        methods.insert(String::from("get"), Arc::new(Method::RustMethod { fun: Box::new(vector_get) }));
-        methods.insert(String::from("set"), Arc::new(Method::RustMethod { fun: Box::new(vector_set) }));
-        methods.insert(String::from("map"), Arc::new(Method::RustMethod { fun: Box::new(vector_map) }));
-        methods.insert(String::from("fold"), Arc::new(Method::RustMethod { fun: Box::new(vector_fold) }));
-        methods.insert(String::from("sort"), Arc::new(Method::RustMethod { fun: Box::new(vector_sort) }));
-        methods.insert(String::from("concat"), Arc::new(Method::RustMethod { fun: Box::new(vector_concat) }));
+        methods.insert(Symbol::from("length"), Arc::new(Method::RustMethod { fun: Box::new(vector_length) }));
+        methods.insert(Symbol::from("get"), Arc::new(Method::RustMethod { fun: Box::new(vector_get) }));
+        methods.insert(Symbol::from("set"), Arc::new(Method::RustMethod { fun: Box::new(vector_set) }));
+        methods.insert(Symbol::from("map"), Arc::new(Method::RustMethod { fun: Box::new(vector_map) }));
+        methods.insert(Symbol::from("fold"), Arc::new(Method::RustMethod { fun: Box::new(vector_fold) }));
+        methods.insert(Symbol::from("sort"), Arc::new(Method::RustMethod { fun: Box::new(vector_sort) }));
+        methods.insert(Symbol::from("sort_unstable"), Arc::new(Method::RustMethod { fun: Box::new(vector_sort_unstable) }));
+        methods.insert(Symbol::from("sort_by"), Arc::new(Method::RustMethod { fun: Box::new(vector_sort_by) }));
+        methods.insert(Symbol::from("sort_unstable_by"), Arc::new(Method::RustMethod { fun: Box::new(vector_sort_unstable_by) }));
+        methods.insert(Symbol::from("sort_by_key"), Arc::new(Method::RustMethod { fun: Box::new(vector_sort_by_key) }));
+        methods.insert(Symbol::from("sort_unstable_by_key"), Arc::new(Method::RustMethod { fun: Box::new(vector_sort_unstable_by_key) }));
+        methods.insert(Symbol::from("reverse"), Arc::new(Method::RustMethod { fun: Box::new(vector_reverse) }));
+        methods.insert(Symbol::from("binary_search"), Arc::new(Method::RustMethod { fun: Box::new(vector_binary_search) }));
+        methods.insert(Symbol::from("min"), Arc::new(Method::RustMethod { fun: Box::new(vector_min) }));
+        methods.insert(Symbol::from("max"), Arc::new(Method::RustMethod { fun: Box::new(vector_max) }));
+        methods.insert(Symbol::from("dedup"), Arc::new(Method::RustMethod { fun: Box::new(vector_dedup) }));
+        methods.insert(Symbol::from("concat"), Arc::new(Method::RustMethod { fun: Box::new(vector_concat) }));
+        methods.insert(Symbol::from("to_list"), Arc::new(Method::RustMethod { fun: Box::new(vector_to_list) }));
+        methods.insert(Symbol::from("iterator"), Arc::new(Method::RustMethod { fun: Box::new(vector_iterator) }));
         VTable::new(methods)
     }
 }
@@ -44,7 +58,7 @@ impl Object for VectorObject {
         self.super_object.clone()
     }
     fn get_field(&self, index: usize) -> Option<ObjectBox> {
-        Some(self.value[index].clone())
+        self.value.get(index).cloned()
     }
     fn set_field(&mut self, index: usize, value: ObjectBox) {
         self.value[index] = value;
@@ -53,66 +67,74 @@ impl Object for VectorObject {
         Some(self.value.len())
     }
     fn duplicate(&self) -> ObjectBox {
-        let vector = VectorObject::make_object(self.super_object.clone().unwrap().borrow().duplicate(), self.value.clone());
+        let vector = VectorObject::make_object(self.super_object.clone().unwrap(), self.value.clone());
         let mut vec_obj = vector.borrow_mut();
         vec_obj.initialize(Vec::new(), self.vtable.clone());
         drop(vec_obj);
         vector
     }
     fn initialize(&mut self, args: Vec<ObjectBox>, vtable: VTable) {
-        self.vtable.extend(VectorObject::make_vtable());
         self.vtable.extend(vtable);
-        match &mut self.super_object {
-            Some(super_object) => {
-                super_object.borrow_mut().initialize(Vec::new(), self.vtable.clone());
-            }
-            None => {}
+        if let Some(arg) = args.first() {
+            let arg = arg.borrow();
+            let arg = arg.downcast_ref::<PrimitiveObject<u64>>().unwrap();
+            let size = arg.data as usize;
+            let mut vec = Vec::new();
+            vec.resize(size, super::Nil::new());
+            self.value = vec.into_boxed_slice();
         }
-        let arg = args.get(0).unwrap();
-        let arg = arg.borrow();
-        let arg = arg.downcast_ref::<PrimitiveObject<u64>>().unwrap();
-        let size = arg.data as usize;
-        let mut vec = Vec::new();
-        vec.resize(size, super::Nil::new());
-        self.value = vec.into_boxed_slice();
+    }
+    fn children(&self) -> Vec<ObjectBox> {
+        self.value.iter().cloned().chain(self.super_object.clone()).collect()
+    }
+    fn clear_children(&mut self) {
+        self.value = Box::new([]);
     }
 }
 
 
+fn out_of_range(name: &str, index: usize, length: usize) -> Fault {
+    Fault::InvalidOperation(format!("Vector {}: index {} is out of range for length {}", name, index, length))
+}
+
 fn vector_get(object: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
     let index = context.arguments[0].clone();
     let index = index.borrow();
-    let index = index.downcast_ref::<PrimitiveObject<u64>>().ok_or(Fault::InvalidType(format!("Vector get: Expected u64")))?;
+    let index = index.downcast_ref::<PrimitiveObject<u64>>().ok_or(Fault::InvalidType("Vector get: Expected u64".to_string()))?;
     let index = index.data as usize;
     let vector = object.borrow();
-    let vector = vector.downcast_ref::<VectorObject>().ok_or(Fault::InvalidType(format!("Vector get: Expected Vector")))?;
-    Ok(Some(vector.get_field(index).unwrap()))
+    let vector = vector.downcast_ref::<VectorObject>().ok_or(Fault::InvalidType("Vector get: Expected Vector".to_string()))?;
+    let item = vector.get_field(index).ok_or_else(|| out_of_range("get", index, vector.value.len()))?;
+    Ok(Some(item))
 }
 
 fn vector_set(object: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
     let index = context.arguments[0].clone();
     let index = index.borrow();
-    let index = index.downcast_ref::<PrimitiveObject<u64>>().ok_or(Fault::InvalidType(format!("Vector set: Expected u64")))?;
+    let index = index.downcast_ref::<PrimitiveObject<u64>>().ok_or(Fault::InvalidType("Vector set: Expected u64".to_string()))?;
     let index = index.data as usize;
     let value = context.arguments[1].clone();
     let mut vector = object.borrow_mut();
-    let vector = vector.downcast_mut::<VectorObject>().ok_or(Fault::InvalidType(format!("Vector set: Expected Vector")))?;
+    let vector = vector.downcast_mut::<VectorObject>().ok_or(Fault::InvalidType("Vector set: Expected Vector".to_string()))?;
+    if index >= vector.value.len() {
+        return Err(out_of_range("set", index, vector.value.len()));
+    }
     vector.set_field(index, value);
     Ok(None)
 }
 
 fn vector_length(object: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
     let vector = object.borrow();
-    let vector = vector.downcast_ref::<VectorObject>().ok_or(Fault::InvalidType(format!("Vector length: Expected Vector")))?;
+    let vector = vector.downcast_ref::<VectorObject>().ok_or(Fault::InvalidType("Vector length: Expected Vector".to_string()))?;
     Ok(Some(super::create_u64(vector.size().unwrap() as u64)))
 }
 
 fn vector_map(object: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
     let function = context.arguments[0].clone();
     let function = function.borrow();
-    let function = function.downcast_ref::<Block>().ok_or(Fault::InvalidType(format!("Vector map: Expected Block")))?;
+    let function = function.downcast_ref::<Block>().ok_or(Fault::InvalidType("Vector map: Expected Block".to_string()))?;
     let vector = object.borrow();
-    let vector = vector.downcast_ref::<VectorObject>().ok_or(Fault::InvalidType(format!("Vector map: Expected map")))?;
+    let vector = vector.downcast_ref::<VectorObject>().ok_or(Fault::InvalidType("Vector map: Expected map".to_string()))?;
     let mut new_vector = Vec::new();
     for item in vector.value.iter() {
         context.set_argument(0, item.clone());
@@ -128,9 +150,9 @@ fn vector_map(object: ObjectBox, context: &mut ContextData) -> Result<Option<Obj
 fn vector_fold(object: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
     let function = context.arguments[0].clone();
     let function = function.borrow();
-    let function = function.downcast_ref::<Block>().ok_or(Fault::InvalidType(format!("Vector fold: Expected Block")))?;
+    let function = function.downcast_ref::<Block>().ok_or(Fault::InvalidType("Vector fold: Expected Block".to_string()))?;
     let vector = object.borrow();
-    let vector = vector.downcast_ref::<VectorObject>().ok_or(Fault::InvalidType(format!("Vector fold: Expected Vector")))?;
+    let vector = vector.downcast_ref::<VectorObject>().ok_or(Fault::InvalidType("Vector fold: Expected Vector".to_string()))?;
     let mut result = context.arguments[1].clone();
     for item in vector.value.iter() {
         context.set_argument(0, result.clone());
@@ -141,47 +163,218 @@ fn vector_fold(object: ObjectBox, context: &mut ContextData) -> Result<Option<Ob
     Ok(Some(result))
 }
 
-fn vector_sort(object: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
-    let mut vector = object.borrow_mut();
-    let vector = vector.downcast_mut::<VectorObject>().ok_or(Fault::InvalidType(format!("Vector sort: Expected Vector")))?;
-    vector.value.sort_by(|a, b| {
-        let a_ = a.borrow();
-        let method = a_.process_message(super::create_message("order")).expect("Expected order method");
-        context.set_argument(0, b.clone());
-        context.arg_count = 1;
-        match method.call(a.clone(), context) {
-            Ok(Some(result)) => {
-                let result = result.borrow();
-                let result = result.downcast_ref::<PrimitiveObject<i8>>().expect("Expected integer");
-                if result.data > 0 {
-                    std::cmp::Ordering::Greater
-                } else if result.data < 0 {
-                    std::cmp::Ordering::Less
-                } else {
-                    std::cmp::Ordering::Equal
-                }
+/// Sort with a comparison that can fault
+/// The slice sorts of the standard library can't stop part way and may panic when the comparison
+/// isn't a total order, which a block can't promise, so the sorts are done here instead. The
+/// stable sort is a merge sort and the unstable one a heap sort that needs no extra room.
+fn sort_elements<T>(elements: Vec<T>, stable: bool, compare: &mut impl FnMut(&T, &T) -> Result<Ordering, Fault>) -> Result<Vec<T>, Fault> {
+    if stable {
+        merge_sort(elements, compare)
+    } else {
+        let mut elements = elements;
+        heap_sort(&mut elements, compare)?;
+        Ok(elements)
+    }
+}
+
+fn merge_sort<T>(mut elements: Vec<T>, compare: &mut impl FnMut(&T, &T) -> Result<Ordering, Fault>) -> Result<Vec<T>, Fault> {
+    if elements.len() <= 1 {
+        return Ok(elements);
+    }
+    let right = elements.split_off(elements.len() / 2);
+    let mut left = merge_sort(elements, compare)?.into_iter().peekable();
+    let mut right = merge_sort(right, compare)?.into_iter().peekable();
+    let mut merged = Vec::with_capacity(left.len() + right.len());
+    while let (Some(a), Some(b)) = (left.peek(), right.peek()) {
+        // Equal elements are taken from the left so they keep their order
+        if compare(b, a)? == Ordering::Less {
+            merged.extend(right.next());
+        } else {
+            merged.extend(left.next());
+        }
+    }
+    merged.extend(left);
+    merged.extend(right);
+    Ok(merged)
+}
+
+fn heap_sort<T>(elements: &mut [T], compare: &mut impl FnMut(&T, &T) -> Result<Ordering, Fault>) -> Result<(), Fault> {
+    let mut sift_down = |elements: &mut [T], mut root: usize| -> Result<(), Fault> {
+        loop {
+            let mut child = 2 * root + 1;
+            if child >= elements.len() {
+                return Ok(());
             }
-            Ok(None) => {
-                panic!("Order method returned nothing");
+            if child + 1 < elements.len() && compare(&elements[child], &elements[child + 1])? == Ordering::Less {
+                child += 1;
             }
-            Err(fault) => {
-                panic!("Order method failed: {:?}", fault);
+            if compare(&elements[root], &elements[child])? != Ordering::Less {
+                return Ok(());
             }
+            elements.swap(root, child);
+            root = child;
         }
-    });
+    };
+    for root in (0..elements.len() / 2).rev() {
+        sift_down(elements, root)?;
+    }
+    for end in (1..elements.len()).rev() {
+        elements.swap(0, end);
+        sift_down(&mut elements[..end], 0)?;
+    }
+    Ok(())
+}
+
+fn elements(object: &ObjectBox, name: &str) -> Result<Vec<ObjectBox>, Fault> {
+    let vector = object.borrow();
+    let vector = vector.downcast_ref::<VectorObject>().ok_or(Fault::InvalidType(format!("Vector {}: Expected Vector", name)))?;
+    Ok(vector.value.to_vec())
+}
+
+/// Put the elements back after working on a copy
+/// The vector isn't borrowed while order, equals or a block runs because they can use it too.
+fn replace_elements(object: &ObjectBox, elements: Vec<ObjectBox>, name: &str) -> Result<(), Fault> {
+    let mut vector = object.borrow_mut();
+    let vector = vector.downcast_mut::<VectorObject>().ok_or(Fault::InvalidType(format!("Vector {}: Expected Vector", name)))?;
+    vector.value = elements.into_boxed_slice();
+    Ok(())
+}
+
+fn block_argument(context: &ContextData, name: &str) -> Result<ObjectBox, Fault> {
+    let block = context.arguments[0].clone();
+    if !block.borrow().is::<Block>() {
+        return Err(Fault::InvalidType(format!("Vector {}: Expected Block", name)));
+    }
+    Ok(block)
+}
+
+/// Sort in place by sending order to the elements
+fn sort(object: ObjectBox, name: &str, stable: bool) -> Result<Option<ObjectBox>, Fault> {
+    let elements = elements(&object, name)?;
+    let sorted = sort_elements(elements, stable, &mut |a, b| super::compare::order(a, b, &format!("Vector {}", name)))?;
+    replace_elements(&object, sorted, name)?;
+    Ok(None)
+}
+
+/// Sort in place with a block that is given two elements and gives an order like order does
+fn sort_by(object: ObjectBox, context: &mut ContextData, name: &str, stable: bool) -> Result<Option<ObjectBox>, Fault> {
+    let block = block_argument(context, name)?;
+    let elements = elements(&object, name)?;
+    let sorted = sort_elements(elements, stable, &mut |a, b| {
+        let order = Block::evaluate(&block, None, vec![a.clone(), b.clone()])?
+            .ok_or(Fault::InvalidOperation(format!("Vector {}: the block didn't give a result", name)))?;
+        super::compare::to_ordering(&order, &format!("Vector {}", name))
+    })?;
+    replace_elements(&object, sorted, name)?;
+    Ok(None)
+}
+
+/// Sort in place by the order of the keys a block gives, the block is evaluated once for each element
+fn sort_by_key(object: ObjectBox, context: &mut ContextData, name: &str, stable: bool) -> Result<Option<ObjectBox>, Fault> {
+    let block = block_argument(context, name)?;
+    let mut keyed = Vec::new();
+    for element in elements(&object, name)? {
+        let key = Block::evaluate(&block, None, vec![element.clone()])?
+            .ok_or(Fault::InvalidOperation(format!("Vector {}: the block didn't give a result", name)))?;
+        keyed.push((key, element));
+    }
+    let sorted = sort_elements(keyed, stable, &mut |(a, _), (b, _)| super::compare::order(a, b, &format!("Vector {}", name)))?;
+    replace_elements(&object, sorted.into_iter().map(|(_, element)| element).collect(), name)?;
+    Ok(None)
+}
+
+fn vector_sort(object: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    sort(object, "sort", true)
+}
+
+fn vector_sort_unstable(object: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    sort(object, "sort_unstable", false)
+}
+
+fn vector_sort_by(object: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    sort_by(object, context, "sort_by", true)
+}
+
+fn vector_sort_unstable_by(object: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    sort_by(object, context, "sort_unstable_by", false)
+}
+
+fn vector_sort_by_key(object: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    sort_by_key(object, context, "sort_by_key", true)
+}
+
+fn vector_sort_unstable_by_key(object: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    sort_by_key(object, context, "sort_unstable_by_key", false)
+}
+
+fn vector_reverse(object: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let mut vector = object.borrow_mut();
+    let vector = vector.downcast_mut::<VectorObject>().ok_or(Fault::InvalidType("Vector reverse: Expected Vector".to_string()))?;
+    vector.value.reverse();
+    Ok(None)
+}
+
+/// Find the index of an element in a vector sorted by order, Nil when it isn't there
+fn vector_binary_search(object: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let target = context.arguments[0].clone();
+    let elements = elements(&object, "binary_search")?;
+    let (mut low, mut high) = (0, elements.len());
+    while low < high {
+        let middle = low + (high - low) / 2;
+        match super::compare::order(&elements[middle], &target, "Vector binary_search")? {
+            Ordering::Less => low = middle + 1,
+            Ordering::Greater => high = middle,
+            Ordering::Equal => return Ok(Some(super::create_u64(middle as u64))),
+        }
+    }
+    Ok(Some(Nil::new()))
+}
+
+/// Find the least or the greatest element by order, the first of equal ones
+/// Like min and max on two objects the result is a copy because numbers change in place.
+fn extreme(object: ObjectBox, name: &str, wanted: Ordering) -> Result<Option<ObjectBox>, Fault> {
+    let mut elements = elements(&object, name)?.into_iter();
+    let Some(mut chosen) = elements.next() else { return Ok(Some(Nil::new())) };
+    for element in elements {
+        if super::compare::order(&element, &chosen, &format!("Vector {}", name))? == wanted {
+            chosen = element;
+        }
+    }
+    let copy = chosen.borrow().duplicate();
+    Ok(Some(copy))
+}
+
+fn vector_min(object: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    extreme(object, "min", Ordering::Less)
+}
+
+fn vector_max(object: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    extreme(object, "max", Ordering::Greater)
+}
+
+/// Remove each element that equals the one before it so a sorted vector keeps one of each
+fn vector_dedup(object: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let mut kept: Vec<ObjectBox> = Vec::new();
+    for element in elements(&object, "dedup")? {
+        match kept.last() {
+            Some(last) if super::set::equal(last, &element)? => {}
+            _ => kept.push(element),
+        }
+    }
+    replace_elements(&object, kept, "dedup")?;
     Ok(None)
 }
 
 fn vector_concat(object: ObjectBox, context: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
     let vector = object.borrow();
-    let vector = vector.downcast_ref::<VectorObject>().ok_or(Fault::InvalidType(format!("Vector concat: Expected Vector")))?;
+    let vector = vector.downcast_ref::<VectorObject>().ok_or(Fault::InvalidType("Vector concat: Expected Vector".to_string()))?;
     let mut new_vector = Vec::new();
     for item in vector.value.iter() {
         new_vector.push(item.clone());
     }
     for item in context.arguments.iter() {
         let item = item.borrow();
-        let item = item.downcast_ref::<VectorObject>().ok_or(Fault::InvalidType(format!("Vector concat: Expected Vector")))?;
+        let item = item.downcast_ref::<VectorObject>().ok_or(Fault::InvalidType("Vector concat: Expected Vector".to_string()))?;
         for item in item.value.iter() {
             new_vector.push(item.clone());
         }
@@ -189,3 +382,90 @@ fn vector_concat(object: ObjectBox, context: &mut ContextData) -> Result<Option<
     context.pop();
     Ok(Some(VectorObject::make_object(object.clone(), new_vector.into_boxed_slice())))
 }
+
+fn vector_to_list(object: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let vector = object.borrow();
+    let vector = vector.downcast_ref::<VectorObject>().ok_or(Fault::InvalidType("Vector to_list: Expected Vector".to_string()))?;
+    Ok(Some(super::create_list(vector.value.to_vec())))
+}
+
+fn vector_iterator(object: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    if !object.borrow().is::<VectorObject>() {
+        return Err(Fault::InvalidType("Vector iterator: Expected Vector".to_string()));
+    }
+    Ok(Some(super::iterator::over_fields(object)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::object::value::Immediate;
+    use crate::object::{create_i64, create_vector};
+
+    fn numbers(values: &[i64]) -> ObjectBox {
+        create_vector(values.iter().copied().map(create_i64).collect())
+    }
+
+    fn send(receiver: &ObjectBox, selector: &str, arguments: Vec<ObjectBox>) -> Result<Option<ObjectBox>, Fault> {
+        let method = receiver.borrow().lookup_method(Symbol::from(selector)).unwrap();
+        method.evaluate(receiver.clone(), arguments)
+    }
+
+    fn values(vector: &ObjectBox) -> Vec<Option<Immediate>> {
+        elements(vector, "test").unwrap().iter().map(|element| Immediate::from_object(&*element.borrow())).collect()
+    }
+
+    #[test]
+    fn the_stable_sort_keeps_equal_elements_in_order() {
+        let pairs: Vec<(i32, usize)> = [3, 1, 3, 2, 1, 3].into_iter().zip(0..).collect();
+        let sorted = sort_elements(pairs, true, &mut |a, b| Ok(a.0.cmp(&b.0))).unwrap();
+        assert_eq!(sorted, vec![(1, 1), (1, 4), (2, 3), (3, 0), (3, 2), (3, 5)]);
+    }
+
+    #[test]
+    fn the_unstable_sort_sorts() {
+        let elements: Vec<i32> = (0..50).map(|value| (value * 37) % 50).collect();
+        let sorted = sort_elements(elements, false, &mut |a, b| Ok(a.cmp(b))).unwrap();
+        assert_eq!(sorted, (0..50).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn sorts_survive_comparisons_that_are_not_an_order() {
+        for stable in [true, false] {
+            let elements: Vec<i32> = (0..20).collect();
+            let mut flip = false;
+            let sorted = sort_elements(elements, stable, &mut |_, _| {
+                flip = !flip;
+                Ok(if flip { Ordering::Less } else { Ordering::Greater })
+            }).unwrap();
+            assert_eq!(sorted.len(), 20);
+        }
+    }
+
+    #[test]
+    fn a_faulting_comparison_stops_the_sort() {
+        for stable in [true, false] {
+            let mut calls = 0;
+            let result = sort_elements((0..20).collect::<Vec<i32>>(), stable, &mut |_, _| {
+                calls += 1;
+                match calls {
+                    3 => Err(Fault::InvalidOperation("stop".to_string())),
+                    _ => Ok(Ordering::Less),
+                }
+            });
+            assert!(result.is_err());
+            assert_eq!(calls, 3);
+        }
+    }
+
+    #[test]
+    fn sorting_and_searching_vectors() {
+        let vector = numbers(&[5, -2, 9, 0, 5]);
+        send(&vector, "sort", vec![]).unwrap();
+        assert_eq!(values(&vector), [-2, 0, 5, 5, 9].map(|value| Some(Immediate::I64(value))));
+        let found = send(&vector, "binary_search", vec![create_i64(9)]).unwrap().unwrap();
+        assert_eq!(Immediate::from_object(&*found.borrow()), Some(Immediate::U64(4)));
+        let missing = send(&vector, "binary_search", vec![create_i64(1)]).unwrap().unwrap();
+        assert!(missing.borrow().is::<Nil>());
+    }
+}
@@ -0,0 +1,115 @@
+use std::sync::Arc;
+use std::collections::HashMap;
+use crate::object::ContextData;
+use crate::object::Method;
+
+use super::{Fault, Object, ObjectBox, WeakObjectBox};
+use crate::object::VTable;
+use crate::object::symbol::Symbol;
+
+
+/// WeakRef
+/// A WeakRef points to an object without keeping it alive.
+/// The cycle collector doesn't follow the reference either so a WeakRef never keeps a cycle alive.
+pub struct WeakRef {
+    super_object: Option<ObjectBox>,
+    vtable: VTable,
+    pub target: Option<WeakObjectBox>,
+}
+
+impl WeakRef {
+    pub fn make_object(parent: ObjectBox, target: Option<WeakObjectBox>) -> ObjectBox {
+        let weak = WeakRef {
+            super_object: Some(parent),
+            vtable: VTable::new_empty(),
+            target,
+        };
+        ObjectBox::new(weak)
+    }
+    pub fn make_vtable() -> VTable {
+        let mut methods = HashMap::new();
+        methods.insert(Symbol::from("get"), Arc::new(Method::RustMethod { fun: Box::new(weak_get)}));
+        methods.insert(Symbol::from("alive"), Arc::new(Method::RustMethod { fun: Box::new(weak_alive)}));
+        VTable::new(methods)
+    }
+}
+
+impl Object for WeakRef {
+    fn get_vtable(&self) -> &VTable {
+        &self.vtable
+    }
+    fn get_super_object(&self) -> Option<ObjectBox> {
+        self.super_object.clone()
+    }
+    fn get_field(&self, _: usize) -> Option<ObjectBox> {
+        panic!("WeakRef object has no fields")
+    }
+    fn set_field(&mut self, _: usize, _: ObjectBox) {
+        panic!("WeakRef object has no fields")
+    }
+    fn size(&self) -> Option<usize> {
+        None
+    }
+    fn duplicate(&self) -> ObjectBox {
+        let object = WeakRef::make_object(self.super_object.clone().unwrap(), self.target.clone());
+        let mut object_mut = object.borrow_mut();
+        object_mut.initialize(vec![], self.vtable.clone());
+        drop(object_mut);
+        object
+    }
+    fn initialize(&mut self, _args: Vec<ObjectBox>, vtable: VTable) {
+        self.vtable.extend(vtable);
+    }
+}
+
+
+fn weak_get(object: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let object = object.borrow();
+    let object = object.downcast_ref::<WeakRef>().ok_or(Fault::InvalidType("WeakRef get: Expected WeakRef".to_string()))?;
+    match object.target.as_ref().and_then(|target| target.upgrade()) {
+        Some(target) => Ok(Some(target)),
+        None => Ok(Some(super::Nil::new())),
+    }
+}
+
+fn weak_alive(object: ObjectBox, _: &mut ContextData) -> Result<Option<ObjectBox>, Fault> {
+    let object = object.borrow();
+    let object = object.downcast_ref::<WeakRef>().ok_or(Fault::InvalidType("WeakRef alive: Expected WeakRef".to_string()))?;
+    let alive = object.target.as_ref().is_some_and(|target| target.strong_count() > 0);
+    Ok(Some(super::create_boolean(alive)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::object::primitive::PrimitiveObject;
+
+    fn send(receiver: &ObjectBox, selector: &str) -> ObjectBox {
+        let method = receiver.borrow().lookup_method(Symbol::from(selector)).unwrap();
+        let mut context = ContextData::new(crate::object::init_stack());
+        method.call(receiver.clone(), &mut context).unwrap().unwrap()
+    }
+
+    fn alive(weak: &ObjectBox) -> bool {
+        send(weak, "alive").borrow().downcast_ref::<PrimitiveObject<bool>>().unwrap().data
+    }
+
+    #[test]
+    fn weak_refs_dont_keep_their_target_alive() {
+        let target = crate::object::create_string("target".to_string());
+        let weak = crate::object::create_weak_ref(&target);
+        assert!(alive(&weak));
+        assert_eq!(send(&weak, "get").as_ptr(), target.as_ptr());
+        drop(target);
+        assert!(!alive(&weak));
+        assert!(send(&weak, "get").borrow().is::<crate::object::Nil>());
+    }
+
+    #[test]
+    fn copies_point_at_the_same_target() {
+        let target = crate::object::create_string("target".to_string());
+        let weak = crate::object::create_weak_ref(&target);
+        let copy = weak.borrow().duplicate();
+        assert_eq!(send(&copy, "get").as_ptr(), target.as_ptr());
+    }
+}
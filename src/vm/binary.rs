@@ -6,9 +6,27 @@
 //! string_table: length (u64), \[string_table_entry\]
 //!
 //! block_table: length (u64), \[block_table_entry\]
+//!
+//! trait_table: length (u64), \[trait_table_entry\]
+//!
+//! interface_table: length (u64), \[interface_table_entry\]
 //! 
 //!
-//! class_table_entry: name_index (u64), flag (u8), parent_index (?u64), method_count (u64), \[method_entry\], override_count (u64), \[override_entry\]
+//! class_table_entry: name_index (u64), flag (u8), parent_index (?u64), method_count (u64), \[method_entry\], override_count (u64), \[override_entry\], class_field_count (u64), class_method_count (u64), \[method_entry\], trait_count (u64), \[trait_use\], abstract_count (u64), \[name_index (u64)\], interface_count (u64), \[name_index (u64)\]
+//!
+//! The Symbol literal was added in version 0.0.2, it is written as 14, name_index (u64).
+//! The class-side entries were added in version 0.0.3 and are left out by older files.
+//! The trait table and the trait uses were added in version 0.0.4.
+//! The interface table, the abstract selectors and the interfaces of a class were added in version 0.0.5.
+//! The BigInt literal was added in version 0.0.6, it is written as 15, negative (u8), limb_count (u64), \[limb (u32)\]
+//! with the least significant limb first.
+//! Files that are newer than the VM are rejected instead of being read as far as it understands them.
+//!
+//! trait_use: name_index (u64), exclusion_count (u64), \[name_index (u64)\], alias_count (u64), \[alias_index (u64), name_index (u64)\]
+//!
+//! trait_table_entry: name_index (u64), method_count (u64), \[method_entry\]
+//!
+//! interface_table_entry: name_index (u64), selector_count (u64), \[name_index (u64)\]
 //!
 //! method_entry: name_index (u64), bytecode_entry
 //!
@@ -18,54 +36,79 @@
 //!
 //! string_table_entry: length (u64), string (utf-8)
 //!
+//! A name_index is the position of an entry in the string table, counting from 0, and duplicate
+//! entries keep their own position. Older loaders keyed each entry by its length instead, which
+//! mixed up strings of the same length. The bytes of the table didn't change, only how an index is read.
+//!
 //! block_table_entry: length (u64), \[bytecode_entry\]
 
 use std::collections::{BTreeMap, HashMap};
 use std::cell::RefCell;
 use std::sync::Arc;
 
-use nom::{character, number, IResult, Parser, error::Error, multi, bytes, Finish};
+use nom::{character, number, IResult, Parser, error::{Error, ErrorKind}, multi, bytes, Finish};
 
-use crate::object::{Class, Method, VTable};
+use crate::object::primitive::bigint::BigInt;
+use crate::object::{Class, Fault, Interface, Method, Trait, TraitUse, VTable};
+use crate::object::symbol::Symbol;
 use crate::vm::bytecode::ByteCode;
 
 
-pub fn binary_data_to_binary<'a>(input: &'a [u8]) -> Result<Binary,Error<&[u8]>> {
+/// Parse a binary and compose the traits of its classes
+/// Traits that the binary doesn't define are looked up in the ones that have already been loaded.
+pub fn binary_data_to_binary(input: &[u8]) -> Result<Binary, Fault> {
     let binary = parse_binary(input).finish();
     match binary {
-        Ok((_, binary)) => Ok(binary.into_binary()),
-        Err(err) => Err(err)
+        Ok((_, binary)) => binary.into_binary(),
+        Err(err) if err.code == ErrorKind::Verify => Err(Fault::InvalidOperation(format!("binary is newer than version {}.{}.{}", VERSION.0, VERSION.1, VERSION.2))),
+        Err(err) => Err(Fault::InvalidOperation(format!("malformed binary: {:?}", err.code)))
     }
 }
 
 
 fn parse_binary(input: &[u8]) -> IResult<&[u8], ProtoBinary> {
-    let (input, _) = parse_header(input)?;
-    let (input, class_table) = parse_class_table(input)?;
+    let (input, version) = parse_header(input)?;
+    let (input, class_table) = parse_class_table(input, version)?;
     let (input, string_table) = parse_string_table(input)?;
-    let (input, block_table) = parse_block_table(input)?;
-    Ok((input, ProtoBinary { class_table, string_table, block_table }))
+    let (input, block_table) = parse_block_table(input, version)?;
+    let (input, trait_table) = if version >= (0, 0, 4) {
+        parse_trait_table(input, version)?
+    } else {
+        (input, ProtoTraitTable { traits: Vec::new() })
+    };
+    let (input, interface_table) = if version >= (0, 0, 5) {
+        parse_interface_table(input)?
+    } else {
+        (input, ProtoInterfaceTable { interfaces: Vec::new() })
+    };
+    Ok((input, ProtoBinary { class_table, string_table, block_table, trait_table, interface_table }))
 }
 
 
-fn parse_header(input: &[u8]) -> IResult<&[u8],()> {
+/// The version of the SPK format that gets written
+pub(crate) const VERSION: (u8, u8, u8) = (0, 0, 6);
+
+fn parse_header(input: &[u8]) -> IResult<&[u8], (u8, u8, u8)> {
     let (input, _) = character::complete::char('S')(input)?;
     let (input, _) = character::complete::char('P')(input)?;
     let (input, _) = character::complete::char('K')(input)?;
-    let (input, _) = number::complete::u8(input)?;
-    let (input, _) = number::complete::u8(input)?;
-    let (input, _) = number::complete::u8(input)?;
-    Ok((input, ()))
+    let (input, major) = number::complete::u8(input)?;
+    let (input, minor) = number::complete::u8(input)?;
+    let (input, patch) = number::complete::u8(input)?;
+    if (major, minor, patch) > VERSION {
+        return Err(nom::Err::Failure(Error::new(input, ErrorKind::Verify)));
+    }
+    Ok((input, (major, minor, patch)))
 }
 
-fn parse_class_table(input: &[u8]) -> IResult<&[u8], ProtoClassTable> {
+fn parse_class_table(input: &[u8], version: (u8, u8, u8)) -> IResult<&[u8], ProtoClassTable> {
     let (input, length) = number::complete::le_u64(input)?;
-    let (input, classes) = multi::count(parse_class(), length as usize)(input)?;
+    let (input, classes) = multi::count(parse_class(version), length as usize)(input)?;
     Ok((input, ProtoClassTable { classes }))
 }
 
-fn parse_class<'a>() -> impl Parser<&'a [u8], (usize, ProtoClass), Error<&'a [u8]>> {
-    |input| {
+fn parse_class<'a>(version: (u8, u8, u8)) -> impl Parser<&'a [u8], (usize, ProtoClass), Error<&'a [u8]>> {
+    move |input| {
         let (input, name_index) = number::complete::le_u64(input)?;
         let (input, flag) = number::complete::u8(input)?;
         let (input, parent_index) = if flag != 0 {
@@ -75,53 +118,134 @@ fn parse_class<'a>() -> impl Parser<&'a [u8], (usize, ProtoClass), Error<&'a [u8
             (input, None)
         };
         let (input, method_count) = number::complete::le_u64(input)?;
-        let (input, methods) = multi::count(parse_method(), method_count as usize)(input)?;
+        let (input, methods) = multi::count(parse_method(version), method_count as usize)(input)?;
         let (input, override_count) = number::complete::le_u64(input)?;
-        let (input, overrides) = multi::count(parse_override, override_count as usize)(input)?;
-        Ok((input, (name_index as usize, ProtoClass { parent: parent_index.map(|x| x as usize), methods, overrides })))
+        let (input, overrides) = multi::count(parse_override(version), override_count as usize)(input)?;
+        let (input, class_field_count, class_methods) = if version >= (0, 0, 3) {
+            let (input, class_field_count) = number::complete::le_u64(input)?;
+            let (input, class_method_count) = number::complete::le_u64(input)?;
+            let (input, class_methods) = multi::count(parse_method(version), class_method_count as usize)(input)?;
+            (input, class_field_count as usize, class_methods)
+        } else {
+            (input, 0, Vec::new())
+        };
+        let (input, traits) = if version >= (0, 0, 4) {
+            let (input, trait_count) = number::complete::le_u64(input)?;
+            multi::count(parse_trait_use, trait_count as usize)(input)?
+        } else {
+            (input, Vec::new())
+        };
+        let (input, abstract_methods, interfaces) = if version >= (0, 0, 5) {
+            let (input, abstract_methods) = parse_indices(input)?;
+            let (input, interfaces) = parse_indices(input)?;
+            (input, abstract_methods, interfaces)
+        } else {
+            (input, Vec::new(), Vec::new())
+        };
+        let class = ProtoClass {
+            parent: parent_index.map(|x| x as usize),
+            methods,
+            overrides,
+            class_methods,
+            class_field_count,
+            traits,
+            abstract_methods,
+            interfaces,
+        };
+        Ok((input, (name_index as usize, class)))
     }
 }
 
-fn parse_method<'a>() -> impl Parser<&'a [u8], (usize, Vec<ProtoByteCode>), Error<&'a [u8]>> {
-    |input| {
+fn parse_method<'a>(version: (u8, u8, u8)) -> impl Parser<&'a [u8], (usize, Vec<ProtoByteCode>), Error<&'a [u8]>> {
+    move |input| {
         let (input, name_index) = number::complete::le_u64(input)?;
-        let (input, bytecode) = parse_bytecode(input)?;
+        let (input, bytecode) = parse_bytecode(version).parse(input)?;
         Ok((input, (name_index as usize, bytecode)))
     }
 }
 
-fn parse_override(input: &[u8]) -> IResult<&[u8], (usize, Vec<(usize, Vec<ProtoByteCode>)>)> {
+fn parse_override<'a>(version: (u8, u8, u8)) -> impl Parser<&'a [u8], (usize, ProtoMethods), Error<&'a [u8]>> {
+    move |input| {
+        let (input, length) = number::complete::le_u64(input)?;
+        let (input, depth) = number::complete::le_u64(input)?;
+        let (input, methods) = multi::count(parse_method(version), length as usize)(input)?;
+        Ok((input, (depth as usize, methods)))
+    }
+}
+
+fn parse_trait_use(input: &[u8]) -> IResult<&[u8], ProtoTraitUse> {
+    let (input, name) = number::complete::le_u64(input)?;
+    let (input, exclusion_count) = number::complete::le_u64(input)?;
+    let (input, excluded) = multi::count(number::complete::le_u64, exclusion_count as usize)(input)?;
+    let (input, alias_count) = number::complete::le_u64(input)?;
+    let (input, aliases) = multi::count(|input| {
+        let (input, alias) = number::complete::le_u64(input)?;
+        let (input, name) = number::complete::le_u64(input)?;
+        Ok((input, (alias as usize, name as usize)))
+    }, alias_count as usize)(input)?;
+    let excluded = excluded.into_iter().map(|idx| idx as usize).collect();
+    Ok((input, ProtoTraitUse { name: name as usize, excluded, aliases }))
+}
+
+fn parse_indices(input: &[u8]) -> IResult<&[u8], Vec<usize>> {
+    let (input, length) = number::complete::le_u64(input)?;
+    let (input, indices) = multi::count(number::complete::le_u64, length as usize)(input)?;
+    Ok((input, indices.into_iter().map(|idx| idx as usize).collect()))
+}
+
+fn parse_interface_table(input: &[u8]) -> IResult<&[u8], ProtoInterfaceTable> {
+    let (input, length) = number::complete::le_u64(input)?;
+    let (input, interfaces) = multi::count(|input| {
+        let (input, name) = number::complete::le_u64(input)?;
+        let (input, selectors) = parse_indices(input)?;
+        Ok((input, (name as usize, selectors)))
+    }, length as usize)(input)?;
+    Ok((input, ProtoInterfaceTable { interfaces }))
+}
+
+fn parse_trait_table(input: &[u8], version: (u8, u8, u8)) -> IResult<&[u8], ProtoTraitTable> {
     let (input, length) = number::complete::le_u64(input)?;
-    let (input, depth) = number::complete::le_u64(input)?;
-    let (input, methods) = multi::count(parse_method(), length as usize)(input)?;
-    Ok((input, (depth as usize, methods)))
+    let (input, traits) = multi::count(|input| {
+        let (input, name) = number::complete::le_u64(input)?;
+        let (input, method_count) = number::complete::le_u64(input)?;
+        let (input, methods) = multi::count(parse_method(version), method_count as usize)(input)?;
+        Ok((input, (name as usize, methods)))
+    }, length as usize)(input)?;
+    Ok((input, ProtoTraitTable { traits }))
 }
 
-fn parse_string_table(input: &[u8]) -> IResult<&[u8], StringTable> {
+pub(crate) fn parse_string_table(input: &[u8]) -> IResult<&[u8], StringTable> {
     let (input, length) = number::complete::le_u64(input)?;
     let (input, strings) = multi::count(parse_string_entry, length as usize)(input)?;
-    Ok((input, StringTable { strings: strings.into_iter().collect(), strings_to_idx: HashMap::new() }))
+    let mut string_table = StringTable::new();
+    for string in strings {
+        string_table.push_string(string);
+    }
+    Ok((input, string_table))
 }
 
-fn parse_string_entry(input: &[u8]) -> IResult<&[u8], (usize, String)> {
+fn parse_string_entry(input: &[u8]) -> IResult<&[u8], String> {
     let (input, length) = number::complete::le_u64(input)?;
     let (input, string) = bytes::complete::take(length)(input)?;
-    Ok((input, (length as usize, String::from_utf8_lossy(string).to_string())))
+    Ok((input, String::from_utf8_lossy(string).to_string()))
 }
 
-fn parse_block_table(input: &[u8]) -> IResult<&[u8], ProtoBlockTable> {
+fn parse_block_table(input: &[u8], version: (u8, u8, u8)) -> IResult<&[u8], ProtoBlockTable> {
     let (input, length) = number::complete::le_u64(input)?;
-    let (input, blocks) = multi::count(parse_bytecode, length as usize)(input)?;
+    let (input, blocks) = multi::count(parse_bytecode(version), length as usize)(input)?;
     Ok((input, ProtoBlockTable { blocks: blocks.into_iter().enumerate().collect() }))
 }
 
-fn parse_bytecode(input: &[u8]) -> IResult<&[u8], Vec<ProtoByteCode>> {
-    let (input, length) = number::complete::le_u64(input)?;
-    let (input, bytecode) = multi::count(parse_bytecode_entry, length as usize)(input)?;
-    Ok((input, bytecode))
+/// Parse the bytecode of a block that was written with the given version of the format
+pub(crate) fn parse_bytecode<'a>(version: (u8, u8, u8)) -> impl Parser<&'a [u8], Vec<ProtoByteCode>, Error<&'a [u8]>> {
+    move |input| {
+        let (input, length) = number::complete::le_u64(input)?;
+        let (input, bytecode) = multi::count(|input| parse_bytecode_entry(input, version), length as usize)(input)?;
+        Ok((input, bytecode))
+    }
 }
 
-fn parse_bytecode_entry(input: &[u8]) -> IResult<&[u8], ProtoByteCode> {
+fn parse_bytecode_entry(input: &[u8], version: (u8, u8, u8)) -> IResult<&[u8], ProtoByteCode> {
     let (input, byte) = number::complete::u8(input)?;
     match byte {
         0 => Ok((input, ProtoByteCode::Halt)),
@@ -135,7 +259,7 @@ fn parse_bytecode_entry(input: &[u8]) -> IResult<&[u8], ProtoByteCode> {
             Ok((input, ProtoByteCode::AccessTemp(idx as usize)))
         }
         4 => {
-            let (input, lit) = parse_literal(input)?;
+            let (input, lit) = parse_literal(input, version)?;
             Ok((input, ProtoByteCode::PushLiteral(lit)))
         }
         5 => {
@@ -169,7 +293,7 @@ fn parse_bytecode_entry(input: &[u8]) -> IResult<&[u8], ProtoByteCode> {
     }
 }
 
-fn parse_literal(input: &[u8]) -> IResult<&[u8], ProtoLiteral> {
+fn parse_literal(input: &[u8], version: (u8, u8, u8)) -> IResult<&[u8], ProtoLiteral> {
     let (input, byte) = number::complete::u8(input)?;
     match byte {
         0 => {
@@ -225,7 +349,17 @@ fn parse_literal(input: &[u8]) -> IResult<&[u8], ProtoLiteral> {
             let (input, byte) = number::complete::le_u64(input)?;
             Ok((input, ProtoLiteral::ByteCode(byte as usize)))
         }
-        _ => unreachable!("Unknown literal")
+        14 if version >= (0, 0, 2) => {
+            let (input, idx) = number::complete::le_u64(input)?;
+            Ok((input, ProtoLiteral::Symbol(idx as usize)))
+        }
+        15 if version >= (0, 0, 6) => {
+            let (input, negative) = number::complete::u8(input)?;
+            let (input, count) = number::complete::le_u64(input)?;
+            let (input, limbs) = multi::count(number::complete::le_u32, count as usize)(input)?;
+            Ok((input, ProtoLiteral::BigInt(BigInt::from_parts(negative != 0, limbs))))
+        }
+        _ => Err(nom::Err::Failure(Error::new(input, ErrorKind::Switch)))
     }
 }
 
@@ -268,23 +402,29 @@ pub struct ProtoBinary {
     class_table: ProtoClassTable,
     string_table: StringTable,
     block_table: ProtoBlockTable,
+    trait_table: ProtoTraitTable,
+    interface_table: ProtoInterfaceTable,
 }
 
 impl ProtoBinary {
-    pub fn into_binary(self) -> Binary {
-        let block_table = self.block_table.into_block_table(&self.string_table);
-        let class_table = self.class_table.into_class_table(&self.string_table, &block_table);
+    pub fn into_binary(self) -> Result<Binary, Fault> {
+        let block_table = self.block_table.into_block_table(&self.string_table)?;
+        let trait_table = self.trait_table.into_trait_table(&self.string_table, &block_table)?;
+        let class_table = self.class_table.into_class_table(&self.string_table, &block_table, &trait_table)?;
+        let interfaces = self.interface_table.into_interfaces(&self.string_table)?;
         let string_table = RefCell::new(self.string_table);
-        Binary { class_table, string_table, block_table }
+        Ok(Binary { class_table, string_table, block_table, trait_table, interfaces })
     }
 
     pub fn to_binary(self) -> Vec<u8> {
         let mut binary = vec![];
         binary.extend_from_slice(&[0x53, 0,50, 0x4b]); // SPK
-        binary.extend_from_slice(&[0,0,1]); // version
+        binary.extend_from_slice(&[VERSION.0, VERSION.1, VERSION.2]); // version
         binary.extend(self.class_table.to_binary(None));
         binary.extend(self.string_table.to_binary(None));
         binary.extend(self.block_table.to_binary(None));
+        binary.extend(self.trait_table.to_binary(None));
+        binary.extend(self.interface_table.to_binary(None));
         binary
     }
 }
@@ -295,12 +435,13 @@ pub struct ProtoClassTable {
 }
 
 impl ProtoClassTable {
-    pub fn into_class_table(self, string_table: &StringTable, block_table: &BlockTable) -> ClassTable {
+    pub fn into_class_table(self, string_table: &StringTable, block_table: &BlockTable, trait_table: &TraitTable) -> Result<ClassTable, Fault> {
         let classes = self.classes.into_iter().map(|(idx, class)| {
             let name = string_table.strings.get(&idx).expect("Expected string").clone();
-            (name, class.into_class(string_table, block_table))
-        }).collect();
-        ClassTable { classes }
+            let class = class.into_class(&name, string_table, block_table, trait_table)?;
+            Ok((name, class))
+        }).collect::<Result<_, Fault>>()?;
+        Ok(ClassTable { classes })
     }
 }
 impl ToBinary for ProtoClassTable {
@@ -315,32 +456,36 @@ impl ToBinary for ProtoClassTable {
     }
 }
 
+/// The methods of a class or a trait as pairs of a string table index and the method's bytecode
+pub type ProtoMethods = Vec<(usize, Vec<ProtoByteCode>)>;
+
 pub struct ProtoClass {
     parent: Option<usize>,
-    methods: Vec<(usize, Vec<ProtoByteCode>)>,
-    overrides: Vec<(usize, Vec<(usize, Vec<ProtoByteCode>)>)>,
+    methods: ProtoMethods,
+    overrides: Vec<(usize, ProtoMethods)>,
+    class_methods: ProtoMethods,
+    class_field_count: usize,
+    traits: Vec<ProtoTraitUse>,
+    abstract_methods: Vec<usize>,
+    interfaces: Vec<usize>,
 }
 
 impl ProtoClass {
-    pub fn into_class(self, string_table: &StringTable, block_table: &BlockTable) -> Class {
-        let parent = if let Some(idx) = self.parent {
-            Some(string_table.strings.get(&idx).expect("Expected string").as_str())
-        } else {
-            None
-        };
+    pub fn into_class(self, name: &str, string_table: &StringTable, block_table: &BlockTable, trait_table: &TraitTable) -> Result<Class, Fault> {
+        let parent = self.parent.map(|idx| string_table.strings.get(&idx).expect("Expected string").as_str());
         let mut methods = HashMap::new();
         for (idx, bytecode) in self.methods {
-            let name = string_table.strings.get(&idx).expect("Expected string").clone();
-            let bytecode = bytecode.into_iter().map(|bytecode| bytecode.into_bytecode(string_table, block_table)).collect::<Vec<ByteCode>>();
+            let name = string_table.symbol(idx)?;
+            let bytecode = bytecode.into_iter().map(|bytecode| bytecode.into_bytecode(string_table, block_table)).collect::<Result<Vec<ByteCode>, Fault>>()?;
             let block = crate::object::create_block(bytecode);
             methods.insert(name, Arc::new(Method::BytecodeMethod { block }));
         }
         let mut overrides = BTreeMap::new();
         for (depth, methods) in self.overrides {
-            let mut vtable: HashMap<String, Arc<Method>> = HashMap::new();
+            let mut vtable: HashMap<Symbol, Arc<Method>> = HashMap::new();
             for (idx, bytecode) in methods {
-                let name = string_table.strings.get(&idx).expect("Expected string").clone();
-                let bytecode = bytecode.into_iter().map(|bytecode| bytecode.into_bytecode(string_table, block_table)).collect();
+                let name = string_table.symbol(idx)?;
+                let bytecode = bytecode.into_iter().map(|bytecode| bytecode.into_bytecode(string_table, block_table)).collect::<Result<_, Fault>>()?;
                 let block = crate::object::create_block(bytecode);
                 vtable.insert(name, Arc::new(Method::BytecodeMethod { block }));
             }
@@ -350,7 +495,28 @@ impl ProtoClass {
         for (_, vtable) in overrides.into_iter().rev() {
             overrides_vec.push(vtable);
         }
+        let mut class_methods = HashMap::new();
+        for (idx, bytecode) in self.class_methods {
+            let name = string_table.symbol(idx)?;
+            let bytecode = bytecode.into_iter().map(|bytecode| bytecode.into_bytecode(string_table, block_table)).collect::<Result<Vec<ByteCode>, Fault>>()?;
+            let block = crate::object::create_block(bytecode);
+            class_methods.insert(name, Arc::new(Method::BytecodeMethod { block }));
+        }
+        let mut traits = Vec::new();
+        for trait_use in self.traits {
+            let trait_use = trait_use.into_trait_use(string_table)?;
+            let trait_ = trait_table.get_trait(&trait_use.name)
+                .ok_or(Fault::InvalidType(format!("trait not found: {}", trait_use.name)))?;
+            traits.push((trait_use, trait_));
+        }
+        let abstract_methods = self.abstract_methods.into_iter().map(|idx| string_table.symbol(idx)).collect::<Result<_, Fault>>()?;
+        let interfaces = self.interfaces.into_iter()
+            .map(|idx| string_table.strings.get(&idx).expect("Expected string").clone())
+            .collect();
         Class::new(parent, VTable::new(methods), overrides_vec)
+            .with_class_side(VTable::new(class_methods), self.class_field_count)
+            .with_requirements(abstract_methods, interfaces)
+            .compose(name, &traits)
     }
 }
 
@@ -383,23 +549,145 @@ impl ToBinary for ProtoClass {
                 }
             }
         }
+        binary.extend_from_slice(self.class_field_count.to_binary(None).as_slice());
+        binary.extend_from_slice(self.class_methods.len().to_binary(None).as_slice());
+        for (idx, bytecode) in &self.class_methods {
+            binary.extend_from_slice(idx.to_binary(None).as_slice());
+            binary.extend_from_slice(bytecode.len().to_binary(None).as_slice());
+            for byte in bytecode.iter() {
+                binary.extend_from_slice(byte.to_binary(None).as_slice());
+            }
+        }
+        binary.extend_from_slice(self.traits.len().to_binary(None).as_slice());
+        for trait_use in &self.traits {
+            binary.extend_from_slice(trait_use.to_binary(None).as_slice());
+        }
+        for indices in [&self.abstract_methods, &self.interfaces] {
+            binary.extend_from_slice(indices.len().to_binary(None).as_slice());
+            for idx in indices.iter() {
+                binary.extend_from_slice(idx.to_binary(None).as_slice());
+            }
+        }
         binary
 
     }
 }
 
+pub struct ProtoTraitUse {
+    name: usize,
+    excluded: Vec<usize>,
+    aliases: Vec<(usize, usize)>,
+}
+
+impl ProtoTraitUse {
+    pub fn into_trait_use(self, string_table: &StringTable) -> Result<TraitUse, Fault> {
+        Ok(TraitUse {
+            name: string_table.strings.get(&self.name).expect("Expected string").clone(),
+            excluded: self.excluded.into_iter().map(|idx| string_table.symbol(idx)).collect::<Result<_, Fault>>()?,
+            aliases: self.aliases.into_iter()
+                .map(|(alias, name)| Ok((string_table.symbol(alias)?, string_table.symbol(name)?)))
+                .collect::<Result<_, Fault>>()?,
+        })
+    }
+}
+
+impl ToBinary for ProtoTraitUse {
+    fn to_binary(&self, _: Option<&mut StringTable>) -> Vec<u8> {
+        let mut binary = vec![];
+        binary.extend_from_slice(self.name.to_binary(None).as_slice());
+        binary.extend_from_slice(self.excluded.len().to_binary(None).as_slice());
+        for idx in self.excluded.iter() {
+            binary.extend_from_slice(idx.to_binary(None).as_slice());
+        }
+        binary.extend_from_slice(self.aliases.len().to_binary(None).as_slice());
+        for (alias, name) in self.aliases.iter() {
+            binary.extend_from_slice(alias.to_binary(None).as_slice());
+            binary.extend_from_slice(name.to_binary(None).as_slice());
+        }
+        binary
+    }
+}
+
+pub struct ProtoInterfaceTable {
+    interfaces: Vec<(usize, Vec<usize>)>,
+}
+
+impl ProtoInterfaceTable {
+    pub fn into_interfaces(self, string_table: &StringTable) -> Result<HashMap<String, Arc<Interface>>, Fault> {
+        self.interfaces.into_iter().map(|(idx, selectors)| {
+            let name = string_table.strings.get(&idx).expect("Expected string").clone();
+            let selectors = selectors.into_iter().map(|idx| string_table.symbol(idx)).collect::<Result<_, Fault>>()?;
+            Ok((name, Arc::new(Interface::new(selectors))))
+        }).collect()
+    }
+}
+
+impl ToBinary for ProtoInterfaceTable {
+    fn to_binary(&self, _: Option<&mut StringTable>) -> Vec<u8> {
+        let mut binary = vec![];
+        binary.extend_from_slice(self.interfaces.len().to_binary(None).as_slice());
+        for (idx, selectors) in self.interfaces.iter() {
+            binary.extend_from_slice(idx.to_binary(None).as_slice());
+            binary.extend_from_slice(selectors.len().to_binary(None).as_slice());
+            for idx in selectors.iter() {
+                binary.extend_from_slice(idx.to_binary(None).as_slice());
+            }
+        }
+        binary
+    }
+}
+
+pub struct ProtoTraitTable {
+    traits: Vec<(usize, ProtoMethods)>,
+}
+
+impl ProtoTraitTable {
+    pub fn into_trait_table(self, string_table: &StringTable, block_table: &BlockTable) -> Result<TraitTable, Fault> {
+        let traits = self.traits.into_iter().map(|(idx, methods)| {
+            let name = string_table.strings.get(&idx).expect("Expected string").clone();
+            let mut vtable = HashMap::new();
+            for (idx, bytecode) in methods {
+                let bytecode = bytecode.into_iter().map(|bytecode| bytecode.into_bytecode(string_table, block_table)).collect::<Result<_, Fault>>()?;
+                let block = crate::object::create_block(bytecode);
+                vtable.insert(string_table.symbol(idx)?, Arc::new(Method::BytecodeMethod { block }));
+            }
+            Ok((name, Arc::new(Trait::new(VTable::new(vtable)))))
+        }).collect::<Result<_, Fault>>()?;
+        Ok(TraitTable { traits })
+    }
+}
+
+impl ToBinary for ProtoTraitTable {
+    fn to_binary(&self, _: Option<&mut StringTable>) -> Vec<u8> {
+        let mut binary = vec![];
+        binary.extend_from_slice(self.traits.len().to_binary(None).as_slice());
+        for (idx, methods) in self.traits.iter() {
+            binary.extend_from_slice(idx.to_binary(None).as_slice());
+            binary.extend_from_slice(methods.len().to_binary(None).as_slice());
+            for (idx, bytecode) in methods {
+                binary.extend_from_slice(idx.to_binary(None).as_slice());
+                binary.extend_from_slice(bytecode.len().to_binary(None).as_slice());
+                for byte in bytecode.iter() {
+                    binary.extend_from_slice(byte.to_binary(None).as_slice());
+                }
+            }
+        }
+        binary
+    }
+}
+
 pub struct ProtoBlockTable {
     blocks: BTreeMap<usize, Vec<ProtoByteCode>>,
 }
 
 impl ProtoBlockTable {
-    pub fn into_block_table(self, string_table: &StringTable) -> BlockTable {
+    pub fn into_block_table(self, string_table: &StringTable) -> Result<BlockTable, Fault> {
         let mut block_table = BlockTable { blocks: BTreeMap::new() };
-        let _ = self.blocks.into_iter().map(|(idx, bytecode)| {
-            let bytecode = bytecode.into_iter().map(|bytecode| bytecode.into_bytecode(string_table, &block_table)).collect();
+        for (idx, bytecode) in self.blocks {
+            let bytecode = bytecode.into_iter().map(|bytecode| bytecode.into_bytecode(string_table, &block_table)).collect::<Result<_, Fault>>()?;
             block_table.blocks.insert(idx, bytecode);
-        });
-        block_table
+        }
+        Ok(block_table)
     }
 }
 
@@ -435,20 +723,20 @@ pub enum ProtoByteCode {
 }
 
 impl ProtoByteCode {
-    pub fn into_bytecode(self, string_table: &StringTable, block_table: &BlockTable) -> ByteCode {
-        match self {
+    pub fn into_bytecode(self, string_table: &StringTable, block_table: &BlockTable) -> Result<ByteCode, Fault> {
+        Ok(match self {
             ProtoByteCode::Halt => ByteCode::Halt,
             ProtoByteCode::NoOp => ByteCode::NoOp,
             ProtoByteCode::AccessField(idx) => ByteCode::AccessField(idx),
             ProtoByteCode::AccessTemp(idx) => ByteCode::AccessTemp(idx),
-            ProtoByteCode::PushLiteral(lit) => ByteCode::PushLiteral(lit.into_literal(string_table, block_table)),
+            ProtoByteCode::PushLiteral(lit) => ByteCode::PushLiteral(lit.into_literal(string_table, block_table)?),
             ProtoByteCode::StoreField(idx) => ByteCode::StoreField(idx),
             ProtoByteCode::StoreTemp(idx) => ByteCode::StoreTemp(idx),
-            ProtoByteCode::SendMsg(arg, msg) => ByteCode::SendMsg(arg, string_table.strings.get(&msg).expect("Expected string").clone()),
-            ProtoByteCode::SendSuperMsg(arg, msg) => ByteCode::SendSuperMsg(arg, string_table.strings.get(&msg).expect("Expected string").clone()),
+            ProtoByteCode::SendMsg(arg, msg) => ByteCode::SendMsg(arg, string_table.symbol(msg)?),
+            ProtoByteCode::SendSuperMsg(arg, msg) => ByteCode::SendSuperMsg(arg, string_table.symbol(msg)?),
             ProtoByteCode::SpecialInstruction(inst) => ByteCode::SpecialInstruction(inst.into()),
             ProtoByteCode::GetStack(frame, idx) => ByteCode::GetStack(frame, idx),
-        }
+        })
     }
 }
 
@@ -521,9 +809,9 @@ pub enum ProtoSpecialInstruction {
 
 }
 
-impl Into<crate::vm::bytecode::SpecialInstruction> for ProtoSpecialInstruction {
-    fn into(self) -> crate::vm::bytecode::SpecialInstruction {
-        match self {
+impl From<ProtoSpecialInstruction> for crate::vm::bytecode::SpecialInstruction {
+    fn from(val: ProtoSpecialInstruction) -> Self {
+        match val {
             ProtoSpecialInstruction::DupStack => crate::vm::bytecode::SpecialInstruction::DupStack,
             ProtoSpecialInstruction::DiscardStack => crate::vm::bytecode::SpecialInstruction::DiscardStack,
             ProtoSpecialInstruction::ReturnStack => crate::vm::bytecode::SpecialInstruction::ReturnStack,
@@ -596,11 +884,13 @@ pub enum ProtoLiteral {
     Boolean(bool),
     Nil,
     ByteCode(usize),
+    Symbol(usize),
+    BigInt(BigInt),
 }
 
 impl ProtoLiteral {
-    pub fn into_literal(self, string_table: &StringTable, block_table: &BlockTable) -> crate::vm::bytecode::Literal {
-        match self {
+    pub fn into_literal(self, string_table: &StringTable, block_table: &BlockTable) -> Result<crate::vm::bytecode::Literal, Fault> {
+        Ok(match self {
             ProtoLiteral::String(idx) => crate::vm::bytecode::Literal::String(string_table.strings.get(&idx).expect("Expected string").clone()),
             ProtoLiteral::I8(byte) => crate::vm::bytecode::Literal::I8(byte),
             ProtoLiteral::U8(byte) => crate::vm::bytecode::Literal::U8(byte),
@@ -615,7 +905,9 @@ impl ProtoLiteral {
             ProtoLiteral::Boolean(byte) => crate::vm::bytecode::Literal::Boolean(byte),
             ProtoLiteral::Nil => crate::vm::bytecode::Literal::Nil,
             ProtoLiteral::ByteCode(byte) => crate::vm::bytecode::Literal::ByteCode(block_table.blocks.get(&byte).expect("Expected block").clone()),
-        }
+            ProtoLiteral::Symbol(idx) => crate::vm::bytecode::Literal::Symbol(string_table.symbol(idx)?),
+            ProtoLiteral::BigInt(value) => crate::vm::bytecode::Literal::BigInt(value),
+        })
     }
 }
 
@@ -679,6 +971,19 @@ impl ToBinary for ProtoLiteral {
                 binary.push(13);
                 binary.extend_from_slice(byte.to_binary(None).as_slice());
             }
+            ProtoLiteral::Symbol(idx) => {
+                binary.push(14);
+                binary.extend_from_slice(idx.to_binary(None).as_slice());
+            }
+            ProtoLiteral::BigInt(value) => {
+                binary.push(15);
+                let (negative, limbs) = value.parts();
+                binary.push(if negative { 1 } else { 0 });
+                binary.extend_from_slice(limbs.len().to_binary(None).as_slice());
+                for limb in limbs {
+                    binary.extend_from_slice(&limb.to_le_bytes());
+                }
+            }
         }
         binary
     }
@@ -688,13 +993,15 @@ pub struct Binary {
     pub class_table: ClassTable,
     string_table: RefCell<StringTable>,
     block_table: BlockTable,
+    pub trait_table: TraitTable,
+    interfaces: HashMap<String, Arc<Interface>>,
 }
 
 impl Binary {
     pub fn to_binary(&self) -> Vec<u8> {
         let mut binary = vec![];
         binary.extend_from_slice(&[0x53, 0,50, 0x4b]); // SPK
-        binary.extend_from_slice(&[0,0,1]); // version
+        binary.extend_from_slice(&[VERSION.0, VERSION.1, VERSION.2]); // version
         binary.extend(self.class_table.to_binary(Some(&mut self.string_table.borrow_mut())));
         binary.extend(self.string_table.borrow().to_binary(None));
         binary.extend(self.block_table.to_binary(Some(&mut self.string_table.borrow_mut())));
@@ -704,9 +1011,16 @@ impl Binary {
     pub fn iter(&self) -> impl Iterator<Item = (&String, &Class)> {
         self.class_table.iter()
     }
+    #[allow(clippy::should_implement_trait)]
     pub fn into_iter(self) -> impl Iterator<Item = (String, Class)> {
         self.class_table.classes.into_iter()
     }
+    pub fn traits(&self) -> impl Iterator<Item = (&String, &Arc<Trait>)> {
+        self.trait_table.traits.iter()
+    }
+    pub fn interfaces(&self) -> impl Iterator<Item = (&String, &Arc<Interface>)> {
+        self.interfaces.iter()
+    }
 }
 
 
@@ -715,6 +1029,19 @@ pub trait ToBinary {
     fn to_binary(&self, string_table: Option<&mut StringTable>) -> Vec<u8>;
 }
 
+/// TraitTable
+/// The traits that a binary defines
+pub struct TraitTable {
+    traits: HashMap<String, Arc<Trait>>,
+}
+
+impl TraitTable {
+    /// Get a trait from this binary or one that was loaded before it
+    pub fn get_trait(&self, name: &str) -> Option<Arc<Trait>> {
+        self.traits.get(name).cloned().or_else(|| crate::object::get_trait(name))
+    }
+}
+
 pub struct ClassTable {
     classes: HashMap<String, Class>,
 }
@@ -733,29 +1060,62 @@ impl ToBinary for ClassTable {
         for name in self.classes.keys() {
             let idx = string_table.add_string(name.clone());
             binary.extend_from_slice(idx.to_binary(None).as_slice());
-            self.classes.get(name).unwrap().to_binary(Some(string_table));
+            binary.extend(self.classes.get(name).unwrap().to_binary(Some(string_table)));
         }
         binary
     }
 }
 
 
+/// StringTable
+/// Every string gets interned as a symbol when it is added so that the loader can turn string
+/// table indices into symbols without hashing the string again.
 pub struct StringTable {
     strings: BTreeMap<usize, String>,
     strings_to_idx: HashMap<String, usize>,
+    symbols: Vec<Symbol>,
+}
+
+impl Default for StringTable {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl StringTable {
+    pub fn new() -> StringTable {
+        StringTable {
+            strings: BTreeMap::new(),
+            strings_to_idx: HashMap::new(),
+            symbols: Vec::new(),
+        }
+    }
     pub fn add_string(&mut self, string: String) -> usize {
-        let idx = if self.strings_to_idx.contains_key(&string) {
-            *self.strings_to_idx.get(&string).unwrap()
-        } else {
-            self.strings.len()
-        };
+        if let Some(idx) = self.strings_to_idx.get(&string) {
+            return *idx;
+        }
+        let idx = self.strings.len();
+        self.symbols.push(Symbol::intern(&string));
         self.strings.insert(idx, string.clone());
         self.strings_to_idx.insert(string, idx);
         idx
     }
+    /// Add a string at the next index even if it is already in the table
+    /// Parsed tables use this so that every entry stays at the index the file gives it.
+    pub fn push_string(&mut self, string: String) -> usize {
+        let idx = self.strings.len();
+        self.symbols.push(Symbol::intern(&string));
+        self.strings.insert(idx, string.clone());
+        self.strings_to_idx.entry(string).or_insert(idx);
+        idx
+    }
+    pub fn get_string(&self, idx: usize) -> Option<&str> {
+        self.strings.get(&idx).map(|string| string.as_str())
+    }
+    /// Get the symbol of the string at an index
+    pub fn symbol(&self, idx: usize) -> Result<Symbol, Fault> {
+        self.symbols.get(idx).copied().ok_or(Fault::InvalidOperation(format!("string {} is missing", idx)))
+    }
 }
 
 impl ToBinary for StringTable {
@@ -775,6 +1135,21 @@ pub struct BlockTable {
     //block_to_idx: HashMap<Vec<ByteCode>, usize>,
 }
 
+impl Default for BlockTable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BlockTable {
+    pub fn new() -> BlockTable {
+        BlockTable { blocks: BTreeMap::new() }
+    }
+    pub fn insert(&mut self, idx: usize, bytecode: Vec<ByteCode>) {
+        self.blocks.insert(idx, bytecode);
+    }
+}
+
 impl ToBinary for BlockTable {
     fn to_binary(&self, string_table: Option<&mut StringTable>) -> Vec<u8> {
         let string_table = string_table.expect("BlockTable::to_binary called without a StringTable");
@@ -797,3 +1172,249 @@ impl ToBinary for usize {
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn string_table(strings: &[&str]) -> Vec<u8> {
+        let mut binary = strings.len().to_binary(None);
+        for string in strings {
+            binary.extend(string.len().to_binary(None));
+            binary.extend_from_slice(string.as_bytes());
+        }
+        binary
+    }
+
+    fn words(values: &[u64]) -> Vec<u8> {
+        values.iter().flat_map(|value| value.to_le_bytes()).collect()
+    }
+
+    /// The name, the parent and whatever comes after the parent of a class table entry
+    fn class_entry(name: u64, parent: Option<u64>, rest: &[u64]) -> Vec<u8> {
+        let mut binary = words(&[name]);
+        binary.push(parent.is_some() as u8);
+        binary.extend(words(&parent.into_iter().chain(rest.iter().copied()).collect::<Vec<_>>()));
+        binary
+    }
+
+    #[test]
+    fn class_side_entries_are_read_from_0_0_3_on() {
+        // Point < Object with no methods, then 2 class fields and the class method at index 2
+        let old = class_entry(0, Some(1), &[0, 0]);
+        let mut new = old.clone();
+        new.extend(words(&[2, 1, 2, 0]));
+
+        let (rest, (_, class)) = parse_class((0, 0, 2)).parse(&old).finish().unwrap();
+        assert!(rest.is_empty());
+        assert_eq!(class.class_field_count, 0);
+        assert!(class.class_methods.is_empty());
+
+        let (rest, (name, class)) = parse_class((0, 0, 3)).parse(&new).finish().unwrap();
+        assert!(rest.is_empty());
+        assert_eq!(name, 0);
+        assert_eq!(class.parent, Some(1));
+        assert_eq!(class.class_field_count, 2);
+        assert_eq!(class.class_methods.len(), 1);
+        assert_eq!(class.class_methods[0].0, 2);
+    }
+
+    /// A file with one class, the strings and no blocks followed by the tables of later versions
+    fn file(version: u8, class: Vec<u8>, strings: &[&str], tables: &[u64]) -> Vec<u8> {
+        let mut binary = b"SPK".to_vec();
+        binary.extend_from_slice(&[0, 0, version]);
+        binary.extend(words(&[1]));
+        binary.extend(class);
+        binary.extend(string_table(strings));
+        binary.extend(words(&[0]));
+        binary.extend(words(tables));
+        binary
+    }
+
+    #[test]
+    fn traits_are_read_from_0_0_4_on() {
+        let strings = ["Point", "Printable", "print", "show"];
+        let old = file(3, class_entry(0, None, &[0, 0, 0, 0]), &strings, &[]);
+        let (rest, binary) = parse_binary(&old).finish().unwrap();
+        assert!(rest.is_empty());
+        assert!(binary.trait_table.traits.is_empty());
+        assert!(binary.class_table.classes[0].1.traits.is_empty());
+
+        // Point uses Printable without print but with show as another name for it
+        let class = class_entry(0, None, &[0, 0, 0, 0, 1, 1, 1, 2, 1, 3, 2]);
+        // Printable has print with an empty body
+        let new = file(4, class, &strings, &[1, 1, 1, 2, 0]);
+        let (rest, binary) = parse_binary(&new).finish().unwrap();
+        assert!(rest.is_empty());
+        assert_eq!(binary.trait_table.traits.len(), 1);
+        assert_eq!(binary.trait_table.traits[0].0, 1);
+        assert_eq!(binary.trait_table.traits[0].1[0].0, 2);
+        let uses = &binary.class_table.classes[0].1.traits;
+        assert_eq!(uses.len(), 1);
+        assert_eq!(uses[0].name, 1);
+        assert_eq!(uses[0].excluded, vec![2]);
+        assert_eq!(uses[0].aliases, vec![(3, 2)]);
+    }
+
+    #[test]
+    fn interfaces_are_read_from_0_0_5_on() {
+        let strings = ["Shape", "area", "HasArea"];
+        let old = file(4, class_entry(0, None, &[0, 0, 0, 0, 0]), &strings, &[0]);
+        let (rest, binary) = parse_binary(&old).finish().unwrap();
+        assert!(rest.is_empty());
+        assert!(binary.interface_table.interfaces.is_empty());
+
+        // Shape leaves area abstract and says it is a HasArea, which asks for area
+        let class = class_entry(0, None, &[0, 0, 0, 0, 0, 1, 1, 1, 2]);
+        let new = file(5, class, &strings, &[0, 1, 2, 1, 1]);
+        let (rest, binary) = parse_binary(&new).finish().unwrap();
+        assert!(rest.is_empty());
+        let class = &binary.class_table.classes[0].1;
+        assert_eq!(class.abstract_methods, vec![1]);
+        assert_eq!(class.interfaces, vec![2]);
+        assert_eq!(binary.interface_table.interfaces, vec![(2, vec![1])]);
+    }
+
+    /// A method that sends a selector to itself
+    fn sending(selector: &str) -> Arc<Method> {
+        let bytecode = vec![ByteCode::SendMsg(0, Symbol::from(selector))];
+        Arc::new(Method::BytecodeMethod { block: crate::object::create_block(bytecode) })
+    }
+
+    /// The selectors that a method sends
+    fn sends(method: &Method) -> Vec<Symbol> {
+        let Method::BytecodeMethod { block } = method else { panic!("Expected a bytecode method") };
+        let block = block.borrow();
+        let block = block.downcast_ref::<crate::object::block::Block>().unwrap();
+        block.bytecode.iter().filter_map(|bytecode| match bytecode {
+            ByteCode::SendMsg(_, selector) => Some(*selector),
+            _ => None,
+        }).collect()
+    }
+
+    /// Write a class and read its entry back the way the loader does
+    fn write_and_read(class: &Class) -> Class {
+        let mut strings = StringTable::new();
+        let name = strings.add_string("Point".to_string());
+        let mut binary = name.to_binary(None);
+        binary.extend(class.to_binary(Some(&mut strings)));
+        let (rest, (name, class)) = parse_class(VERSION).parse(&binary).finish().unwrap();
+        assert!(rest.is_empty());
+        assert_eq!(strings.get_string(name), Some("Point"));
+        let traits = TraitTable { traits: HashMap::new() };
+        class.into_class("Point", &strings, &BlockTable::new(), &traits).unwrap()
+    }
+
+    fn vtable(methods: &[(&str, &str)]) -> VTable {
+        VTable::new(methods.iter().map(|(name, sent)| (Symbol::from(*name), sending(sent))).collect())
+    }
+
+    #[test]
+    fn classes_are_written_with_their_class_side() {
+        let class = Class::new(Some("Object"), vtable(&[("x", "y")]), vec![vtable(&[("deep", "a")]), vtable(&[("shallow", "b")])])
+            .with_class_side(vtable(&[("origin", "new")]), 2);
+        let read = write_and_read(&class);
+        assert_eq!(read.get_parent(), Some("Object"));
+        assert_eq!(sends(&read.get_method(Symbol::from("x")).unwrap()), vec![Symbol::from("y")]);
+        let overrides = read.get_overrides();
+        assert_eq!(overrides.len(), 2);
+        assert!(overrides[0].get_method(Symbol::from("deep")).is_some());
+        assert!(overrides[1].get_method(Symbol::from("shallow")).is_some());
+        assert_eq!(read.get_class_field_count(), 2);
+        let origin = read.get_class_vtable().get_method(Symbol::from("origin")).unwrap();
+        assert_eq!(sends(&origin), vec![Symbol::from("new")]);
+    }
+
+    #[test]
+    fn classes_are_written_with_their_traits_composed() {
+        let printable = Arc::new(Trait::new(vtable(&[("print", "show_on")])));
+        let uses = TraitUse { name: "Printable".to_string(), excluded: Vec::new(), aliases: vec![(Symbol::from("show"), Symbol::from("print"))] };
+        let class = Class::new(Some("Object"), vtable(&[("x", "y")]), Vec::new())
+            .compose("Point", &[(uses, printable)])
+            .unwrap();
+        // The trait isn't needed to read the class back
+        let read = write_and_read(&class);
+        assert_eq!(sends(&read.get_method(Symbol::from("print")).unwrap()), vec![Symbol::from("show_on")]);
+        assert_eq!(sends(&read.get_method(Symbol::from("show")).unwrap()), vec![Symbol::from("show_on")]);
+        assert!(read.get_method(Symbol::from("x")).is_some());
+    }
+
+    #[test]
+    fn classes_are_written_with_their_abstract_selectors_and_interfaces() {
+        let class = Class::new(Some("Object"), vtable(&[("describe", "area")]), Vec::new())
+            .with_requirements(vec![Symbol::from("area")], vec!["HasArea".to_string(), "Printable".to_string()]);
+        let read = write_and_read(&class);
+        assert!(read.is_abstract());
+        assert_eq!(read.get_abstract_methods(), &[Symbol::from("area")]);
+        assert_eq!(read.get_interfaces(), &["HasArea".to_string(), "Printable".to_string()]);
+    }
+
+    #[test]
+    fn symbol_literals_are_read_from_0_0_2_on() {
+        // A block that pushes the symbol at index 3
+        let mut block = words(&[1]);
+        block.extend_from_slice(&[4, 14]);
+        block.extend(words(&[3]));
+
+        assert!(parse_bytecode((0, 0, 1)).parse(&block).finish().is_err());
+        let (rest, bytecode) = parse_bytecode((0, 0, 2)).parse(&block).finish().unwrap();
+        assert!(rest.is_empty());
+        assert!(matches!(bytecode[..], [ProtoByteCode::PushLiteral(ProtoLiteral::Symbol(3))]));
+    }
+
+    #[test]
+    fn unknown_literals_are_malformed() {
+        let mut block = words(&[1]);
+        block.extend_from_slice(&[4, 99]);
+        assert!(parse_bytecode(VERSION).parse(&block).finish().is_err());
+    }
+
+    #[test]
+    fn newer_files_are_rejected() {
+        let strings = ["Point"];
+        let class = class_entry(0, None, &[0, 0, 0, 0, 0, 0, 0]);
+        let current = file(VERSION.2, class.clone(), &strings, &[0, 0]);
+        assert!(parse_binary(&current).finish().is_ok());
+        let newer = file(VERSION.2 + 1, class, &strings, &[0, 0]);
+        assert!(matches!(binary_data_to_binary(&newer), Err(Fault::InvalidOperation(_))));
+    }
+
+    #[test]
+    fn missing_selectors_are_faults() {
+        // Point has one method whose name is past the end of the string table
+        let class = class_entry(0, None, &[1, 9, 0, 0, 0, 0, 0, 0, 0]);
+        let (_, binary) = parse_binary(&file(VERSION.2, class, &["Point"], &[0, 0])).finish().unwrap();
+        assert!(matches!(binary.into_binary(), Err(Fault::InvalidOperation(_))));
+    }
+
+    #[test]
+    fn duplicate_strings_keep_their_index() {
+        let binary = string_table(&["add", "sub", "add", "mul"]);
+        let (rest, table) = parse_string_table(&binary).finish().unwrap();
+        assert!(rest.is_empty());
+        assert_eq!(table.get_string(2), Some("add"));
+        assert_eq!(table.get_string(3), Some("mul"));
+        assert_eq!(table.symbol(3).unwrap(), Symbol::from("mul"));
+        assert!(table.symbol(4).is_err());
+    }
+
+    #[test]
+    fn strings_of_the_same_length_are_kept_apart() {
+        let binary = string_table(&["one", "two"]);
+        let (_, table) = parse_string_table(&binary).finish().unwrap();
+        assert_eq!(table.get_string(0), Some("one"));
+        assert_eq!(table.get_string(1), Some("two"));
+    }
+
+    #[test]
+    fn string_tables_round_trip() {
+        let mut table = StringTable::new();
+        for string in ["new", "", "to_string", "new", "naïve"] {
+            table.add_string(string.to_string());
+        }
+        let (_, parsed) = parse_string_table(&table.to_binary(None)).finish().unwrap();
+        for idx in 0..4 {
+            assert_eq!(parsed.get_string(idx), table.get_string(idx));
+        }
+        assert_eq!(parsed.get_string(4), None);
+    }
+}
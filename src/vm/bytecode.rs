@@ -6,6 +6,8 @@
 //! - How Blocks' (closures) captures are put after the arguments in the temporary variables.
 //! - The importance of running the init message on an object before it is used.
 use super::binary::ToBinary;
+use crate::object::primitive::bigint::BigInt;
+use crate::object::symbol::Symbol;
 
 
 
@@ -34,12 +36,12 @@ pub enum ByteCode {
     StoreTemp(usize),
     /// Send a message to an object
     /// The usize is the number of arguments to send
-    /// The string is the name of the message to send
-    SendMsg(usize, String),
+    /// The symbol is the name of the message to send
+    SendMsg(usize, Symbol),
     /// Send a message to the super object
     /// The usize is the number of arguments to send
-    /// The string is the name of the message to send
-    SendSuperMsg(usize, String),
+    /// The symbol is the name of the message to send
+    SendSuperMsg(usize, Symbol),
     /// Perform a special instruction
     SpecialInstruction(SpecialInstruction),
     /// Get from the current runtime stack
@@ -82,14 +84,14 @@ impl ToBinary for ByteCode {
                 binary
             },
             ByteCode::SendMsg(num_args, name) => {
-                let idx = string_table.add_string(name.clone());
+                let idx = string_table.add_string(name.as_str().to_string());
                 let mut binary = vec![7];
                 binary.extend(num_args.to_binary(None));
                 binary.extend(idx.to_binary(None));
                 binary
             },
             ByteCode::SendSuperMsg(num_args, name) => {
-                let idx = string_table.add_string(name.clone());
+                let idx = string_table.add_string(name.as_str().to_string());
                 let mut binary = vec![8];
                 binary.extend(num_args.to_binary(None));
                 binary.extend(idx.to_binary(None));
@@ -196,6 +198,8 @@ pub enum Literal {
     Boolean(bool),
     Nil,
     ByteCode(Vec<ByteCode>),
+    Symbol(Symbol),
+    BigInt(BigInt),
 }
 
 impl ToBinary for Literal {
@@ -263,6 +267,21 @@ impl ToBinary for Literal {
                     output.extend(code.to_binary(Some(string_table)));
                 }
             },
+            Literal::Symbol(symbol) => {
+                output.push(14);
+                let string_table = string_table.expect("Literal::to_binary called without a StringTable");
+                let idx = string_table.add_string(symbol.as_str().to_string());
+                output.extend_from_slice(&idx.to_binary(None));
+            },
+            Literal::BigInt(value) => {
+                output.push(15);
+                let (negative, limbs) = value.parts();
+                output.push(if negative { 1 } else { 0 });
+                output.extend(limbs.len().to_binary(None));
+                for limb in limbs {
+                    output.extend(limb.to_le_bytes());
+                }
+            },
                     
         }
         output
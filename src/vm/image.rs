@@ -0,0 +1,1732 @@
+//! Heap images
+//! An image holds everything that is needed to continue a VM later: the loaded classes, every
+//! object that a suspended interpreter can reach and the interpreters themselves.
+//! Strings and bytecode are encoded the same way as in an SPK file.
+//!
+//! The binary format is as follows:
+//! header: "SPI" version (u8, u8, u8)
+//!
+//! string_table: length (u64), \[string_table_entry\]
+//!
+//! code_table: length (u64), \[bytecode_entry\]
+//!
+//! interface_table: length (u64), \[interface_entry\]
+//!
+//! class_table: length (u64), \[class_entry\]
+//!
+//! object_table: length (u64), \[object_entry\]
+//!
+//! task_table: length (u64), \[task_entry\]
+//!
+//! finalizer_table: length (u64), \[object_id (u64)\]
+//!
+//!
+//! A block literal holds an index into the code table like it does into the block table of an SPK file.
+//!
+//! class_entry: name_index (u64), flag (u8), parent_index (?u64), method_count (u64), \[code_method\], override_count (u64), \[method_count (u64), \[code_method\]\], class_field_count (u64), method_count (u64), \[code_method\], abstract_count (u64), \[name_index (u64)\], interface_count (u64), \[name_index (u64)\]
+//!
+//! interface_entry: name_index (u64), selector_count (u64), \[name_index (u64)\]
+//!
+//! code_method: name_index (u64), code_index (u64)
+//!
+//! object_entry: tag (u8), then one of
+//! - 0 shared builtin: name_index (u64), depth (u64)
+//! - 2 nil
+//! - 3 object: name_index (u64), flag (u8), parent_id (?u64), payload, method_count (u64), \[object_method\]
+//! - 4 class object: name_index (u64), payload, method_count (u64), \[object_method\]
+//!
+//! object_method: name_index (u64), block_id (u64)
+//!
+//! payload: tag (u8), then one of
+//! - 0 nothing
+//! - 1 immediate
+//! - 2 string: string_index (u64)
+//! - 3 symbol: string_index (u64)
+//! - 4 message: string_index (u64)
+//! - 5 vector: length (u64), \[object_id (u64)\]
+//! - 6 fields: length (u64), \[object_id (u64)\]
+//! - 7 stack: length (u64), \[value\]
+//! - 8 block: code_index (u64), length (u64), \[object_id (u64)\]
+//! - 9 weak reference: flag (u8), object_id (?u64)
+//! - 10 bigint: negative (u8), length (u64), \[limb (u32)\]
+//! - 11 rational: numerator (i128), denominator (i128)
+//! - 12 decimal: mantissa (i128), scale (u32)
+//! - 13 regex: string_index (u64)
+//! - 14 match: start (u64), end (u64), length (u64), \[flag (u8), string_index (?u64)\], length (u64), \[flag (u8), string_index (?u64)\], the text of each group and then the name of each group
+//! - 15 string builder: string_index (u64)
+//! - 16 byte array: length (u64), \[byte (u8)\]
+//! - 17 list: length (u64), \[object_id (u64)\]
+//! - 18 set: length (u64), \[object_id (u64)\], the elements are hashed again when the set is next used
+//! - 19 iterator: peeked (u8), object_id (?u64), source, peeked is 0 when the iterator hasn't looked ahead, 1 when it found the end and 2 when it holds the element
+//! - 20 range: kind (u8), start (i128), end (i128), step (i128), inclusive (u8), the kind is the immediate tag of the element type or 8 for a bigint
+//!
+//! source: tag (u8), then one of
+//! - 0 fields: object_id (u64), index (u64)
+//! - 1 chars: object_id (u64), offset (u64)
+//! - 2 elements: length (u64), \[object_id (u64)\], index (u64)
+//! - 3 map, 4 filter: inner_id (u64), block_id (u64)
+//! - 5 take, 6 skip: inner_id (u64), remaining (u64)
+//! - 7 zip, 9 chain: first_id (u64), second_id (u64)
+//! - 8 enumerate: inner_id (u64), index (u64)
+//! - 10 flat map: inner_id (u64), block_id (u64), flag (u8), current_id (?u64)
+//! - 11 range: range, index (u128), the range is written like the payload
+//!
+//! value: tag (u8), then 0 object_id (u64) or 1 immediate
+//!
+//! immediate: tag (u8), value (little endian)
+//!
+//! task_entry: length (u64), \[code_index (u64), position (u64)\], context
+//!
+//! context: stack_id (u64), length (u64), \[object_id (u64)\], flag (u8), receiver_id (?u64), arg_count (u64), flag (u8), method_count (?u64), \[object_method\], flag (u8), code_index (?u64), flag (u8), immediate?
+//!
+//! Objects that the factory shares between instances are only saved by name so that a loaded
+//! image uses the shared objects of the new VM. Rust methods can't be saved either so every
+//! object gets the vtable of its type back when it is loaded and only the bytecode methods that
+//! were added to it are saved.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use lazy_static::lazy_static;
+use nom::{bytes, multi, number, Finish, IResult};
+
+use crate::object::block::Block;
+use crate::object::primitive::PrimitiveObject;
+use crate::object::primitive::bigint::{BigInt, BigIntObject};
+use crate::object::primitive::decimal::{Decimal, DecimalObject};
+use crate::object::primitive::rational::{Rational, RationalObject};
+use crate::object::regex::{Match, MatchObject, RegexObject};
+use crate::object::builder::StringBuilder;
+use crate::object::bytes::ByteArray;
+use crate::object::range::{Kind, Range, RangeObject};
+use crate::object::iterator::{IteratorObject, Source};
+use crate::object::list::ListObject;
+use crate::object::set::{ObjectSet, SetObject};
+use crate::object::stack::Stack;
+use crate::object::string::StringObject;
+use crate::object::symbol::Symbol;
+use crate::object::value::{Immediate, Value};
+use crate::object::vector::VectorObject;
+use crate::object::weak::WeakRef;
+use crate::object::class::ClassObject;
+use crate::object::gc;
+use crate::object::{self, BaseObject, Class, ContextData, Fault, Interface, Message, Method, Nil, Object, ObjectBox, ObjectStruct, SharedObject, VTable};
+use crate::object::primitive::{NumberObject, float::FloatObject, integer::IntegerObject};
+use crate::object::log::Logger;
+use crate::object::system::System;
+use crate::vm::binary::{self, parse_bytecode, parse_string_table, BlockTable, ProtoByteCode, StringTable, ToBinary};
+use crate::vm::bytecode::{ByteCode, Literal};
+use crate::vm::interpreter::Interpreter;
+
+lazy_static! {
+    static ref SAVE_REQUEST: Mutex<Option<String>> = Mutex::new(None);
+}
+
+/// Ask for an image to be saved the next time the scheduler stops the world.
+/// Saving can't happen while other cores are running since the heap must not change.
+pub fn request_save(path: String) {
+    *SAVE_REQUEST.lock().expect("image::request_save: lock poisoned") = Some(path);
+}
+
+pub fn save_requested() -> bool {
+    SAVE_REQUEST.lock().expect("image::save_requested: lock poisoned").is_some()
+}
+
+/// Take the path of the image that was asked for, if any
+pub fn take_save_request() -> Option<String> {
+    SAVE_REQUEST.lock().expect("image::take_save_request: lock poisoned").take()
+}
+
+/// Save the classes, the interpreters and everything they can reach to a file.
+/// This must only be called while no interpreter is running.
+/// Interpreters that are in the middle of running an instruction don't hold their context so they
+/// are left out.
+pub fn save_image(path: &str, interpreters: &[&Interpreter]) -> Result<(), Fault> {
+    let binary = ImageWriter::new().write(interpreters)?;
+    std::fs::write(path, binary).map_err(Fault::IO)
+}
+
+/// Load an image into this VM.
+/// This adds the classes of the image and returns the interpreters so they can be scheduled.
+pub fn load_image(path: &str) -> Result<Vec<Interpreter>, Fault> {
+    let data = std::fs::read(path).map_err(Fault::IO)?;
+    let (_, image) = parse_image(&data).finish()
+        .map_err(|_| Fault::InvalidOperation(format!("{} is not a valid image", path)))?;
+    image.restore()
+}
+
+
+/// ImageWriter
+/// Assigns every object an id the first time it is seen. An object's super object always gets its
+/// id first so that the objects can be created in order when they are loaded. The rest of an
+/// object is written later since it may refer back to objects that are still being written.
+struct ImageWriter {
+    strings: StringTable,
+    codes: HashMap<*const Vec<ByteCode>, usize>,
+    code_table: Vec<Vec<u8>>,
+    shared: HashMap<*const (), SharedObject>,
+    ids: HashMap<*const (), usize>,
+    objects: Vec<Vec<u8>>,
+    pending: Vec<(usize, ObjectBox)>,
+}
+
+/// What an object holds besides its super object and vtable
+enum Payload {
+    Empty,
+    Immediate(Immediate),
+    String(String),
+    Symbol(Symbol),
+    Message(Symbol),
+    Vector(Vec<ObjectBox>),
+    Fields(Vec<ObjectBox>),
+    Stack(Vec<Value>),
+    Block(Arc<Vec<ByteCode>>, Vec<ObjectBox>),
+    Weak(Option<ObjectBox>),
+    BigInt(BigInt),
+    Rational(Rational),
+    Decimal(Decimal),
+    Regex(String),
+    Match(Match),
+    Builder(String),
+    Bytes(Vec<u8>),
+    List(Vec<ObjectBox>),
+    Set(Vec<ObjectBox>),
+    Iterator(Source, Option<Option<ObjectBox>>),
+    Range(Range),
+}
+
+impl ImageWriter {
+    fn new() -> ImageWriter {
+        ImageWriter {
+            strings: StringTable::new(),
+            codes: HashMap::new(),
+            code_table: Vec::new(),
+            shared: object::shared_objects().into_iter()
+                .map(|(shared, object)| (object.as_ptr(), shared))
+                .rev()
+                .collect(),
+            ids: HashMap::new(),
+            objects: Vec::new(),
+            pending: Vec::new(),
+        }
+    }
+
+    fn write(mut self, interpreters: &[&Interpreter]) -> Result<Vec<u8>, Fault> {
+        let interfaces = object::get_interfaces();
+        let mut interface_table = interfaces.len().to_binary(None);
+        for (name, interface) in interfaces.iter() {
+            interface_table.extend(self.string(name).to_binary(None));
+            interface_table.extend(self.symbols(interface.get_selectors()));
+        }
+
+        let classes = object::get_classes();
+        let mut class_table = classes.len().to_binary(None);
+        for (name, class) in classes.iter() {
+            class_table.extend(self.class(name, class));
+        }
+
+        let interpreters: Vec<_> = interpreters.iter()
+            .filter_map(|interpreter| interpreter.context().map(|context| (interpreter, context)))
+            .collect();
+        let mut task_table = interpreters.len().to_binary(None);
+        for (interpreter, context) in interpreters {
+            task_table.extend(interpreter.frames().len().to_binary(None));
+            for (position, code) in interpreter.frames() {
+                task_table.extend(self.code_index(code).to_binary(None));
+                task_table.extend(position.to_binary(None));
+            }
+            task_table.extend(self.context(context)?);
+        }
+
+        let finalizers = gc::finalizable_objects();
+        let mut finalizer_table = finalizers.len().to_binary(None);
+        for object in finalizers.iter() {
+            finalizer_table.extend(self.object_id(object)?.to_binary(None));
+        }
+
+        while let Some((id, object)) = self.pending.pop() {
+            let body = self.object_body(&object)?;
+            self.objects[id].extend(body);
+        }
+
+        let mut binary = vec![];
+        binary.extend_from_slice(b"SPI");
+        binary.extend_from_slice(&[0, 0, 5]); // version
+        binary.extend(self.strings.to_binary(None));
+        binary.extend(self.code_table.len().to_binary(None));
+        for code in self.code_table {
+            binary.extend(code);
+        }
+        binary.extend(interface_table);
+        binary.extend(class_table);
+        binary.extend(self.objects.len().to_binary(None));
+        for object in self.objects {
+            binary.extend(object);
+        }
+        binary.extend(task_table);
+        binary.extend(finalizer_table);
+        Ok(binary)
+    }
+
+    fn string(&mut self, string: &str) -> usize {
+        self.strings.add_string(string.to_string())
+    }
+
+    fn code_index(&mut self, code: &Arc<Vec<ByteCode>>) -> usize {
+        if let Some(index) = self.codes.get(&Arc::as_ptr(code)) {
+            return *index;
+        }
+        let index = self.add_code(code);
+        self.codes.insert(Arc::as_ptr(code), index);
+        index
+    }
+
+    /// Add code to the code table
+    /// Blocks inside of the code get added first so that they are already loaded when the code
+    /// that refers to them gets loaded.
+    fn add_code(&mut self, code: &[ByteCode]) -> usize {
+        let mut binary = code.len().to_binary(None);
+        for bytecode in code {
+            match bytecode {
+                ByteCode::PushLiteral(Literal::ByteCode(block)) => {
+                    let index = self.add_code(block);
+                    binary.extend_from_slice(&[4, 13]);
+                    binary.extend(index.to_binary(None));
+                }
+                bytecode => binary.extend(bytecode.to_binary(Some(&mut self.strings))),
+            }
+        }
+        self.code_table.push(binary);
+        self.code_table.len() - 1
+    }
+
+    fn class(&mut self, name: &str, class: &Class) -> Vec<u8> {
+        let mut binary = self.string(name).to_binary(None);
+        match class.get_parent() {
+            Some(parent) => {
+                binary.push(1);
+                binary.extend(self.string(parent).to_binary(None));
+            }
+            None => binary.push(0),
+        }
+        binary.extend(self.code_methods(&class.get_vtable()));
+        let overrides = class.get_overrides();
+        binary.extend(overrides.len().to_binary(None));
+        for vtable in overrides.iter() {
+            binary.extend(self.code_methods(vtable));
+        }
+        binary.extend(class.get_class_field_count().to_binary(None));
+        binary.extend(self.code_methods(&class.get_class_vtable()));
+        binary.extend(self.symbols(class.get_abstract_methods()));
+        let interfaces = class.get_interfaces();
+        binary.extend(interfaces.len().to_binary(None));
+        for interface in interfaces {
+            binary.extend(self.string(interface).to_binary(None));
+        }
+        binary
+    }
+
+    fn symbols(&mut self, symbols: &[Symbol]) -> Vec<u8> {
+        let mut binary = symbols.len().to_binary(None);
+        for symbol in symbols {
+            binary.extend(self.string(symbol.as_str()).to_binary(None));
+        }
+        binary
+    }
+
+    fn code_methods(&mut self, vtable: &VTable) -> Vec<u8> {
+        let methods: Vec<_> = vtable.iter()
+            .filter_map(|(name, method)| match &**method {
+                Method::BytecodeMethod { block } => Some((*name, block.clone())),
+                Method::RustMethod { .. } => None,
+            })
+            .collect();
+        let mut binary = methods.len().to_binary(None);
+        for (name, block) in methods {
+            let code = block.borrow().downcast_ref::<Block>().map(|block| block.bytecode.clone());
+            let code = code.expect("ImageWriter: bytecode method was not a block");
+            binary.extend(self.string(name.as_str()).to_binary(None));
+            binary.extend(self.code_index(&code).to_binary(None));
+        }
+        binary
+    }
+
+    fn object_methods(&mut self, methods: Vec<(Symbol, ObjectBox)>) -> Result<Vec<u8>, Fault> {
+        let mut binary = methods.len().to_binary(None);
+        for (name, block) in methods {
+            binary.extend(self.string(name.as_str()).to_binary(None));
+            binary.extend(self.object_id(&block)?.to_binary(None));
+        }
+        Ok(binary)
+    }
+
+    fn object_id(&mut self, object: &ObjectBox) -> Result<usize, Fault> {
+        if let Some(id) = self.ids.get(&object.as_ptr()) {
+            return Ok(*id);
+        }
+        let mut binary = vec![];
+        match self.shared.get(&object.as_ptr()) {
+            Some(SharedObject::Builtin(name, depth)) => {
+                let (name, depth) = (name.clone(), *depth);
+                binary.push(0);
+                binary.extend(self.string(&name).to_binary(None));
+                binary.extend(depth.to_binary(None));
+            }
+            None if object.borrow().is::<Nil>() => binary.push(2),
+            None if object.borrow().is::<ClassObject>() => {
+                let name = object.borrow().downcast_ref::<ClassObject>().map(|class| class.name.clone()).unwrap_or_default();
+                binary.push(4);
+                binary.extend(self.string(&name).to_binary(None));
+                self.pending.push((self.objects.len(), object.clone()));
+            }
+            None => {
+                let borrowed = object.borrow();
+                let name = type_name(&*borrowed)?;
+                let parent = borrowed.get_super_object();
+                drop(borrowed);
+                binary.push(3);
+                binary.extend(self.string(&name).to_binary(None));
+                match parent {
+                    Some(parent) => {
+                        binary.push(1);
+                        binary.extend(self.object_id(&parent)?.to_binary(None));
+                    }
+                    None => binary.push(0),
+                }
+                self.pending.push((self.objects.len(), object.clone()));
+            }
+        }
+        let id = self.objects.len();
+        self.ids.insert(object.as_ptr(), id);
+        self.objects.push(binary);
+        Ok(id)
+    }
+
+    /// Write the payload and the methods of an object
+    fn object_body(&mut self, object: &ObjectBox) -> Result<Vec<u8>, Fault> {
+        let borrowed = object.borrow();
+        let payload = payload(&*borrowed);
+        let default = if let Some(object) = borrowed.downcast_ref::<ObjectStruct>() {
+            object.get_class().map(|class| class.get_vtable())
+        } else if let Some(object) = borrowed.downcast_ref::<ClassObject>() {
+            object::get_class(&object.name).map(|class| class.get_class_vtable())
+        } else {
+            object::prototype_vtable(&type_name(&*borrowed)?)
+        };
+        let default = default.unwrap_or_else(VTable::new_empty);
+        let methods = added_methods(borrowed.get_vtable(), &default);
+        drop(borrowed);
+
+        let mut binary = vec![];
+        match payload {
+            Payload::Empty => binary.push(0),
+            Payload::Immediate(immediate) => {
+                binary.push(1);
+                binary.extend(immediate.to_binary(None));
+            }
+            Payload::String(string) => {
+                binary.push(2);
+                binary.extend(self.string(&string).to_binary(None));
+            }
+            Payload::Symbol(symbol) => {
+                binary.push(3);
+                binary.extend(self.string(symbol.as_str()).to_binary(None));
+            }
+            Payload::Message(index) => {
+                binary.push(4);
+                binary.extend(self.string(index.as_str()).to_binary(None));
+            }
+            Payload::Vector(objects) => {
+                binary.push(5);
+                binary.extend(self.object_ids(&objects)?);
+            }
+            Payload::Fields(objects) => {
+                binary.push(6);
+                binary.extend(self.object_ids(&objects)?);
+            }
+            Payload::Stack(values) => {
+                binary.push(7);
+                binary.extend(values.len().to_binary(None));
+                for value in values.iter() {
+                    binary.extend(self.value(value)?);
+                }
+            }
+            Payload::Block(code, captures) => {
+                binary.push(8);
+                binary.extend(self.code_index(&code).to_binary(None));
+                binary.extend(self.object_ids(&captures)?);
+            }
+            Payload::Weak(target) => {
+                binary.push(9);
+                match target {
+                    Some(target) => {
+                        binary.push(1);
+                        binary.extend(self.object_id(&target)?.to_binary(None));
+                    }
+                    None => binary.push(0),
+                }
+            }
+            Payload::BigInt(value) => {
+                binary.push(10);
+                let (negative, limbs) = value.parts();
+                binary.push(negative as u8);
+                binary.extend(limbs.len().to_binary(None));
+                for limb in limbs {
+                    binary.extend(limb.to_le_bytes());
+                }
+            }
+            Payload::Rational(value) => {
+                binary.push(11);
+                binary.extend(value.numerator().to_le_bytes());
+                binary.extend(value.denominator().to_le_bytes());
+            }
+            Payload::Decimal(value) => {
+                binary.push(12);
+                binary.extend(value.mantissa().to_le_bytes());
+                binary.extend(value.scale().to_le_bytes());
+            }
+            Payload::Regex(pattern) => {
+                binary.push(13);
+                binary.extend(self.string(&pattern).to_binary(None));
+            }
+            Payload::Match(found) => {
+                binary.push(14);
+                binary.extend(found.start.to_binary(None));
+                binary.extend(found.end.to_binary(None));
+                for strings in [&found.groups, &found.names] {
+                    binary.extend(strings.len().to_binary(None));
+                    for string in strings {
+                        match string {
+                            Some(string) => {
+                                binary.push(1);
+                                binary.extend(self.string(string).to_binary(None));
+                            }
+                            None => binary.push(0),
+                        }
+                    }
+                }
+            }
+            Payload::Builder(string) => {
+                binary.push(15);
+                binary.extend(self.string(&string).to_binary(None));
+            }
+            Payload::Bytes(bytes) => {
+                binary.push(16);
+                binary.extend(bytes.len().to_binary(None));
+                binary.extend(bytes);
+            }
+            Payload::List(objects) => {
+                binary.push(17);
+                binary.extend(self.object_ids(&objects)?);
+            }
+            Payload::Set(objects) => {
+                binary.push(18);
+                binary.extend(self.object_ids(&objects)?);
+            }
+            Payload::Iterator(source, peeked) => {
+                binary.push(19);
+                match peeked {
+                    None => binary.push(0),
+                    Some(None) => binary.push(1),
+                    Some(Some(element)) => {
+                        binary.push(2);
+                        binary.extend(self.object_id(&element)?.to_binary(None));
+                    }
+                }
+                binary.extend(self.source(&source)?);
+            }
+            Payload::Range(range) => {
+                binary.push(20);
+                binary.extend(range_binary(&range));
+            }
+        }
+        binary.extend(self.object_methods(methods)?);
+        Ok(binary)
+    }
+
+    /// Write where an iterator gets its elements from
+    fn source(&mut self, source: &Source) -> Result<Vec<u8>, Fault> {
+        let mut binary = vec![];
+        let (tag, objects, count): (u8, Vec<&ObjectBox>, Option<u64>) = match source {
+            Source::Fields { collection, index } => (0, vec![collection], Some(*index as u64)),
+            Source::Chars { string, offset } => (1, vec![string], Some(*offset as u64)),
+            Source::Elements { elements, index } => {
+                binary.push(2);
+                binary.extend(self.object_ids(elements)?);
+                binary.extend(index.to_binary(None));
+                return Ok(binary);
+            }
+            Source::Range { range, index } => {
+                binary.push(11);
+                binary.extend(range_binary(range));
+                binary.extend(index.to_le_bytes());
+                return Ok(binary);
+            }
+            Source::Map { inner, block } => (3, vec![inner, block], None),
+            Source::Filter { inner, block } => (4, vec![inner, block], None),
+            Source::Take { inner, remaining } => (5, vec![inner], Some(*remaining as u64)),
+            Source::Skip { inner, remaining } => (6, vec![inner], Some(*remaining as u64)),
+            Source::Zip { first, second } => (7, vec![first, second], None),
+            Source::Enumerate { inner, index } => (8, vec![inner], Some(*index)),
+            Source::Chain { first, second } => (9, vec![first, second], None),
+            Source::FlatMap { inner, block, current } => {
+                binary.push(10);
+                binary.extend(self.object_id(inner)?.to_binary(None));
+                binary.extend(self.object_id(block)?.to_binary(None));
+                match current {
+                    Some(current) => {
+                        binary.push(1);
+                        binary.extend(self.object_id(current)?.to_binary(None));
+                    }
+                    None => binary.push(0),
+                }
+                return Ok(binary);
+            }
+        };
+        binary.push(tag);
+        for object in objects {
+            binary.extend(self.object_id(object)?.to_binary(None));
+        }
+        if let Some(count) = count {
+            binary.extend(count.to_le_bytes());
+        }
+        Ok(binary)
+    }
+
+    fn object_ids(&mut self, objects: &[ObjectBox]) -> Result<Vec<u8>, Fault> {
+        let mut binary = objects.len().to_binary(None);
+        for object in objects {
+            binary.extend(self.object_id(object)?.to_binary(None));
+        }
+        Ok(binary)
+    }
+
+    fn value(&mut self, value: &Value) -> Result<Vec<u8>, Fault> {
+        let mut binary = vec![];
+        match value {
+            Value::Object(object) => {
+                binary.push(0);
+                binary.extend(self.object_id(object)?.to_binary(None));
+            }
+            Value::Immediate(immediate) => {
+                binary.push(1);
+                binary.extend(immediate.to_binary(None));
+            }
+        }
+        Ok(binary)
+    }
+
+    fn context(&mut self, context: &ContextData) -> Result<Vec<u8>, Fault> {
+        let mut binary = self.object_id(&context.stack)?.to_binary(None);
+        binary.extend(self.object_ids(&context.arguments)?);
+        match &context.receiver {
+            Some(receiver) => {
+                binary.push(1);
+                binary.extend(self.object_id(receiver)?.to_binary(None));
+            }
+            None => binary.push(0),
+        }
+        binary.extend(context.arg_count.to_binary(None));
+        match &context.vtable {
+            Some(vtable) => {
+                binary.push(1);
+                binary.extend(self.object_methods(added_methods(vtable, &VTable::new_empty()))?);
+            }
+            None => binary.push(0),
+        }
+        match &context.code {
+            Some(code) => {
+                binary.push(1);
+                binary.extend(self.code_index(code).to_binary(None));
+            }
+            None => binary.push(0),
+        }
+        match context.deferred_argument() {
+            Some(immediate) => {
+                binary.push(1);
+                binary.extend(immediate.to_binary(None));
+            }
+            None => binary.push(0),
+        }
+        Ok(binary)
+    }
+}
+
+/// Get the name of the builtin type or class that an object was made from
+fn type_name(object: &dyn Object) -> Result<String, Fault> {
+    if let Some(object) = object.downcast_ref::<ObjectStruct>() {
+        let class = object.get_class().ok_or(Fault::InvalidType("object has no class".to_string()))?;
+        return object::class_name(&class).ok_or(Fault::InvalidType("object's class was not added".to_string()));
+    }
+    let name = if object.is::<BaseObject>() {
+        "Object"
+    } else if object.is::<NumberObject>() {
+        "Number"
+    } else if object.is::<IntegerObject>() {
+        "Integer"
+    } else if object.is::<FloatObject>() {
+        "Float"
+    } else if object.is::<PrimitiveObject<i64>>() {
+        "I64"
+    } else if object.is::<PrimitiveObject<u64>>() {
+        "U64"
+    } else if object.is::<PrimitiveObject<i32>>() {
+        "I32"
+    } else if object.is::<PrimitiveObject<u32>>() {
+        "U32"
+    } else if object.is::<PrimitiveObject<i16>>() {
+        "I16"
+    } else if object.is::<PrimitiveObject<u16>>() {
+        "U16"
+    } else if object.is::<PrimitiveObject<i8>>() {
+        "I8"
+    } else if object.is::<PrimitiveObject<u8>>() {
+        "U8"
+    } else if object.is::<PrimitiveObject<f64>>() {
+        "F64"
+    } else if object.is::<PrimitiveObject<f32>>() {
+        "F32"
+    } else if object.is::<BigIntObject>() {
+        "BigInt"
+    } else if object.is::<RationalObject>() {
+        "Rational"
+    } else if object.is::<DecimalObject>() {
+        "Decimal"
+    } else if object.is::<StringObject>() {
+        "String"
+    } else if object.is::<PrimitiveObject<char>>() {
+        "Char"
+    } else if object.is::<PrimitiveObject<Symbol>>() {
+        "Symbol"
+    } else if object.is::<PrimitiveObject<bool>>() {
+        "Boolean"
+    } else if object.is::<Message>() {
+        "Message"
+    } else if object.is::<Logger>() {
+        "Logger"
+    } else if object.is::<Stack>() {
+        "Stack"
+    } else if object.is::<Block>() {
+        "Block"
+    } else if object.is::<VectorObject>() {
+        "Vector"
+    } else if object.is::<RegexObject>() {
+        "Regex"
+    } else if object.is::<MatchObject>() {
+        "Match"
+    } else if object.is::<IteratorObject>() {
+        "Iterator"
+    } else if object.is::<StringBuilder>() {
+        "StringBuilder"
+    } else if object.is::<ByteArray>() {
+        "ByteArray"
+    } else if object.is::<ListObject>() {
+        "List"
+    } else if object.is::<SetObject>() {
+        "Set"
+    } else if object.is::<RangeObject>() {
+        "Range"
+    } else if object.is::<System>() {
+        "System"
+    } else if object.is::<object::Context>() {
+        "Context"
+    } else if object.is::<WeakRef>() {
+        "WeakRef"
+    } else {
+        return Err(Fault::NotImplemented("saving this kind of object to an image".to_string()));
+    };
+    Ok(name.to_string())
+}
+
+fn range_binary(range: &Range) -> Vec<u8> {
+    let mut binary = vec![range.kind.tag()];
+    binary.extend(range.start.to_le_bytes());
+    binary.extend(range.end.to_le_bytes());
+    binary.extend(range.step.to_le_bytes());
+    binary.push(range.inclusive as u8);
+    binary
+}
+
+fn payload(object: &dyn Object) -> Payload {
+    if let Some(immediate) = Immediate::from_object(object) {
+        Payload::Immediate(immediate)
+    } else if let Some(big) = object.downcast_ref::<BigIntObject>() {
+        Payload::BigInt(big.value.clone())
+    } else if let Some(rational) = object.downcast_ref::<RationalObject>() {
+        Payload::Rational(rational.value)
+    } else if let Some(decimal) = object.downcast_ref::<DecimalObject>() {
+        Payload::Decimal(decimal.value)
+    } else if let Some(string) = object.downcast_ref::<StringObject>() {
+        Payload::String(string.value.clone())
+    } else if let Some(symbol) = object.downcast_ref::<PrimitiveObject<Symbol>>() {
+        Payload::Symbol(symbol.data)
+    } else if let Some(message) = object.downcast_ref::<Message>() {
+        Payload::Message(message.index)
+    } else if let Some(vector) = object.downcast_ref::<VectorObject>() {
+        Payload::Vector(vector.value.to_vec())
+    } else if let Some(regex) = object.downcast_ref::<RegexObject>() {
+        Payload::Regex(regex.value.as_str().to_string())
+    } else if let Some(found) = object.downcast_ref::<MatchObject>() {
+        Payload::Match(found.value.clone())
+    } else if let Some(iterator) = object.downcast_ref::<IteratorObject>() {
+        Payload::Iterator(iterator.source.clone(), iterator.peeked.clone())
+    } else if let Some(builder) = object.downcast_ref::<StringBuilder>() {
+        Payload::Builder(builder.value.clone())
+    } else if let Some(bytes) = object.downcast_ref::<ByteArray>() {
+        Payload::Bytes(bytes.value.clone())
+    } else if let Some(list) = object.downcast_ref::<ListObject>() {
+        Payload::List(list.value.clone())
+    } else if let Some(set) = object.downcast_ref::<SetObject>() {
+        Payload::Set(set.value.elements())
+    } else if let Some(range) = object.downcast_ref::<RangeObject>() {
+        Payload::Range(range.value)
+    } else if let Some(stack) = object.downcast_ref::<Stack>() {
+        Payload::Stack(stack.data.clone())
+    } else if let Some(block) = object.downcast_ref::<Block>() {
+        Payload::Block(block.bytecode.clone(), block.captures.clone())
+    } else if let Some(class) = object.downcast_ref::<ClassObject>() {
+        let size = class.size().unwrap_or(0);
+        Payload::Fields((0..size).filter_map(|index| class.get_field(index)).collect())
+    } else if let Some(weak) = object.downcast_ref::<WeakRef>() {
+        Payload::Weak(weak.target.as_ref().and_then(|target| target.upgrade()))
+    } else if let Some(object) = object.downcast_ref::<ObjectStruct>() {
+        // An instance that never got initialized doesn't have its class's methods yet
+        let size = object.size().unwrap_or(0);
+        if size == 0 && object.get_vtable().empty() {
+            return Payload::Empty;
+        }
+        Payload::Fields((0..size).filter_map(|index| object.get_field(index)).collect())
+    } else {
+        Payload::Empty
+    }
+}
+
+fn set_immediate(object: &mut dyn Object, immediate: Immediate) -> Result<(), Fault> {
+    macro_rules! set_immediate {
+        ($($type:ty => $variant:ident),*) => {
+            match immediate {
+                $(Immediate::$variant(value) => downcast_mut::<PrimitiveObject<$type>>(object)?.data = value,)*
+            }
+        };
+    }
+    set_immediate!(i8 => I8, i16 => I16, i32 => I32, i64 => I64, u8 => U8, u16 => U16, u32 => U32,
+                   u64 => U64, f32 => F32, f64 => F64, bool => Boolean, char => Char);
+    Ok(())
+}
+
+fn downcast_mut<T: Object>(object: &mut dyn Object) -> Result<&mut T, Fault> {
+    object.downcast_mut::<T>().ok_or(Fault::InvalidType("image object doesn't match its type".to_string()))
+}
+
+/// Get the bytecode methods of a vtable that aren't the same as in the default vtable
+fn added_methods(vtable: &VTable, default: &VTable) -> Vec<(Symbol, ObjectBox)> {
+    vtable.iter()
+        .filter_map(|(name, method)| match &**method {
+            Method::BytecodeMethod { block } => {
+                let inherited = default.get_method(*name).is_some_and(|other| Arc::ptr_eq(method, &other));
+                (!inherited).then(|| (*name, block.clone()))
+            }
+            Method::RustMethod { .. } => None,
+        })
+        .collect()
+}
+
+impl ToBinary for Immediate {
+    fn to_binary(&self, _: Option<&mut StringTable>) -> Vec<u8> {
+        let mut binary = vec![];
+        match self {
+            Immediate::I8(value) => {
+                binary.push(0);
+                binary.extend(value.to_le_bytes());
+            }
+            Immediate::I16(value) => {
+                binary.push(1);
+                binary.extend(value.to_le_bytes());
+            }
+            Immediate::I32(value) => {
+                binary.push(2);
+                binary.extend(value.to_le_bytes());
+            }
+            Immediate::I64(value) => {
+                binary.push(3);
+                binary.extend(value.to_le_bytes());
+            }
+            Immediate::U8(value) => {
+                binary.push(4);
+                binary.extend(value.to_le_bytes());
+            }
+            Immediate::U16(value) => {
+                binary.push(5);
+                binary.extend(value.to_le_bytes());
+            }
+            Immediate::U32(value) => {
+                binary.push(6);
+                binary.extend(value.to_le_bytes());
+            }
+            Immediate::U64(value) => {
+                binary.push(7);
+                binary.extend(value.to_le_bytes());
+            }
+            Immediate::F32(value) => {
+                binary.push(8);
+                binary.extend(value.to_le_bytes());
+            }
+            Immediate::F64(value) => {
+                binary.push(9);
+                binary.extend(value.to_le_bytes());
+            }
+            Immediate::Boolean(value) => {
+                binary.push(10);
+                binary.push(*value as u8);
+            }
+            Immediate::Char(value) => {
+                binary.push(11);
+                binary.extend((*value as u32).to_le_bytes());
+            }
+        }
+        binary
+    }
+}
+
+
+struct ProtoImage {
+    string_table: StringTable,
+    code_table: Vec<Vec<ProtoByteCode>>,
+    interfaces: Vec<(usize, Vec<usize>)>,
+    classes: Vec<ProtoImageClass>,
+    objects: Vec<ProtoObject>,
+    tasks: Vec<ProtoTask>,
+    finalizers: Vec<usize>,
+}
+
+struct ProtoImageClass {
+    name: usize,
+    parent: Option<usize>,
+    methods: Vec<(usize, usize)>,
+    overrides: Vec<Vec<(usize, usize)>>,
+    class_field_count: usize,
+    class_methods: Vec<(usize, usize)>,
+    abstract_methods: Vec<usize>,
+    interfaces: Vec<usize>,
+}
+
+enum ProtoObject {
+    SharedBuiltin(usize, usize),
+    Nil,
+    ClassObject {
+        name: usize,
+        payload: ProtoPayload,
+        methods: Vec<(usize, usize)>,
+    },
+    Object {
+        name: usize,
+        parent: Option<usize>,
+        payload: ProtoPayload,
+        methods: Vec<(usize, usize)>,
+    },
+}
+
+enum ProtoPayload {
+    Empty,
+    Immediate(Immediate),
+    String(usize),
+    Symbol(usize),
+    Message(usize),
+    Vector(Vec<usize>),
+    Fields(Vec<usize>),
+    Stack(Vec<ProtoValue>),
+    Block(usize, Vec<usize>),
+    Weak(Option<usize>),
+    BigInt(BigInt),
+    Rational(i128, i128),
+    Decimal(i128, u32),
+    Regex(usize),
+    Match(usize, usize, Vec<Option<usize>>, Vec<Option<usize>>),
+    Builder(usize),
+    Bytes(Vec<u8>),
+    List(Vec<usize>),
+    Set(Vec<usize>),
+    Iterator(ProtoSource, Option<Option<usize>>),
+    Range(ProtoRange),
+}
+
+/// kind, start, end, step and inclusive
+type ProtoRange = (u8, i128, i128, i128, bool);
+
+enum ProtoSource {
+    Fields(usize, usize),
+    Chars(usize, usize),
+    Elements(Vec<usize>, usize),
+    Range(ProtoRange, u128),
+    Map(usize, usize),
+    Filter(usize, usize),
+    Take(usize, usize),
+    Skip(usize, usize),
+    Zip(usize, usize),
+    Enumerate(usize, u64),
+    Chain(usize, usize),
+    FlatMap(usize, usize, Option<usize>),
+}
+
+enum ProtoValue {
+    Object(usize),
+    Immediate(Immediate),
+}
+
+struct ProtoTask {
+    frames: Vec<(usize, usize)>,
+    context: ProtoContext,
+}
+
+struct ProtoContext {
+    stack: usize,
+    arguments: Vec<usize>,
+    receiver: Option<usize>,
+    arg_count: usize,
+    vtable: Option<Vec<(usize, usize)>>,
+    code: Option<usize>,
+    deferred_argument: Option<Immediate>,
+}
+
+impl ProtoImage {
+    fn restore(mut self) -> Result<Vec<Interpreter>, Fault> {
+        let code = self.restore_code()?;
+        self.restore_classes(&code)?;
+        let objects = self.restore_objects(&code)?;
+
+        let mut interpreters = Vec::with_capacity(self.tasks.len());
+        for task in self.tasks.iter() {
+            let frames = task.frames.iter()
+                .map(|(idx, position)| get_code(&code, *idx).map(|code| (*position, code)))
+                .collect::<Result<Vec<_>, _>>()?;
+            let proto = &task.context;
+            let mut context = ContextData::new(get_object(&objects, proto.stack)?);
+            context.arguments = get_objects(&objects, &proto.arguments)?;
+            context.receiver = proto.receiver.map(|id| get_object(&objects, id)).transpose()?;
+            context.arg_count = proto.arg_count;
+            context.vtable = proto.vtable.as_deref().map(|methods| self.object_vtable(&objects, methods)).transpose()?;
+            context.code = proto.code.map(|idx| get_code(&code, idx)).transpose()?;
+            if let Some(immediate) = proto.deferred_argument {
+                context.defer_argument(immediate);
+            }
+            interpreters.push(Interpreter::resume(frames, Some(context)));
+        }
+        for id in self.finalizers.iter() {
+            gc::register_finalizer(&get_object(&objects, *id)?);
+        }
+        Ok(interpreters)
+    }
+
+    fn string(&self, idx: usize) -> Result<&str, Fault> {
+        self.string_table.get_string(idx).ok_or(Fault::InvalidOperation(format!("image string {} is missing", idx)))
+    }
+
+    /// Turn the code table into bytecode, a block only refers to code that comes before it
+    fn restore_code(&mut self) -> Result<Vec<Arc<Vec<ByteCode>>>, Fault> {
+        let mut block_table = BlockTable::new();
+        let mut code = Vec::with_capacity(self.code_table.len());
+        for (idx, bytecode) in std::mem::take(&mut self.code_table).into_iter().enumerate() {
+            let bytecode: Vec<ByteCode> = bytecode.into_iter()
+                .map(|bytecode| bytecode.into_bytecode(&self.string_table, &block_table))
+                .collect::<Result<_, Fault>>()?;
+            block_table.insert(idx, bytecode.clone());
+            code.push(Arc::new(bytecode));
+        }
+        Ok(code)
+    }
+
+    fn code_vtable(&self, code: &[Arc<Vec<ByteCode>>], methods: &[(usize, usize)]) -> Result<VTable, Fault> {
+        let mut table = HashMap::new();
+        for (name, idx) in methods {
+            let block = make_block(get_code(code, *idx)?);
+            table.insert(self.string_table.symbol(*name)?, Arc::new(Method::BytecodeMethod { block }));
+        }
+        Ok(VTable::new(table))
+    }
+
+    fn restore_classes(&self, code: &[Arc<Vec<ByteCode>>]) -> Result<(), Fault> {
+        for (name, selectors) in self.interfaces.iter() {
+            let selectors = selectors.iter().map(|idx| self.string_table.symbol(*idx)).collect::<Result<_, _>>()?;
+            object::add_interface(self.string(*name)?, Arc::new(Interface::new(selectors)));
+        }
+        for class in self.classes.iter() {
+            let parent = class.parent.map(|idx| self.string(idx)).transpose()?;
+            let overrides = class.overrides.iter()
+                .map(|methods| self.code_vtable(code, methods))
+                .collect::<Result<Vec<_>, _>>()?;
+            let new_class = Class::new(parent, self.code_vtable(code, &class.methods)?, overrides)
+                .with_class_side(self.code_vtable(code, &class.class_methods)?, class.class_field_count)
+                .with_requirements(
+                    class.abstract_methods.iter().map(|idx| self.string_table.symbol(*idx)).collect::<Result<_, _>>()?,
+                    class.interfaces.iter().map(|idx| self.string(*idx).map(str::to_string)).collect::<Result<_, _>>()?,
+                );
+            object::add_class(self.string(class.name)?, new_class);
+        }
+        Ok(())
+    }
+
+    fn object_vtable(&self, objects: &[ObjectBox], methods: &[(usize, usize)]) -> Result<VTable, Fault> {
+        if methods.is_empty() {
+            return Ok(VTable::new_empty());
+        }
+        let mut table = HashMap::new();
+        for (name, id) in methods {
+            table.insert(self.string_table.symbol(*name)?, Arc::new(Method::BytecodeMethod { block: get_object(objects, *id)? }));
+        }
+        Ok(VTable::new(table))
+    }
+
+    /// Make every object of the image and then fill them in, so objects can refer to each other
+    fn restore_objects(&self, code: &[Arc<Vec<ByteCode>>]) -> Result<Vec<ObjectBox>, Fault> {
+        let mut objects: Vec<ObjectBox> = Vec::with_capacity(self.objects.len());
+        for entry in self.objects.iter() {
+            let object = match entry {
+                ProtoObject::SharedBuiltin(name, depth) => object::get_shared_object(&SharedObject::Builtin(self.string(*name)?.to_string(), *depth))?,
+                ProtoObject::Nil => Nil::new(),
+                ProtoObject::ClassObject { name, .. } => object::get_class_object(self.string(*name)?)?,
+                ProtoObject::Object { name, parent, payload, .. } => {
+                    let parent = parent.map(|id| objects.get(id).cloned()
+                        .ok_or(Fault::InvalidOperation("image object's parent comes after it".to_string())))
+                        .transpose()?;
+                    let name = self.string(*name)?;
+                    match payload {
+                        // Matches and iterators can't be made with new so they start out empty and get their state below
+                        ProtoPayload::Match(..) => make_builtin(name, parent, |parent| MatchObject::make_object(parent, Match::default())),
+                        ProtoPayload::Iterator(..) => make_builtin(name, parent, |parent| IteratorObject::make_object(parent, Source::Elements { elements: Vec::new(), index: 0 })),
+                        ProtoPayload::Symbol(index) | ProtoPayload::Message(index) | ProtoPayload::Regex(index) => {
+                            object::create_object_with_parent(name, parent, &[object::create_string(self.string(*index)?.to_string())])?
+                        }
+                        _ => object::create_object_with_parent(name, parent, &[])?,
+                    }
+                }
+            };
+            objects.push(object);
+        }
+        let get_objects = |ids: &[usize]| get_objects(&objects, ids);
+
+        for (object, entry) in objects.iter().zip(self.objects.iter()) {
+            let (payload, methods) = match entry {
+                ProtoObject::Object { payload, methods, .. } => (payload, methods),
+                ProtoObject::ClassObject { payload, methods, .. } => {
+                    // The class object already exists so only its class variables and methods are restored
+                    let vtable = self.object_vtable(&objects, methods)?;
+                    let mut object = object.borrow_mut();
+                    if let ProtoPayload::Fields(ids) = payload {
+                        let size = object.size().unwrap_or(0);
+                        for (index, field) in get_objects(ids)?.into_iter().enumerate().take(size) {
+                            object.set_field(index, field);
+                        }
+                    }
+                    if !vtable.empty() {
+                        object.initialize(vec![], vtable);
+                    }
+                    continue;
+                }
+                _ => continue,
+            };
+            let vtable = self.object_vtable(&objects, methods)?;
+            let mut object = object.borrow_mut();
+            match payload {
+                ProtoPayload::Empty | ProtoPayload::Symbol(_) | ProtoPayload::Message(_) | ProtoPayload::Regex(_) => {}
+                ProtoPayload::Immediate(immediate) => set_immediate(&mut *object, *immediate)?,
+                ProtoPayload::String(idx) => downcast_mut::<StringObject>(&mut *object)?.value = self.string(*idx)?.to_string(),
+                ProtoPayload::Builder(idx) => downcast_mut::<StringBuilder>(&mut *object)?.value = self.string(*idx)?.to_string(),
+                ProtoPayload::Bytes(bytes) => downcast_mut::<ByteArray>(&mut *object)?.value = bytes.clone(),
+                ProtoPayload::List(ids) => downcast_mut::<ListObject>(&mut *object)?.value = get_objects(ids)?,
+                ProtoPayload::Set(ids) => downcast_mut::<SetObject>(&mut *object)?.value = ObjectSet::pending(get_objects(ids)?),
+                ProtoPayload::Iterator(source, peeked) => {
+                    let get_object = |id: usize| get_object(&objects, id);
+                    let source = match source {
+                        ProtoSource::Fields(collection, index) => Source::Fields { collection: get_object(*collection)?, index: *index },
+                        ProtoSource::Chars(string, offset) => Source::Chars { string: get_object(*string)?, offset: *offset },
+                        ProtoSource::Elements(ids, index) => Source::Elements { elements: get_objects(ids)?, index: *index },
+                        ProtoSource::Range(range, index) => Source::Range { range: restore_range(range)?, index: *index },
+                        ProtoSource::Map(inner, block) => Source::Map { inner: get_object(*inner)?, block: get_object(*block)? },
+                        ProtoSource::Filter(inner, block) => Source::Filter { inner: get_object(*inner)?, block: get_object(*block)? },
+                        ProtoSource::Take(inner, remaining) => Source::Take { inner: get_object(*inner)?, remaining: *remaining },
+                        ProtoSource::Skip(inner, remaining) => Source::Skip { inner: get_object(*inner)?, remaining: *remaining },
+                        ProtoSource::Zip(first, second) => Source::Zip { first: get_object(*first)?, second: get_object(*second)? },
+                        ProtoSource::Enumerate(inner, index) => Source::Enumerate { inner: get_object(*inner)?, index: *index },
+                        ProtoSource::Chain(first, second) => Source::Chain { first: get_object(*first)?, second: get_object(*second)? },
+                        ProtoSource::FlatMap(inner, block, current) => Source::FlatMap {
+                            inner: get_object(*inner)?,
+                            block: get_object(*block)?,
+                            current: current.map(get_object).transpose()?,
+                        },
+                    };
+                    let peeked = peeked.map(|element| element.map(get_object).transpose()).transpose()?;
+                    let iterator = downcast_mut::<IteratorObject>(&mut *object)?;
+                    iterator.source = source;
+                    iterator.peeked = peeked;
+                }
+                ProtoPayload::Range(range) => downcast_mut::<RangeObject>(&mut *object)?.value = restore_range(range)?,
+                ProtoPayload::Match(start, end, groups, names) => {
+                    let strings = |indices: &[Option<usize>]| indices.iter()
+                        .map(|idx| idx.map(|idx| self.string(idx).map(str::to_string)).transpose())
+                        .collect::<Result<Vec<_>, _>>();
+                    let (groups, names) = (strings(groups)?, strings(names)?);
+                    downcast_mut::<MatchObject>(&mut *object)?.value = Match { start: *start, end: *end, groups, names };
+                }
+                ProtoPayload::BigInt(value) => downcast_mut::<BigIntObject>(&mut *object)?.value = value.clone(),
+                ProtoPayload::Rational(numerator, denominator) => downcast_mut::<RationalObject>(&mut *object)?.value = Rational::new(*numerator, *denominator)?,
+                ProtoPayload::Decimal(mantissa, scale) => downcast_mut::<DecimalObject>(&mut *object)?.value = Decimal::new(*mantissa, *scale)?,
+                ProtoPayload::Vector(ids) => downcast_mut::<VectorObject>(&mut *object)?.value = get_objects(ids)?.into_boxed_slice(),
+                ProtoPayload::Stack(values) => {
+                    downcast_mut::<Stack>(&mut *object)?.data = values.iter()
+                        .map(|value| match value {
+                            ProtoValue::Object(id) => get_object(&objects, *id).map(Value::Object),
+                            ProtoValue::Immediate(immediate) => Ok(Value::Immediate(*immediate)),
+                        })
+                        .collect::<Result<_, _>>()?;
+                }
+                ProtoPayload::Weak(target) => {
+                    downcast_mut::<WeakRef>(&mut *object)?.target = target.map(|id| get_object(&objects, id)).transpose()?.map(|target| target.downgrade());
+                }
+                ProtoPayload::Block(idx, captures) => {
+                    downcast_mut::<Block>(&mut *object)?.bytecode = get_code(code, *idx)?;
+                    object.initialize(get_objects(captures)?, vtable);
+                    continue;
+                }
+                ProtoPayload::Fields(ids) => {
+                    object.initialize(get_objects(ids)?, vtable);
+                    continue;
+                }
+            }
+            if !vtable.empty() {
+                object.initialize(vec![], vtable);
+            }
+        }
+        Ok(objects)
+    }
+}
+
+
+fn restore_range((kind, start, end, step, inclusive): &ProtoRange) -> Result<Range, Fault> {
+    let kind = Kind::from_tag(*kind).ok_or(Fault::InvalidOperation(format!("image range has an unknown kind {}", kind)))?;
+    Range::new(*start, *end, *step, *inclusive, kind)
+}
+
+fn get_code(code: &[Arc<Vec<ByteCode>>], idx: usize) -> Result<Arc<Vec<ByteCode>>, Fault> {
+    code.get(idx).cloned().ok_or(Fault::InvalidOperation(format!("image code {} is missing", idx)))
+}
+
+fn get_object(objects: &[ObjectBox], id: usize) -> Result<ObjectBox, Fault> {
+    objects.get(id).cloned().ok_or(Fault::InvalidOperation(format!("image object {} is missing", id)))
+}
+
+fn get_objects(objects: &[ObjectBox], ids: &[usize]) -> Result<Vec<ObjectBox>, Fault> {
+    ids.iter().map(|id| get_object(objects, *id)).collect()
+}
+
+/// Make an instance of a builtin type that can't be made with new and give it the type's vtable
+fn make_builtin(name: &str, parent: Option<ObjectBox>, make: impl FnOnce(ObjectBox) -> ObjectBox) -> ObjectBox {
+    let object = make(parent.unwrap_or_else(Nil::new));
+    if let Some(vtable) = object::prototype_vtable(name).filter(|vtable| !vtable.empty()) {
+        object.borrow_mut().initialize(vec![], vtable);
+    }
+    object
+}
+
+/// Make a block that shares its code with the code table
+fn make_block(code: Arc<Vec<ByteCode>>) -> ObjectBox {
+    let block = object::create_block(vec![]);
+    if let Some(block) = block.borrow_mut().downcast_mut::<Block>() {
+        block.bytecode = code;
+    }
+    block
+}
+
+
+fn parse_image(input: &[u8]) -> IResult<&[u8], ProtoImage> {
+    let (input, _) = bytes::complete::tag("SPI")(input)?;
+    let (input, _) = bytes::complete::take(3usize)(input)?;
+    let (input, string_table) = parse_string_table(input)?;
+    let (input, length) = number::complete::le_u64(input)?;
+    // The code of an image is written with the bytecode of the VM that saved it
+    let (input, code_table) = multi::count(parse_bytecode(binary::VERSION), length as usize)(input)?;
+    let (input, length) = number::complete::le_u64(input)?;
+    let (input, interfaces) = multi::count(|input| {
+        let (input, name) = parse_index(input)?;
+        let (input, selectors) = parse_indices(input)?;
+        Ok((input, (name, selectors)))
+    }, length as usize)(input)?;
+    let (input, length) = number::complete::le_u64(input)?;
+    let (input, classes) = multi::count(parse_class, length as usize)(input)?;
+    let (input, length) = number::complete::le_u64(input)?;
+    let (input, objects) = multi::count(parse_object, length as usize)(input)?;
+    let (input, length) = number::complete::le_u64(input)?;
+    let (input, tasks) = multi::count(parse_task, length as usize)(input)?;
+    let (input, finalizers) = parse_indices(input)?;
+    Ok((input, ProtoImage { string_table, code_table, interfaces, classes, objects, tasks, finalizers }))
+}
+
+fn parse_index(input: &[u8]) -> IResult<&[u8], usize> {
+    let (input, index) = number::complete::le_u64(input)?;
+    Ok((input, index as usize))
+}
+
+fn parse_indices(input: &[u8]) -> IResult<&[u8], Vec<usize>> {
+    let (input, length) = number::complete::le_u64(input)?;
+    multi::count(parse_index, length as usize)(input)
+}
+
+fn parse_optional_index(input: &[u8]) -> IResult<&[u8], Option<usize>> {
+    let (input, flag) = number::complete::u8(input)?;
+    if flag != 0 {
+        let (input, index) = parse_index(input)?;
+        Ok((input, Some(index)))
+    } else {
+        Ok((input, None))
+    }
+}
+
+fn parse_methods(input: &[u8]) -> IResult<&[u8], Vec<(usize, usize)>> {
+    let (input, length) = number::complete::le_u64(input)?;
+    multi::count(|input| {
+        let (input, name) = parse_index(input)?;
+        let (input, index) = parse_index(input)?;
+        Ok((input, (name, index)))
+    }, length as usize)(input)
+}
+
+fn parse_class(input: &[u8]) -> IResult<&[u8], ProtoImageClass> {
+    let (input, name) = parse_index(input)?;
+    let (input, parent) = parse_optional_index(input)?;
+    let (input, methods) = parse_methods(input)?;
+    let (input, length) = number::complete::le_u64(input)?;
+    let (input, overrides) = multi::count(parse_methods, length as usize)(input)?;
+    let (input, class_field_count) = parse_index(input)?;
+    let (input, class_methods) = parse_methods(input)?;
+    let (input, abstract_methods) = parse_indices(input)?;
+    let (input, interfaces) = parse_indices(input)?;
+    Ok((input, ProtoImageClass { name, parent, methods, overrides, class_field_count, class_methods, abstract_methods, interfaces }))
+}
+
+fn parse_object(input: &[u8]) -> IResult<&[u8], ProtoObject> {
+    let (input, tag) = number::complete::u8(input)?;
+    match tag {
+        0 => {
+            let (input, name) = parse_index(input)?;
+            let (input, depth) = parse_index(input)?;
+            Ok((input, ProtoObject::SharedBuiltin(name, depth)))
+        }
+        2 => Ok((input, ProtoObject::Nil)),
+        4 => {
+            let (input, name) = parse_index(input)?;
+            let (input, payload) = parse_payload(input)?;
+            let (input, methods) = parse_methods(input)?;
+            Ok((input, ProtoObject::ClassObject { name, payload, methods }))
+        }
+        _ => {
+            let (input, name) = parse_index(input)?;
+            let (input, parent) = parse_optional_index(input)?;
+            let (input, payload) = parse_payload(input)?;
+            let (input, methods) = parse_methods(input)?;
+            Ok((input, ProtoObject::Object { name, parent, payload, methods }))
+        }
+    }
+}
+
+fn parse_payload(input: &[u8]) -> IResult<&[u8], ProtoPayload> {
+    let (input, tag) = number::complete::u8(input)?;
+    match tag {
+        1 => {
+            let (input, immediate) = parse_immediate(input)?;
+            Ok((input, ProtoPayload::Immediate(immediate)))
+        }
+        2 => {
+            let (input, index) = parse_index(input)?;
+            Ok((input, ProtoPayload::String(index)))
+        }
+        3 => {
+            let (input, index) = parse_index(input)?;
+            Ok((input, ProtoPayload::Symbol(index)))
+        }
+        4 => {
+            let (input, index) = parse_index(input)?;
+            Ok((input, ProtoPayload::Message(index)))
+        }
+        5 => {
+            let (input, ids) = parse_indices(input)?;
+            Ok((input, ProtoPayload::Vector(ids)))
+        }
+        6 => {
+            let (input, ids) = parse_indices(input)?;
+            Ok((input, ProtoPayload::Fields(ids)))
+        }
+        7 => {
+            let (input, length) = number::complete::le_u64(input)?;
+            let (input, values) = multi::count(parse_value, length as usize)(input)?;
+            Ok((input, ProtoPayload::Stack(values)))
+        }
+        8 => {
+            let (input, code) = parse_index(input)?;
+            let (input, captures) = parse_indices(input)?;
+            Ok((input, ProtoPayload::Block(code, captures)))
+        }
+        9 => {
+            let (input, target) = parse_optional_index(input)?;
+            Ok((input, ProtoPayload::Weak(target)))
+        }
+        10 => {
+            let (input, negative) = number::complete::u8(input)?;
+            let (input, length) = number::complete::le_u64(input)?;
+            let (input, limbs) = multi::count(number::complete::le_u32, length as usize)(input)?;
+            Ok((input, ProtoPayload::BigInt(BigInt::from_parts(negative != 0, limbs))))
+        }
+        11 => {
+            let (input, numerator) = number::complete::le_i128(input)?;
+            let (input, denominator) = number::complete::le_i128(input)?;
+            Ok((input, ProtoPayload::Rational(numerator, denominator)))
+        }
+        12 => {
+            let (input, mantissa) = number::complete::le_i128(input)?;
+            let (input, scale) = number::complete::le_u32(input)?;
+            Ok((input, ProtoPayload::Decimal(mantissa, scale)))
+        }
+        13 => {
+            let (input, index) = parse_index(input)?;
+            Ok((input, ProtoPayload::Regex(index)))
+        }
+        14 => {
+            let (input, start) = parse_index(input)?;
+            let (input, end) = parse_index(input)?;
+            let (input, length) = number::complete::le_u64(input)?;
+            let (input, groups) = multi::count(parse_optional_index, length as usize)(input)?;
+            let (input, length) = number::complete::le_u64(input)?;
+            let (input, names) = multi::count(parse_optional_index, length as usize)(input)?;
+            Ok((input, ProtoPayload::Match(start, end, groups, names)))
+        }
+        15 => {
+            let (input, index) = parse_index(input)?;
+            Ok((input, ProtoPayload::Builder(index)))
+        }
+        16 => {
+            let (input, length) = number::complete::le_u64(input)?;
+            let (input, data) = bytes::complete::take(length as usize)(input)?;
+            Ok((input, ProtoPayload::Bytes(data.to_vec())))
+        }
+        17 => {
+            let (input, ids) = parse_indices(input)?;
+            Ok((input, ProtoPayload::List(ids)))
+        }
+        18 => {
+            let (input, ids) = parse_indices(input)?;
+            Ok((input, ProtoPayload::Set(ids)))
+        }
+        19 => {
+            let (input, flag) = number::complete::u8(input)?;
+            let (input, peeked) = match flag {
+                0 => (input, None),
+                1 => (input, Some(None)),
+                _ => {
+                    let (input, element) = parse_index(input)?;
+                    (input, Some(Some(element)))
+                }
+            };
+            let (input, source) = parse_source(input)?;
+            Ok((input, ProtoPayload::Iterator(source, peeked)))
+        }
+        20 => {
+            let (input, range) = parse_range(input)?;
+            Ok((input, ProtoPayload::Range(range)))
+        }
+        _ => Ok((input, ProtoPayload::Empty)),
+    }
+}
+
+fn parse_range(input: &[u8]) -> IResult<&[u8], ProtoRange> {
+    let (input, kind) = number::complete::u8(input)?;
+    let (input, start) = number::complete::le_i128(input)?;
+    let (input, end) = number::complete::le_i128(input)?;
+    let (input, step) = number::complete::le_i128(input)?;
+    let (input, inclusive) = number::complete::u8(input)?;
+    Ok((input, (kind, start, end, step, inclusive != 0)))
+}
+
+fn parse_source(input: &[u8]) -> IResult<&[u8], ProtoSource> {
+    let (input, tag) = number::complete::u8(input)?;
+    match tag {
+        2 => {
+            let (input, ids) = parse_indices(input)?;
+            let (input, index) = parse_index(input)?;
+            Ok((input, ProtoSource::Elements(ids, index)))
+        }
+        8 => {
+            let (input, inner) = parse_index(input)?;
+            let (input, index) = number::complete::le_u64(input)?;
+            Ok((input, ProtoSource::Enumerate(inner, index)))
+        }
+        10 => {
+            let (input, inner) = parse_index(input)?;
+            let (input, block) = parse_index(input)?;
+            let (input, current) = parse_optional_index(input)?;
+            Ok((input, ProtoSource::FlatMap(inner, block, current)))
+        }
+        11 => {
+            let (input, range) = parse_range(input)?;
+            let (input, index) = number::complete::le_u128(input)?;
+            Ok((input, ProtoSource::Range(range, index)))
+        }
+        _ => {
+            let (input, first) = parse_index(input)?;
+            let (input, second) = parse_index(input)?;
+            let source = match tag {
+                0 => ProtoSource::Fields(first, second),
+                1 => ProtoSource::Chars(first, second),
+                3 => ProtoSource::Map(first, second),
+                4 => ProtoSource::Filter(first, second),
+                5 => ProtoSource::Take(first, second),
+                6 => ProtoSource::Skip(first, second),
+                7 => ProtoSource::Zip(first, second),
+                _ => ProtoSource::Chain(first, second),
+            };
+            Ok((input, source))
+        }
+    }
+}
+
+fn parse_value(input: &[u8]) -> IResult<&[u8], ProtoValue> {
+    let (input, tag) = number::complete::u8(input)?;
+    if tag == 0 {
+        let (input, id) = parse_index(input)?;
+        Ok((input, ProtoValue::Object(id)))
+    } else {
+        let (input, immediate) = parse_immediate(input)?;
+        Ok((input, ProtoValue::Immediate(immediate)))
+    }
+}
+
+fn parse_immediate(input: &[u8]) -> IResult<&[u8], Immediate> {
+    let (input, tag) = number::complete::u8(input)?;
+    match tag {
+        0 => number::complete::i8(input).map(|(input, value)| (input, Immediate::I8(value))),
+        1 => number::complete::le_i16(input).map(|(input, value)| (input, Immediate::I16(value))),
+        2 => number::complete::le_i32(input).map(|(input, value)| (input, Immediate::I32(value))),
+        3 => number::complete::le_i64(input).map(|(input, value)| (input, Immediate::I64(value))),
+        4 => number::complete::u8(input).map(|(input, value)| (input, Immediate::U8(value))),
+        5 => number::complete::le_u16(input).map(|(input, value)| (input, Immediate::U16(value))),
+        6 => number::complete::le_u32(input).map(|(input, value)| (input, Immediate::U32(value))),
+        7 => number::complete::le_u64(input).map(|(input, value)| (input, Immediate::U64(value))),
+        8 => number::complete::le_f32(input).map(|(input, value)| (input, Immediate::F32(value))),
+        9 => number::complete::le_f64(input).map(|(input, value)| (input, Immediate::F64(value))),
+        10 => number::complete::u8(input).map(|(input, value)| (input, Immediate::Boolean(value != 0))),
+        _ => {
+            let (input, value) = number::complete::le_u32(input)?;
+            Ok((input, Immediate::Char(char::from_u32(value).unwrap_or(char::REPLACEMENT_CHARACTER))))
+        }
+    }
+}
+
+fn parse_task(input: &[u8]) -> IResult<&[u8], ProtoTask> {
+    let (input, length) = number::complete::le_u64(input)?;
+    let (input, frames) = multi::count(|input| {
+        let (input, code) = parse_index(input)?;
+        let (input, position) = parse_index(input)?;
+        Ok((input, (code, position)))
+    }, length as usize)(input)?;
+    let (input, stack) = parse_index(input)?;
+    let (input, arguments) = parse_indices(input)?;
+    let (input, receiver) = parse_optional_index(input)?;
+    let (input, arg_count) = parse_index(input)?;
+    let (input, flag) = number::complete::u8(input)?;
+    let (input, vtable) = if flag != 0 {
+        let (input, methods) = parse_methods(input)?;
+        (input, Some(methods))
+    } else {
+        (input, None)
+    };
+    let (input, code) = parse_optional_index(input)?;
+    let (input, flag) = number::complete::u8(input)?;
+    let (input, deferred_argument) = if flag != 0 {
+        let (input, immediate) = parse_immediate(input)?;
+        (input, Some(immediate))
+    } else {
+        (input, None)
+    };
+    let context = ProtoContext { stack, arguments, receiver, arg_count, vtable, code, deferred_argument };
+    Ok((input, ProtoTask { frames, context }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Write an object to an image and read it back without touching the loaded classes
+    fn round_trip(object: &ObjectBox) -> ObjectBox {
+        let mut writer = ImageWriter::new();
+        let id = writer.object_id(object).unwrap();
+        let binary = writer.write(&[]).unwrap();
+        let (_, mut image) = parse_image(&binary).finish().unwrap();
+        let code = image.restore_code().unwrap();
+        image.restore_objects(&code).unwrap().swap_remove(id)
+    }
+
+    #[test]
+    fn cycles_are_saved_with_their_shape() {
+        let first = object::create_vector(vec![Nil::new(), Nil::new()]);
+        let second = object::create_vector(vec![first.clone()]);
+        first.borrow_mut().set_field(0, second.clone());
+        first.borrow_mut().set_field(1, first.clone());
+        let loaded = round_trip(&first);
+        let second = loaded.borrow().get_field(0).unwrap();
+        assert_eq!(second.borrow().get_field(0).unwrap().as_ptr(), loaded.as_ptr());
+        assert_eq!(loaded.borrow().get_field(1).unwrap().as_ptr(), loaded.as_ptr());
+        // Break the cycles so the objects of the test don't wait for the collector
+        first.borrow_mut().set_field(0, Nil::new());
+        first.borrow_mut().set_field(1, Nil::new());
+        loaded.borrow_mut().set_field(0, Nil::new());
+        loaded.borrow_mut().set_field(1, Nil::new());
+    }
+
+    #[test]
+    fn stacks_keep_their_immediates_unboxed() {
+        let string = object::create_string("shared".to_string());
+        let stack = object::init_stack();
+        {
+            let mut stack = stack.borrow_mut();
+            let stack = stack.downcast_mut::<Stack>().unwrap();
+            stack.push_value(Value::Immediate(Immediate::I64(7)));
+            stack.push(string.clone());
+            stack.push(string);
+        }
+        let loaded = round_trip(&stack);
+        let loaded = loaded.borrow();
+        let data = &loaded.downcast_ref::<Stack>().unwrap().data;
+        assert_eq!(data.len(), stack.borrow().size().unwrap());
+        let [.., immediate, Value::Object(first), Value::Object(second)] = &data[..] else { panic!("Expected objects") };
+        assert_eq!(immediate.as_immediate(), Some(Immediate::I64(7)));
+        assert_eq!(first.as_ptr(), second.as_ptr());
+        assert_eq!(first.borrow().downcast_ref::<StringObject>().unwrap().value, "shared");
+    }
+
+    #[test]
+    fn shared_objects_are_saved_by_name() {
+        let string = object::create_string("text".to_string());
+        let loaded = round_trip(&string);
+        assert!(loaded.as_ptr() != string.as_ptr());
+        let parent = |object: &ObjectBox| object.borrow().get_super_object().unwrap().as_ptr();
+        assert_eq!(parent(&loaded), parent(&string));
+    }
+
+    #[test]
+    fn weak_refs_are_saved_with_their_targets() {
+        let target = object::create_string("target".to_string());
+        let weak = object::create_weak_ref(&target);
+        let vector = object::create_vector(vec![target, weak]);
+        let loaded = round_trip(&vector);
+        let target = loaded.borrow().get_field(0).unwrap();
+        let weak = loaded.borrow().get_field(1).unwrap();
+        let weak_target = weak.borrow().downcast_ref::<WeakRef>().unwrap().target.as_ref().and_then(|target| target.upgrade());
+        assert_eq!(weak_target.unwrap().as_ptr(), target.as_ptr());
+
+        // A weak ref doesn't save its target so a target that only it refers to isn't kept
+        let weak = object::create_weak_ref(&object::create_string("gone".to_string()));
+        let loaded = round_trip(&weak);
+        assert!(loaded.borrow().downcast_ref::<WeakRef>().unwrap().target.is_none());
+    }
+
+    #[test]
+    fn the_finalizer_table_is_saved() {
+        let object = object::create_string("finalize me".to_string());
+        gc::register_finalizer(&object);
+        let mut writer = ImageWriter::new();
+        let id = writer.object_id(&object).unwrap();
+        let binary = writer.write(&[]).unwrap();
+        let (_, image) = parse_image(&binary).finish().unwrap();
+        assert!(image.finalizers.contains(&id));
+        image.restore().unwrap();
+
+        // Both the saved object and the one loaded from the image wait for their finalizer, take
+        // them out of the registry of the running VM again
+        drop(object);
+        let ready = gc::take_finalizable();
+        let finalized = ready.iter()
+            .filter(|ready| ready.borrow().downcast_ref::<StringObject>().is_some_and(|string| string.value == "finalize me"))
+            .count();
+        assert_eq!(finalized, 2);
+    }
+
+    #[test]
+    fn tasks_are_saved_where_they_stopped() {
+        let code = Arc::new(vec![ByteCode::NoOp, ByteCode::NoOp, ByteCode::Halt]);
+        let mut context = ContextData::new(object::init_stack());
+        context.push_frame(None);
+        context.push_value(Value::Immediate(Immediate::I64(3)));
+        context.defer_argument(Immediate::U8(1));
+        let interpreter = Interpreter::resume(vec![(1, code)], Some(context));
+        let binary = ImageWriter::new().write(&[&interpreter]).unwrap();
+        let (_, image) = parse_image(&binary).finish().unwrap();
+        assert_eq!(image.tasks.len(), 1);
+        let task = &image.tasks[0];
+        assert_eq!(task.frames.len(), 1);
+        assert_eq!(task.frames[0].1, 1);
+        assert_eq!(task.context.deferred_argument, Some(Immediate::U8(1)));
+
+        let loaded = image.restore().unwrap().swap_remove(0);
+        let [(position, code)] = loaded.frames() else { panic!("Expected one frame") };
+        assert_eq!((*position, code.len()), (1, 3));
+        assert_eq!(loaded.context().unwrap().peek_immediate(0), Some(Immediate::I64(3)));
+    }
+
+    #[test]
+    fn classes_are_saved_with_their_requirements() {
+        let class = Class::new(Some("Object"), VTable::new_empty(), Vec::new())
+            .with_requirements(vec![Symbol::from("area")], vec!["Shape".to_string(), "Named".to_string()]);
+        let mut writer = ImageWriter::new();
+        let binary = writer.class("Square", &class);
+        let (rest, parsed) = parse_class(&binary).finish().unwrap();
+        assert!(rest.is_empty());
+        let string = |idx: usize| writer.strings.get_string(idx).unwrap().to_string();
+        assert_eq!(string(parsed.name), "Square");
+        assert_eq!(parsed.abstract_methods.into_iter().map(string).collect::<Vec<_>>(), vec!["area"]);
+        assert_eq!(parsed.interfaces.into_iter().map(string).collect::<Vec<_>>(), vec!["Shape", "Named"]);
+    }
+
+    #[test]
+    fn matches_are_saved_with_their_groups() {
+        let found = object::create_match(Match {
+            start: 1,
+            end: 4,
+            groups: vec![Some("abc".to_string()), None],
+            names: vec![None, Some("rest".to_string())],
+        });
+        let loaded = round_trip(&found);
+        let loaded = loaded.borrow();
+        let value = &loaded.downcast_ref::<MatchObject>().unwrap().value;
+        assert_eq!((value.start, value.end), (1, 4));
+        assert_eq!(value.groups, vec![Some("abc".to_string()), None]);
+        assert_eq!(value.names, vec![None, Some("rest".to_string())]);
+        assert!(loaded.lookup_method(Symbol::from("text")).is_some());
+    }
+
+    #[test]
+    fn regexes_are_saved_with_their_pattern() {
+        let pattern = object::create_string("a(b+)".to_string());
+        let regex = object::create_object("Regex", &[pattern]).unwrap().unwrap();
+        let loaded = round_trip(&regex);
+        let loaded = loaded.borrow();
+        assert_eq!(loaded.downcast_ref::<RegexObject>().unwrap().value.as_str(), "a(b+)");
+    }
+
+    #[test]
+    fn iterators_are_saved_where_they_stopped() {
+        let numbers = (1..=4).map(object::create_i64).collect();
+        let vector = object::create_vector(numbers);
+        let inner = object::create_iterator(Source::Fields { collection: vector, index: 1 });
+        let iterator = object::create_iterator(Source::Take { inner, remaining: 2 });
+        let loaded = round_trip(&iterator);
+
+        let next = |iterator: &ObjectBox| {
+            let method = iterator.borrow().lookup_method(Symbol::from("next")).unwrap();
+            let element = method.evaluate(iterator.clone(), vec![]).unwrap().unwrap();
+            let element = element.borrow();
+            Immediate::from_object(&*element)
+        };
+        assert!(matches!(next(&loaded), Some(Immediate::I64(2))));
+        assert!(matches!(next(&loaded), Some(Immediate::I64(3))));
+        let has_next = loaded.borrow().lookup_method(Symbol::from("has_next")).unwrap();
+        let has_next = has_next.evaluate(loaded.clone(), vec![]).unwrap().unwrap();
+        assert!(matches!(Immediate::from_object(&*has_next.borrow()), Some(Immediate::Boolean(false))));
+    }
+}
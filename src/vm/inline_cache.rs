@@ -0,0 +1,272 @@
+//! Inline caches for message sends
+//! Each SendMsg instruction remembers which method it found for the last few kinds of receivers
+//! it has seen so that sending the same message to the same kind of object again doesn't have to
+//! walk the super object chain.
+//! The caches belong to the thread rather than to an interpreter, so the short-lived interpreters
+//! that Block::evaluate starts find the call sites warm. They only hold weak references so nothing
+//! stays alive because it is in a cache, and they are thrown away when a class is reloaded.
+use std::any::TypeId;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::sync::{Arc, Weak};
+
+use crate::object::{class_epoch, Class, Method, Object, ObjectStruct, WeakObjectBox, WeakVTable};
+use crate::object::symbol::Symbol;
+
+/// The most receiver shapes that a call site remembers before it stops caching
+const POLYMORPHIC_LIMIT: usize = 4;
+/// The most call sites that a thread remembers before it starts over
+const SITE_LIMIT: usize = 4096;
+
+thread_local! {
+    static CACHE: RefCell<InlineCache> = RefCell::new(InlineCache::new());
+}
+
+/// Shape
+/// Everything that a method lookup depends on. Objects with the same shape find the same methods.
+/// An instance of a class finds the methods it doesn't have in the parent chain of its class, so
+/// its shape is its class rather than its super object, which becomes a copy of its own after a
+/// super send. Other objects are keyed by their super object.
+/// Everything is only referred to weakly. That is enough to keep the addresses from being reused
+/// while they are in the cache, and changing the object's vtable still makes a copy of it which
+/// gives the object a new shape. A change to the vtable of a parent bumps the class epoch.
+struct Shape {
+    type_id: TypeId,
+    vtable: WeakVTable,
+    origin: Origin,
+}
+
+/// Where the methods that aren't in an object's own vtable are looked up
+enum Origin {
+    Class(Weak<Class>),
+    Parent(Option<WeakObjectBox>),
+}
+
+impl Origin {
+    fn of(object: &dyn Object) -> Origin {
+        match object.downcast_ref::<ObjectStruct>().and_then(|object| object.get_class()) {
+            Some(class) => Origin::Class(Arc::downgrade(&class)),
+            None => Origin::Parent(object.get_super_object().map(|parent| parent.downgrade())),
+        }
+    }
+
+    fn matches(&self, object: &dyn Object) -> bool {
+        let class = object.downcast_ref::<ObjectStruct>().and_then(|object| object.get_class());
+        match (self, class) {
+            (Origin::Class(class), Some(other)) => std::ptr::eq(class.as_ptr(), Arc::as_ptr(&other)),
+            (Origin::Parent(parent), None) => match (parent, object.get_super_object()) {
+                (None, None) => true,
+                (Some(parent), Some(other)) => parent.as_ptr() == other.as_ptr(),
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+}
+
+impl Shape {
+    fn of(object: &dyn Object) -> Shape {
+        Shape {
+            type_id: object.as_any().type_id(),
+            vtable: object.get_vtable().downgrade(),
+            origin: Origin::of(object),
+        }
+    }
+
+    fn matches(&self, object: &dyn Object) -> bool {
+        self.type_id == object.as_any().type_id()
+            && object.get_vtable().is(&self.vtable)
+            && self.origin.matches(object)
+    }
+}
+
+/// CallSite
+/// The cache of a single SendMsg instruction.
+/// It starts out monomorphic and becomes polymorphic when it sees more shapes. Once it has seen
+/// more than POLYMORPHIC_LIMIT shapes it is megamorphic and doesn't cache anything anymore.
+struct CallSite {
+    selector: Symbol,
+    entries: Vec<(Shape, Weak<Method>)>,
+    megamorphic: bool,
+}
+
+impl CallSite {
+    fn new(selector: Symbol) -> CallSite {
+        CallSite {
+            selector,
+            entries: Vec::new(),
+            megamorphic: false,
+        }
+    }
+}
+
+/// InlineCache
+/// The call site caches of a thread.
+/// Call sites are identified by the address of their instruction. Since that address may get
+/// reused by another instruction after the code is freed each site also checks its selector.
+pub struct InlineCache {
+    sites: HashMap<usize, CallSite>,
+    /// The class epoch that the cached methods were found in
+    epoch: usize,
+}
+
+impl Default for InlineCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl InlineCache {
+    pub fn new() -> InlineCache {
+        InlineCache {
+            sites: HashMap::new(),
+            epoch: class_epoch(),
+        }
+    }
+
+    /// Find the method for a message sent to the receiver from the call site
+    pub fn lookup(&mut self, site: usize, selector: Symbol, receiver: &dyn Object) -> Option<Arc<Method>> {
+        let epoch = class_epoch();
+        if self.epoch != epoch {
+            self.sites.clear();
+            self.epoch = epoch;
+        }
+        if self.sites.len() >= SITE_LIMIT && !self.sites.contains_key(&site) {
+            self.sites.clear();
+        }
+        let call_site = self.sites.entry(site).or_insert_with(|| CallSite::new(selector));
+        if call_site.selector != selector {
+            *call_site = CallSite::new(selector);
+        }
+        for (shape, method) in call_site.entries.iter() {
+            if shape.matches(receiver) {
+                if let Some(method) = method.upgrade() {
+                    return Some(method);
+                }
+            }
+        }
+
+        let method = receiver.lookup_method(selector)?;
+        if !call_site.megamorphic {
+            // Drop the entries whose method is gone before counting how many shapes we've seen
+            call_site.entries.retain(|(_, method)| method.strong_count() > 0);
+            if call_site.entries.len() < POLYMORPHIC_LIMIT {
+                call_site.entries.push((Shape::of(receiver), Arc::downgrade(&method)));
+            } else {
+                call_site.entries.clear();
+                call_site.megamorphic = true;
+            }
+        }
+        Some(method)
+    }
+}
+
+/// Find the method for a message using the cache of the current thread
+pub fn lookup(site: usize, selector: Symbol, receiver: &dyn Object) -> Option<Arc<Method>> {
+    CACHE.with(|cache| cache.borrow_mut().lookup(site, selector, receiver))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::object::{create_i64, ObjectBox, VTable};
+
+    fn instances(class: &Arc<Class>, parent: &ObjectBox, count: usize) -> Vec<ObjectBox> {
+        (0..count).map(|_| {
+            let object = ObjectStruct::new(Some(class.clone()), Some(parent.clone()));
+            object.borrow_mut().initialize(Vec::new(), VTable::new(HashMap::new()));
+            object
+        }).collect()
+    }
+
+    fn class(vtable: VTable) -> Arc<Class> {
+        Arc::new(Class::new(Some("I64"), vtable, Vec::new()))
+    }
+
+    #[test]
+    fn repeated_sends_hit_the_cache() {
+        let mut cache = InlineCache::new();
+        let integer = create_i64(1);
+        let first = cache.lookup(1, Symbol::from("add"), &*integer.borrow()).unwrap();
+        let second = cache.lookup(1, Symbol::from("add"), &*integer.borrow()).unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
+        assert_eq!(cache.sites[&1].entries.len(), 1);
+    }
+
+    #[test]
+    fn cached_shapes_do_not_keep_objects_alive() {
+        let mut cache = InlineCache::new();
+        let parent = create_i64(1);
+        let object = ObjectStruct::new(None, Some(parent.clone()));
+        let parent_weak = parent.downgrade();
+        drop(parent);
+        cache.lookup(1, Symbol::from("add"), &*object.borrow()).unwrap();
+        drop(object);
+        assert!(parent_weak.upgrade().is_none());
+    }
+
+    #[test]
+    fn changing_the_vtable_changes_the_shape() {
+        let mut cache = InlineCache::new();
+        let integer = create_i64(1);
+        let method = integer.borrow().lookup_method(Symbol::from("add")).unwrap();
+        let vtable = |name: &str| VTable::new(HashMap::from([(Symbol::from(name), method.clone())]));
+        integer.borrow_mut().initialize(Vec::new(), vtable("first"));
+        cache.lookup(1, Symbol::from("add"), &*integer.borrow()).unwrap();
+        // The integer holds the only strong reference to its table so this changes it in place
+        integer.borrow_mut().initialize(Vec::new(), vtable("second"));
+        cache.lookup(1, Symbol::from("add"), &*integer.borrow()).unwrap();
+        assert_eq!(cache.sites[&1].entries.len(), 2);
+    }
+
+    #[test]
+    fn new_class_epoch_clears_the_cache() {
+        let mut cache = InlineCache::new();
+        let integer = create_i64(1);
+        cache.lookup(1, Symbol::from("add"), &*integer.borrow()).unwrap();
+        cache.epoch = cache.epoch.wrapping_sub(1);
+        cache.lookup(2, Symbol::from("add"), &*integer.borrow()).unwrap();
+        assert!(!cache.sites.contains_key(&1));
+    }
+
+    #[test]
+    fn the_amount_of_sites_is_bounded() {
+        let mut cache = InlineCache::new();
+        let integer = create_i64(1);
+        for site in 0..SITE_LIMIT * 2 {
+            cache.lookup(site, Symbol::from("add"), &*integer.borrow()).unwrap();
+            assert!(cache.sites.len() <= SITE_LIMIT);
+        }
+    }
+
+    #[test]
+    fn instances_of_a_class_stay_monomorphic() {
+        let mut cache = InlineCache::new();
+        let method = Arc::new(Method::RustMethod { fun: Box::new(|_, _| Ok(None)) });
+        let class = class(VTable::new(HashMap::from([(Symbol::from("name"), method)])));
+        let instances = instances(&class, &create_i64(1), POLYMORPHIC_LIMIT * 2);
+        // A super send gives an instance a parent of its own, which doesn't change its shape
+        instances[0].borrow_mut().downcast_mut::<ObjectStruct>().unwrap().own_super_object();
+        for instance in instances.iter() {
+            cache.lookup(1, Symbol::from("name"), &*instance.borrow()).unwrap();
+            cache.lookup(2, Symbol::from("add"), &*instance.borrow()).unwrap();
+        }
+        for site in [1, 2] {
+            assert_eq!(cache.sites[&site].entries.len(), 1);
+            assert!(!cache.sites[&site].megamorphic);
+        }
+    }
+
+    #[test]
+    fn instances_of_different_classes_have_different_shapes() {
+        let mut cache = InlineCache::new();
+        let parent = create_i64(1);
+        let vtable = VTable::new(HashMap::new());
+        let first = instances(&class(vtable.clone()), &parent, 1);
+        let second = instances(&class(vtable), &parent, 1);
+        assert!(first[0].borrow().get_vtable().same_table(second[0].borrow().get_vtable()));
+        cache.lookup(1, Symbol::from("add"), &*first[0].borrow()).unwrap();
+        cache.lookup(1, Symbol::from("add"), &*second[0].borrow()).unwrap();
+        assert_eq!(cache.sites[&1].entries.len(), 2);
+    }
+}
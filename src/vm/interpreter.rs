@@ -1,10 +1,77 @@
-use crate::object::{ContextData, Fault, Method, Nil};
+use crate::object::{ContextData, Fault, Method, Nil, ObjectStruct};
 use crate::object::block::Block;
+use crate::object::symbol::Symbol;
+use crate::object::value::{Immediate, Reply, Value};
 use crate::vm::bytecode::{ByteCode, SpecialInstruction};
+use crate::vm::inline_cache;
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
 
 use super::bytecode::Literal;
 
+/// The interpreter running on each core, None when the core is idle
+pub type Cores = Arc<RwLock<Vec<Arc<Mutex<Option<Interpreter>>>>>>;
+
+/// How many instructions an interpreter started by a Rust method runs between safepoints
+const STEP_BUDGET: usize = 1024;
+
+/// The number of cores that are running instructions
+/// A core stops counting when it sees that the scheduler holds its lock, either between tasks or
+/// at a safepoint of a nested interpreter, so the world is stopped once this is 0.
+static RUNNING: AtomicUsize = AtomicUsize::new(0);
+
+thread_local! {
+    /// The lock that the scheduler holds to stop the core running on this thread
+    static CORE_LOCK: RefCell<Option<Arc<Mutex<()>>>> = const { RefCell::new(None) };
+}
+
+/// Counts a core as running for as long as it is alive
+struct Running;
+
+impl Running {
+    fn enter() -> Running {
+        RUNNING.fetch_add(1, Ordering::AcqRel);
+        Running
+    }
+}
+
+impl Drop for Running {
+    fn drop(&mut self) {
+        RUNNING.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+/// Wait for the scheduler if it wants to stop this core
+/// Threads that aren't cores have no lock and never wait.
+fn safepoint() {
+    CORE_LOCK.with(|lock| {
+        if let Some(lock) = &*lock.borrow() {
+            if lock.try_lock().is_err() {
+                RUNNING.fetch_sub(1, Ordering::AcqRel);
+                drop(lock.lock());
+                RUNNING.fetch_add(1, Ordering::AcqRel);
+            }
+        }
+    });
+}
+
+/// Wait until every core has stopped for the scheduler
+/// The scheduler must hold the lock of every core. This gives up after the timeout since a core
+/// can be in a Rust method that takes a long time without running any bytecode, like one that
+/// waits for input.
+pub fn wait_for_cores(timeout: Duration) -> bool {
+    let start = Instant::now();
+    while RUNNING.load(Ordering::Acquire) > 0 {
+        if start.elapsed() > timeout {
+            return false;
+        }
+        std::thread::yield_now();
+    }
+    true
+}
+
 pub struct Interpreter {
     code: Vec<(usize, Arc<Vec<ByteCode>>)>,
     context: Option<ContextData>,
@@ -17,10 +84,28 @@ impl Interpreter {
             context: Some(context),
         }
     }
+
+    /// Create an interpreter that continues running the given frames
+    pub fn resume(code: Vec<(usize, Arc<Vec<ByteCode>>)>, context: Option<ContextData>) -> Self {
+        Self {
+            code,
+            context,
+        }
+    }
+
+    /// The code that is being run and the index of the next instruction for each frame
+    pub fn frames(&self) -> &[(usize, Arc<Vec<ByteCode>>)] {
+        &self.code
+    }
+
+    pub fn context(&self) -> Option<&ContextData> {
+        self.context.as_ref()
+    }
     
-    pub fn run_loop(index: usize, interpreters: Arc<RwLock<Vec<Arc<Mutex<Option<Interpreter>>>>>>, lock: Arc<Mutex<()>>) {
+    pub fn run_loop(index: usize, interpreters: Cores, lock: Arc<Mutex<()>>) {
+        CORE_LOCK.with(|core_lock| *core_lock.borrow_mut() = Some(lock.clone()));
         'control: loop {
-            let _ = lock.lock();
+            drop(lock.lock());
             let interpreters_ref = interpreters.read().expect("Expected read lock");
             let Some(interpreter) = interpreters_ref.get(index) else {
                 continue;
@@ -31,14 +116,18 @@ impl Interpreter {
             if interpreter_mut.is_none() {
                 continue;
             }
+            // Only count as running once the task is ours so that a core waiting on the scheduler
+            // for the task list doesn't hold up stopping the world. The lock is checked again
+            // before the first instruction.
+            let _running = Running::enter();
             let interpreter = interpreter_mut.as_mut().unwrap();
             let mut context = interpreter.context.take();
             if let Some(code) = context.as_mut().unwrap().detach_code() {
                 interpreter.code.push((0, code));
             }
             loop {
-                if let Ok(_) = lock.try_lock() {
-                    match interpreter.run(&mut context.as_mut().unwrap()) {
+                if lock.try_lock().is_ok() {
+                    match interpreter.run(context.as_mut().unwrap()) {
                         Ok(true) => {}
                         Ok(false) => {
                             *interpreter_mut = None;
@@ -58,18 +147,40 @@ impl Interpreter {
         }
     }
     
+    /// Run an interpreter that a Rust method started until it halts
+    /// The core can't switch to another task before the Rust method returns, but it stops at a
+    /// safepoint every STEP_BUDGET instructions when the scheduler wants to stop the world, so
+    /// a long sort or a block that loops doesn't hold up collecting cycles for every other core.
+    pub fn run_nested(&mut self, context: &mut ContextData) -> Result<(), Fault> {
+        let mut budget = STEP_BUDGET;
+        while self.run(context)? {
+            budget -= 1;
+            if budget == 0 {
+                safepoint();
+                budget = STEP_BUDGET;
+            }
+        }
+        Ok(())
+    }
+
     pub fn run(&mut self, context: &mut ContextData) -> Result<bool, Fault> {
+        let depth = self.code.len();
         let mut index = self.code.last().expect("Expected last frame").0;
         let index_copy = index;
         let bytecode = self.code.last().expect("Expected last frame").1.clone();
         if index >= bytecode.len() {
-            return Ok(false);
+            // Running off the end of a method returns from it without a value
+            return self.return_(context);
         }
         let result = self.interpret(&mut index, context, &bytecode[index_copy])?;
         if index_copy == index {
             index += 1;
         }
-        self.code.last_mut().expect("Expected last frame").0 = index;
+        // A send may have pushed a frame and a return may have popped this one so we can't just
+        // update the last frame
+        if let Some(frame) = self.code.get_mut(depth - 1) {
+            frame.0 = index;
+        }
 
 
         Ok(result)
@@ -85,8 +196,11 @@ impl Interpreter {
             ByteCode::PushLiteral(literal) => self.push_literal(context, literal),
             ByteCode::StoreField(index) => self.store_field(context, *index),
             ByteCode::StoreTemp(index) => self.store_temp(*index, context),
-            ByteCode::SendMsg(arg, msg_index) => self.send_msg(*arg, msg_index, context)?,
-            ByteCode::SendSuperMsg(arg, msg_index) => self.send_super_msg(*arg, msg_index, context)?,
+            ByteCode::SendMsg(arg, msg_index) => {
+                let site = bytecode as *const ByteCode as usize;
+                self.send_msg(*arg, *msg_index, site, context)?
+            }
+            ByteCode::SendSuperMsg(arg, msg_index) => self.send_super_msg(*arg, *msg_index, context)?,
             ByteCode::SpecialInstruction(instruction) => return self.special_instruction(index, context, instruction),
             _ => unimplemented!()
         }
@@ -101,29 +215,31 @@ impl Interpreter {
     }
 
     fn access_temp(&self, index: usize, context: &mut ContextData) {
-        
+        context.flush_arguments();
         let value = context.arguments[index].clone();
         context.push(value);
     }
 
     fn push_literal(&self, context: &mut ContextData, literal: &Literal) {
-        let object = match literal {
-            Literal::String(string) => crate::object::create_string(string.to_string()),
-            Literal::I8(i) => crate::object::create_i8(*i),
-            Literal::I16(i) => crate::object::create_i16(*i),
-            Literal::I32(i) => crate::object::create_i32(*i),
-            Literal::I64(i) => crate::object::create_i64(*i),
-            Literal::U8(i) => crate::object::create_u8(*i),
-            Literal::U16(i) => crate::object::create_u16(*i),
-            Literal::U32(i) => crate::object::create_u32(*i),
-            Literal::U64(i) => crate::object::create_u64(*i),
-            Literal::F32(f) => crate::object::create_f32(*f),
-            Literal::F64(f) => crate::object::create_f64(*f),
-            Literal::Boolean(b) => crate::object::create_boolean(*b),
-            Literal::Nil => Nil::new(),
-            Literal::ByteCode(bytecode) => crate::object::create_block(bytecode.to_vec()),
+        let value = match literal {
+            Literal::String(string) => Value::Object(crate::object::create_string(string.to_string())),
+            Literal::I8(i) => Value::Immediate(Immediate::I8(*i)),
+            Literal::I16(i) => Value::Immediate(Immediate::I16(*i)),
+            Literal::I32(i) => Value::Immediate(Immediate::I32(*i)),
+            Literal::I64(i) => Value::Immediate(Immediate::I64(*i)),
+            Literal::U8(i) => Value::Immediate(Immediate::U8(*i)),
+            Literal::U16(i) => Value::Immediate(Immediate::U16(*i)),
+            Literal::U32(i) => Value::Immediate(Immediate::U32(*i)),
+            Literal::U64(i) => Value::Immediate(Immediate::U64(*i)),
+            Literal::F32(f) => Value::Immediate(Immediate::F32(*f)),
+            Literal::F64(f) => Value::Immediate(Immediate::F64(*f)),
+            Literal::Boolean(b) => Value::Immediate(Immediate::Boolean(*b)),
+            Literal::Nil => Value::Object(Nil::new()),
+            Literal::ByteCode(bytecode) => Value::Object(crate::object::create_block(bytecode.to_vec())),
+            Literal::Symbol(symbol) => Value::Object(crate::object::create_symbol(*symbol)),
+            Literal::BigInt(value) => Value::Object(crate::object::create_bigint(value.clone())),
         };
-        context.push(object);
+        context.push_value(value);
     }
 
     fn store_field(&self, context: &mut ContextData, index: usize) {
@@ -139,7 +255,35 @@ impl Interpreter {
         context.set_argument(index, value);
     }
 
-    fn send_msg(&mut self, arg: usize, msg_index: &str, context: &mut ContextData) -> Result<(), Fault>{
+    /// Handle a send between unboxed primitives without going through the method lookup.
+    /// None means the receiver or the argument is an object or the message isn't handled inline.
+    fn send_immediate(arg: usize, msg_index: Symbol, context: &mut ContextData) -> Option<Result<(), Fault>> {
+        let (receiver, argument) = match arg {
+            0 => (context.peek_immediate(0)?, None),
+            1 => (context.peek_immediate(1)?, Some(context.peek_immediate(0)?)),
+            _ => return None,
+        };
+        let reply = match receiver.send(msg_index, argument)? {
+            Ok(reply) => reply,
+            Err(fault) => return Some(Err(fault)),
+        };
+        if let Some(argument) = argument {
+            context.pop_value();
+            context.defer_argument(argument);
+        }
+        context.arg_count = arg;
+        match reply {
+            Reply::Replace(value) => context.replace_top(Value::Immediate(value)),
+            Reply::Push(value) => context.push_value(Value::Immediate(value)),
+        }
+        Some(Ok(()))
+    }
+
+    fn send_msg(&mut self, arg: usize, msg_index: Symbol, site: usize, context: &mut ContextData) -> Result<(), Fault>{
+        if let Some(result) = Self::send_immediate(arg, msg_index, context) {
+            return result;
+        }
+        context.flush_arguments();
         for i in 0..arg {
             let value = context.pop().expect("Expected argument");
             context.set_argument(i, value)
@@ -148,9 +292,7 @@ impl Interpreter {
         let object = context.top().expect("Stack was empty").clone();
         let borrowed_object = object.borrow();
 
-        let message = crate::object::create_message(&msg_index);
-
-        let method = borrowed_object.process_message(message);
+        let method = inline_cache::lookup(site, msg_index, &*borrowed_object);
         drop(borrowed_object);
         if let Some(method) = method {
             match *method {
@@ -178,21 +320,25 @@ impl Interpreter {
         Ok(())
     }
 
-    fn send_super_msg(&mut self, arg: usize, msg_index: &str, context: &mut ContextData) -> Result<(), Fault> {
+    fn send_super_msg(&mut self, arg: usize, msg_index: Symbol, context: &mut ContextData) -> Result<(), Fault> {
+        context.flush_arguments();
         for i in 0..arg {
             let value = context.pop().expect("Expected argument");
             context.set_argument(i, value)
         }
         context.arg_count = arg;
         let object = context.top().expect("Stack was empty").clone();
-        let borrowed_object = object.borrow();
+        let mut borrowed_object = object.borrow_mut();
 
-        let message = crate::object::create_message(&msg_index);
-        
-        let parent = borrowed_object.get_super_object().expect("Expected super object");
+        let parent = match borrowed_object.downcast_mut::<ObjectStruct>() {
+            Some(object) => object.own_super_object(),
+            None => borrowed_object.get_super_object(),
+        };
+        drop(borrowed_object);
+        let parent = parent.expect("Expected super object");
         let borrowed_parent = parent.borrow();
 
-        let method = borrowed_parent.process_message(message);
+        let method = borrowed_parent.lookup_method(msg_index);
         drop(borrowed_parent);
         if let Some(method) = method {
             match *method {
@@ -214,6 +360,7 @@ impl Interpreter {
                 }
             }
             if let Some(code) = context.detach_code() {
+                context.push_frame(None);
                 self.code.push((0, code));
             }
         } else {
@@ -222,12 +369,12 @@ impl Interpreter {
         Ok(())
     }
     
-    fn special_instruction(&self, index: &mut usize, context: &mut ContextData, instruction: &SpecialInstruction) -> Result<bool, Fault> {
+    fn special_instruction(&mut self, index: &mut usize, context: &mut ContextData, instruction: &SpecialInstruction) -> Result<bool, Fault> {
         match instruction {
             SpecialInstruction::DupStack => Self::dup_stack(context),
             SpecialInstruction::DiscardStack => Self::discard_stack(context),
-            SpecialInstruction::ReturnStack => Self::return_stack(context),
-            SpecialInstruction::Return => Self::return_(context),
+            SpecialInstruction::ReturnStack => self.return_stack(context),
+            SpecialInstruction::Return => self.return_(context),
             SpecialInstruction::PopTrueSkip(skip) => Self::pop_true_skip(context, index, *skip),
             SpecialInstruction::PopFalseSkip(skip) => Self::pop_false_skip(context, index, *skip),
             SpecialInstruction::PopTrueBackSkip(skip) => Self::pop_true_back_skip(context, index, *skip),
@@ -244,6 +391,10 @@ impl Interpreter {
     }
     
     fn dup_stack(context: &mut ContextData) -> Result<bool, Fault> {
+        if let Some(value) = context.peek_immediate(0) {
+            context.push_value(Value::Immediate(value));
+            return Ok(true);
+        }
         let value = context.top().expect("Expected value").clone();
         let value_ref = value.borrow();
         let value = value_ref.duplicate();
@@ -253,61 +404,177 @@ impl Interpreter {
     }
 
     fn discard_stack(context: &mut ContextData) -> Result<bool, Fault> {
-        context.pop();
+        context.pop_value();
         Ok(true)
     }
 
-    fn return_stack(context: &mut ContextData) -> Result<bool, Fault> {
-        let value = context.pop().expect("Expected value").clone();
-        let frame = context.pop_frame();
-        context.push(value);
-        context.push_frame(frame);
-        Ok(false)
+    /// Return the top of the stack to the caller
+    /// Returning from the outermost frame ends the task.
+    fn return_stack(&mut self, context: &mut ContextData) -> Result<bool, Fault> {
+        if self.code.len() <= 1 {
+            return Ok(false);
+        }
+        let value = context.pop_value().expect("Expected value");
+        self.code.pop();
+        context.pop_frame();
+        context.push_value(value);
+        Ok(true)
     }
 
-    fn return_(context: &mut ContextData) -> Result<bool, Fault> {
-        //let value = context.pop().expect("Expected value").clone();
-        let _ = context.pop_frame();
-        Ok(false)
+    /// Return to the caller without a value
+    /// Returning from the outermost frame ends the task.
+    fn return_(&mut self, context: &mut ContextData) -> Result<bool, Fault> {
+        if self.code.len() <= 1 {
+            return Ok(false);
+        }
+        self.code.pop();
+        context.pop_frame();
+        Ok(true)
     }
     
+    fn pop_boolean(context: &mut ContextData) -> Result<bool, Fault> {
+        match context.pop_value().expect("Expected value") {
+            Value::Immediate(Immediate::Boolean(value)) => Ok(value),
+            Value::Immediate(_) => Err(Fault::InvalidType(String::from("Expected boolean"))),
+            Value::Object(value) => {
+                let value = value.borrow();
+                let value = value.downcast_ref::<crate::object::primitive::PrimitiveObject<bool>>().ok_or(Fault::InvalidType(String::from("Expected boolean")))?;
+                Ok(value.data)
+            }
+        }
+    }
+
     fn pop_true_skip(context: &mut ContextData, index: &mut usize, skip: usize) -> Result<bool, Fault> {
-        let value = context.pop().expect("Expected value").clone();
-        let value = value.borrow();
-        let value = value.downcast_ref::<crate::object::primitive::PrimitiveObject<bool>>().ok_or(Fault::InvalidType(String::from("Expected boolean")))?;
-        if value.data {
+        if Self::pop_boolean(context)? {
             *index += skip;
         }
         Ok(true)
     }
 
     fn pop_false_skip(context: &mut ContextData, index: &mut usize, skip: usize) -> Result<bool, Fault> {
-        let value = context.pop().expect("Expected value").clone();
-        let value = value.borrow();
-        let value = value.downcast_ref::<crate::object::primitive::PrimitiveObject<bool>>().ok_or(Fault::InvalidType(String::from("Expected boolean")))?;
-        if !value.data {
+        if !Self::pop_boolean(context)? {
             *index += skip;
         }
         Ok(true)
     }
 
     fn pop_true_back_skip(context: &mut ContextData, index: &mut usize, skip: usize) -> Result<bool, Fault> {
-        let value = context.pop().expect("Expected value").clone();
-        let value = value.borrow();
-        let value = value.downcast_ref::<crate::object::primitive::PrimitiveObject<bool>>().ok_or(Fault::InvalidType(String::from("Expected boolean")))?;
-        if value.data {
+        if Self::pop_boolean(context)? {
             *index -= skip;
         }
         Ok(true)
     }
 
     fn pop_false_back_skip(context: &mut ContextData, index: &mut usize, skip: usize) -> Result<bool, Fault> {
-        let value = context.pop().expect("Expected value").clone();
-        let value = value.borrow();
-        let value = value.downcast_ref::<crate::object::primitive::PrimitiveObject<bool>>().ok_or(Fault::InvalidType(String::from("Expected boolean")))?;
-        if !value.data {
+        if !Self::pop_boolean(context)? {
             *index -= skip;
         }
         Ok(true)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::object::{Object, ObjectBox};
+    use crate::object::primitive::PrimitiveObject;
+    use std::sync::mpsc;
+
+    /// Run bytecode in a frame of its own and keep the context to look at its stack
+    fn run(mut code: Vec<ByteCode>) -> Result<ContextData, Fault> {
+        code.push(ByteCode::Halt);
+        let mut context = ContextData::new(crate::object::init_stack());
+        context.push_frame(None);
+        let mut interpreter = Interpreter { code: vec![(0, Arc::new(code))], context: None };
+        while interpreter.run(&mut context)? {}
+        Ok(context)
+    }
+
+    fn push(immediate: Immediate) -> ByteCode {
+        ByteCode::PushLiteral(match immediate {
+            Immediate::I8(value) => Literal::I8(value),
+            Immediate::I64(value) => Literal::I64(value),
+            Immediate::F64(value) => Literal::F64(value),
+            Immediate::Boolean(value) => Literal::Boolean(value),
+            _ => unimplemented!(),
+        })
+    }
+
+    /// The value of a boxed primitive
+    fn primitive<T: Copy>(object: &ObjectBox) -> T where PrimitiveObject<T>: Object {
+        object.borrow().downcast_ref::<PrimitiveObject<T>>().unwrap().data
+    }
+
+    fn send(arguments: usize, selector: &str) -> ByteCode {
+        ByteCode::SendMsg(arguments, Symbol::from(selector))
+    }
+
+    #[test]
+    fn primitive_literals_stay_unboxed() {
+        let context = run(vec![push(Immediate::I64(2)), push(Immediate::I64(3)), send(1, "add")]).unwrap();
+        assert_eq!(context.peek_immediate(0), Some(Immediate::I64(5)));
+        let context = run(vec![push(Immediate::F64(-1.5)), send(0, "abs"), push(Immediate::Boolean(true))]).unwrap();
+        assert_eq!(context.peek_immediate(0), Some(Immediate::Boolean(true)));
+        assert_eq!(context.peek_immediate(1), Some(Immediate::F64(1.5)));
+    }
+
+    #[test]
+    fn immediates_are_boxed_when_the_send_needs_an_object() {
+        let context = run(vec![push(Immediate::I8(1)), push(Immediate::I64(2)), send(1, "add")]).unwrap();
+        assert!(context.peek_immediate(0).is_none());
+        assert_eq!(primitive::<i8>(&context.top().unwrap()), 3);
+        // The argument of an inline send is boxed once something reads the arguments
+        let mut context = run(vec![push(Immediate::I64(2)), push(Immediate::I64(3)), send(1, "mul")]).unwrap();
+        assert!(context.get_argument(0).is_none());
+        context.flush_arguments();
+        assert_eq!(primitive::<i64>(&context.get_argument(0).unwrap()), 3);
+    }
+
+    #[test]
+    fn inline_faults_stop_the_interpreter() {
+        assert!(matches!(run(vec![push(Immediate::I8(i8::MAX)), push(Immediate::I8(1)), send(1, "add")]), Err(Fault::Overflow(_))));
+    }
+
+    #[test]
+    fn comparisons_of_immediates_push_a_boolean() {
+        let context = run(vec![push(Immediate::I64(2)), push(Immediate::I64(3)), send(1, "less_than")]).unwrap();
+        assert_eq!(context.peek_immediate(0), Some(Immediate::Boolean(true)));
+        assert_eq!(context.peek_immediate(1), Some(Immediate::I64(2)));
+        let context = run(vec![push(Immediate::F64(2.5)), push(Immediate::F64(-1.0)), send(1, "min")]).unwrap();
+        assert_eq!(context.peek_immediate(0), Some(Immediate::F64(-1.0)));
+    }
+
+    #[test]
+    fn nested_interpreters_stop_at_safepoints() {
+        let lock = Arc::new(Mutex::new(()));
+        let held = lock.lock().unwrap();
+        let (started, start) = mpsc::channel();
+        let (finished, finish) = mpsc::channel();
+        let core = {
+            let lock = lock.clone();
+            std::thread::spawn(move || {
+                CORE_LOCK.with(|core_lock| *core_lock.borrow_mut() = Some(lock));
+                let _running = Running::enter();
+                started.send(()).unwrap();
+                let mut code = vec![ByteCode::NoOp; STEP_BUDGET * 2];
+                code.push(ByteCode::Halt);
+                let mut interpreter = Interpreter::resume(vec![(0, Arc::new(code))], None);
+                let result = interpreter.run_nested(&mut ContextData::new(crate::object::init_stack()));
+                finished.send(()).unwrap();
+                result
+            })
+        };
+        start.recv().unwrap();
+        assert!(wait_for_cores(Duration::from_secs(10)));
+        assert!(finish.try_recv().is_err());
+        drop(held);
+        core.join().unwrap().unwrap();
+        assert!(finish.try_recv().is_ok());
+    }
+
+    #[test]
+    fn waiting_for_cores_gives_up() {
+        let _running = Running::enter();
+        assert!(!wait_for_cores(Duration::from_millis(10)));
+    }
+}
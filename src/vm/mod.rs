@@ -4,5 +4,7 @@
 pub mod interpreter;
 pub mod bytecode;
 pub mod binary;
+pub mod inline_cache;
+pub mod image;
 
 pub use crate::vm::binary::binary_data_to_binary as create_binary;